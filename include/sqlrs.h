@@ -0,0 +1,318 @@
+#ifndef SQLRS_H
+#define SQLRS_H
+
+/* Generated by cbindgen from src/capi, don't edit it by hand: run `make header`. */
+
+#include <stdbool.h>
+#include <stdint.h>
+
+/* The arrow C data and C stream interfaces, see https://arrow.apache.org/docs/format/CDataInterface.html */
+
+#ifndef ARROW_C_DATA_INTERFACE
+#define ARROW_C_DATA_INTERFACE
+
+struct ArrowSchema {
+  const char *format;
+  const char *name;
+  const char *metadata;
+  int64_t flags;
+  int64_t n_children;
+  struct ArrowSchema **children;
+  struct ArrowSchema *dictionary;
+  void (*release)(struct ArrowSchema *);
+  void *private_data;
+};
+
+struct ArrowArray {
+  int64_t length;
+  int64_t null_count;
+  int64_t offset;
+  int64_t n_buffers;
+  int64_t n_children;
+  const void **buffers;
+  struct ArrowArray **children;
+  struct ArrowArray *dictionary;
+  void (*release)(struct ArrowArray *);
+  void *private_data;
+};
+
+#endif /* ARROW_C_DATA_INTERFACE */
+
+#ifndef ARROW_C_STREAM_INTERFACE
+#define ARROW_C_STREAM_INTERFACE
+
+struct ArrowArrayStream {
+  int (*get_schema)(struct ArrowArrayStream *, struct ArrowSchema *out);
+  int (*get_next)(struct ArrowArrayStream *, struct ArrowArray *out);
+  const char *(*get_last_error)(struct ArrowArrayStream *);
+  void (*release)(struct ArrowArrayStream *);
+  void *private_data;
+};
+
+#endif /* ARROW_C_STREAM_INTERFACE */
+
+/**
+ * The result code of a function of the C interface.
+ */
+typedef enum SqlrsCode {
+  SQLRS_OK = 0,
+  /**
+   * A null pointer, an invalid UTF-8 string or an out of range index was given
+   */
+  SQLRS_INVALID_ARGUMENT = 1,
+  SQLRS_PARSER_ERROR = 2,
+  SQLRS_CATALOG_ERROR = 3,
+  SQLRS_PLANNER_ERROR = 4,
+  SQLRS_EXECUTOR_ERROR = 5,
+  SQLRS_FUNCTION_ERROR = 6,
+  SQLRS_ARROW_ERROR = 7,
+  /**
+   * The query was interrupted, or ran longer than the statement timeout
+   */
+  SQLRS_INTERRUPTED = 8,
+  SQLRS_INTERNAL_ERROR = 9,
+} SqlrsCode;
+
+/**
+ * A connection created by `sqlrs_connect`.
+ */
+typedef struct SqlrsConnection SqlrsConnection;
+
+/**
+ * A database opened by `sqlrs_open`, the queries of its connections are executed by its
+ * runtime.
+ */
+typedef struct SqlrsDatabase SqlrsDatabase;
+
+/**
+ * A statement prepared by `sqlrs_prepare`, with the values bound to its parameters.
+ */
+typedef struct SqlrsPreparedStatement SqlrsPreparedStatement;
+
+/**
+ * An error returned by a function of the C interface. The message is allocated by sqlrs, and
+ * freed by `sqlrs_error_free`. The error must be zeroed before its first use, a message left in
+ * it is freed when a function writes another error into it.
+ */
+typedef struct SqlrsError {
+  SqlrsCode code;
+  char *message;
+} SqlrsError;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Free the message of an error, the error can be reused afterwards.
+ *
+ * # Safety
+ *
+ * The error must be null, or a valid error written by a function of the C interface.
+ */
+void sqlrs_error_free(SqlrsError *error);
+
+/**
+ * Open an in-memory database.
+ *
+ * # Safety
+ *
+ * `out_database` must point to memory writable as a pointer, the database is closed by
+ * `sqlrs_close`.
+ */
+SqlrsCode sqlrs_open(SqlrsDatabase **out_database, SqlrsError *error);
+
+/**
+ * Close a database. Its connections, prepared statements and result streams stay usable until
+ * they are released.
+ *
+ * # Safety
+ *
+ * The database must be null, or a database opened by `sqlrs_open` and not closed yet.
+ */
+void sqlrs_close(SqlrsDatabase *database);
+
+/**
+ * Create a connection to a database, each connection has its own client session.
+ *
+ * # Safety
+ *
+ * The database must be a database opened by `sqlrs_open`, and `out_connection` must point to
+ * memory writable as a pointer. The connection is closed by `sqlrs_disconnect`.
+ */
+SqlrsCode sqlrs_connect(SqlrsDatabase *database,
+                        SqlrsConnection **out_connection,
+                        SqlrsError *error);
+
+/**
+ * Close a connection.
+ *
+ * # Safety
+ *
+ * The connection must be null, or a connection created by `sqlrs_connect` and not closed yet.
+ */
+void sqlrs_disconnect(SqlrsConnection *connection);
+
+/**
+ * Run the statements of the sql, the result of the last one is written into `out_stream`. The
+ * batches of the result are produced while the stream is read, and the stream must be released
+ * by the caller.
+ *
+ * # Safety
+ *
+ * The connection must be a connection created by `sqlrs_connect`, the sql a nul terminated
+ * string, and `out_stream` must point to memory writable as an `ArrowArrayStream`.
+ */
+SqlrsCode sqlrs_query(SqlrsConnection *connection,
+                      const char *sql,
+                      struct ArrowArrayStream *out_stream,
+                      SqlrsError *error);
+
+/**
+ * Interrupt the running query of a connection, the stream of its result ends with an error.
+ * It can be called from another thread than the one reading the result.
+ *
+ * # Safety
+ *
+ * The connection must be a connection created by `sqlrs_connect`.
+ */
+SqlrsCode sqlrs_interrupt(SqlrsConnection *connection, SqlrsError *error);
+
+/**
+ * Prepare a statement with `$1` or `?` parameters, its values are bound by the `sqlrs_bind_*`
+ * functions before it's executed.
+ *
+ * # Safety
+ *
+ * The connection must be a connection created by `sqlrs_connect`, the sql a nul terminated
+ * string, and `out_statement` must point to memory writable as a pointer. The statement is
+ * released by `sqlrs_destroy_prepared`.
+ */
+SqlrsCode sqlrs_prepare(SqlrsConnection *connection,
+                        const char *sql,
+                        SqlrsPreparedStatement **out_statement,
+                        SqlrsError *error);
+
+/**
+ * The number of parameters of a prepared statement, or 0 when the statement is null.
+ *
+ * # Safety
+ *
+ * The statement must be null, or a statement created by `sqlrs_prepare`.
+ */
+uint64_t sqlrs_parameter_count(SqlrsPreparedStatement *statement);
+
+/**
+ * Bind a boolean to the parameter at the index, `$1` has the index 1.
+ *
+ * # Safety
+ *
+ * The statement must be a statement created by `sqlrs_prepare`, and not used by another thread
+ * during the call.
+ */
+SqlrsCode sqlrs_bind_boolean(SqlrsPreparedStatement *statement,
+                             uint64_t index,
+                             bool value,
+                             SqlrsError *error);
+
+/**
+ * Bind an integer to the parameter at the index, `$1` has the index 1.
+ *
+ * # Safety
+ *
+ * The statement must be a statement created by `sqlrs_prepare`, and not used by another thread
+ * during the call.
+ */
+SqlrsCode sqlrs_bind_int32(SqlrsPreparedStatement *statement,
+                           uint64_t index,
+                           int32_t value,
+                           SqlrsError *error);
+
+/**
+ * Bind a bigint to the parameter at the index, `$1` has the index 1.
+ *
+ * # Safety
+ *
+ * The statement must be a statement created by `sqlrs_prepare`, and not used by another thread
+ * during the call.
+ */
+SqlrsCode sqlrs_bind_int64(SqlrsPreparedStatement *statement,
+                           uint64_t index,
+                           int64_t value,
+                           SqlrsError *error);
+
+/**
+ * Bind a double to the parameter at the index, `$1` has the index 1.
+ *
+ * # Safety
+ *
+ * The statement must be a statement created by `sqlrs_prepare`, and not used by another thread
+ * during the call.
+ */
+SqlrsCode sqlrs_bind_double(SqlrsPreparedStatement *statement,
+                            uint64_t index,
+                            double value,
+                            SqlrsError *error);
+
+/**
+ * Bind a nul terminated string to the parameter at the index, `$1` has the index 1. The string
+ * is copied.
+ *
+ * # Safety
+ *
+ * The statement must be a statement created by `sqlrs_prepare`, and not used by another thread
+ * during the call. The value must be a nul terminated string.
+ */
+SqlrsCode sqlrs_bind_varchar(SqlrsPreparedStatement *statement,
+                             uint64_t index,
+                             const char *value,
+                             SqlrsError *error);
+
+/**
+ * Bind NULL to the parameter at the index, `$1` has the index 1.
+ *
+ * # Safety
+ *
+ * The statement must be a statement created by `sqlrs_prepare`, and not used by another thread
+ * during the call.
+ */
+SqlrsCode sqlrs_bind_null(SqlrsPreparedStatement *statement, uint64_t index, SqlrsError *error);
+
+/**
+ * Clear the values bound to the parameters of a prepared statement.
+ *
+ * # Safety
+ *
+ * The statement must be null, or a statement created by `sqlrs_prepare` and not used by
+ * another thread during the call.
+ */
+void sqlrs_clear_bindings(SqlrsPreparedStatement *statement);
+
+/**
+ * Execute a prepared statement with the values bound to its parameters, its result is written
+ * into `out_stream` like the result of `sqlrs_query`. The values stay bound for the next
+ * executions.
+ *
+ * # Safety
+ *
+ * The statement must be a statement created by `sqlrs_prepare`, and `out_stream` must point to
+ * memory writable as an `ArrowArrayStream`.
+ */
+SqlrsCode sqlrs_execute_prepared(SqlrsPreparedStatement *statement,
+                                 struct ArrowArrayStream *out_stream,
+                                 SqlrsError *error);
+
+/**
+ * Release a prepared statement.
+ *
+ * # Safety
+ *
+ * The statement must be null, or a statement created by `sqlrs_prepare` and not released yet.
+ */
+void sqlrs_destroy_prepared(SqlrsPreparedStatement *statement);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* SQLRS_H */
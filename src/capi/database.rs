@@ -0,0 +1,139 @@
+use std::os::raw::c_char;
+use std::sync::Arc;
+
+use arrow::ffi_stream::FFI_ArrowArrayStream;
+use tokio::runtime::Runtime;
+
+use super::{export_result, ffi_call, handle_arg, handle_arg_mut, str_arg, SqlrsCode, SqlrsError};
+use crate::main_entry::{Connection, Database, DatabaseError};
+
+/// A database opened by `sqlrs_open`, the queries of its connections are executed by its
+/// runtime.
+pub struct SqlrsDatabase {
+    database: Database,
+    runtime: Arc<Runtime>,
+}
+
+/// A connection created by `sqlrs_connect`.
+pub struct SqlrsConnection {
+    pub(crate) connection: Connection,
+    pub(crate) runtime: Arc<Runtime>,
+}
+
+/// Open an in-memory database.
+///
+/// # Safety
+///
+/// `out_database` must point to memory writable as a pointer, the database is closed by
+/// `sqlrs_close`.
+#[no_mangle]
+pub unsafe extern "C" fn sqlrs_open(
+    out_database: *mut *mut SqlrsDatabase,
+    error: *mut SqlrsError,
+) -> SqlrsCode {
+    ffi_call(error, || {
+        let out_database = handle_arg_mut("out_database", out_database)?;
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| DatabaseError::InternalError(e.to_string()))?;
+        let database = SqlrsDatabase {
+            database: Database::open()?,
+            runtime: Arc::new(runtime),
+        };
+        *out_database = Box::into_raw(Box::new(database));
+        Ok(())
+    })
+}
+
+/// Close a database. Its connections, prepared statements and result streams stay usable until
+/// they are released.
+///
+/// # Safety
+///
+/// The database must be null, or a database opened by `sqlrs_open` and not closed yet.
+#[no_mangle]
+pub unsafe extern "C" fn sqlrs_close(database: *mut SqlrsDatabase) {
+    if !database.is_null() {
+        drop(Box::from_raw(database));
+    }
+}
+
+/// Create a connection to a database, each connection has its own client session.
+///
+/// # Safety
+///
+/// The database must be a database opened by `sqlrs_open`, and `out_connection` must point to
+/// memory writable as a pointer. The connection is closed by `sqlrs_disconnect`.
+#[no_mangle]
+pub unsafe extern "C" fn sqlrs_connect(
+    database: *mut SqlrsDatabase,
+    out_connection: *mut *mut SqlrsConnection,
+    error: *mut SqlrsError,
+) -> SqlrsCode {
+    ffi_call(error, || {
+        let database = handle_arg("database", database)?;
+        let out_connection = handle_arg_mut("out_connection", out_connection)?;
+        let connection = SqlrsConnection {
+            connection: database.database.connect(),
+            runtime: database.runtime.clone(),
+        };
+        *out_connection = Box::into_raw(Box::new(connection));
+        Ok(())
+    })
+}
+
+/// Close a connection.
+///
+/// # Safety
+///
+/// The connection must be null, or a connection created by `sqlrs_connect` and not closed yet.
+#[no_mangle]
+pub unsafe extern "C" fn sqlrs_disconnect(connection: *mut SqlrsConnection) {
+    if !connection.is_null() {
+        drop(Box::from_raw(connection));
+    }
+}
+
+/// Run the statements of the sql, the result of the last one is written into `out_stream`. The
+/// batches of the result are produced while the stream is read, and the stream must be released
+/// by the caller.
+///
+/// # Safety
+///
+/// The connection must be a connection created by `sqlrs_connect`, the sql a nul terminated
+/// string, and `out_stream` must point to memory writable as an `ArrowArrayStream`.
+#[no_mangle]
+pub unsafe extern "C" fn sqlrs_query(
+    connection: *mut SqlrsConnection,
+    sql: *const c_char,
+    out_stream: *mut FFI_ArrowArrayStream,
+    error: *mut SqlrsError,
+) -> SqlrsCode {
+    ffi_call(error, || {
+        let connection = handle_arg("connection", connection)?;
+        let sql = str_arg("sql", sql)?;
+        let result = connection
+            .runtime
+            .block_on(connection.connection.query(sql))?;
+        export_result(result, connection.runtime.clone(), out_stream)
+    })
+}
+
+/// Interrupt the running query of a connection, the stream of its result ends with an error.
+/// It can be called from another thread than the one reading the result.
+///
+/// # Safety
+///
+/// The connection must be a connection created by `sqlrs_connect`.
+#[no_mangle]
+pub unsafe extern "C" fn sqlrs_interrupt(
+    connection: *mut SqlrsConnection,
+    error: *mut SqlrsError,
+) -> SqlrsCode {
+    ffi_call(error, || {
+        let connection = handle_arg("connection", connection)?;
+        connection.connection.interrupt();
+        Ok(())
+    })
+}
@@ -0,0 +1,226 @@
+//! The C interface of sqlrs, for the programs embedding sqlrs from other languages. The
+//! functions are declared by `include/sqlrs.h`, and the results of queries are returned as arrow
+//! C streams.
+//!
+//! A function returns `SQLRS_OK` on success, otherwise the code of its error, and the error with
+//! its message is written into the `error` argument when it isn't null. The message is freed by
+//! `sqlrs_error_free`, or when another error is written into the same error.
+mod database;
+mod prepared_statement;
+
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::Arc;
+
+use arrow::datatypes::SchemaRef;
+use arrow::error::ArrowError;
+use arrow::ffi_stream::FFI_ArrowArrayStream;
+use arrow::record_batch::{RecordBatch, RecordBatchReader};
+pub use database::*;
+pub use prepared_statement::*;
+use tokio::runtime::Runtime;
+
+use crate::execution::ExecutorError;
+use crate::main_entry::{DatabaseError, QueryResult, QueryResultReader};
+
+/// The result code of a function of the C interface.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SqlrsCode {
+    SqlrsOk = 0,
+    /// A null pointer, an invalid UTF-8 string or an out of range index was given
+    SqlrsInvalidArgument = 1,
+    SqlrsParserError = 2,
+    SqlrsCatalogError = 3,
+    SqlrsPlannerError = 4,
+    SqlrsExecutorError = 5,
+    SqlrsFunctionError = 6,
+    SqlrsArrowError = 7,
+    /// The query was interrupted, or ran longer than the statement timeout
+    SqlrsInterrupted = 8,
+    SqlrsInternalError = 9,
+}
+
+/// An error returned by a function of the C interface. The message is allocated by sqlrs, and
+/// freed by `sqlrs_error_free`. The error must be zeroed before its first use, a message left in
+/// it is freed when a function writes another error into it.
+#[repr(C)]
+#[derive(Debug)]
+pub struct SqlrsError {
+    pub code: SqlrsCode,
+    pub message: *mut c_char,
+}
+
+impl Default for SqlrsError {
+    fn default() -> Self {
+        Self {
+            code: SqlrsCode::SqlrsOk,
+            message: std::ptr::null_mut(),
+        }
+    }
+}
+
+/// Free the message of an error, the error can be reused afterwards.
+///
+/// # Safety
+///
+/// The error must be null, or a valid error written by a function of the C interface.
+#[no_mangle]
+pub unsafe extern "C" fn sqlrs_error_free(error: *mut SqlrsError) {
+    if let Some(error) = error.as_mut() {
+        if !error.message.is_null() {
+            drop(CString::from_raw(error.message));
+        }
+        *error = SqlrsError::default();
+    }
+}
+
+/// The error of a function, before it's written into the error of the caller.
+pub(crate) struct CApiError {
+    code: SqlrsCode,
+    message: String,
+}
+
+impl CApiError {
+    pub(crate) fn invalid_argument(message: impl Into<String>) -> Self {
+        Self {
+            code: SqlrsCode::SqlrsInvalidArgument,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<DatabaseError> for CApiError {
+    fn from(e: DatabaseError) -> Self {
+        let code = match &e {
+            DatabaseError::ParserError(_) => SqlrsCode::SqlrsParserError,
+            DatabaseError::CatalogError(_) => SqlrsCode::SqlrsCatalogError,
+            DatabaseError::PlannerError(_) | DatabaseError::PreparedStatementTypesChanged => {
+                SqlrsCode::SqlrsPlannerError
+            }
+            DatabaseError::ExecutorError(
+                ExecutorError::Interrupted | ExecutorError::StatementTimeout,
+            ) => SqlrsCode::SqlrsInterrupted,
+            DatabaseError::ExecutorError(_) => SqlrsCode::SqlrsExecutorError,
+            DatabaseError::ArrowError(_) => SqlrsCode::SqlrsArrowError,
+            DatabaseError::FunctionError(_) => SqlrsCode::SqlrsFunctionError,
+            DatabaseError::StorageError(_) => SqlrsCode::SqlrsInternalError,
+            DatabaseError::InternalError(_) => SqlrsCode::SqlrsInternalError,
+        };
+        Self {
+            code,
+            message: e.to_string(),
+        }
+    }
+}
+
+/// Run the body of a function of the C interface, its error is written into the error of the
+/// caller. A panic is returned as an internal error, it must not unwind into the caller.
+pub(crate) fn ffi_call(
+    error: *mut SqlrsError,
+    body: impl FnOnce() -> Result<(), CApiError>,
+) -> SqlrsCode {
+    let result = catch_unwind(AssertUnwindSafe(body)).unwrap_or_else(|_| {
+        Err(CApiError {
+            code: SqlrsCode::SqlrsInternalError,
+            message: "sqlrs panicked".to_string(),
+        })
+    });
+    match result {
+        Ok(()) => SqlrsCode::SqlrsOk,
+        Err(e) => {
+            // SAFETY: the caller gives a null pointer or a valid error, the message of the
+            // previous error written into it is freed before it's overwritten
+            unsafe { sqlrs_error_free(error) };
+            if let Some(error) = unsafe { error.as_mut() } {
+                // a message can't have a nul byte inside a C string
+                let message = CString::new(e.message.replace('\0', " ")).unwrap();
+                error.code = e.code;
+                error.message = message.into_raw();
+            }
+            e.code
+        }
+    }
+}
+
+/// Read a C string argument.
+///
+/// # Safety
+///
+/// The pointer must be null, or a valid nul terminated string.
+pub(crate) unsafe fn str_arg<'a>(name: &str, value: *const c_char) -> Result<&'a str, CApiError> {
+    if value.is_null() {
+        return Err(CApiError::invalid_argument(format!("{} is null", name)));
+    }
+    CStr::from_ptr(value)
+        .to_str()
+        .map_err(|_| CApiError::invalid_argument(format!("{} is not valid UTF-8", name)))
+}
+
+/// Read a handle argument.
+///
+/// # Safety
+///
+/// The pointer must be null, or a valid handle created by the C interface.
+pub(crate) unsafe fn handle_arg<'a, T>(name: &str, value: *const T) -> Result<&'a T, CApiError> {
+    value
+        .as_ref()
+        .ok_or_else(|| CApiError::invalid_argument(format!("{} is null", name)))
+}
+
+/// Read a handle argument changed by the function, or an output argument.
+///
+/// # Safety
+///
+/// The pointer must be null, or valid and not used by another thread during the call.
+pub(crate) unsafe fn handle_arg_mut<'a, T>(
+    name: &str,
+    value: *mut T,
+) -> Result<&'a mut T, CApiError> {
+    value
+        .as_mut()
+        .ok_or_else(|| CApiError::invalid_argument(format!("{} is null", name)))
+}
+
+/// Write a query result into the arrow C stream of the caller.
+///
+/// # Safety
+///
+/// The stream must be null, or point to memory writable as a stream.
+pub(crate) unsafe fn export_result(
+    result: QueryResult,
+    runtime: Arc<Runtime>,
+    out_stream: *mut FFI_ArrowArrayStream,
+) -> Result<(), CApiError> {
+    if out_stream.is_null() {
+        return Err(CApiError::invalid_argument("out_stream is null"));
+    }
+    let reader = RuntimeReader {
+        reader: QueryResultReader::new(result),
+        _runtime: runtime,
+    };
+    std::ptr::write(out_stream, FFI_ArrowArrayStream::new(Box::new(reader)));
+    Ok(())
+}
+
+/// The reader of an exported result, it keeps the runtime executing the query alive until the
+/// stream is released, even when the database is closed first.
+struct RuntimeReader {
+    reader: QueryResultReader,
+    _runtime: Arc<Runtime>,
+}
+
+impl Iterator for RuntimeReader {
+    type Item = Result<RecordBatch, ArrowError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.reader.next()
+    }
+}
+
+impl RecordBatchReader for RuntimeReader {
+    fn schema(&self) -> SchemaRef {
+        self.reader.schema()
+    }
+}
@@ -0,0 +1,259 @@
+use std::os::raw::c_char;
+use std::sync::Arc;
+
+use arrow::ffi_stream::FFI_ArrowArrayStream;
+use tokio::runtime::Runtime;
+
+use super::{
+    export_result, ffi_call, handle_arg, handle_arg_mut, str_arg, CApiError, SqlrsCode,
+    SqlrsConnection, SqlrsError,
+};
+use crate::main_entry::PreparedStatement;
+use crate::types_v2::ScalarValue;
+
+/// A statement prepared by `sqlrs_prepare`, with the values bound to its parameters.
+pub struct SqlrsPreparedStatement {
+    statement: PreparedStatement,
+    runtime: Arc<Runtime>,
+    /// The values of the parameters, the first one is the value of `$1`
+    values: Vec<Option<ScalarValue>>,
+}
+
+impl SqlrsPreparedStatement {
+    fn bind(&mut self, index: u64, value: ScalarValue) -> Result<(), CApiError> {
+        let count = self.values.len();
+        match (index as usize)
+            .checked_sub(1)
+            .and_then(|i| self.values.get_mut(i))
+        {
+            Some(slot) => {
+                *slot = Some(value);
+                Ok(())
+            }
+            None => Err(CApiError::invalid_argument(format!(
+                "parameter index {} is out of range 1..={}",
+                index, count
+            ))),
+        }
+    }
+}
+
+/// Prepare a statement with `$1` or `?` parameters, its values are bound by the `sqlrs_bind_*`
+/// functions before it's executed.
+///
+/// # Safety
+///
+/// The connection must be a connection created by `sqlrs_connect`, the sql a nul terminated
+/// string, and `out_statement` must point to memory writable as a pointer. The statement is
+/// released by `sqlrs_destroy_prepared`.
+#[no_mangle]
+pub unsafe extern "C" fn sqlrs_prepare(
+    connection: *mut SqlrsConnection,
+    sql: *const c_char,
+    out_statement: *mut *mut SqlrsPreparedStatement,
+    error: *mut SqlrsError,
+) -> SqlrsCode {
+    ffi_call(error, || {
+        let connection = handle_arg("connection", connection)?;
+        let sql = str_arg("sql", sql)?;
+        let out_statement = handle_arg_mut("out_statement", out_statement)?;
+        let statement = connection.connection.prepare(sql)?;
+        let values = vec![None; statement.parameter_types().len()];
+        *out_statement = Box::into_raw(Box::new(SqlrsPreparedStatement {
+            statement,
+            runtime: connection.runtime.clone(),
+            values,
+        }));
+        Ok(())
+    })
+}
+
+/// The number of parameters of a prepared statement, or 0 when the statement is null.
+///
+/// # Safety
+///
+/// The statement must be null, or a statement created by `sqlrs_prepare`.
+#[no_mangle]
+pub unsafe extern "C" fn sqlrs_parameter_count(statement: *mut SqlrsPreparedStatement) -> u64 {
+    statement.as_ref().map_or(0, |s| s.values.len() as u64)
+}
+
+/// Bind a boolean to the parameter at the index, `$1` has the index 1.
+///
+/// # Safety
+///
+/// The statement must be a statement created by `sqlrs_prepare`, and not used by another thread
+/// during the call.
+#[no_mangle]
+pub unsafe extern "C" fn sqlrs_bind_boolean(
+    statement: *mut SqlrsPreparedStatement,
+    index: u64,
+    value: bool,
+    error: *mut SqlrsError,
+) -> SqlrsCode {
+    bind_value(statement, index, ScalarValue::Boolean(Some(value)), error)
+}
+
+/// Bind an integer to the parameter at the index, `$1` has the index 1.
+///
+/// # Safety
+///
+/// The statement must be a statement created by `sqlrs_prepare`, and not used by another thread
+/// during the call.
+#[no_mangle]
+pub unsafe extern "C" fn sqlrs_bind_int32(
+    statement: *mut SqlrsPreparedStatement,
+    index: u64,
+    value: i32,
+    error: *mut SqlrsError,
+) -> SqlrsCode {
+    bind_value(statement, index, ScalarValue::Int32(Some(value)), error)
+}
+
+/// Bind a bigint to the parameter at the index, `$1` has the index 1.
+///
+/// # Safety
+///
+/// The statement must be a statement created by `sqlrs_prepare`, and not used by another thread
+/// during the call.
+#[no_mangle]
+pub unsafe extern "C" fn sqlrs_bind_int64(
+    statement: *mut SqlrsPreparedStatement,
+    index: u64,
+    value: i64,
+    error: *mut SqlrsError,
+) -> SqlrsCode {
+    bind_value(statement, index, ScalarValue::Int64(Some(value)), error)
+}
+
+/// Bind a double to the parameter at the index, `$1` has the index 1.
+///
+/// # Safety
+///
+/// The statement must be a statement created by `sqlrs_prepare`, and not used by another thread
+/// during the call.
+#[no_mangle]
+pub unsafe extern "C" fn sqlrs_bind_double(
+    statement: *mut SqlrsPreparedStatement,
+    index: u64,
+    value: f64,
+    error: *mut SqlrsError,
+) -> SqlrsCode {
+    bind_value(statement, index, ScalarValue::Float64(Some(value)), error)
+}
+
+/// Bind the value to the parameter at the index, `$1` has the index 1.
+///
+/// # Safety
+///
+/// The statement must be a statement created by `sqlrs_prepare`, and not used by another thread
+/// during the call.
+unsafe fn bind_value(
+    statement: *mut SqlrsPreparedStatement,
+    index: u64,
+    value: ScalarValue,
+    error: *mut SqlrsError,
+) -> SqlrsCode {
+    ffi_call(error, || {
+        let statement = handle_arg_mut("statement", statement)?;
+        statement.bind(index, value)
+    })
+}
+
+/// Bind a nul terminated string to the parameter at the index, `$1` has the index 1. The string
+/// is copied.
+///
+/// # Safety
+///
+/// The statement must be a statement created by `sqlrs_prepare`, and not used by another thread
+/// during the call. The value must be a nul terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn sqlrs_bind_varchar(
+    statement: *mut SqlrsPreparedStatement,
+    index: u64,
+    value: *const c_char,
+    error: *mut SqlrsError,
+) -> SqlrsCode {
+    match str_arg("value", value) {
+        Ok(value) => bind_value(
+            statement,
+            index,
+            ScalarValue::Utf8(Some(value.to_string())),
+            error,
+        ),
+        Err(e) => ffi_call(error, || Err(e)),
+    }
+}
+
+/// Bind NULL to the parameter at the index, `$1` has the index 1.
+///
+/// # Safety
+///
+/// The statement must be a statement created by `sqlrs_prepare`, and not used by another thread
+/// during the call.
+#[no_mangle]
+pub unsafe extern "C" fn sqlrs_bind_null(
+    statement: *mut SqlrsPreparedStatement,
+    index: u64,
+    error: *mut SqlrsError,
+) -> SqlrsCode {
+    bind_value(statement, index, ScalarValue::Null, error)
+}
+
+/// Clear the values bound to the parameters of a prepared statement.
+///
+/// # Safety
+///
+/// The statement must be null, or a statement created by `sqlrs_prepare` and not used by
+/// another thread during the call.
+#[no_mangle]
+pub unsafe extern "C" fn sqlrs_clear_bindings(statement: *mut SqlrsPreparedStatement) {
+    if let Some(statement) = statement.as_mut() {
+        statement.values.iter_mut().for_each(|v| *v = None);
+    }
+}
+
+/// Execute a prepared statement with the values bound to its parameters, its result is written
+/// into `out_stream` like the result of `sqlrs_query`. The values stay bound for the next
+/// executions.
+///
+/// # Safety
+///
+/// The statement must be a statement created by `sqlrs_prepare`, and `out_stream` must point to
+/// memory writable as an `ArrowArrayStream`.
+#[no_mangle]
+pub unsafe extern "C" fn sqlrs_execute_prepared(
+    statement: *mut SqlrsPreparedStatement,
+    out_stream: *mut FFI_ArrowArrayStream,
+    error: *mut SqlrsError,
+) -> SqlrsCode {
+    ffi_call(error, || {
+        let statement = handle_arg("statement", statement)?;
+        let values = statement
+            .values
+            .iter()
+            .enumerate()
+            .map(|(i, value)| {
+                value.clone().ok_or_else(|| {
+                    CApiError::invalid_argument(format!("parameter ${} is not bound", i + 1))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let result = statement
+            .runtime
+            .block_on(statement.statement.execute(values))?;
+        export_result(result, statement.runtime.clone(), out_stream)
+    })
+}
+
+/// Release a prepared statement.
+///
+/// # Safety
+///
+/// The statement must be null, or a statement created by `sqlrs_prepare` and not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn sqlrs_destroy_prepared(statement: *mut SqlrsPreparedStatement) {
+    if !statement.is_null() {
+        drop(Box::from_raw(statement));
+    }
+}
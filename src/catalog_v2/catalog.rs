@@ -1,9 +1,9 @@
-use std::sync::Arc;
+use std::sync::{Arc, RwLockReadGuard, RwLockWriteGuard};
 
 use super::entry::{CatalogEntry, DataTable};
 use super::{
     CatalogError, CatalogSet, ScalarFunctionCatalogEntry, TableCatalogEntry,
-    TableFunctionCatalogEntry,
+    TableFunctionCatalogEntry, TableStatistics,
 };
 use crate::common::{CreateScalarFunctionInfo, CreateTableFunctionInfo};
 use crate::main_entry::ClientContext;
@@ -24,16 +24,18 @@ impl Catalog {
         self.schemas.create_entry(name, entry)
     }
 
+    /// The version of the catalog, the plans bound before a change of the catalog are stale.
+    pub fn catalog_version(client_context: Arc<ClientContext>) -> Result<usize, CatalogError> {
+        Ok(Self::read(&client_context)?.catalog_version)
+    }
+
     pub fn create_table(
         client_context: Arc<ClientContext>,
         schema: String,
         table: String,
         data_table: DataTable,
     ) -> Result<(), CatalogError> {
-        let mut catalog = match client_context.db.catalog.try_write() {
-            Ok(c) => c,
-            Err(_) => return Err(CatalogError::CatalogLockedError),
-        };
+        let mut catalog = Self::write(&client_context)?;
         if let CatalogEntry::SchemaCatalogEntry(mut entry) =
             catalog.schemas.get_entry(schema.clone())?
         {
@@ -47,29 +49,62 @@ impl Catalog {
         Err(CatalogError::CatalogEntryTypeNotMatch)
     }
 
+    /// Drop the table from the catalog, returns its storage so the data can be dropped too.
+    pub fn drop_table(
+        client_context: Arc<ClientContext>,
+        schema: String,
+        table: String,
+    ) -> Result<DataTable, CatalogError> {
+        let mut catalog = Self::write(&client_context)?;
+        if let CatalogEntry::SchemaCatalogEntry(mut entry) =
+            catalog.schemas.get_entry(schema.clone())?
+        {
+            catalog.catalog_version += 1;
+            let table = entry.drop_table(table)?;
+            catalog
+                .schemas
+                .replace_entry(schema, CatalogEntry::SchemaCatalogEntry(entry))?;
+            return Ok(table.storage);
+        }
+        Err(CatalogError::CatalogEntryTypeNotMatch)
+    }
+
     pub fn get_table(
         client_context: Arc<ClientContext>,
         schema: String,
         table: String,
     ) -> Result<TableCatalogEntry, CatalogError> {
-        let catalog = match client_context.db.catalog.try_read() {
-            Ok(c) => c,
-            Err(_) => return Err(CatalogError::CatalogLockedError),
-        };
+        let catalog = Self::read(&client_context)?;
         if let CatalogEntry::SchemaCatalogEntry(entry) = catalog.schemas.get_entry(schema)? {
             return entry.get_table(table);
         }
         Err(CatalogError::CatalogEntryTypeNotMatch)
     }
 
+    /// Update the statistics of a table in place, returns the result of the callback. Statistics
+    /// are not part of the catalog version, so this does not bump it.
+    pub fn update_table_statistics<F, R>(
+        client_context: Arc<ClientContext>,
+        schema: String,
+        table: String,
+        callback: F,
+    ) -> Result<R, CatalogError>
+    where
+        F: FnOnce(&mut TableStatistics) -> R,
+    {
+        let mut catalog = Self::write(&client_context)?;
+        if let CatalogEntry::SchemaCatalogEntry(entry) = catalog.schemas.get_mut_entry(schema)? {
+            let table = entry.get_mut_table(table)?;
+            return Ok(callback(&mut table.stats));
+        }
+        Err(CatalogError::CatalogEntryTypeNotMatch)
+    }
+
     pub fn create_table_function(
         client_context: Arc<ClientContext>,
         info: CreateTableFunctionInfo,
     ) -> Result<(), CatalogError> {
-        let mut catalog = match client_context.db.catalog.try_write() {
-            Ok(c) => c,
-            Err(_) => return Err(CatalogError::CatalogLockedError),
-        };
+        let mut catalog = Self::write(&client_context)?;
         if let CatalogEntry::SchemaCatalogEntry(mut entry) =
             catalog.schemas.get_entry(info.base.schema.clone())?
         {
@@ -92,25 +127,35 @@ impl Catalog {
     where
         F: Fn(&CatalogEntry) -> bool,
     {
-        let catalog = match client_context.db.catalog.try_read() {
-            Ok(c) => c,
-            Err(_) => return Err(CatalogError::CatalogLockedError),
-        };
+        let catalog = Self::read(&client_context)?;
         if let CatalogEntry::SchemaCatalogEntry(entry) = catalog.schemas.get_entry(schema)? {
             return Ok(entry.scan_entries(callback));
         }
         Err(CatalogError::CatalogEntryTypeNotMatch)
     }
 
+    /// The names of the schemas of the catalog, in the order of their names.
+    pub fn schema_names(client_context: Arc<ClientContext>) -> Result<Vec<String>, CatalogError> {
+        let catalog = Self::read(&client_context)?;
+        let mut names = catalog
+            .schemas
+            .scan_entries(&|entry| matches!(entry, CatalogEntry::SchemaCatalogEntry(_)))
+            .into_iter()
+            .filter_map(|entry| match entry {
+                CatalogEntry::SchemaCatalogEntry(schema) => Some(schema.base.name),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        names.sort();
+        Ok(names)
+    }
+
     pub fn get_table_function(
         client_context: Arc<ClientContext>,
         schema: String,
         table_function: String,
     ) -> Result<TableFunctionCatalogEntry, CatalogError> {
-        let catalog = match client_context.db.catalog.try_read() {
-            Ok(c) => c,
-            Err(_) => return Err(CatalogError::CatalogLockedError),
-        };
+        let catalog = Self::read(&client_context)?;
         if let CatalogEntry::SchemaCatalogEntry(entry) = catalog.schemas.get_entry(schema)? {
             return entry.get_table_function(table_function);
         }
@@ -121,10 +166,7 @@ impl Catalog {
         client_context: Arc<ClientContext>,
         info: CreateScalarFunctionInfo,
     ) -> Result<(), CatalogError> {
-        let mut catalog = match client_context.db.catalog.try_write() {
-            Ok(c) => c,
-            Err(_) => return Err(CatalogError::CatalogLockedError),
-        };
+        let mut catalog = Self::write(&client_context)?;
         let version = catalog.catalog_version;
         let entry = catalog.schemas.get_mut_entry(info.base.schema.clone())?;
 
@@ -142,13 +184,31 @@ impl Catalog {
         schema: String,
         scalar_function: String,
     ) -> Result<ScalarFunctionCatalogEntry, CatalogError> {
-        let catalog = match client_context.db.catalog.try_read() {
-            Ok(c) => c,
-            Err(_) => return Err(CatalogError::CatalogLockedError),
-        };
+        let catalog = Self::read(&client_context)?;
         if let CatalogEntry::SchemaCatalogEntry(entry) = catalog.schemas.get_entry(schema)? {
             return entry.get_scalar_function(scalar_function);
         }
         Err(CatalogError::CatalogEntryTypeNotMatch)
     }
+
+    /// The catalog is locked only while its entries are read or changed, so the lock is waited
+    /// for instead of failing the query when another query holds it. A query locking both the
+    /// catalog and the storage locks the catalog first.
+    fn read(client_context: &ClientContext) -> Result<RwLockReadGuard<'_, Catalog>, CatalogError> {
+        client_context
+            .db
+            .catalog
+            .read()
+            .map_err(|_| CatalogError::CatalogPoisonedError)
+    }
+
+    fn write(
+        client_context: &ClientContext,
+    ) -> Result<RwLockWriteGuard<'_, Catalog>, CatalogError> {
+        client_context
+            .db
+            .catalog
+            .write()
+            .map_err(|_| CatalogError::CatalogPoisonedError)
+    }
 }
@@ -56,6 +56,15 @@ impl CatalogSet {
         Err(CatalogError::CatalogEntryNotExists(name))
     }
 
+    pub fn drop_entry(&mut self, name: String) -> Result<CatalogEntry, CatalogError> {
+        if let Some(index) = self.mapping.remove(&name) {
+            if let Some(entry) = self.entries.remove(&index) {
+                return Ok(entry);
+            }
+        }
+        Err(CatalogError::CatalogEntryNotExists(name))
+    }
+
     pub fn scan_entries<F>(&self, callback: &F) -> Vec<CatalogEntry>
     where
         F: Fn(&CatalogEntry) -> bool,
@@ -8,7 +8,7 @@ use crate::common::{CreateScalarFunctionInfo, CreateTableFunctionInfo};
 #[allow(dead_code)]
 #[derive(Clone, Debug)]
 pub struct SchemaCatalogEntry {
-    base: CatalogEntryBase,
+    pub(crate) base: CatalogEntryBase,
     tables: CatalogSet,
     functions: CatalogSet,
 }
@@ -45,6 +45,19 @@ impl SchemaCatalogEntry {
         }
     }
 
+    pub fn get_mut_table(&mut self, table: String) -> Result<&mut TableCatalogEntry, CatalogError> {
+        match self.tables.get_mut_entry(table.clone())? {
+            CatalogEntry::TableCatalogEntry(e) => Ok(e),
+            _ => Err(CatalogError::CatalogEntryNotExists(table)),
+        }
+    }
+
+    pub fn drop_table(&mut self, table: String) -> Result<TableCatalogEntry, CatalogError> {
+        let entry = self.get_table(table.clone())?;
+        self.tables.drop_entry(table)?;
+        Ok(entry)
+    }
+
     pub fn create_table_function(
         &mut self,
         oid: usize,
@@ -1,8 +1,10 @@
 use std::collections::HashMap;
 
+use arrow::record_batch::RecordBatch;
 use derive_new::new;
 
 use super::CatalogEntryBase;
+use crate::catalog_v2::{CatalogError, TableStatistics};
 use crate::types_v2::LogicalType;
 
 #[allow(dead_code)]
@@ -15,6 +17,8 @@ pub struct TableCatalogEntry {
     pub(crate) columns: Vec<ColumnDefinition>,
     /// A map of column name to column index
     pub(crate) name_map: HashMap<String, usize>,
+    /// The statistics of the table, used for cardinality estimation
+    pub(crate) stats: TableStatistics,
 }
 
 impl TableCatalogEntry {
@@ -34,12 +38,14 @@ impl TableCatalogEntry {
                 columns.push(col.clone());
                 name_map.insert(col.name.clone(), idx);
             });
+        let stats = TableStatistics::new(columns.len());
         Self {
             base: CatalogEntryBase::new(oid, table),
             schema_base,
             storage,
             columns,
             name_map,
+            stats,
         }
     }
 }
@@ -53,6 +59,21 @@ pub struct DataTable {
     pub(crate) column_definitions: Vec<ColumnDefinition>,
 }
 
+impl DataTable {
+    /// Check the rows appended to the table satisfy the constraints of its columns.
+    pub fn verify_append_constraints(&self, batch: &RecordBatch) -> Result<(), CatalogError> {
+        for (column, array) in self.column_definitions.iter().zip(batch.columns()) {
+            if column.not_null && array.null_count() > 0 {
+                return Err(CatalogError::NotNullConstraintViolation(format!(
+                    "{}.{}",
+                    self.info.table, column.name
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
 #[derive(new, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct DataTableInfo {
     /// schema of the table
@@ -68,4 +89,7 @@ pub struct ColumnDefinition {
     pub(crate) name: String,
     /// The type of the column
     pub(crate) ty: LogicalType,
+    /// Whether the column has a NOT NULL constraint
+    #[new(default)]
+    pub(crate) not_null: bool,
 }
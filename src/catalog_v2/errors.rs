@@ -6,6 +6,8 @@ pub enum CatalogError {
     CatalogEntryNotExists(String),
     #[error("CatalogEntry type not match")]
     CatalogEntryTypeNotMatch,
-    #[error("Catalog locked, please retry")]
-    CatalogLockedError,
+    #[error("Catalog lock poisoned")]
+    CatalogPoisonedError,
+    #[error("NOT NULL constraint failed: {0}")]
+    NotNullConstraintViolation(String),
 }
@@ -3,9 +3,11 @@ mod catalog_set;
 mod constants;
 mod entry;
 mod errors;
+mod statistics;
 
 pub use catalog::*;
 pub use catalog_set::*;
 pub use constants::*;
 pub use entry::*;
 pub use errors::*;
+pub use statistics::*;
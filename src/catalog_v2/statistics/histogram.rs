@@ -0,0 +1,141 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use derive_new::new;
+
+use crate::types_v2::ScalarValue;
+
+/// The number of buckets of the histograms built by ANALYZE.
+pub const DEFAULT_HISTOGRAM_BUCKETS: usize = 16;
+
+#[derive(new, Clone, Debug)]
+pub struct HistogramBucket {
+    /// The largest value of the bucket, inclusive
+    pub(crate) upper: ScalarValue,
+    /// The number of values in the bucket
+    pub(crate) count: usize,
+    /// The number of distinct values in the bucket
+    pub(crate) distinct_count: usize,
+}
+
+/// An equi-depth histogram, each bucket holds roughly the same number of values. A value never
+/// spans two buckets, so the buckets of skewed columns can be larger.
+#[derive(Clone, Debug)]
+pub struct Histogram {
+    /// The smallest value of the first bucket
+    pub(crate) min: ScalarValue,
+    pub(crate) buckets: Vec<HistogramBucket>,
+}
+
+impl Histogram {
+    /// Build a histogram of the non-null values, returns None if there are no values.
+    pub fn build(mut values: Vec<ScalarValue>, bucket_count: usize) -> Option<Self> {
+        if values.is_empty() || bucket_count == 0 {
+            return None;
+        }
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+        let depth = (values.len() + bucket_count - 1) / bucket_count;
+        let mut buckets = vec![];
+        let mut start = 0;
+        while start < values.len() {
+            let mut end = (start + depth).min(values.len());
+            // keep equal values in the same bucket
+            while end < values.len() && values[end] == values[end - 1] {
+                end += 1;
+            }
+            let bucket = &values[start..end];
+            let distinct_count = 1 + bucket.windows(2).filter(|w| w[0] != w[1]).count();
+            buckets.push(HistogramBucket::new(
+                bucket[bucket.len() - 1].clone(),
+                bucket.len(),
+                distinct_count,
+            ));
+            start = end;
+        }
+        Some(Self {
+            min: values[0].clone(),
+            buckets,
+        })
+    }
+
+    pub fn total_count(&self) -> usize {
+        self.buckets.iter().map(|b| b.count).sum()
+    }
+
+    /// Estimate the fraction of values smaller than (or equal to, if inclusive) the value.
+    /// Values are assumed to be spread evenly over the distinct values of a bucket. Returns None
+    /// if the value can not be compared with the values of the histogram.
+    pub fn fraction_less_than(&self, value: &ScalarValue, inclusive: bool) -> Option<f64> {
+        let total = self.total_count() as f64;
+        match value.partial_cmp(&self.min)? {
+            Ordering::Less => return Some(0.0),
+            Ordering::Equal if !inclusive => return Some(0.0),
+            _ => {}
+        }
+        let mut count = 0.0;
+        for bucket in self.buckets.iter() {
+            match value.partial_cmp(&bucket.upper)? {
+                Ordering::Greater => count += bucket.count as f64,
+                Ordering::Equal => {
+                    let per_value = bucket.count as f64 / bucket.distinct_count as f64;
+                    count += bucket.count as f64 - if inclusive { 0.0 } else { per_value };
+                    break;
+                }
+                Ordering::Less => {
+                    // the value is somewhere inside the bucket
+                    count += bucket.count as f64 / 2.0;
+                    break;
+                }
+            }
+        }
+        Some(count / total)
+    }
+}
+
+impl fmt::Display for Histogram {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut lower = format!("[{}", self.min);
+        let buckets = self
+            .buckets
+            .iter()
+            .map(|b| {
+                let bucket = format!("{}, {}]: {}", lower, b.upper, b.count);
+                lower = format!("({}", b.upper);
+                bucket
+            })
+            .collect::<Vec<_>>();
+        write!(f, "{}", buckets.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_build() {
+        let values = vec![5, 1, 2, 2, 2, 3, 4, 6]
+            .into_iter()
+            .map(|v| ScalarValue::Int32(Some(v)))
+            .collect::<Vec<_>>();
+        let histogram = Histogram::build(values, 4).unwrap();
+        assert_eq!(histogram.total_count(), 8);
+        assert_eq!(
+            histogram.to_string(),
+            "[1, 2]: 4, (2, 4]: 2, (4, 6]: 2".to_string()
+        );
+
+        let less_than = |v: i32, inclusive: bool| {
+            histogram
+                .fraction_less_than(&ScalarValue::Int32(Some(v)), inclusive)
+                .unwrap()
+        };
+        assert_eq!(less_than(0, true), 0.0);
+        assert_eq!(less_than(1, false), 0.0);
+        assert_eq!(less_than(2, true), 0.5);
+        assert_eq!(less_than(6, true), 1.0);
+        assert!(histogram
+            .fraction_less_than(&ScalarValue::Utf8(None), true)
+            .is_none());
+    }
+}
@@ -0,0 +1,94 @@
+use std::hash::{BuildHasher, Hash, Hasher};
+
+/// The number of bits of the hash used to select a register.
+const HLL_PRECISION: u32 = 12;
+/// The number of registers, 4096 registers give a standard error of about 1.6%.
+const HLL_REGISTER_COUNT: usize = 1 << HLL_PRECISION;
+
+/// HyperLogLog is a sketch estimating the number of distinct values of a column with a fixed
+/// amount of memory. Sketches can be merged, so they can be kept current when rows are appended.
+#[derive(Clone, Debug)]
+pub struct HyperLogLog {
+    registers: Vec<u8>,
+}
+
+impl Default for HyperLogLog {
+    fn default() -> Self {
+        Self {
+            registers: vec![0; HLL_REGISTER_COUNT],
+        }
+    }
+}
+
+impl HyperLogLog {
+    fn hash<T: Hash>(value: &T) -> u64 {
+        // fixed seeds, so sketches built by different inserts can be merged
+        let state = ahash::RandomState::with_seeds(
+            0x243f_6a88_85a3_08d3,
+            0x1319_8a2e_0370_7344,
+            0xa409_3822_299f_31d0,
+            0x082e_fa98_ec4e_6c89,
+        );
+        let mut hasher = state.build_hasher();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    pub fn add<T: Hash>(&mut self, value: &T) {
+        let hash = Self::hash(value);
+        let index = (hash >> (u64::BITS - HLL_PRECISION)) as usize;
+        // the position of the first set bit of the remaining bits, the sentinel bit bounds it
+        let remaining = (hash << HLL_PRECISION) | (1 << (HLL_PRECISION - 1));
+        let rank = remaining.leading_zeros() as u8 + 1;
+        self.registers[index] = self.registers[index].max(rank);
+    }
+
+    pub fn merge(&mut self, other: &HyperLogLog) {
+        for (register, other) in self.registers.iter_mut().zip(other.registers.iter()) {
+            *register = (*register).max(*other);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.registers.iter().all(|r| *r == 0)
+    }
+
+    pub fn estimate(&self) -> usize {
+        let m = HLL_REGISTER_COUNT as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum: f64 = self.registers.iter().map(|r| 2f64.powi(-(*r as i32))).sum();
+        let raw_estimate = alpha * m * m / sum;
+        let zeros = self.registers.iter().filter(|r| **r == 0).count();
+        let estimate = if raw_estimate <= 2.5 * m && zeros > 0 {
+            // small range correction: linear counting
+            m * (m / zeros as f64).ln()
+        } else {
+            raw_estimate
+        };
+        estimate.round() as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hyper_log_log_estimate() {
+        let mut hll = HyperLogLog::default();
+        assert!(hll.is_empty());
+        for i in 0..10000 {
+            hll.add(&(i % 1000));
+        }
+        let estimate = hll.estimate() as f64;
+        assert!((estimate - 1000.0).abs() / 1000.0 < 0.05);
+
+        let mut other = HyperLogLog::default();
+        for i in 500..1500 {
+            other.add(&i);
+        }
+        hll.merge(&other);
+        let estimate = hll.estimate() as f64;
+        assert!((estimate - 1500.0).abs() / 1500.0 < 0.05);
+    }
+}
@@ -0,0 +1,191 @@
+mod histogram;
+mod hyper_log_log;
+
+use std::hash::Hash;
+
+use arrow::array::{as_boolean_array, as_primitive_array, as_string_array, ArrayRef};
+use arrow::datatypes::{
+    DataType, Date32Type, Float32Type, Float64Type, Int16Type, Int32Type, Int64Type, Int8Type,
+    IntervalUnit, UInt16Type, UInt32Type, UInt64Type, UInt8Type,
+};
+use arrow::record_batch::RecordBatch;
+pub use histogram::*;
+pub use hyper_log_log::*;
+
+use crate::types_v2::{ScalarValue, TypeError};
+
+/// The statistics of a table, used by the optimizer to estimate cardinalities. The row count,
+/// null counts and distinct sketches are kept current on insert, the histograms are only rebuilt
+/// by ANALYZE.
+#[derive(Clone, Debug, Default)]
+pub struct TableStatistics {
+    /// The number of rows in the table
+    pub(crate) row_count: usize,
+    /// The statistics of each column, indexed by the column index of the table
+    pub(crate) column_stats: Vec<ColumnStatistics>,
+}
+
+impl TableStatistics {
+    pub fn new(column_count: usize) -> Self {
+        Self {
+            row_count: 0,
+            column_stats: vec![ColumnStatistics::default(); column_count],
+        }
+    }
+
+    /// Compute the statistics of all the rows of a table, including the histograms. The columns
+    /// whose values can't be read as scalar values get no histogram.
+    pub fn analyze(column_count: usize, batches: &[RecordBatch]) -> Result<Self, TypeError> {
+        let mut stats = Self::new(column_count);
+        for batch in batches.iter() {
+            stats.update(batch)?;
+        }
+        for (idx, column_stats) in stats.column_stats.iter_mut().enumerate() {
+            let mut values = vec![];
+            for batch in batches.iter() {
+                let array = batch.column(idx);
+                if !has_scalar_values(array.data_type()) {
+                    continue;
+                }
+                for row in 0..array.len() {
+                    if !array.is_null(row) {
+                        values.push(ScalarValue::try_from_array(array, row)?);
+                    }
+                }
+            }
+            column_stats.histogram = Histogram::build(values, DEFAULT_HISTOGRAM_BUCKETS);
+        }
+        Ok(stats)
+    }
+
+    /// Add the rows of the batch to the statistics.
+    pub fn update(&mut self, batch: &RecordBatch) -> Result<(), TypeError> {
+        self.row_count += batch.num_rows();
+        for (column_stats, array) in self.column_stats.iter_mut().zip(batch.columns()) {
+            column_stats.update(array)?;
+        }
+        Ok(())
+    }
+
+    /// Merge the statistics of newly appended rows.
+    pub fn merge(&mut self, other: &TableStatistics) {
+        self.row_count += other.row_count;
+        for (column_stats, other) in self.column_stats.iter_mut().zip(other.column_stats.iter()) {
+            column_stats.merge(other);
+        }
+    }
+
+    /// The estimated number of distinct values of the column, None if it is unknown.
+    pub fn distinct_count(&self, column_idx: usize) -> Option<usize> {
+        self.column_stats
+            .get(column_idx)
+            .and_then(|s| s.distinct_count())
+            .map(|c| c.min(self.row_count))
+    }
+
+    /// The fraction of NULL values of the column.
+    pub fn null_fraction(&self, column_idx: usize) -> f64 {
+        match self.column_stats.get(column_idx) {
+            Some(s) if self.row_count > 0 => s.null_count as f64 / self.row_count as f64,
+            _ => 0.0,
+        }
+    }
+
+    pub fn histogram(&self, column_idx: usize) -> Option<&Histogram> {
+        self.column_stats
+            .get(column_idx)
+            .and_then(|s| s.histogram.as_ref())
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ColumnStatistics {
+    /// The number of NULL values
+    pub(crate) null_count: usize,
+    /// The sketch of the distinct non-null values
+    pub(crate) distinct: HyperLogLog,
+    /// The histogram of the non-null values, None until the table is analyzed
+    pub(crate) histogram: Option<Histogram>,
+}
+
+impl ColumnStatistics {
+    /// Add the values of the array. The values of the primitive and string arrays are hashed
+    /// as they are stored in the array, the other values are hashed as scalar values. Only the
+    /// NULLs of the types without scalar values, such as structs and lists, are counted.
+    pub fn update(&mut self, array: &ArrayRef) -> Result<(), TypeError> {
+        self.null_count += array.null_count();
+        match array.data_type() {
+            DataType::Boolean => self.add_values(as_boolean_array(array).iter()),
+            DataType::Int8 => self.add_values(as_primitive_array::<Int8Type>(array).iter()),
+            DataType::Int16 => self.add_values(as_primitive_array::<Int16Type>(array).iter()),
+            DataType::Int32 => self.add_values(as_primitive_array::<Int32Type>(array).iter()),
+            DataType::Int64 => self.add_values(as_primitive_array::<Int64Type>(array).iter()),
+            DataType::UInt8 => self.add_values(as_primitive_array::<UInt8Type>(array).iter()),
+            DataType::UInt16 => self.add_values(as_primitive_array::<UInt16Type>(array).iter()),
+            DataType::UInt32 => self.add_values(as_primitive_array::<UInt32Type>(array).iter()),
+            DataType::UInt64 => self.add_values(as_primitive_array::<UInt64Type>(array).iter()),
+            DataType::Date32 => self.add_values(as_primitive_array::<Date32Type>(array).iter()),
+            // floats are hashed by their bits
+            DataType::Float32 => {
+                let values = as_primitive_array::<Float32Type>(array).iter();
+                self.add_values(values.map(|v| v.map(f32::to_bits)))
+            }
+            DataType::Float64 => {
+                let values = as_primitive_array::<Float64Type>(array).iter();
+                self.add_values(values.map(|v| v.map(f64::to_bits)))
+            }
+            DataType::Utf8 => self.add_values(as_string_array(array).iter()),
+            data_type if has_scalar_values(data_type) => {
+                for row in 0..array.len() {
+                    if !array.is_null(row) {
+                        self.distinct.add(&ScalarValue::try_from_array(array, row)?);
+                    }
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn add_values<T: Hash>(&mut self, values: impl Iterator<Item = Option<T>>) {
+        for value in values.flatten() {
+            self.distinct.add(&value);
+        }
+    }
+
+    pub fn merge(&mut self, other: &ColumnStatistics) {
+        self.null_count += other.null_count;
+        self.distinct.merge(&other.distinct);
+    }
+
+    /// The estimated number of distinct non-null values, None if no value has been added yet.
+    pub fn distinct_count(&self) -> Option<usize> {
+        if self.distinct.is_empty() {
+            None
+        } else {
+            Some(self.distinct.estimate())
+        }
+    }
+}
+
+/// Whether the values of the type can be read by `ScalarValue::try_from_array`.
+fn has_scalar_values(data_type: &DataType) -> bool {
+    matches!(
+        data_type,
+        DataType::Null
+            | DataType::Boolean
+            | DataType::Int8
+            | DataType::Int16
+            | DataType::Int32
+            | DataType::Int64
+            | DataType::UInt8
+            | DataType::UInt16
+            | DataType::UInt32
+            | DataType::UInt64
+            | DataType::Float32
+            | DataType::Float64
+            | DataType::Utf8
+            | DataType::Date32
+            | DataType::Interval(IntervalUnit::YearMonth | IntervalUnit::DayTime)
+    )
+}
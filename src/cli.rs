@@ -7,7 +7,7 @@ use rustyline::error::ReadlineError;
 use rustyline::Editor;
 
 use crate::main_entry::ClientContext;
-use crate::util::pretty_batches;
+use crate::util::{pretty_batches, pretty_batches_with};
 use crate::Database;
 
 pub async fn interactive(db: Database, client_context: Arc<ClientContext>) -> Result<()> {
@@ -31,10 +31,7 @@ pub async fn interactive(db: Database, client_context: Arc<ClientContext>) -> Re
                     }
 
                     if enable_v2 {
-                        match client_context.query(sql).await {
-                            Ok(_) => {}
-                            Err(err) => println!("Run Error: {}", err),
-                        }
+                        run_sql_v2(&client_context, &sql).await;
                     } else {
                         run_sql(&db, sql).await?;
                     }
@@ -132,6 +129,30 @@ async fn run_sql(db: &Database, sql: String) -> Result<()> {
     Ok(())
 }
 
+async fn run_sql_v2(client_context: &Arc<ClientContext>, sql: &str) {
+    // Ctrl-C while the query runs cancels the query instead of exiting the shell
+    let cancel_handle = {
+        let client_context = client_context.clone();
+        tokio::spawn(async move {
+            while tokio::signal::ctrl_c().await.is_ok() {
+                println!("Cancelling the query");
+                client_context.interrupt();
+            }
+        })
+    };
+    let result = client_context.run(sql).await;
+    cancel_handle.abort();
+    match result {
+        Ok(results) => {
+            for res in results.iter() {
+                let base = res.base();
+                pretty_batches_with(res.batches(), base.names(), base.types());
+            }
+        }
+        Err(err) => println!("Run Error: {}", err),
+    }
+}
+
 async fn run_internal(db: &Database, cmds: &str) -> Result<()> {
     if cmds.starts_with("load csv") {
         if let Some((table_name, filepath)) = cmds.trim_start_matches("load csv ").split_once(' ') {
@@ -8,11 +8,12 @@ use sqlparser::parser::ParserError;
 use crate::binder::{BindError, Binder};
 use crate::executor::{try_collect, ExecutorBuilder, ExecutorError};
 use crate::optimizer::{
-    CollapseProject, CombineFilter, EliminateLimits, HepBatch, HepBatchStrategy, HepOptimizer,
-    InputRefRewriter, LimitProjectTranspose, PhysicalRewriteRule, PlanRef, PlanRewriter,
-    PushLimitIntoTableScan, PushLimitThroughJoin, PushPredicateThroughJoin,
-    PushPredicateThroughNonJoin, PushProjectIntoTableScan, PushProjectThroughChild,
-    RemoveNoopOperators, SimplifyCasts,
+    CascadesOptimizer, CollapseProject, CombineFilter, EliminateLimits,
+    ExtractJoinConditionToFilter, HepBatch, HepBatchStrategy, HepOptimizer,
+    ImplementPhysicalOperator, InputRefRewriter, LimitProjectTranspose, OptimizerError, PlanRef,
+    PlanRewriter, PushLimitIntoTableScan, PushLimitThroughJoin, PushPredicateIntoCrossJoin,
+    PushPredicateThroughJoin, PushPredicateThroughNonJoin, PushProjectIntoTableScan,
+    PushProjectThroughChild, RemoveNoopOperators, SimplifyCasts,
 };
 use crate::parser::parse;
 use crate::planner::{LogicalPlanError, Planner};
@@ -94,16 +95,31 @@ impl Database {
                 HepBatchStrategy::once_topdown(),
                 vec![SimplifyCasts::create()],
             ),
-            HepBatch::new(
-                "Rewrite physical plan".to_string(),
-                HepBatchStrategy::once_topdown(),
-                vec![PhysicalRewriteRule::create()],
-            ),
         ];
 
         HepOptimizer::new(batches, root)
     }
 
+    /// The rewritten logical plan is implemented by the memo optimizer, which chooses the
+    /// cheapest physical plan among the alternatives.
+    fn default_physical_optimizer(&self, root: PlanRef) -> CascadesOptimizer {
+        CascadesOptimizer::new(
+            vec![
+                PushPredicateIntoCrossJoin::create(),
+                ExtractJoinConditionToFilter::create(),
+            ],
+            vec![ImplementPhysicalOperator::create()],
+            root,
+        )
+    }
+
+    fn optimize(&self, logical_plan: PlanRef) -> Result<PlanRef, OptimizerError> {
+        let mut optimizer = self.default_optimizer(logical_plan);
+        let logical_plan = optimizer.find_best();
+        let mut optimizer = self.default_physical_optimizer(logical_plan);
+        optimizer.find_best()
+    }
+
     pub async fn run(&self, sql: &str) -> Result<Vec<RecordBatch>, DatabaseError> {
         let storage = if let StorageImpl::CsvStorage(ref storage) = self.storage {
             storage
@@ -131,8 +147,7 @@ impl Database {
         );
 
         // 4. optimize logical plan to physical plan
-        let mut optimizer = self.default_optimizer(logical_plan);
-        let physical_plan = optimizer.find_best();
+        let physical_plan = self.optimize(logical_plan)?;
         println!(
             "optimized_plan:\n{}\n",
             pretty_plan_tree_string(&*physical_plan)
@@ -173,8 +188,7 @@ impl Database {
             pretty_plan_tree_string(&*logical_plan)
         );
 
-        let mut optimizer = self.default_optimizer(logical_plan);
-        let physical_plan = optimizer.find_best();
+        let physical_plan = self.optimize(logical_plan)?;
         _ = write!(
             explain_str,
             "optimized plan:\n{}\n",
@@ -205,6 +219,12 @@ pub enum DatabaseError {
         #[from]
         LogicalPlanError,
     ),
+    #[error("optimize error: {0}")]
+    Optimize(
+        #[source]
+        #[from]
+        OptimizerError,
+    ),
     #[error("execute error: {0}")]
     Execute(
         #[source]
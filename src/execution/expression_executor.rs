@@ -74,6 +74,12 @@ impl ExpressionExecutor {
                 }
                 conjunction_result
             }
+            BoundExpression::BoundParameterExpression(e) => {
+                return Err(ExecutorError::InternalError(format!(
+                    "parameter {} has no value",
+                    e.base.alias
+                )))
+            }
         })
     }
 }
@@ -2,32 +2,68 @@ mod column_binding_resolver;
 mod expression_executor;
 mod physical_plan;
 mod physical_plan_generator;
+mod pipeline_executor;
 mod volcano_executor;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::Instant;
 mod util;
 
 use arrow::error::ArrowError;
 pub use column_binding_resolver::*;
-use derive_new::new;
 pub use expression_executor::*;
 pub use physical_plan::*;
 pub use physical_plan_generator::*;
+pub use pipeline_executor::*;
 pub use util::*;
 pub use volcano_executor::*;
 
 use crate::catalog_v2::CatalogError;
 use crate::function::FunctionError;
 use crate::main_entry::ClientContext;
+use crate::storage_v2::StorageError;
 use crate::types_v2::TypeError;
 
 static LOGGING_TARGET: &str = "sqlrs::execution";
 
-#[derive(new)]
 pub struct ExecutionContext {
     pub(crate) client_context: Arc<ClientContext>,
+    /// The query is cancelled when it's still running at the deadline, set by the
+    /// `statement_timeout` option
+    pub(crate) deadline: Option<Instant>,
+    /// Set when the result of the query is dropped before its end
+    cancelled: AtomicBool,
 }
 
 impl ExecutionContext {
+    pub fn new(client_context: Arc<ClientContext>) -> Self {
+        let deadline = client_context
+            .statement_timeout()
+            .map(|timeout| Instant::now() + timeout);
+        Self {
+            client_context,
+            deadline,
+            cancelled: AtomicBool::new(false),
+        }
+    }
+
+    /// Cancel the query, nobody reads its result anymore.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Release);
+    }
+
+    /// Check whether the query was interrupted by the client or ran past its deadline. The
+    /// executors check it between batches, so a running query stops at the next batch.
+    pub fn check_interrupted(&self) -> Result<(), ExecutorError> {
+        if self.client_context.is_interrupted() || self.cancelled.load(Ordering::Acquire) {
+            return Err(ExecutorError::Interrupted);
+        }
+        match self.deadline {
+            Some(deadline) if Instant::now() >= deadline => Err(ExecutorError::StatementTimeout),
+            _ => Ok(()),
+        }
+    }
+
     pub fn clone_client_context(&self) -> Arc<ClientContext> {
         self.client_context.clone()
     }
@@ -59,6 +95,16 @@ pub enum ExecutorError {
         #[from]
         FunctionError,
     ),
+    #[error("storage error: {0}")]
+    StorageError(
+        #[source]
+        #[from]
+        StorageError,
+    ),
+    #[error("canceling statement due to user request")]
+    Interrupted,
+    #[error("canceling statement due to statement timeout")]
+    StatementTimeout,
     #[error("Executor internal error: {0}")]
     InternalError(String),
 }
@@ -1,5 +1,8 @@
+mod physical_analyze;
 mod physical_column_data_scan;
+mod physical_copy_to_file;
 mod physical_create_table;
+mod physical_cross_product;
 mod physical_dummy_scan;
 mod physical_explain;
 mod physical_expression_scan;
@@ -7,11 +10,15 @@ mod physical_filter;
 mod physical_insert;
 mod physical_limit;
 mod physical_projection;
+mod physical_set;
 mod physical_table_scan;
 
 use derive_new::new;
+pub use physical_analyze::*;
 pub use physical_column_data_scan::*;
+pub use physical_copy_to_file::*;
 pub use physical_create_table::*;
+pub use physical_cross_product::*;
 pub use physical_dummy_scan::*;
 pub use physical_explain::*;
 pub use physical_expression_scan::*;
@@ -19,6 +26,7 @@ pub use physical_filter::*;
 pub use physical_insert::*;
 pub use physical_limit::*;
 pub use physical_projection::*;
+pub use physical_set::*;
 pub use physical_table_scan::*;
 
 use crate::planner_v2::BoundExpression;
@@ -41,6 +49,10 @@ pub enum PhysicalOperator {
     PhysicalColumnDataScan(PhysicalColumnDataScan),
     PhysicalFilter(PhysicalFilter),
     PhysicalLimit(PhysicalLimit),
+    PhysicalCrossProduct(PhysicalCrossProduct),
+    PhysicalAnalyze(PhysicalAnalyze),
+    PhysicalSet(PhysicalSet),
+    PhysicalCopyToFile(PhysicalCopyToFile),
 }
 
 impl PhysicalOperator {
@@ -55,6 +67,10 @@ impl PhysicalOperator {
             PhysicalOperator::PhysicalColumnDataScan(op) => &op.base.children,
             PhysicalOperator::PhysicalFilter(op) => &op.base.children,
             PhysicalOperator::PhysicalLimit(op) => &op.base.children,
+            PhysicalOperator::PhysicalCrossProduct(op) => &op.base.children,
+            PhysicalOperator::PhysicalAnalyze(op) => &op.base.children,
+            PhysicalOperator::PhysicalSet(op) => &op.base.children,
+            PhysicalOperator::PhysicalCopyToFile(op) => &op.base.children,
         }
     }
 }
@@ -0,0 +1,19 @@
+use derive_new::new;
+
+use super::{PhysicalOperator, PhysicalOperatorBase};
+use crate::catalog_v2::TableCatalogEntry;
+use crate::execution::PhysicalPlanGenerator;
+use crate::planner_v2::LogicalAnalyze;
+
+#[derive(new, Clone)]
+pub struct PhysicalAnalyze {
+    #[new(default)]
+    pub(crate) base: PhysicalOperatorBase,
+    pub(crate) tables: Vec<TableCatalogEntry>,
+}
+
+impl PhysicalPlanGenerator {
+    pub(crate) fn create_physical_analyze(&self, op: LogicalAnalyze) -> PhysicalOperator {
+        PhysicalOperator::PhysicalAnalyze(PhysicalAnalyze::new(op.tables))
+    }
+}
@@ -1,7 +1,9 @@
 use arrow::record_batch::RecordBatch;
 use derive_new::new;
 
-use super::PhysicalOperatorBase;
+use super::{PhysicalOperator, PhysicalOperatorBase};
+use crate::execution::PhysicalPlanGenerator;
+use crate::planner_v2::LogicalEmptyResult;
 
 /// The PhysicalColumnDataScan scans a Arrow RecordBatch
 #[derive(new, Clone)]
@@ -9,3 +11,11 @@ pub struct PhysicalColumnDataScan {
     pub(crate) base: PhysicalOperatorBase,
     pub(crate) collection: Vec<RecordBatch>,
 }
+
+impl PhysicalPlanGenerator {
+    pub(crate) fn create_physical_empty_result(&self, op: LogicalEmptyResult) -> PhysicalOperator {
+        // the empty result is a column data scan without any batches
+        let base = self.create_physical_operator_base(op.base);
+        PhysicalOperator::PhysicalColumnDataScan(PhysicalColumnDataScan::new(base, vec![]))
+    }
+}
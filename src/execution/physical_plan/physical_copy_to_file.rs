@@ -0,0 +1,18 @@
+use derive_new::new;
+
+use super::{PhysicalOperator, PhysicalOperatorBase};
+use crate::execution::PhysicalPlanGenerator;
+use crate::planner_v2::{CopyToFileInfo, LogicalCopyToFile};
+
+#[derive(new, Clone)]
+pub struct PhysicalCopyToFile {
+    pub(crate) base: PhysicalOperatorBase,
+    pub(crate) info: CopyToFileInfo,
+}
+
+impl PhysicalPlanGenerator {
+    pub(crate) fn create_physical_copy_to_file(&self, op: LogicalCopyToFile) -> PhysicalOperator {
+        let base = self.create_physical_operator_base(op.base);
+        PhysicalOperator::PhysicalCopyToFile(PhysicalCopyToFile::new(base, op.info))
+    }
+}
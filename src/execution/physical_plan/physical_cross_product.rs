@@ -0,0 +1,21 @@
+use derive_new::new;
+
+use super::{PhysicalOperator, PhysicalOperatorBase};
+use crate::execution::PhysicalPlanGenerator;
+use crate::planner_v2::LogicalCrossProduct;
+
+#[derive(new, Clone)]
+pub struct PhysicalCrossProduct {
+    pub(crate) base: PhysicalOperatorBase,
+}
+
+impl PhysicalPlanGenerator {
+    pub(crate) fn create_physical_cross_product(
+        &self,
+        op: LogicalCrossProduct,
+    ) -> PhysicalOperator {
+        assert!(op.base.children.len() == 2);
+        let base = self.create_physical_operator_base(op.base);
+        PhysicalOperator::PhysicalCrossProduct(PhysicalCrossProduct::new(base))
+    }
+}
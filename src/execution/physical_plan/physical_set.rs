@@ -0,0 +1,19 @@
+use derive_new::new;
+
+use super::{PhysicalOperator, PhysicalOperatorBase};
+use crate::execution::PhysicalPlanGenerator;
+use crate::main_entry::ConfigOption;
+use crate::planner_v2::LogicalSet;
+
+#[derive(new, Clone)]
+pub struct PhysicalSet {
+    #[new(default)]
+    pub(crate) base: PhysicalOperatorBase,
+    pub(crate) option: ConfigOption,
+}
+
+impl PhysicalPlanGenerator {
+    pub(crate) fn create_physical_set(&self, op: LogicalSet) -> PhysicalOperator {
+        PhysicalOperator::PhysicalSet(PhysicalSet::new(op.option))
+    }
+}
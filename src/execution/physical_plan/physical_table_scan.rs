@@ -15,13 +15,43 @@ pub struct PhysicalTableScan {
     pub(crate) returned_types: Vec<LogicalType>,
     /// The names of ALL columns that can be returned by the table function
     pub(crate) names: Vec<String>,
+    /// Bound column IDs, empty means all columns are returned
+    pub(crate) column_ids: Vec<usize>,
+}
+
+impl PhysicalTableScan {
+    pub fn projected_types(&self) -> Vec<LogicalType> {
+        if self.column_ids.is_empty() {
+            return self.returned_types.clone();
+        }
+        self.column_ids
+            .iter()
+            .map(|idx| self.returned_types[*idx].clone())
+            .collect()
+    }
+
+    pub fn projected_names(&self) -> Vec<String> {
+        if self.column_ids.is_empty() {
+            return self.names.clone();
+        }
+        self.column_ids
+            .iter()
+            .map(|idx| self.names[*idx].clone())
+            .collect()
+    }
 }
 
 impl PhysicalPlanGenerator {
     pub(crate) fn create_physical_table_scan(&self, op: LogicalGet) -> PhysicalOperator {
         let base = self.create_physical_operator_base(op.base);
-        let plan =
-            PhysicalTableScan::new(base, op.function, op.bind_data, op.returned_types, op.names);
+        let plan = PhysicalTableScan::new(
+            base,
+            op.function,
+            op.bind_data,
+            op.returned_types,
+            op.names,
+            op.column_ids,
+        );
         PhysicalOperator::PhysicalTableScan(plan)
     }
 }
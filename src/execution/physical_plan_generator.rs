@@ -44,6 +44,11 @@ impl PhysicalPlanGenerator {
             LogicalOperator::LogicalExplain(op) => self.create_physical_explain(op),
             LogicalOperator::LogicalFilter(op) => self.create_physical_filter(op),
             LogicalOperator::LogicalLimit(op) => self.create_physical_limit(op),
+            LogicalOperator::LogicalEmptyResult(op) => self.create_physical_empty_result(op),
+            LogicalOperator::LogicalCrossProduct(op) => self.create_physical_cross_product(op),
+            LogicalOperator::LogicalAnalyze(op) => self.create_physical_analyze(op),
+            LogicalOperator::LogicalSet(op) => self.create_physical_set(op),
+            LogicalOperator::LogicalCopyToFile(op) => self.create_physical_copy_to_file(op),
         }
     }
 
@@ -0,0 +1,95 @@
+mod operator;
+mod pipeline;
+mod sink;
+mod source;
+use std::sync::Arc;
+
+use arrow::record_batch::RecordBatch;
+use futures::stream::BoxStream;
+pub use operator::*;
+pub use pipeline::*;
+pub use sink::*;
+pub use source::*;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use super::{ExecutionContext, ExecutorError, PhysicalOperator};
+
+/// The number of batches of a streamed result buffered ahead of its reader.
+const STREAM_BUFFER_BATCHES: usize = 16;
+
+/// The push-based executor. The physical plan is split into pipelines at the pipeline breakers,
+/// each pipeline pushes the morsels of its source through its operators into its sink on
+/// `threads` workers of the blocking pool. Pipelines run after the pipelines they depend on, so
+/// the sink of a dependency is complete when it is read.
+#[derive(Debug)]
+pub struct PipelineExecutor {
+    threads: usize,
+}
+
+impl PipelineExecutor {
+    pub fn new(threads: usize) -> Self {
+        Self {
+            threads: threads.max(1),
+        }
+    }
+
+    pub(crate) async fn try_execute(
+        &self,
+        plan: PhysicalOperator,
+        context: Arc<ExecutionContext>,
+    ) -> Result<Vec<RecordBatch>, ExecutorError> {
+        let result = Arc::new(ColumnDataCollection::default());
+        let mut builder = PipelineBuilder::default();
+        builder.build(plan, PipelineSink::Collect(result.clone()));
+        for pipeline in builder.pipelines {
+            pipeline.execute(context.clone(), self.threads).await?;
+        }
+        Ok(result.batches())
+    }
+
+    /// Execute the plan on a background task, the batches of the result are returned by the
+    /// stream in order as soon as their morsels are finished. An error ends the stream. The
+    /// channel of the batches is bounded, so the pipelines wait for a slow reader, and the
+    /// execution is cancelled when the stream is dropped before its end.
+    pub(crate) fn execute_stream(
+        &self,
+        plan: PhysicalOperator,
+        context: Arc<ExecutionContext>,
+    ) -> BoxStream<'static, Result<RecordBatch, ExecutorError>> {
+        let (sender, mut receiver) = mpsc::channel(STREAM_BUFFER_BATCHES);
+        let mut builder = PipelineBuilder::default();
+        builder.build(plan, PipelineSink::Stream(StreamSink::new(sender.clone())));
+        let threads = self.threads;
+        let task_context = context.clone();
+        let task = tokio::spawn(async move {
+            for pipeline in builder.pipelines {
+                if let Err(e) = pipeline.execute(task_context.clone(), threads).await {
+                    let _ = sender.send(Err(e)).await;
+                    return;
+                }
+            }
+        });
+        let producer = StreamProducer { task, context };
+        Box::pin(async_stream::stream! {
+            let _producer = producer;
+            while let Some(batch) = receiver.recv().await {
+                yield batch;
+            }
+        })
+    }
+}
+
+/// The background task of a streamed result, it's stopped when the stream is dropped. The
+/// workers of the running pipeline stop at their next batch once the query is cancelled.
+struct StreamProducer {
+    task: JoinHandle<()>,
+    context: Arc<ExecutionContext>,
+}
+
+impl Drop for StreamProducer {
+    fn drop(&mut self) {
+        self.context.cancel();
+        self.task.abort();
+    }
+}
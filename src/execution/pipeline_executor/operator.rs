@@ -0,0 +1,77 @@
+use std::sync::Arc;
+
+use arrow::compute::{concat_batches, filter_record_batch};
+use arrow::record_batch::RecordBatch;
+use derive_new::new;
+
+use super::ColumnDataCollection;
+use crate::common::as_boolean_array;
+use crate::execution::{
+    CrossProduct, ExecutorError, ExpressionExecutor, PhysicalFilter, PhysicalProjection, SchemaUtil,
+};
+
+/// The batches produced by an operator for one input batch.
+pub type OperatorOutput<'a> = Box<dyn Iterator<Item = Result<RecordBatch, ExecutorError>> + 'a>;
+
+/// A streaming operator of a pipeline, it transforms one batch into zero or more batches. The
+/// batches are produced lazily, so an operator multiplying its input like a cross product never
+/// holds all of its output.
+pub enum PipelineOperator {
+    Filter(PhysicalFilter),
+    Projection(PhysicalProjection),
+    CrossProductProbe(CrossProductProbe),
+}
+
+impl PipelineOperator {
+    /// Called once the pipelines this operator depends on are finished.
+    pub fn initialize(&mut self) -> Result<(), ExecutorError> {
+        match self {
+            PipelineOperator::CrossProductProbe(op) => op.initialize(),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn execute(&self, batch: RecordBatch) -> OperatorOutput<'_> {
+        match self {
+            PipelineOperator::Filter(op) => Box::new(std::iter::once(Self::filter(op, &batch))),
+            PipelineOperator::Projection(op) => {
+                Box::new(std::iter::once(Self::project(op, &batch)))
+            }
+            PipelineOperator::CrossProductProbe(op) => match &op.right {
+                Some(right) => Box::new(CrossProduct::product(batch, right.clone())),
+                None => Box::new(std::iter::empty()),
+            },
+        }
+    }
+
+    fn filter(op: &PhysicalFilter, batch: &RecordBatch) -> Result<RecordBatch, ExecutorError> {
+        let eval_mask = ExpressionExecutor::execute(&op.base.expressioins, batch)?;
+        let predicate = as_boolean_array(&eval_mask[0])?;
+        Ok(filter_record_batch(batch, predicate)?)
+    }
+
+    fn project(op: &PhysicalProjection, batch: &RecordBatch) -> Result<RecordBatch, ExecutorError> {
+        let exprs = &op.base.expressioins;
+        let schema = SchemaUtil::new_schema_ref_from_exprs(exprs);
+        let columns = ExpressionExecutor::execute(exprs, batch)?;
+        Ok(RecordBatch::try_new(schema, columns)?)
+    }
+}
+
+/// Probe the materialized right side of a cross product with the batches of the left side.
+#[derive(new)]
+pub struct CrossProductProbe {
+    collection: Arc<ColumnDataCollection>,
+    #[new(default)]
+    right: Option<RecordBatch>,
+}
+
+impl CrossProductProbe {
+    fn initialize(&mut self) -> Result<(), ExecutorError> {
+        let batches = self.collection.batches();
+        if !batches.is_empty() {
+            self.right = Some(concat_batches(&batches[0].schema(), &batches)?);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,174 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use super::{
+    ColumnDataCollection, ColumnDataSource, CrossProductProbe, LimitSink, PipelineOperator,
+    PipelineSink, PipelineSource, TableScanSource, VolcanoSource,
+};
+use crate::execution::{ExecutionContext, ExecutorError, PhysicalOperator};
+use crate::function::FunctionData;
+
+/// A pipeline is a chain of streaming operators between a source and a sink. No operator of a
+/// pipeline needs to see all of its input before producing output, so every morsel of the
+/// source can be pushed to the sink independently.
+pub struct Pipeline {
+    pub(crate) source: PipelineSource,
+    /// The operators in the order they are applied to a morsel
+    pub(crate) operators: Vec<PipelineOperator>,
+    pub(crate) sink: PipelineSink,
+    /// Set when a worker failed, the other workers stop at their next morsel
+    pub(crate) stopped: AtomicBool,
+}
+
+impl Pipeline {
+    /// Run the pipeline on `threads` workers of the blocking pool, so the workers don't hold the
+    /// threads of the async runtime while they push morsels.
+    pub async fn execute(
+        mut self,
+        context: Arc<ExecutionContext>,
+        threads: usize,
+    ) -> Result<(), ExecutorError> {
+        self.source.initialize(context.clone()).await?;
+        for operator in self.operators.iter_mut() {
+            operator.initialize()?;
+        }
+
+        let pipeline = Arc::new(self);
+        let workers = (0..threads)
+            .map(|_| {
+                let pipeline = pipeline.clone();
+                let context = context.clone();
+                tokio::task::spawn_blocking(move || pipeline.run_worker(context))
+            })
+            .collect::<Vec<_>>();
+        for worker in workers {
+            worker.await.map_err(|_| {
+                ExecutorError::InternalError("pipeline worker panicked".to_string())
+            })??;
+        }
+
+        // finalizing a stream sink may wait for the reader of the result
+        tokio::task::spawn_blocking(move || pipeline.sink.finalize())
+            .await
+            .map_err(|_| ExecutorError::InternalError("pipeline sink panicked".to_string()))?
+    }
+
+    /// Pull morsels from the source until it is exhausted or the sink needs no more rows, each
+    /// morsel is pushed through all the operators into the sink. The worker stops at the next
+    /// batch when the query is interrupted, and an error of a worker stops the other workers.
+    fn run_worker(&self, context: Arc<ExecutionContext>) -> Result<(), ExecutorError> {
+        let result = self.push_morsels(context);
+        if result.is_err() {
+            self.stopped.store(true, Ordering::Relaxed);
+        }
+        result
+    }
+
+    fn push_morsels(&self, context: Arc<ExecutionContext>) -> Result<(), ExecutorError> {
+        context.check_interrupted()?;
+        while !self.is_finished() {
+            let (morsel_idx, batch) = match self.source.next_morsel(context.clone())? {
+                Some(morsel) => morsel,
+                None => break,
+            };
+            self.push(&context, morsel_idx, 0, batch)?;
+            self.sink.finish_morsel(morsel_idx);
+        }
+        Ok(())
+    }
+
+    /// Push a batch through the operators from `operator_idx` on, each output batch of an
+    /// operator is pushed further before the operator produces the next one.
+    fn push(
+        &self,
+        context: &ExecutionContext,
+        morsel_idx: usize,
+        operator_idx: usize,
+        batch: RecordBatch,
+    ) -> Result<(), ExecutorError> {
+        let operator = match self.operators.get(operator_idx) {
+            Some(operator) => operator,
+            None => {
+                self.sink.sink(morsel_idx, batch);
+                return Ok(());
+            }
+        };
+        for output in operator.execute(batch) {
+            context.check_interrupted()?;
+            if self.is_finished() {
+                break;
+            }
+            self.push(context, morsel_idx, operator_idx + 1, output?)?;
+        }
+        Ok(())
+    }
+
+    fn is_finished(&self) -> bool {
+        self.stopped.load(Ordering::Relaxed) || self.sink.is_finished()
+    }
+}
+
+/// Split a physical plan into pipelines. The pipelines are ordered by their dependencies, a
+/// pipeline comes after all the pipelines whose sinks it reads.
+#[derive(Default)]
+pub struct PipelineBuilder {
+    pub(crate) pipelines: Vec<Pipeline>,
+}
+
+impl PipelineBuilder {
+    pub fn build(&mut self, plan: PhysicalOperator, sink: PipelineSink) {
+        let mut operators = vec![];
+        let mut current = plan;
+        let source = loop {
+            match current {
+                PhysicalOperator::PhysicalFilter(op) => {
+                    current = op.base.children[0].clone();
+                    operators.push(PipelineOperator::Filter(op));
+                }
+                PhysicalOperator::PhysicalProjection(op) => {
+                    current = op.base.children[0].clone();
+                    operators.push(PipelineOperator::Projection(op));
+                }
+                PhysicalOperator::PhysicalCrossProduct(op) => {
+                    // the right side is materialized by its own pipeline, then probed by the
+                    // morsels of the left side
+                    let right = Arc::new(ColumnDataCollection::default());
+                    self.build(
+                        op.base.children[1].clone(),
+                        PipelineSink::Collect(right.clone()),
+                    );
+                    operators.push(PipelineOperator::CrossProductProbe(CrossProductProbe::new(
+                        right,
+                    )));
+                    current = op.base.children[0].clone();
+                }
+                PhysicalOperator::PhysicalLimit(op) => {
+                    // limit and offset depend on the order of all the rows, so the child is
+                    // collected by a limit sink and the result is the source of this pipeline
+                    let output = Arc::new(ColumnDataCollection::default());
+                    self.build(
+                        op.base.children[0].clone(),
+                        PipelineSink::Limit(LimitSink::new(op.limit, op.offset, output.clone())),
+                    );
+                    break PipelineSource::ColumnData(ColumnDataSource::new(output));
+                }
+                PhysicalOperator::PhysicalTableScan(op)
+                    if matches!(op.bind_data, Some(FunctionData::SeqTableScanInputData(_))) =>
+                {
+                    break PipelineSource::TableScan(TableScanSource::new(op));
+                }
+                // the other operators, including the scans of the table functions such as
+                // read_csv, are executed by the volcano executor as a whole. Their batches are
+                // produced by a single task, only the operators above them run in parallel
+                op => break PipelineSource::Volcano(VolcanoSource::new(op)),
+            }
+        };
+        operators.reverse();
+        self.pipelines.push(Pipeline {
+            source,
+            operators,
+            sink,
+            stopped: AtomicBool::new(false),
+        });
+    }
+}
@@ -0,0 +1,319 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use arrow::record_batch::RecordBatch;
+use derive_new::new;
+use tokio::sync::mpsc::Sender;
+
+use crate::execution::ExecutorError;
+
+/// The sink of a pipeline consumes the batches of all the morsels, it is the pipeline breaker
+/// between a pipeline and the pipelines depending on it.
+pub enum PipelineSink {
+    Collect(Arc<ColumnDataCollection>),
+    Limit(LimitSink),
+    Stream(StreamSink),
+}
+
+impl PipelineSink {
+    /// Consume a batch of the morsel, the batches of a morsel are sunk in their order.
+    pub fn sink(&self, morsel_idx: usize, batch: RecordBatch) {
+        match self {
+            PipelineSink::Collect(collection) => collection.append(morsel_idx, vec![batch]),
+            PipelineSink::Limit(sink) => sink.sink(morsel_idx, batch),
+            PipelineSink::Stream(sink) => sink.send(morsel_idx, batch),
+        }
+    }
+
+    /// Called once all the batches of the morsel are sunk.
+    pub fn finish_morsel(&self, morsel_idx: usize) {
+        match self {
+            PipelineSink::Collect(_) => {}
+            PipelineSink::Limit(sink) => sink.finish_morsel(morsel_idx),
+            PipelineSink::Stream(sink) => sink.finish_morsel(morsel_idx),
+        }
+    }
+
+    /// Whether the sink needs no more rows, the workers stop pulling morsels from the source.
+    pub fn is_finished(&self) -> bool {
+        match self {
+            PipelineSink::Limit(sink) => sink.finished.load(Ordering::Relaxed),
+            // the result is dropped before its end
+            PipelineSink::Stream(sink) => sink.sender.is_closed(),
+            PipelineSink::Collect(_) => false,
+        }
+    }
+
+    /// Called once all the workers of the pipeline are finished.
+    pub fn finalize(&self) -> Result<(), ExecutorError> {
+        match self {
+            PipelineSink::Collect(_) => Ok(()),
+            PipelineSink::Limit(sink) => sink.finalize(),
+            PipelineSink::Stream(sink) => sink.finalize(),
+        }
+    }
+}
+
+/// The batches produced by a pipeline. Morsels are finished by the workers in any order, the
+/// batches are ordered by their morsel index when they are read.
+#[derive(Default)]
+pub struct ColumnDataCollection {
+    morsels: Mutex<Vec<(usize, Vec<RecordBatch>)>>,
+}
+
+impl ColumnDataCollection {
+    pub fn append(&self, morsel_idx: usize, batches: Vec<RecordBatch>) {
+        self.morsels.lock().unwrap().push((morsel_idx, batches));
+    }
+
+    pub fn batches(&self) -> Vec<RecordBatch> {
+        let mut morsels = self.morsels.lock().unwrap().clone();
+        morsels.sort_by_key(|(morsel_idx, _)| *morsel_idx);
+        morsels
+            .into_iter()
+            .flat_map(|(_, batches)| batches)
+            .filter(|batch| batch.num_rows() > 0)
+            .collect()
+    }
+}
+
+/// Collect the rows of the child, then apply the offset and limit in the order of the morsels.
+/// The sink is finished once the finished morsels before the first unfinished one, and the rows
+/// of that one sunk so far, hold enough rows. The morsels after them are not needed.
+#[derive(new)]
+pub struct LimitSink {
+    limit: Option<u64>,
+    offset: Option<u64>,
+    output: Arc<ColumnDataCollection>,
+    #[new(default)]
+    buffer: ColumnDataCollection,
+    #[new(default)]
+    progress: Mutex<LimitProgress>,
+    #[new(default)]
+    finished: AtomicBool,
+}
+
+#[derive(Default)]
+struct LimitProgress {
+    /// The index of the first unfinished morsel
+    next_morsel: usize,
+    /// The number of rows sunk by the morsels from the next morsel on
+    morsel_rows: BTreeMap<usize, usize>,
+    /// The finished morsels after the next morsel
+    finished_morsels: BTreeSet<usize>,
+    /// The number of rows of the morsels before the next morsel
+    rows: usize,
+}
+
+impl LimitSink {
+    fn sink(&self, morsel_idx: usize, batch: RecordBatch) {
+        let rows = batch.num_rows();
+        self.buffer.append(morsel_idx, vec![batch]);
+        if self.limit.is_some() {
+            let mut progress = self.progress.lock().unwrap();
+            *progress.morsel_rows.entry(morsel_idx).or_default() += rows;
+            self.update_finished(&progress);
+        }
+    }
+
+    fn finish_morsel(&self, morsel_idx: usize) {
+        if self.limit.is_none() {
+            return;
+        }
+        let mut progress = self.progress.lock().unwrap();
+        progress.finished_morsels.insert(morsel_idx);
+        loop {
+            let next_morsel = progress.next_morsel;
+            if !progress.finished_morsels.remove(&next_morsel) {
+                break;
+            }
+            progress.rows += progress.morsel_rows.remove(&next_morsel).unwrap_or(0);
+            progress.next_morsel += 1;
+        }
+        self.update_finished(&progress);
+    }
+
+    fn update_finished(&self, progress: &LimitProgress) {
+        let needed = match self.limit {
+            Some(limit) => self.offset.unwrap_or(0) + limit,
+            None => return,
+        };
+        let next_rows = progress.morsel_rows.get(&progress.next_morsel);
+        let rows = progress.rows + next_rows.copied().unwrap_or(0);
+        if rows as u64 >= needed {
+            self.finished.store(true, Ordering::Relaxed);
+        }
+    }
+
+    fn finalize(&self) -> Result<(), ExecutorError> {
+        let mut offset = self.offset.unwrap_or(0) as usize;
+        let mut remaining = self.limit.map_or(usize::MAX, |limit| limit as usize);
+        let mut output = vec![];
+        for batch in self.buffer.batches() {
+            if remaining == 0 {
+                break;
+            }
+            if offset >= batch.num_rows() {
+                offset -= batch.num_rows();
+                continue;
+            }
+            let length = (batch.num_rows() - offset).min(remaining);
+            output.push(batch.slice(offset, length));
+            remaining -= length;
+            offset = 0;
+        }
+        self.output.append(0, output);
+        Ok(())
+    }
+}
+
+/// Send the batches of the morsels to the receiver of the result as soon as they are produced.
+/// The batches are sent in the order of the morsels, the batches of the first unfinished morsel
+/// are ready to be sent directly, and the batches of the morsels after it wait for it to finish.
+/// The ready batches are sent by one worker at a time, outside of the lock, so the workers of the
+/// later morsels are not blocked while the channel is full.
+#[derive(new)]
+pub struct StreamSink {
+    sender: Sender<Result<RecordBatch, ExecutorError>>,
+    #[new(default)]
+    state: Mutex<StreamSinkState>,
+}
+
+#[derive(Default)]
+struct StreamSinkState {
+    /// The index of the first unfinished morsel, its batches are sent directly
+    next_morsel: usize,
+    /// The batches of the morsels after the next morsel
+    pending: BTreeMap<usize, Vec<RecordBatch>>,
+    /// The finished morsels after the next morsel
+    finished_morsels: BTreeSet<usize>,
+    /// The batches to send, in the order of the morsels
+    ready: Vec<RecordBatch>,
+    /// Whether a worker is sending the ready batches
+    sending: bool,
+}
+
+impl StreamSink {
+    fn send(&self, morsel_idx: usize, batch: RecordBatch) {
+        let mut state = self.state.lock().unwrap();
+        if morsel_idx == state.next_morsel {
+            state.ready.push(batch);
+            drop(state);
+            self.flush();
+        } else {
+            state.pending.entry(morsel_idx).or_default().push(batch);
+        }
+    }
+
+    fn finish_morsel(&self, morsel_idx: usize) {
+        let mut state = self.state.lock().unwrap();
+        state.finished_morsels.insert(morsel_idx);
+        loop {
+            let next_morsel = state.next_morsel;
+            if !state.finished_morsels.remove(&next_morsel) {
+                break;
+            }
+            state.next_morsel += 1;
+            let next_morsel = state.next_morsel;
+            if let Some(batches) = state.pending.remove(&next_morsel) {
+                state.ready.extend(batches);
+            }
+        }
+        drop(state);
+        self.flush();
+    }
+
+    /// Send the morsels left behind a missing morsel index.
+    fn finalize(&self) -> Result<(), ExecutorError> {
+        let mut state = self.state.lock().unwrap();
+        let pending = std::mem::take(&mut state.pending);
+        state.ready.extend(pending.into_values().flatten());
+        drop(state);
+        self.flush();
+        Ok(())
+    }
+
+    /// Send the ready batches, unless another worker is already sending them. The sending worker
+    /// also sends the batches that become ready while it's waiting for the channel.
+    fn flush(&self) {
+        let mut state = self.state.lock().unwrap();
+        if state.sending {
+            return;
+        }
+        while !state.ready.is_empty() {
+            let batches = std::mem::take(&mut state.ready);
+            state.sending = true;
+            drop(state);
+            self.send_batches(batches);
+            state = self.state.lock().unwrap();
+            state.sending = false;
+        }
+    }
+
+    /// Send the batches on a worker of the blocking pool, the worker waits while the channel
+    /// is full.
+    fn send_batches(&self, batches: Vec<RecordBatch>) {
+        for batch in batches.into_iter().filter(|batch| batch.num_rows() > 0) {
+            // the receiver is gone when the result is dropped before its end, the rest of the
+            // batches are discarded
+            if self.sender.blocking_send(Ok(batch)).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::Int32Array;
+    use arrow::datatypes::{DataType, Field, Schema};
+
+    use super::*;
+
+    fn batch(rows: i32) -> RecordBatch {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        RecordBatch::try_new(
+            schema,
+            vec![Arc::new(Int32Array::from_iter_values(0..rows))],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_limit_sink_finishes_after_enough_rows_in_order() {
+        let output = Arc::new(ColumnDataCollection::default());
+        let sink = PipelineSink::Limit(LimitSink::new(Some(3), Some(1), output.clone()));
+        // the second morsel alone has enough rows, but the first one precedes it
+        sink.sink(1, batch(10));
+        sink.finish_morsel(1);
+        assert!(!sink.is_finished());
+        sink.sink(0, batch(2));
+        assert!(!sink.is_finished());
+        sink.finish_morsel(0);
+        assert!(sink.is_finished());
+
+        sink.finalize().unwrap();
+        let rows = output.batches().iter().map(|b| b.num_rows()).sum::<usize>();
+        assert_eq!(rows, 3);
+    }
+
+    #[test]
+    fn test_stream_sink_sends_in_morsel_order() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(10);
+        let sink = PipelineSink::Stream(StreamSink::new(sender));
+        // the batches of the second morsel wait for the first one to finish
+        sink.sink(1, batch(2));
+        sink.sink(0, batch(1));
+        sink.finish_morsel(1);
+        sink.sink(0, batch(3));
+        sink.finish_morsel(0);
+        sink.finalize().unwrap();
+
+        let mut rows = vec![];
+        while let Ok(batch) = receiver.try_recv() {
+            rows.push(batch.unwrap().num_rows());
+        }
+        assert_eq!(rows, vec![1, 3, 2]);
+    }
+}
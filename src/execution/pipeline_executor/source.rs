@@ -0,0 +1,183 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use arrow::record_batch::RecordBatch;
+use derive_new::new;
+use futures::StreamExt;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use super::ColumnDataCollection;
+use crate::execution::{
+    ExecutionContext, ExecutorError, PhysicalOperator, PhysicalTableScan, SchemaUtil,
+    VolcanoExecutor,
+};
+use crate::function::FunctionData;
+use crate::storage_v2::LocalStorage;
+
+/// The number of batches a volcano source buffers ahead of the workers.
+const VOLCANO_SOURCE_BUFFER: usize = 4;
+
+/// The source of a pipeline hands out morsels to the workers. A morsel is identified by its
+/// index, the sinks use it to keep the order of the source.
+pub enum PipelineSource {
+    TableScan(TableScanSource),
+    ColumnData(ColumnDataSource),
+    Volcano(VolcanoSource),
+}
+
+impl PipelineSource {
+    /// Called once the pipelines this source depends on are finished.
+    pub async fn initialize(
+        &mut self,
+        context: Arc<ExecutionContext>,
+    ) -> Result<(), ExecutorError> {
+        match self {
+            PipelineSource::TableScan(_) => Ok(()),
+            PipelineSource::ColumnData(source) => {
+                source.batches = source.collection.batches();
+                Ok(())
+            }
+            PipelineSource::Volcano(source) => {
+                source.initialize(context);
+                Ok(())
+            }
+        }
+    }
+
+    pub fn next_morsel(
+        &self,
+        context: Arc<ExecutionContext>,
+    ) -> Result<Option<(usize, RecordBatch)>, ExecutorError> {
+        match self {
+            PipelineSource::TableScan(source) => source.next_morsel(context),
+            PipelineSource::ColumnData(source) => {
+                Ok(next_materialized_morsel(&source.batches, &source.cursor))
+            }
+            PipelineSource::Volcano(source) => source.next_morsel(),
+        }
+    }
+}
+
+fn next_materialized_morsel(
+    batches: &[RecordBatch],
+    cursor: &AtomicUsize,
+) -> Option<(usize, RecordBatch)> {
+    let morsel_idx = cursor.fetch_add(1, Ordering::Relaxed);
+    batches
+        .get(morsel_idx)
+        .map(|batch| (morsel_idx, batch.clone()))
+}
+
+/// Scan a base table, every row group of the table is a morsel.
+#[derive(new)]
+pub struct TableScanSource {
+    plan: PhysicalTableScan,
+    #[new(default)]
+    cursor: AtomicUsize,
+}
+
+impl TableScanSource {
+    fn next_morsel(
+        &self,
+        context: Arc<ExecutionContext>,
+    ) -> Result<Option<(usize, RecordBatch)>, ExecutorError> {
+        let table = match &self.plan.bind_data {
+            Some(FunctionData::SeqTableScanInputData(data)) => &data.bind_table.storage,
+            _ => {
+                return Err(ExecutorError::InternalError(
+                    "unexpected bind data type".to_string(),
+                ))
+            }
+        };
+        let morsel_idx = self.cursor.fetch_add(1, Ordering::Relaxed);
+        let batch =
+            match LocalStorage::fetch_row_group(context.clone_client_context(), table, morsel_idx)?
+            {
+                Some(batch) => batch,
+                None => return Ok(None),
+            };
+        // the row group contains all columns, so we need to filter out the unused columns
+        let schema =
+            SchemaUtil::new_schema_ref(&self.plan.projected_names(), &self.plan.projected_types());
+        let columns = if self.plan.column_ids.is_empty() {
+            batch.columns().to_vec()
+        } else {
+            self.plan
+                .column_ids
+                .iter()
+                .map(|idx| batch.column(*idx).clone())
+                .collect::<Vec<_>>()
+        };
+        Ok(Some((morsel_idx, RecordBatch::try_new(schema, columns)?)))
+    }
+}
+
+/// Read the batches collected by the sink of another pipeline.
+#[derive(new)]
+pub struct ColumnDataSource {
+    collection: Arc<ColumnDataCollection>,
+    #[new(default)]
+    batches: Vec<RecordBatch>,
+    #[new(default)]
+    cursor: AtomicUsize,
+}
+
+/// Execute an operator which is not supported by the pipelines yet with the volcano executor,
+/// its batches are the morsels of the pipeline. The executor runs on a task of the runtime and
+/// hands its batches to the workers through a bounded channel, so it only runs ahead of the
+/// workers by a few batches, and it's stopped once the pipeline is dropped.
+#[derive(new)]
+pub struct VolcanoSource {
+    plan: PhysicalOperator,
+    #[new(default)]
+    state: Mutex<Option<VolcanoSourceState>>,
+}
+
+struct VolcanoSourceState {
+    receiver: mpsc::Receiver<Result<RecordBatch, ExecutorError>>,
+    next_morsel: usize,
+    task: JoinHandle<()>,
+}
+
+impl VolcanoSource {
+    fn initialize(&mut self, context: Arc<ExecutionContext>) {
+        let (sender, receiver) = mpsc::channel(VOLCANO_SOURCE_BUFFER);
+        let mut executor = VolcanoExecutor::new().build(self.plan.clone(), context);
+        let task = tokio::spawn(async move {
+            while let Some(batch) = executor.next().await {
+                let is_err = batch.is_err();
+                if sender.send(batch).await.is_err() || is_err {
+                    break;
+                }
+            }
+        });
+        *self.state.get_mut().unwrap() = Some(VolcanoSourceState {
+            receiver,
+            next_morsel: 0,
+            task,
+        });
+    }
+
+    /// Wait for the next batch of the executor, it's called on the workers of the blocking pool.
+    fn next_morsel(&self) -> Result<Option<(usize, RecordBatch)>, ExecutorError> {
+        let mut state = self.state.lock().unwrap();
+        let state = state.as_mut().ok_or_else(|| {
+            ExecutorError::InternalError("volcano source is not initialized".to_string())
+        })?;
+        match state.receiver.blocking_recv() {
+            Some(batch) => {
+                let morsel_idx = state.next_morsel;
+                state.next_morsel += 1;
+                Ok(Some((morsel_idx, batch?)))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+impl Drop for VolcanoSourceState {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
@@ -0,0 +1,56 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow::array::StringArray;
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use arrow::record_batch::RecordBatch;
+use derive_new::new;
+use futures_async_stream::try_stream;
+
+use crate::catalog_v2::{Catalog, TableCatalogEntry, TableStatistics};
+use crate::execution::{ExecutionContext, ExecutorError, PhysicalAnalyze};
+use crate::storage_v2::LocalStorage;
+
+#[derive(new)]
+pub struct Analyze {
+    pub(crate) plan: PhysicalAnalyze,
+}
+
+impl Analyze {
+    fn analyze_table(
+        context: &Arc<ExecutionContext>,
+        table: &TableCatalogEntry,
+    ) -> Result<(), ExecutorError> {
+        let mut reader = LocalStorage::create_reader(&table.storage);
+        let mut batches = vec![];
+        while let Some(batch) = reader.next_batch(context.clone_client_context())? {
+            batches.push(batch);
+        }
+        let stats = TableStatistics::analyze(table.columns.len(), &batches)?;
+        Catalog::update_table_statistics(
+            context.clone_client_context(),
+            table.storage.info.schema.clone(),
+            table.storage.info.table.clone(),
+            |s| *s = stats,
+        )?;
+        Ok(())
+    }
+
+    #[try_stream(boxed, ok = RecordBatch, error = ExecutorError)]
+    pub async fn execute(self, context: Arc<ExecutionContext>) {
+        let mut names = vec![];
+        for table in self.plan.tables.iter() {
+            Self::analyze_table(&context, table)?;
+            names.push(table.base.name.clone());
+        }
+        let array = Arc::new(StringArray::from(vec![format!(
+            "ANALYZE {}",
+            names.join(", ")
+        )]));
+        let fields = vec![Field::new("success", DataType::Utf8, false)];
+        yield RecordBatch::try_new(
+            SchemaRef::new(Schema::new_with_metadata(fields, HashMap::new())),
+            vec![array],
+        )?;
+    }
+}
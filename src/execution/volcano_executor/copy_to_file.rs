@@ -0,0 +1,236 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use arrow::array::{Array, Int64Array, StringArray, UInt32Array};
+use arrow::compute::{cast, take};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use arrow::record_batch::RecordBatch;
+use derive_new::new;
+use futures_async_stream::try_stream;
+
+use crate::execution::{
+    BoxedExecutor, ExecutionContext, ExecutorError, PhysicalCopyToFile, SchemaUtil,
+};
+use crate::function::{
+    CSVWriter, FunctionResult, HivePartitioning, IpcWriter, JsonWriter, ParquetWriter,
+    HIVE_DEFAULT_PARTITION,
+};
+use crate::planner_v2::{CopyToFileFormat, CopyToFileInfo};
+
+/// The name of the file written in each partition directory, without its extension.
+const PARTITION_FILE_STEM: &str = "data_0";
+
+/// A writer of the format of the copy.
+enum FileWriter {
+    Csv(CSVWriter<BufWriter<File>>),
+    Parquet(ParquetWriter<BufWriter<File>>),
+    Json(JsonWriter<BufWriter<File>>),
+    Arrow(IpcWriter<BufWriter<File>>),
+}
+
+impl FileWriter {
+    fn try_new(path: &Path, schema: SchemaRef, format: &CopyToFileFormat) -> FunctionResult<Self> {
+        let file = BufWriter::new(File::create(path)?);
+        Ok(match format {
+            CopyToFileFormat::Csv(options) => {
+                let names = schema.fields().iter().map(|f| f.name().clone()).collect();
+                FileWriter::Csv(CSVWriter::new(file, names, options.clone()))
+            }
+            CopyToFileFormat::Parquet(options) => {
+                FileWriter::Parquet(ParquetWriter::try_new(file, schema, options)?)
+            }
+            CopyToFileFormat::Json => FileWriter::Json(JsonWriter::new(file)),
+            CopyToFileFormat::Arrow => FileWriter::Arrow(IpcWriter::try_new(file, schema)?),
+        })
+    }
+
+    fn write(&mut self, batch: &RecordBatch) -> FunctionResult<()> {
+        match self {
+            FileWriter::Csv(writer) => writer.write(batch),
+            FileWriter::Parquet(writer) => writer.write(batch),
+            FileWriter::Json(writer) => writer.write(batch),
+            FileWriter::Arrow(writer) => writer.write(batch),
+        }
+    }
+
+    fn finish(self) -> FunctionResult<()> {
+        match self {
+            FileWriter::Csv(writer) => writer.finish().map(|_| ()),
+            FileWriter::Parquet(writer) => writer.finish(),
+            FileWriter::Json(writer) => writer.finish().map(|_| ()),
+            FileWriter::Arrow(writer) => writer.finish().map(|_| ()),
+        }
+    }
+}
+
+/// Write the rows into one file per partition, the files stay open until all the rows are
+/// written.
+struct PartitionedWriter {
+    root: PathBuf,
+    names: Vec<String>,
+    partition_by: Vec<usize>,
+    /// The indices of the columns written into the files, the partition columns are only kept
+    /// in the directory names
+    data_columns: Vec<usize>,
+    /// The schema of the data columns
+    schema: SchemaRef,
+    format: CopyToFileFormat,
+    writers: HashMap<PathBuf, FileWriter>,
+}
+
+impl PartitionedWriter {
+    fn new(info: CopyToFileInfo) -> Self {
+        let data_columns = (0..info.names.len())
+            .filter(|idx| !info.partition_by.contains(idx))
+            .collect::<Vec<_>>();
+        let names = data_columns
+            .iter()
+            .map(|idx| info.names[*idx].clone())
+            .collect::<Vec<_>>();
+        let types = data_columns
+            .iter()
+            .map(|idx| info.types[*idx].clone())
+            .collect::<Vec<_>>();
+        Self {
+            root: PathBuf::from(info.file_path),
+            names: info.names,
+            partition_by: info.partition_by,
+            data_columns,
+            schema: SchemaUtil::new_schema_ref(&names, &types),
+            format: info.format,
+            writers: HashMap::new(),
+        }
+    }
+
+    /// Group the rows of the batch by the directory of their partition values, the groups are
+    /// in the order of their first row.
+    fn partition_rows(
+        &self,
+        batch: &RecordBatch,
+    ) -> Result<Vec<(PathBuf, UInt32Array)>, ExecutorError> {
+        let values = self
+            .partition_by
+            .iter()
+            .map(|idx| cast(batch.column(*idx), &DataType::Utf8))
+            .collect::<Result<Vec<_>, _>>()?;
+        let values = values
+            .iter()
+            .map(|v| v.as_any().downcast_ref::<StringArray>().unwrap())
+            .collect::<Vec<_>>();
+        let mut groups: Vec<(PathBuf, Vec<u32>)> = vec![];
+        let mut group_ids: HashMap<PathBuf, usize> = HashMap::new();
+        for row in 0..batch.num_rows() {
+            let dir = self
+                .partition_by
+                .iter()
+                .zip(values.iter())
+                .map(|(idx, column)| {
+                    let value = if column.is_valid(row) {
+                        column.value(row)
+                    } else {
+                        HIVE_DEFAULT_PARTITION
+                    };
+                    format!(
+                        "{}={}",
+                        HivePartitioning::escape_path_part(&self.names[*idx]),
+                        HivePartitioning::escape_path_part(value)
+                    )
+                })
+                .collect::<PathBuf>();
+            let group_id = *group_ids.entry(dir.clone()).or_insert_with(|| {
+                groups.push((dir, vec![]));
+                groups.len() - 1
+            });
+            groups[group_id].1.push(row as u32);
+        }
+        Ok(groups
+            .into_iter()
+            .map(|(dir, rows)| (dir, UInt32Array::from(rows)))
+            .collect())
+    }
+
+    fn write(&mut self, batch: &RecordBatch) -> Result<(), ExecutorError> {
+        for (dir, rows) in self.partition_rows(batch)? {
+            let columns = self
+                .data_columns
+                .iter()
+                .map(|idx| take(batch.column(*idx).as_ref(), &rows, None))
+                .collect::<Result<Vec<_>, _>>()?;
+            let partition = RecordBatch::try_new(self.schema.clone(), columns)?;
+            let writer = match self.writers.entry(self.root.join(dir)) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    let writer = Self::create_partition_writer(
+                        entry.key(),
+                        self.schema.clone(),
+                        &self.format,
+                    )?;
+                    entry.insert(writer)
+                }
+            };
+            writer.write(&partition)?;
+        }
+        Ok(())
+    }
+
+    fn create_partition_writer(
+        dir: &Path,
+        schema: SchemaRef,
+        format: &CopyToFileFormat,
+    ) -> FunctionResult<FileWriter> {
+        fs::create_dir_all(dir)?;
+        let filename = format!("{}.{}", PARTITION_FILE_STEM, format.extension());
+        FileWriter::try_new(&dir.join(filename), schema, format)
+    }
+
+    fn finish(self) -> FunctionResult<()> {
+        for writer in self.writers.into_values() {
+            writer.finish()?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(new)]
+pub struct CopyToFile {
+    pub(crate) plan: PhysicalCopyToFile,
+    pub(crate) child: BoxedExecutor,
+}
+
+impl CopyToFile {
+    #[try_stream(boxed, ok = RecordBatch, error = ExecutorError)]
+    pub async fn execute(self, _context: Arc<ExecutionContext>) {
+        let info = self.plan.info;
+        let mut rows_written = 0;
+        if info.partition_by.is_empty() {
+            // the batches are written as they come, the result is never materialized
+            let path = PathBuf::from(&info.file_path);
+            let schema = SchemaUtil::new_schema_ref(&info.names, &info.types);
+            let mut writer = FileWriter::try_new(&path, schema, &info.format)?;
+            #[for_await]
+            for batch in self.child {
+                let batch = batch?;
+                rows_written += batch.num_rows();
+                writer.write(&batch)?;
+            }
+            writer.finish()?;
+        } else {
+            let mut writer = PartitionedWriter::new(info);
+            #[for_await]
+            for batch in self.child {
+                let batch = batch?;
+                rows_written += batch.num_rows();
+                writer.write(&batch)?;
+            }
+            writer.finish()?;
+        }
+
+        let fields = vec![Field::new("Count", DataType::Int64, false)];
+        let array = Arc::new(Int64Array::from(vec![rows_written as i64]));
+        yield RecordBatch::try_new(Arc::new(Schema::new(fields)), vec![array])?;
+    }
+}
@@ -18,24 +18,26 @@ pub struct CreateTable {
 }
 
 impl CreateTable {
+    /// The storage of the table, the table doesn't exist until it's created by `create_table`.
+    pub fn data_table(info: &BoundCreateTableInfo) -> DataTable {
+        DataTable::new(
+            DataTableInfo::new(info.base.base.schema.clone(), info.base.table.clone()),
+            info.base.columns.clone(),
+        )
+    }
+
     pub fn create_table(
         context: Arc<ExecutionContext>,
         info: &BoundCreateTableInfo,
     ) -> Result<DataTable, ExecutorError> {
-        let schema = info.base.base.schema.clone();
-        let table = info.base.table.clone();
-        let column_definitions = info.base.columns.clone();
-        let data_table = DataTable::new(
-            DataTableInfo::new(schema.clone(), table.clone()),
-            column_definitions,
-        );
+        let data_table = Self::data_table(info);
         Catalog::create_table(
             context.clone_client_context(),
-            schema,
-            table,
+            data_table.info.schema.clone(),
+            data_table.info.table.clone(),
             data_table.clone(),
         )?;
-        LocalStorage::init_table(context.clone_client_context(), &data_table);
+        LocalStorage::init_table(context.clone_client_context(), &data_table)?;
         Ok(data_table)
     }
 
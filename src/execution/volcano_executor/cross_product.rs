@@ -0,0 +1,146 @@
+use std::ops::Range;
+use std::sync::Arc;
+
+use arrow::array::UInt32Array;
+use arrow::compute::{concat_batches, take};
+use arrow::datatypes::{Schema, SchemaRef};
+use arrow::record_batch::RecordBatch;
+use derive_new::new;
+use futures::TryStreamExt;
+use futures_async_stream::try_stream;
+
+use crate::execution::{BoxedExecutor, ExecutionContext, ExecutorError, PhysicalCrossProduct};
+
+/// The maximum number of rows produced by one output batch.
+const CROSS_PRODUCT_BATCH_SIZE: usize = 1024;
+
+#[derive(new)]
+pub struct CrossProduct {
+    pub(crate) _plan: PhysicalCrossProduct,
+    pub(crate) left: BoxedExecutor,
+    pub(crate) right: BoxedExecutor,
+}
+
+impl CrossProduct {
+    #[try_stream(boxed, ok = RecordBatch, error = ExecutorError)]
+    pub async fn execute(self, context: Arc<ExecutionContext>) {
+        // materialize the right side, then stream the left side against it
+        let right_batches = self.right.try_collect::<Vec<_>>().await?;
+        if right_batches.is_empty() {
+            return Ok(());
+        }
+        let right = concat_batches(&right_batches[0].schema(), &right_batches)?;
+        if right.num_rows() == 0 {
+            return Ok(());
+        }
+
+        #[for_await]
+        for left in self.left {
+            for batch in Self::product(left?, right.clone()) {
+                context.check_interrupted()?;
+                yield batch?;
+            }
+        }
+    }
+
+    /// The cross product of the left batch and the materialized right side, it's produced lazily
+    /// in output batches of at most CROSS_PRODUCT_BATCH_SIZE rows.
+    pub(crate) fn product(left: RecordBatch, right: RecordBatch) -> CrossProductIter {
+        let schema = Self::merge_schema(&left.schema(), &right.schema());
+        CrossProductIter {
+            left,
+            right,
+            schema,
+            position: 0,
+        }
+    }
+
+    fn merge_schema(left: &SchemaRef, right: &SchemaRef) -> SchemaRef {
+        let fields = left
+            .fields()
+            .iter()
+            .chain(right.fields().iter())
+            .cloned()
+            .collect::<Vec<_>>();
+        Arc::new(Schema::new(fields))
+    }
+}
+
+/// The lazy cross product of a left batch and the right side.
+pub struct CrossProductIter {
+    left: RecordBatch,
+    right: RecordBatch,
+    schema: SchemaRef,
+    /// The position of the next row in the product, the row at position `p` pairs the left row
+    /// `p / right rows` with the right row `p % right rows`
+    position: usize,
+}
+
+impl CrossProductIter {
+    fn take_rows(&self, positions: Range<usize>) -> Result<RecordBatch, ExecutorError> {
+        let right_rows = self.right.num_rows();
+        let left_indices =
+            UInt32Array::from_iter_values(positions.clone().map(|p| (p / right_rows) as u32));
+        let right_indices =
+            UInt32Array::from_iter_values(positions.map(|p| (p % right_rows) as u32));
+        let mut columns = Vec::with_capacity(self.schema.fields().len());
+        for col in self.left.columns() {
+            columns.push(take(col.as_ref(), &left_indices, None)?);
+        }
+        for col in self.right.columns() {
+            columns.push(take(col.as_ref(), &right_indices, None)?);
+        }
+        Ok(RecordBatch::try_new(self.schema.clone(), columns)?)
+    }
+}
+
+impl Iterator for CrossProductIter {
+    type Item = Result<RecordBatch, ExecutorError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let total = self.left.num_rows() * self.right.num_rows();
+        if self.position >= total {
+            return None;
+        }
+        let end = (self.position + CROSS_PRODUCT_BATCH_SIZE).min(total);
+        let positions = self.position..end;
+        self.position = end;
+        Some(self.take_rows(positions))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::Int32Array;
+
+    use super::*;
+
+    #[test]
+    fn test_product_batches_are_bounded() {
+        let batch = |name: &str, rows: i32| {
+            let values = Int32Array::from_iter_values(0..rows);
+            RecordBatch::try_from_iter(vec![(name, Arc::new(values) as _)]).unwrap()
+        };
+        let batches = CrossProduct::product(batch("a", 3), batch("b", 1000))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let rows = batches.iter().map(|b| b.num_rows()).collect::<Vec<_>>();
+        assert_eq!(rows, vec![1024, 1024, 952]);
+
+        let last = &batches[2];
+        let a = last
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .unwrap();
+        let b = last
+            .column(1)
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .unwrap();
+        // the first row of the last batch is at position 2048, it pairs the left row 2 with the
+        // right row 48
+        assert_eq!((a.value(0), b.value(0)), (2, 48));
+        assert_eq!((a.value(951), b.value(951)), (2, 999));
+    }
+}
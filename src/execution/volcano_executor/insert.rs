@@ -1,13 +1,14 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use arrow::datatypes::{Field, Schema, SchemaRef};
+use arrow::array::Int64Array;
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
 use arrow::record_batch::RecordBatch;
 use derive_new::new;
 use futures_async_stream::try_stream;
 
 use super::CreateTable;
-use crate::catalog_v2::DataTable;
+use crate::catalog_v2::{DataTable, TableStatistics};
 use crate::execution::{
     BoxedExecutor, ExecutionContext, ExecutorError, ExpressionExecutor, PhysicalInsert,
 };
@@ -79,20 +80,42 @@ impl Insert {
 
     #[try_stream(boxed, ok = RecordBatch, error = ExecutorError)]
     pub async fn execute(self, context: Arc<ExecutionContext>) {
-        let (table, schema, exprs) = if let Some(create_table_info) = self.plan.create_table_info {
-            // create table as
-            let table = CreateTable::create_table(context.clone(), &create_table_info)?;
-            Self::create_table_bound_info(table)?
-        } else {
+        let mut plan = self.plan;
+        let create_table_info = plan.create_table_info.take();
+        let (table, schema, exprs) = match &create_table_info {
+            // create table as, the table is created once its rows are read, so a failed query
+            // leaves no table behind
+            Some(info) => Self::create_table_bound_info(CreateTable::data_table(info))?,
             // insert into
-            Self::insert_into_bound_info(self.plan)?
+            None => Self::insert_into_bound_info(plan)?,
         };
+        // every chunk is verified before any of them is appended, so a failed insert leaves the
+        // table unchanged
+        let mut chunks = vec![];
+        let mut inserted_stats = TableStatistics::new(table.column_definitions.len());
         #[for_await]
         for batch in self.child {
             let batch = batch?;
             let columns = ExpressionExecutor::execute(&exprs, &batch)?;
             let chunk = RecordBatch::try_new(schema.clone(), columns)?;
-            LocalStorage::append(context.clone_client_context(), &table, chunk);
+            table.verify_append_constraints(&chunk)?;
+            inserted_stats.update(&chunk)?;
+            chunks.push(chunk);
         }
+        if let Some(info) = &create_table_info {
+            CreateTable::create_table(context.clone(), info)?;
+        }
+        // keep the statistics of the table current for the optimizer
+        LocalStorage::append_with_statistics(
+            context.clone_client_context(),
+            &table,
+            chunks,
+            &inserted_stats,
+        )?;
+
+        // the count of the inserted rows, like copy to
+        let fields = vec![Field::new("Count", DataType::Int64, false)];
+        let array = Arc::new(Int64Array::from(vec![inserted_stats.row_count as i64]));
+        yield RecordBatch::try_new(Arc::new(Schema::new(fields)), vec![array])?;
     }
 }
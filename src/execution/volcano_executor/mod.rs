@@ -1,25 +1,33 @@
+mod analyze;
 mod column_data_scan;
+mod copy_to_file;
 mod create_table;
+mod cross_product;
 mod dummy_scan;
 mod expression_scan;
 mod filter;
 mod insert;
 mod limit;
 mod projection;
+mod set;
 mod table_scan;
 use std::sync::Arc;
 
+pub use analyze::*;
 use arrow::record_batch::RecordBatch;
 pub use column_data_scan::*;
+pub use copy_to_file::*;
 pub use create_table::*;
+pub use cross_product::*;
 pub use dummy_scan::*;
 pub use expression_scan::*;
 pub use filter::*;
 use futures::stream::BoxStream;
-use futures::TryStreamExt;
+use futures_async_stream::try_stream;
 pub use insert::*;
 pub use limit::*;
 pub use projection::*;
+pub use set::*;
 pub use table_scan::*;
 
 use super::{ExecutionContext, ExecutorError, PhysicalOperator};
@@ -34,8 +42,13 @@ impl VolcanoExecutor {
         VolcanoExecutor::default()
     }
 
-    fn build(&self, plan: PhysicalOperator, context: Arc<ExecutionContext>) -> BoxedExecutor {
-        match plan {
+    pub(crate) fn build(
+        &self,
+        plan: PhysicalOperator,
+        context: Arc<ExecutionContext>,
+    ) -> BoxedExecutor {
+        let interrupt_context = context.clone();
+        let executor = match plan {
             PhysicalOperator::PhysicalCreateTable(op) => CreateTable::new(op).execute(context),
             PhysicalOperator::PhysicalExpressionScan(op) => {
                 let child = op.base.children.first().unwrap().clone();
@@ -67,19 +80,34 @@ impl VolcanoExecutor {
                 let child_executor = self.build(child, context.clone());
                 Limit::new(op, child_executor).execute(context)
             }
-        }
+            PhysicalOperator::PhysicalCrossProduct(op) => {
+                let left = op.base.children[0].clone();
+                let right = op.base.children[1].clone();
+                let left_executor = self.build(left, context.clone());
+                let right_executor = self.build(right, context.clone());
+                CrossProduct::new(op, left_executor, right_executor).execute(context)
+            }
+            PhysicalOperator::PhysicalAnalyze(op) => Analyze::new(op).execute(context),
+            PhysicalOperator::PhysicalSet(op) => SetOption::new(op).execute(context),
+            PhysicalOperator::PhysicalCopyToFile(op) => {
+                let child = op.base.children.first().unwrap().clone();
+                let child_executor = self.build(child, context.clone());
+                CopyToFile::new(op, child_executor).execute(context)
+            }
+        };
+        Self::check_interrupted(executor, interrupt_context)
     }
 
-    pub(crate) async fn try_execute(
-        &self,
-        plan: PhysicalOperator,
-        context: Arc<ExecutionContext>,
-    ) -> Result<Vec<RecordBatch>, ExecutorError> {
-        let mut output = Vec::new();
-        let mut volcano_executor = self.build(plan, context.clone());
-        while let Some(batch) = volcano_executor.try_next().await? {
-            output.push(batch);
+    /// Stop the executor with an error when the query is interrupted, it's checked before each
+    /// batch the executor produces.
+    #[try_stream(boxed, ok = RecordBatch, error = ExecutorError)]
+    async fn check_interrupted(executor: BoxedExecutor, context: Arc<ExecutionContext>) {
+        context.check_interrupted()?;
+        #[for_await]
+        for batch in executor {
+            let batch = batch?;
+            context.check_interrupted()?;
+            yield batch;
         }
-        Ok(output)
     }
 }
@@ -0,0 +1,39 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow::array::StringArray;
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use arrow::record_batch::RecordBatch;
+use derive_new::new;
+use futures_async_stream::try_stream;
+
+use crate::execution::{ExecutionContext, ExecutorError, PhysicalSet};
+use crate::main_entry::ConfigOption;
+
+#[derive(new)]
+pub struct SetOption {
+    pub(crate) plan: PhysicalSet,
+}
+
+impl SetOption {
+    #[try_stream(boxed, ok = RecordBatch, error = ExecutorError)]
+    pub async fn execute(self, context: Arc<ExecutionContext>) {
+        match &self.plan.option {
+            ConfigOption::Client(option) => context.client_context.apply_config(option),
+            ConfigOption::Database(option) => match context.client_context.db.config.write() {
+                Ok(mut config) => config.apply(option),
+                Err(_) => {
+                    return Err(ExecutorError::InternalError(
+                        "database config lock poisoned".to_string(),
+                    ))
+                }
+            },
+        }
+        let array = Arc::new(StringArray::from(vec![format!("SET {}", self.plan.option)]));
+        let fields = vec![Field::new("success", DataType::Utf8, false)];
+        yield RecordBatch::try_new(
+            SchemaRef::new(Schema::new_with_metadata(fields, HashMap::new())),
+            vec![array],
+        )?;
+    }
+}
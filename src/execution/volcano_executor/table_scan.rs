@@ -15,7 +15,14 @@ pub struct TableScan {
 impl TableScan {
     #[try_stream(boxed, ok = RecordBatch, error = ExecutorError)]
     pub async fn execute(self, context: Arc<ExecutionContext>) {
-        let schema = SchemaUtil::new_schema_ref(&self.plan.names, &self.plan.returned_types);
+        let schema =
+            SchemaUtil::new_schema_ref(&self.plan.projected_names(), &self.plan.projected_types());
+        // the table function returns all columns, so we need to filter out the unused columns
+        let column_ids = if self.plan.function.projection_pushdown {
+            vec![]
+        } else {
+            self.plan.column_ids
+        };
 
         let bind_data = self.plan.bind_data;
 
@@ -28,7 +35,14 @@ impl TableScan {
         #[for_await]
         for batch in scan_stream {
             let batch = batch?;
-            let columns = batch.columns().to_vec();
+            let columns = if column_ids.is_empty() {
+                batch.columns().to_vec()
+            } else {
+                column_ids
+                    .iter()
+                    .map(|idx| batch.column(*idx).clone())
+                    .collect::<Vec<_>>()
+            };
             yield RecordBatch::try_new(schema.clone(), columns)?
         }
     }
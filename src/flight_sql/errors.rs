@@ -0,0 +1,31 @@
+use arrow::error::ArrowError;
+use tonic::Status;
+
+use crate::execution::ExecutorError;
+use crate::main_entry::DatabaseError;
+
+impl From<DatabaseError> for Status {
+    fn from(e: DatabaseError) -> Self {
+        match &e {
+            DatabaseError::ParserError(_) | DatabaseError::PlannerError(_) => {
+                Status::invalid_argument(e.to_string())
+            }
+            DatabaseError::CatalogError(_) => Status::not_found(e.to_string()),
+            DatabaseError::PreparedStatementTypesChanged => {
+                Status::failed_precondition(e.to_string())
+            }
+            DatabaseError::ExecutorError(ExecutorError::Interrupted) => {
+                Status::cancelled(e.to_string())
+            }
+            DatabaseError::ExecutorError(ExecutorError::StatementTimeout) => {
+                Status::deadline_exceeded(e.to_string())
+            }
+            _ => Status::internal(e.to_string()),
+        }
+    }
+}
+
+/// An invalid arrow message sent by a client.
+pub(crate) fn invalid_arrow_data(e: ArrowError) -> Status {
+    Status::invalid_argument(format!("invalid arrow data: {}", e))
+}
@@ -0,0 +1,193 @@
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, BinaryArray, StringArray};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use arrow::ipc::writer::IpcWriteOptions;
+use arrow::record_batch::RecordBatch;
+use arrow_flight::sql::{CommandGetDbSchemas, CommandGetTables};
+use arrow_flight::{IpcMessage, SchemaAsIpc};
+
+use crate::catalog_v2::{Catalog, CatalogEntry};
+use crate::main_entry::{ClientContext, DatabaseError};
+
+/// The type of the tables of sqlrs in the results of GetTables.
+const TABLE_TYPE: &str = "TABLE";
+
+/// The schema of the result of GetDbSchemas, defined by the Flight SQL protocol.
+pub(crate) fn db_schemas_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("catalog_name", DataType::Utf8, true),
+        Field::new("db_schema_name", DataType::Utf8, false),
+    ]))
+}
+
+/// The schema of the result of GetTables, the schemas of the tables are only included when
+/// they are asked for.
+pub(crate) fn tables_schema(include_schema: bool) -> SchemaRef {
+    let mut fields = vec![
+        Field::new("catalog_name", DataType::Utf8, true),
+        Field::new("db_schema_name", DataType::Utf8, true),
+        Field::new("table_name", DataType::Utf8, false),
+        Field::new("table_type", DataType::Utf8, false),
+    ];
+    if include_schema {
+        fields.push(Field::new("table_schema", DataType::Binary, false));
+    }
+    Arc::new(Schema::new(fields))
+}
+
+/// The schemas matching the filters of GetDbSchemas. sqlrs has no catalogs, so a schema only
+/// matches an unspecified or empty catalog.
+pub(crate) fn db_schemas(
+    client_context: Arc<ClientContext>,
+    query: &CommandGetDbSchemas,
+) -> Result<RecordBatch, DatabaseError> {
+    let names = if matches_catalog(&query.catalog) {
+        Catalog::schema_names(client_context)?
+            .into_iter()
+            .filter(|name| matches_pattern(&query.db_schema_filter_pattern, name))
+            .collect()
+    } else {
+        vec![]
+    };
+    let catalogs = vec![None::<&str>; names.len()];
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(StringArray::from(catalogs)),
+        Arc::new(StringArray::from(names)),
+    ];
+    Ok(RecordBatch::try_new(db_schemas_schema(), columns)?)
+}
+
+/// The tables matching the filters of GetTables, in the order of their schemas and names.
+pub(crate) fn tables(
+    client_context: Arc<ClientContext>,
+    query: &CommandGetTables,
+) -> Result<RecordBatch, DatabaseError> {
+    let mut tables = vec![];
+    let matches_type =
+        query.table_types.is_empty() || query.table_types.iter().any(|ty| ty == TABLE_TYPE);
+    if matches_catalog(&query.catalog) && matches_type {
+        for schema in Catalog::schema_names(client_context.clone())? {
+            if !matches_pattern(&query.db_schema_filter_pattern, &schema) {
+                continue;
+            }
+            let entries = Catalog::scan_entries(client_context.clone(), schema, &|entry| {
+                matches!(entry, CatalogEntry::TableCatalogEntry(table)
+                    if matches_pattern(&query.table_name_filter_pattern, &table.base.name))
+            })?;
+            let mut entries = entries
+                .into_iter()
+                .filter_map(|entry| match entry {
+                    CatalogEntry::TableCatalogEntry(table) => Some(table),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            entries.sort_by(|a, b| a.base.name.cmp(&b.base.name));
+            tables.extend(entries);
+        }
+    }
+
+    let mut columns: Vec<ArrayRef> = vec![
+        Arc::new(StringArray::from(vec![None::<&str>; tables.len()])),
+        Arc::new(StringArray::from_iter_values(
+            tables.iter().map(|t| &t.schema_base.name),
+        )),
+        Arc::new(StringArray::from_iter_values(
+            tables.iter().map(|t| &t.base.name),
+        )),
+        Arc::new(StringArray::from(vec![TABLE_TYPE; tables.len()])),
+    ];
+    if query.include_schema {
+        let options = IpcWriteOptions::default();
+        let schemas = tables
+            .iter()
+            .map(|table| {
+                let fields = table
+                    .columns
+                    .iter()
+                    .map(|c| Field::new(&c.name, c.ty.clone().into(), !c.not_null))
+                    .collect();
+                let schema = Schema::new(fields);
+                let message = IpcMessage::try_from(SchemaAsIpc::new(&schema, &options))?;
+                Ok(message.0.to_vec())
+            })
+            .collect::<Result<Vec<_>, DatabaseError>>()?;
+        columns.push(Arc::new(BinaryArray::from_iter_values(schemas)));
+    }
+    Ok(RecordBatch::try_new(
+        tables_schema(query.include_schema),
+        columns,
+    )?)
+}
+
+fn matches_catalog(catalog: &Option<String>) -> bool {
+    catalog.as_deref().map_or(true, str::is_empty)
+}
+
+/// Whether the name matches a filter pattern, where `%` matches any characters and `_` a single
+/// character like the pattern of LIKE. No pattern matches every name.
+fn matches_pattern(pattern: &Option<String>, name: &str) -> bool {
+    match pattern {
+        Some(pattern) => {
+            let pattern = pattern.chars().collect::<Vec<_>>();
+            let name = name.chars().collect::<Vec<_>>();
+            matches_like(&pattern, &name)
+        }
+        None => true,
+    }
+}
+
+/// Match the name from left to right. On a mismatch, only the last `%` is retried with one more
+/// character of the name, the earlier ones never need to, so the time is O(pattern * name).
+fn matches_like(pattern: &[char], name: &[char]) -> bool {
+    let (mut p, mut n) = (0, 0);
+    // the position in the pattern after the last `%`, and the position in the name it resumes
+    let mut last_percent: Option<(usize, usize)> = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('%') => {
+                p += 1;
+                last_percent = Some((p, n));
+            }
+            Some(c) if *c == '_' || *c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match last_percent {
+                Some((percent_p, percent_n)) => {
+                    p = percent_p;
+                    n = percent_n + 1;
+                    last_percent = Some((percent_p, n));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '%')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_pattern() {
+        let pattern = |p: &str| Some(p.to_string());
+        assert!(matches_pattern(&None, "t1"));
+        assert!(matches_pattern(&pattern("t%"), "t1"));
+        assert!(matches_pattern(&pattern("%1"), "t1"));
+        assert!(matches_pattern(&pattern("_1"), "t1"));
+        assert!(!matches_pattern(&pattern("_"), "t1"));
+        assert!(!matches_pattern(&pattern("t2%"), "t1"));
+        assert!(matches_pattern(&pattern("%"), ""));
+        assert!(matches_pattern(&pattern("t%_%1"), "tab_1"));
+        assert!(!matches_pattern(&pattern(""), "t1"));
+        // a pattern of many `%` against a long name doesn't backtrack exponentially
+        let name = "a".repeat(1000);
+        assert!(!matches_pattern(
+            &pattern(&"%a".repeat(20)).map(|p| p + "b"),
+            &name
+        ));
+        assert!(matches_pattern(&pattern(&"%a".repeat(20)), &name));
+    }
+}
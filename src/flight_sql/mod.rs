@@ -0,0 +1,473 @@
+//! A Flight SQL service, for the clients reading results as arrow record batches. A client opens
+//! a session by a handshake, and sends the token of the handshake as a bearer token with its
+//! other requests. Each session has its own client context, and it's closed once it's idle for
+//! the session timeout.
+mod errors;
+mod metadata;
+mod session;
+
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use arrow::datatypes::{Schema, SchemaRef};
+use arrow::ipc::writer::IpcWriteOptions;
+use arrow::record_batch::RecordBatch;
+use arrow_flight::flight_service_server::{FlightService, FlightServiceServer};
+use arrow_flight::sql::server::FlightSqlService;
+use arrow_flight::sql::{
+    ActionClosePreparedStatementRequest, ActionCreatePreparedStatementRequest,
+    ActionCreatePreparedStatementResult, CommandGetCatalogs, CommandGetCrossReference,
+    CommandGetDbSchemas, CommandGetExportedKeys, CommandGetImportedKeys, CommandGetPrimaryKeys,
+    CommandGetSqlInfo, CommandGetTableTypes, CommandGetTables, CommandPreparedStatementQuery,
+    CommandPreparedStatementUpdate, CommandStatementQuery, CommandStatementUpdate, ProstMessageExt,
+    SqlInfo, TicketStatementQuery,
+};
+use arrow_flight::utils::flight_data_from_arrow_batch;
+use arrow_flight::{
+    Action, FlightData, FlightDescriptor, FlightEndpoint, FlightInfo, HandshakeRequest,
+    HandshakeResponse, IpcMessage, SchemaAsIpc, Ticket,
+};
+pub(crate) use errors::*;
+use futures::stream::{self, BoxStream};
+use futures::{Stream, StreamExt};
+use futures_async_stream::try_stream;
+use prost::Message;
+use session::FlightSession;
+use tokio::net::TcpListener;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::metadata::MetadataMap;
+use tonic::transport::Server;
+use tonic::{Request, Response, Status, Streaming};
+
+use crate::execution::SchemaUtil;
+use crate::main_entry::{Database, DatabaseError, QueryResult};
+
+/// The metadata of the bearer token of a session.
+const AUTHORIZATION: &str = "authorization";
+const BEARER_PREFIX: &str = "Bearer ";
+/// The count of the random bytes of a session token.
+const TOKEN_BYTES: usize = 32;
+/// The default time a session is kept without requests.
+const DEFAULT_SESSION_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// The Flight SQL service of a database.
+pub struct FlightSqlServer {
+    database: Database,
+    /// The sessions opened by handshakes, by their tokens
+    sessions: Mutex<HashMap<String, Arc<FlightSession>>>,
+    /// A session idle for longer is closed
+    session_timeout: Duration,
+}
+
+impl FlightSqlServer {
+    pub fn new(database: Database) -> Self {
+        Self {
+            database,
+            sessions: Mutex::new(HashMap::new()),
+            session_timeout: DEFAULT_SESSION_TIMEOUT,
+        }
+    }
+
+    /// Close the sessions without requests for the timeout, their clients have to handshake
+    /// again.
+    pub fn with_session_timeout(mut self, session_timeout: Duration) -> Self {
+        self.session_timeout = session_timeout;
+        self
+    }
+
+    /// Serve the Flight SQL requests of the connections of the listener.
+    pub async fn serve(self, listener: TcpListener) -> Result<(), tonic::transport::Error> {
+        Server::builder()
+            .add_service(FlightServiceServer::new(self))
+            .serve_with_incoming(TcpListenerStream::new(listener))
+            .await
+    }
+
+    /// The session of the bearer token of a request.
+    fn session(&self, metadata: &MetadataMap) -> Result<Arc<FlightSession>, Status> {
+        let token = metadata
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix(BEARER_PREFIX))
+            .ok_or_else(|| Status::unauthenticated("no session, call Handshake to open one"))?;
+        let mut sessions = self.sessions.lock().unwrap();
+        let session = sessions
+            .get(token)
+            .cloned()
+            .ok_or_else(|| Status::unauthenticated("the session does not exist"))?;
+        if session.idle_time() > self.session_timeout {
+            sessions.remove(token);
+            return Err(Status::unauthenticated("the session expired"));
+        }
+        session.touch();
+        Ok(session)
+    }
+
+    /// Open a session, the expired sessions are closed at the same time.
+    fn open_session(&self) -> Result<String, Status> {
+        let mut bytes = [0u8; TOKEN_BYTES];
+        getrandom::getrandom(&mut bytes)
+            .map_err(|e| Status::internal(format!("failed to generate a session token: {}", e)))?;
+        let token = bytes
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<String>();
+        let session = Arc::new(FlightSession::new(self.database.connect()));
+        let mut sessions = self.sessions.lock().unwrap();
+        sessions.retain(|_, open| open.idle_time() <= self.session_timeout);
+        sessions.insert(token.clone(), session);
+        Ok(token)
+    }
+}
+
+/// The information of a flight, it's fetched by DoGet with the ticket.
+fn flight_info(
+    schema: &Schema,
+    ticket: impl ProstMessageExt,
+    descriptor: FlightDescriptor,
+) -> Result<Response<FlightInfo>, Status> {
+    let options = IpcWriteOptions::default();
+    let message =
+        IpcMessage::try_from(SchemaAsIpc::new(schema, &options)).map_err(DatabaseError::from)?;
+    let endpoint = FlightEndpoint {
+        ticket: Some(Ticket {
+            ticket: ticket.as_any().encode_to_vec().into(),
+        }),
+        location: vec![],
+    };
+    let info = FlightInfo::new(message, Some(descriptor), vec![endpoint], -1, -1);
+    Ok(Response::new(info))
+}
+
+/// Encode the batches of a result as flight data, they are sent while the query is executed.
+/// The batches have the names of the result.
+#[try_stream(boxed, ok = FlightData, error = Status)]
+async fn flight_data_stream(
+    schema: SchemaRef,
+    batches: BoxStream<'static, Result<RecordBatch, DatabaseError>>,
+) {
+    let options = IpcWriteOptions::default();
+    yield FlightData::from(SchemaAsIpc::new(&schema, &options));
+    #[for_await]
+    for batch in batches {
+        let batch = RecordBatch::try_new(schema.clone(), batch?.columns().to_vec())
+            .map_err(DatabaseError::from)?;
+        let (dictionaries, data) = flight_data_from_arrow_batch(&batch, &options);
+        for dictionary in dictionaries {
+            yield dictionary;
+        }
+        yield data;
+    }
+}
+
+fn result_stream(result: QueryResult) -> Response<<FlightSqlServer as FlightService>::DoGetStream> {
+    let schema = result.base().schema();
+    Response::new(flight_data_stream(schema, result.into_stream()))
+}
+
+fn batch_stream(batch: RecordBatch) -> Response<<FlightSqlServer as FlightService>::DoGetStream> {
+    let schema = batch.schema();
+    Response::new(flight_data_stream(
+        schema,
+        stream::iter(vec![Ok(batch)]).boxed(),
+    ))
+}
+
+#[tonic::async_trait]
+impl FlightSqlService for FlightSqlServer {
+    type FlightService = FlightSqlServer;
+
+    /// Open a session, its random token is returned as the payload and as the bearer token of the
+    /// response.
+    async fn do_handshake(
+        &self,
+        _request: Request<Streaming<HandshakeRequest>>,
+    ) -> Result<
+        Response<Pin<Box<dyn Stream<Item = Result<HandshakeResponse, Status>> + Send>>>,
+        Status,
+    > {
+        let token = self.open_session()?;
+
+        let handshake = HandshakeResponse {
+            protocol_version: 0,
+            payload: token.clone().into_bytes().into(),
+        };
+        let output: Pin<Box<dyn Stream<Item = Result<HandshakeResponse, Status>> + Send>> =
+            Box::pin(stream::iter(vec![Ok(handshake)]));
+        let mut response = Response::new(output);
+        let bearer = format!("{}{}", BEARER_PREFIX, token);
+        response
+            .metadata_mut()
+            .insert(AUTHORIZATION, bearer.parse().unwrap());
+        Ok(response)
+    }
+
+    async fn get_flight_info_statement(
+        &self,
+        query: CommandStatementQuery,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        let session = self.session(request.metadata())?;
+        // the statement is prepared for the schema of its result, and executed by DoGet
+        let statement = session.connection.prepare(&query.query)?;
+        let schema = SchemaUtil::new_schema_ref(&statement.names(), &statement.types());
+        let ticket = TicketStatementQuery {
+            statement_handle: query.query.into_bytes().into(),
+        };
+        flight_info(&schema, ticket, request.into_inner())
+    }
+
+    async fn get_flight_info_prepared_statement(
+        &self,
+        query: CommandPreparedStatementQuery,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        let session = self.session(request.metadata())?;
+        let statement = session.prepared_statement(&query.prepared_statement_handle)?;
+        flight_info(&statement.dataset_schema(), query, request.into_inner())
+    }
+
+    async fn get_flight_info_catalogs(
+        &self,
+        _query: CommandGetCatalogs,
+        _request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        Err(Status::unimplemented("GetCatalogs is not supported"))
+    }
+
+    async fn get_flight_info_schemas(
+        &self,
+        query: CommandGetDbSchemas,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        self.session(request.metadata())?;
+        flight_info(&metadata::db_schemas_schema(), query, request.into_inner())
+    }
+
+    async fn get_flight_info_tables(
+        &self,
+        query: CommandGetTables,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        self.session(request.metadata())?;
+        let schema = metadata::tables_schema(query.include_schema);
+        flight_info(&schema, query, request.into_inner())
+    }
+
+    async fn get_flight_info_table_types(
+        &self,
+        _query: CommandGetTableTypes,
+        _request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        Err(Status::unimplemented("GetTableTypes is not supported"))
+    }
+
+    async fn get_flight_info_sql_info(
+        &self,
+        _query: CommandGetSqlInfo,
+        _request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        Err(Status::unimplemented("GetSqlInfo is not supported"))
+    }
+
+    async fn get_flight_info_primary_keys(
+        &self,
+        _query: CommandGetPrimaryKeys,
+        _request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        Err(Status::unimplemented("GetPrimaryKeys is not supported"))
+    }
+
+    async fn get_flight_info_exported_keys(
+        &self,
+        _query: CommandGetExportedKeys,
+        _request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        Err(Status::unimplemented("GetExportedKeys is not supported"))
+    }
+
+    async fn get_flight_info_imported_keys(
+        &self,
+        _query: CommandGetImportedKeys,
+        _request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        Err(Status::unimplemented("GetImportedKeys is not supported"))
+    }
+
+    async fn get_flight_info_cross_reference(
+        &self,
+        _query: CommandGetCrossReference,
+        _request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        Err(Status::unimplemented("GetCrossReference is not supported"))
+    }
+
+    async fn do_get_statement(
+        &self,
+        ticket: TicketStatementQuery,
+        request: Request<Ticket>,
+    ) -> Result<Response<<Self as FlightService>::DoGetStream>, Status> {
+        let session = self.session(request.metadata())?;
+        let query = String::from_utf8(ticket.statement_handle.to_vec())
+            .map_err(|_| Status::invalid_argument("statement handle is not valid UTF-8"))?;
+        let result = session.connection.query(&query).await?;
+        Ok(result_stream(result))
+    }
+
+    async fn do_get_prepared_statement(
+        &self,
+        query: CommandPreparedStatementQuery,
+        request: Request<Ticket>,
+    ) -> Result<Response<<Self as FlightService>::DoGetStream>, Status> {
+        let session = self.session(request.metadata())?;
+        let statement = session.prepared_statement(&query.prepared_statement_handle)?;
+        let result = statement.execute_query().await?;
+        Ok(result_stream(result))
+    }
+
+    async fn do_get_catalogs(
+        &self,
+        _query: CommandGetCatalogs,
+        _request: Request<Ticket>,
+    ) -> Result<Response<<Self as FlightService>::DoGetStream>, Status> {
+        Err(Status::unimplemented("GetCatalogs is not supported"))
+    }
+
+    async fn do_get_schemas(
+        &self,
+        query: CommandGetDbSchemas,
+        request: Request<Ticket>,
+    ) -> Result<Response<<Self as FlightService>::DoGetStream>, Status> {
+        let session = self.session(request.metadata())?;
+        let client_context = session.connection.client_context().clone();
+        Ok(batch_stream(metadata::db_schemas(client_context, &query)?))
+    }
+
+    async fn do_get_tables(
+        &self,
+        query: CommandGetTables,
+        request: Request<Ticket>,
+    ) -> Result<Response<<Self as FlightService>::DoGetStream>, Status> {
+        let session = self.session(request.metadata())?;
+        let client_context = session.connection.client_context().clone();
+        Ok(batch_stream(metadata::tables(client_context, &query)?))
+    }
+
+    async fn do_get_table_types(
+        &self,
+        _query: CommandGetTableTypes,
+        _request: Request<Ticket>,
+    ) -> Result<Response<<Self as FlightService>::DoGetStream>, Status> {
+        Err(Status::unimplemented("GetTableTypes is not supported"))
+    }
+
+    async fn do_get_sql_info(
+        &self,
+        _query: CommandGetSqlInfo,
+        _request: Request<Ticket>,
+    ) -> Result<Response<<Self as FlightService>::DoGetStream>, Status> {
+        Err(Status::unimplemented("GetSqlInfo is not supported"))
+    }
+
+    async fn do_get_primary_keys(
+        &self,
+        _query: CommandGetPrimaryKeys,
+        _request: Request<Ticket>,
+    ) -> Result<Response<<Self as FlightService>::DoGetStream>, Status> {
+        Err(Status::unimplemented("GetPrimaryKeys is not supported"))
+    }
+
+    async fn do_get_exported_keys(
+        &self,
+        _query: CommandGetExportedKeys,
+        _request: Request<Ticket>,
+    ) -> Result<Response<<Self as FlightService>::DoGetStream>, Status> {
+        Err(Status::unimplemented("GetExportedKeys is not supported"))
+    }
+
+    async fn do_get_imported_keys(
+        &self,
+        _query: CommandGetImportedKeys,
+        _request: Request<Ticket>,
+    ) -> Result<Response<<Self as FlightService>::DoGetStream>, Status> {
+        Err(Status::unimplemented("GetImportedKeys is not supported"))
+    }
+
+    async fn do_get_cross_reference(
+        &self,
+        _query: CommandGetCrossReference,
+        _request: Request<Ticket>,
+    ) -> Result<Response<<Self as FlightService>::DoGetStream>, Status> {
+        Err(Status::unimplemented("GetCrossReference is not supported"))
+    }
+
+    /// Run the statements of an update, returns the count of the rows written by an insert or a
+    /// copy, like the tag of the command in the postgres protocol.
+    async fn do_put_statement_update(
+        &self,
+        ticket: CommandStatementUpdate,
+        request: Request<Streaming<FlightData>>,
+    ) -> Result<i64, Status> {
+        let session = self.session(request.metadata())?;
+        let result = session.connection.query(&ticket.query).await?;
+        Ok(result.affected_rows().await?)
+    }
+
+    /// Bind the parameter sets of a prepared query.
+    async fn do_put_prepared_statement_query(
+        &self,
+        query: CommandPreparedStatementQuery,
+        request: Request<Streaming<FlightData>>,
+    ) -> Result<Response<<Self as FlightService>::DoPutStream>, Status> {
+        let session = self.session(request.metadata())?;
+        let statement = session.prepared_statement(&query.prepared_statement_handle)?;
+        statement.bind_parameters(request.into_inner()).await?;
+        Ok(Response::new(stream::empty().boxed()))
+    }
+
+    /// Execute a prepared update once for each parameter set of the request.
+    async fn do_put_prepared_statement_update(
+        &self,
+        query: CommandPreparedStatementUpdate,
+        request: Request<Streaming<FlightData>>,
+    ) -> Result<i64, Status> {
+        let session = self.session(request.metadata())?;
+        let statement = session.prepared_statement(&query.prepared_statement_handle)?;
+        statement.bind_parameters(request.into_inner()).await?;
+        statement.execute_update().await
+    }
+
+    async fn do_action_create_prepared_statement(
+        &self,
+        query: ActionCreatePreparedStatementRequest,
+        request: Request<Action>,
+    ) -> Result<ActionCreatePreparedStatementResult, Status> {
+        let session = self.session(request.metadata())?;
+        let (handle, statement) = session.prepare(&query.query)?;
+        let options = IpcWriteOptions::default();
+        let dataset_schema = statement.dataset_schema();
+        let parameter_schema = statement.parameter_schema();
+        let encode = |schema: &Schema| {
+            IpcMessage::try_from(SchemaAsIpc::new(schema, &options)).map_err(DatabaseError::from)
+        };
+        Ok(ActionCreatePreparedStatementResult {
+            prepared_statement_handle: handle.into(),
+            dataset_schema: encode(&dataset_schema)?.0.into(),
+            parameter_schema: encode(&parameter_schema)?.0.into(),
+        })
+    }
+
+    async fn do_action_close_prepared_statement(
+        &self,
+        query: ActionClosePreparedStatementRequest,
+        request: Request<Action>,
+    ) {
+        if let Ok(session) = self.session(request.metadata()) {
+            session.close_prepared_statement(&query.prepared_statement_handle);
+        }
+    }
+
+    async fn register_sql_info(&self, _id: i32, _result: &SqlInfo) {}
+}
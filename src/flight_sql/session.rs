@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use arrow::datatypes::{Schema, SchemaRef};
+use arrow::ipc::{root_as_message, MessageHeader};
+use arrow_flight::utils::flight_data_to_arrow_batch;
+use arrow_flight::FlightData;
+use futures::{Stream, StreamExt};
+use tonic::Status;
+
+use super::invalid_arrow_data;
+use crate::execution::SchemaUtil;
+use crate::main_entry::{Connection, PreparedStatement, QueryResult};
+use crate::types_v2::ScalarValue;
+
+/// A session of a Flight SQL client, opened by a handshake. Its statements run on the client
+/// context of its connection, and its prepared statements are only visible to the session.
+pub(crate) struct FlightSession {
+    pub(crate) connection: Connection,
+    prepared_statements: Mutex<HashMap<Vec<u8>, Arc<FlightPreparedStatement>>>,
+    next_handle: AtomicUsize,
+    /// The time of the last request of the session
+    last_used: Mutex<Instant>,
+}
+
+/// A statement prepared by CreatePreparedStatement, with the parameter sets bound by DoPut.
+pub(crate) struct FlightPreparedStatement {
+    pub(crate) statement: PreparedStatement,
+    /// The values of the parameters, a row of the parameter batches is a parameter set
+    parameter_sets: Mutex<Vec<Vec<ScalarValue>>>,
+}
+
+impl FlightSession {
+    pub(crate) fn new(connection: Connection) -> Self {
+        Self {
+            connection,
+            prepared_statements: Mutex::new(HashMap::new()),
+            next_handle: AtomicUsize::new(1),
+            last_used: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Record a request of the session, it's idle since then.
+    pub(crate) fn touch(&self) {
+        *self.last_used.lock().unwrap() = Instant::now();
+    }
+
+    pub(crate) fn idle_time(&self) -> Duration {
+        self.last_used.lock().unwrap().elapsed()
+    }
+
+    /// Prepare the statement of the query, returns the handle of the prepared statement.
+    pub(crate) fn prepare(
+        &self,
+        query: &str,
+    ) -> Result<(Vec<u8>, Arc<FlightPreparedStatement>), Status> {
+        let statement = Arc::new(FlightPreparedStatement {
+            // the client keeps the schemas of the result and of the parameters
+            statement: self.connection.prepare(query)?.with_fixed_types(),
+            parameter_sets: Mutex::new(vec![]),
+        });
+        let handle = self.next_handle.fetch_add(1, Ordering::Relaxed);
+        let handle = handle.to_string().into_bytes();
+        self.prepared_statements
+            .lock()
+            .unwrap()
+            .insert(handle.clone(), statement.clone());
+        Ok((handle, statement))
+    }
+
+    pub(crate) fn prepared_statement(
+        &self,
+        handle: &[u8],
+    ) -> Result<Arc<FlightPreparedStatement>, Status> {
+        self.prepared_statements
+            .lock()
+            .unwrap()
+            .get(handle)
+            .cloned()
+            .ok_or_else(|| Status::not_found("prepared statement does not exist"))
+    }
+
+    pub(crate) fn close_prepared_statement(&self, handle: &[u8]) {
+        self.prepared_statements.lock().unwrap().remove(handle);
+    }
+}
+
+impl FlightPreparedStatement {
+    /// The schema of the result of the statement.
+    pub(crate) fn dataset_schema(&self) -> SchemaRef {
+        SchemaUtil::new_schema_ref(&self.statement.names(), &self.statement.types())
+    }
+
+    /// The schema of the parameters, the field of `$1` is the first one.
+    pub(crate) fn parameter_schema(&self) -> SchemaRef {
+        let types = self.statement.parameter_types();
+        let names = (1..=types.len())
+            .map(|i| format!("${}", i))
+            .collect::<Vec<_>>();
+        SchemaUtil::new_schema_ref(&names, &types)
+    }
+
+    /// Read the parameter batches sent by DoPut, they replace the parameter sets bound before.
+    /// The batches are read with the schema sent by the client, or the schema of the parameters
+    /// when the client sent none.
+    pub(crate) async fn bind_parameters(
+        &self,
+        mut stream: impl Stream<Item = Result<FlightData, Status>> + Unpin,
+    ) -> Result<(), Status> {
+        let mut schema = self.parameter_schema();
+        let dictionaries_by_id = HashMap::new();
+        let mut parameter_sets = vec![];
+        while let Some(data) = stream.next().await {
+            let data = data?;
+            if data.data_header.is_empty() {
+                continue;
+            }
+            let message = root_as_message(&data.data_header[..])
+                .map_err(|e| Status::invalid_argument(format!("invalid arrow message: {}", e)))?;
+            match message.header_type() {
+                MessageHeader::Schema => {
+                    schema = Arc::new(Schema::try_from(&data).map_err(invalid_arrow_data)?);
+                }
+                MessageHeader::RecordBatch => {
+                    let batch =
+                        flight_data_to_arrow_batch(&data, schema.clone(), &dictionaries_by_id)
+                            .map_err(invalid_arrow_data)?;
+                    for row in 0..batch.num_rows() {
+                        let values = batch
+                            .columns()
+                            .iter()
+                            .map(|column| ScalarValue::try_from_array(column, row))
+                            .collect::<Result<Vec<_>, _>>()
+                            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+                        parameter_sets.push(values);
+                    }
+                }
+                other => {
+                    return Err(Status::invalid_argument(format!(
+                        "unexpected arrow message {:?}",
+                        other
+                    )))
+                }
+            }
+        }
+        *self.parameter_sets.lock().unwrap() = parameter_sets;
+        Ok(())
+    }
+
+    /// Execute the statement with its single parameter set, or without parameters.
+    pub(crate) async fn execute_query(&self) -> Result<QueryResult, Status> {
+        let parameter_sets = self.parameter_sets.lock().unwrap().clone();
+        let parameters = match parameter_sets.len() {
+            0 => vec![],
+            1 => parameter_sets.into_iter().next().unwrap(),
+            n => {
+                return Err(Status::invalid_argument(format!(
+                    "a query is executed with one parameter set, but {} are bound",
+                    n
+                )))
+            }
+        };
+        Ok(self.statement.execute(parameters).await?)
+    }
+
+    /// Execute the statement once for each of its parameter sets, or once without parameters.
+    /// Returns the count of the rows written by all the executions.
+    pub(crate) async fn execute_update(&self) -> Result<i64, Status> {
+        let mut parameter_sets = self.parameter_sets.lock().unwrap().clone();
+        if parameter_sets.is_empty() {
+            parameter_sets.push(vec![]);
+        }
+        let mut count = 0;
+        for parameters in parameter_sets {
+            count += self
+                .statement
+                .execute(parameters)
+                .await?
+                .affected_rows()
+                .await?;
+        }
+        Ok(count)
+    }
+}
@@ -1,9 +1,11 @@
 use std::io;
 
 use arrow::error::ArrowError;
+use parquet::errors::ParquetError;
 
 use crate::catalog_v2::CatalogError;
 use crate::planner_v2::BindError;
+use crate::storage_v2::StorageError;
 use crate::types_v2::TypeError;
 
 pub type FunctionResult<T> = Result<T, FunctionError>;
@@ -29,6 +31,24 @@ pub enum FunctionError {
         #[source]
         ArrowError,
     ),
+    #[error("parquet error: {0}")]
+    ParquetError(
+        #[from]
+        #[source]
+        ParquetError,
+    ),
+    #[error("json error: {0}")]
+    JsonError(
+        #[from]
+        #[source]
+        serde_json::Error,
+    ),
+    #[error("storage error: {0}")]
+    StorageError(
+        #[from]
+        #[source]
+        StorageError,
+    ),
     #[error("Internal error: {0}")]
     InternalError(String),
     #[error("Cast error: {0}")]
@@ -24,7 +24,13 @@ pub enum FunctionData {
     SeqTableScanInputData(Box<SeqTableScanInputData>),
     SqlrsTablesData(Box<SqlrsTablesData>),
     SqlrsColumnsData(Box<SqlrsColumnsData>),
+    SqlrsColumnStatsData(Box<SqlrsColumnStatsData>),
     ReadCSVInputData(Box<ReadCSVInputData>),
+    ReadParquetInputData(Box<ReadParquetInputData>),
+    ReadJSONInputData(Box<ReadJSONInputData>),
+    ReadArrowInputData(Box<ReadArrowInputData>),
+    SniffCSVData(Box<SniffCSVData>),
+    ArrowScanInputData(Box<ArrowScanInputData>),
 }
 
 #[derive(new)]
@@ -58,11 +64,18 @@ impl BuiltinFunctions {
     pub fn initialize(&mut self) -> Result<(), FunctionError> {
         SqlrsTablesFunc::register_function(self)?;
         SqlrsColumnsFunc::register_function(self)?;
+        SqlrsColumnStatsFunc::register_function(self)?;
         AddFunction::register_function(self)?;
         SubtractFunction::register_function(self)?;
         MultiplyFunction::register_function(self)?;
         DivideFunction::register_function(self)?;
+        NotFunction::register_function(self)?;
         ReadCSV::register_function(self)?;
+        ReadParquet::register_function(self)?;
+        ReadJSON::register_function(self)?;
+        ReadArrow::register_function(self)?;
+        ArrowScan::register_function(self)?;
+        SniffCSVFunc::register_function(self)?;
         Ok(())
     }
 }
@@ -0,0 +1,73 @@
+use std::sync::Arc;
+
+use arrow::array::ArrayRef;
+use arrow::compute::{is_not_null, is_null, not};
+
+use super::ScalarFunction;
+use crate::common::as_boolean_array;
+use crate::function::{BuiltinFunctions, FunctionError};
+use crate::types_v2::LogicalType;
+
+pub struct NotFunction;
+
+impl NotFunction {
+    fn not(inputs: &[ArrayRef]) -> Result<ArrayRef, FunctionError> {
+        assert!(inputs.len() == 1);
+        let input = as_boolean_array(&inputs[0])?;
+        Ok(Arc::new(not(input)?))
+    }
+
+    pub fn get_function() -> ScalarFunction {
+        ScalarFunction::new(
+            "not".to_string(),
+            Self::not,
+            vec![LogicalType::Boolean],
+            LogicalType::Boolean,
+        )
+    }
+
+    pub fn register_function(set: &mut BuiltinFunctions) -> Result<(), FunctionError> {
+        set.add_scalar_functions("not".to_string(), vec![Self::get_function()])?;
+        Ok(())
+    }
+}
+
+/// The IsNullFunction accepts any input type, so it is not registered into catalog and only used
+/// by the optimizer rules.
+pub struct IsNullFunction;
+
+impl IsNullFunction {
+    fn is_null(inputs: &[ArrayRef]) -> Result<ArrayRef, FunctionError> {
+        assert!(inputs.len() == 1);
+        Ok(Arc::new(is_null(inputs[0].as_ref())?))
+    }
+
+    pub fn get_function(ty: LogicalType) -> ScalarFunction {
+        ScalarFunction::new(
+            "is_null".to_string(),
+            Self::is_null,
+            vec![ty],
+            LogicalType::Boolean,
+        )
+    }
+}
+
+/// The IsNotNullFunction accepts any input type, so it is not registered into catalog and only
+/// used by the optimizer rules.
+pub struct IsNotNullFunction;
+
+impl IsNotNullFunction {
+    fn is_not_null(inputs: &[ArrayRef]) -> Result<ArrayRef, FunctionError> {
+        assert!(inputs.len() == 1);
+        Ok(Arc::new(is_not_null(inputs[0].as_ref())?))
+    }
+
+    pub fn get_function(ty: LogicalType) -> ScalarFunction {
+        ScalarFunction::new(
+            "is_not_null".to_string(),
+            Self::is_not_null,
+            vec![ty],
+            LogicalType::Boolean,
+        )
+    }
+}
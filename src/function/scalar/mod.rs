@@ -1,4 +1,6 @@
 mod arithmetic_function;
+mod boolean_function;
 mod scalar_function;
 pub use arithmetic_function::*;
+pub use boolean_function::*;
 pub use scalar_function::*;
@@ -0,0 +1,114 @@
+use std::sync::Arc;
+
+use arrow::record_batch::RecordBatch;
+use derive_new::new;
+use futures::stream::BoxStream;
+use sqlparser::ast::FunctionArg;
+use tokio::sync::mpsc;
+
+use super::{MultiFileReader, TableFunction, TableFunctionBindInput, TableFunctionInput};
+use crate::function::{BuiltinFunctions, FunctionData, FunctionError, FunctionResult};
+use crate::main_entry::{ClientContext, ImportedArrowStream};
+use crate::planner_v2::SqlparserResolver;
+use crate::types_v2::LogicalType;
+
+/// The number of batches read ahead of the scan.
+const ARROW_STREAM_BUFFER: usize = 4;
+
+/// Scan an arrow C stream registered by `ClientContext::register_arrow_stream`, such as
+/// `select * from arrow_scan('name')`. The batches of the stream are scanned without a copy.
+pub struct ArrowScan;
+
+#[derive(new, Debug, Clone)]
+pub struct ArrowScanInputData {
+    /// The name the stream was registered with
+    pub(crate) name: String,
+}
+
+impl ArrowScan {
+    fn bind_func(
+        context: Arc<ClientContext>,
+        input: TableFunctionBindInput,
+        return_types: &mut Vec<LogicalType>,
+        return_names: &mut Vec<String>,
+    ) -> FunctionResult<Option<FunctionData>> {
+        let name = match input.func_args.as_deref() {
+            Some([FunctionArg::Unnamed(arg)]) => {
+                SqlparserResolver::resolve_func_arg_expr_to_string(arg)?
+            }
+            _ => {
+                return Err(FunctionError::InternalError(
+                    "arrow_scan expects the name of a registered stream".to_string(),
+                ))
+            }
+        };
+        let schema = context.arrow_stream_schema(&name).ok_or_else(|| {
+            FunctionError::InternalError(format!(
+                "arrow stream {} is not registered, or was already scanned",
+                name
+            ))
+        })?;
+        let (col_names, col_types) = MultiFileReader::column_names_types(&schema)?;
+        return_types.extend(col_types);
+        return_names.extend(col_names);
+        Ok(Some(FunctionData::ArrowScanInputData(Box::new(
+            ArrowScanInputData::new(name),
+        ))))
+    }
+
+    fn scan_func(
+        context: Arc<ClientContext>,
+        input: TableFunctionInput,
+    ) -> FunctionResult<BoxStream<'static, FunctionResult<RecordBatch>>> {
+        if let Some(FunctionData::ArrowScanInputData(data)) = input.bind_data {
+            let stream = context.take_arrow_stream(&data.name).ok_or_else(|| {
+                FunctionError::InternalError(format!(
+                    "arrow stream {} was already scanned",
+                    data.name
+                ))
+            })?;
+            let mut rx = Self::spawn_stream_reader(stream);
+            let stream = Box::pin(async_stream::try_stream! {
+                while let Some(batch) = rx.recv().await {
+                    yield batch?;
+                }
+            });
+            Ok(stream)
+        } else {
+            Err(FunctionError::InternalError(
+                "unexpected bind data type".to_string(),
+            ))
+        }
+    }
+
+    /// Read the stream on a blocking thread, reading a batch of a C stream blocks until the
+    /// producer of the stream has it. The producer may be a query of this runtime, such as an
+    /// exported sqlrs result, so the threads of the runtime must not wait for it.
+    fn spawn_stream_reader(
+        mut stream: ImportedArrowStream,
+    ) -> mpsc::Receiver<FunctionResult<RecordBatch>> {
+        let (tx, rx) = mpsc::channel(ARROW_STREAM_BUFFER);
+        tokio::task::spawn_blocking(move || loop {
+            let batch = match stream.next_batch() {
+                Ok(Some(batch)) => Ok(batch),
+                Ok(None) => break,
+                Err(e) => Err(e.into()),
+            };
+            let is_err = batch.is_err();
+            // the receiver is dropped when the consumer stops early
+            if tx.blocking_send(batch).is_err() || is_err {
+                break;
+            }
+        });
+        rx
+    }
+
+    pub fn register_function(set: &mut BuiltinFunctions) -> Result<(), FunctionError> {
+        set.add_table_functions(TableFunction::new(
+            "arrow_scan".to_string(),
+            Some(Self::bind_func),
+            Self::scan_func,
+        ))?;
+        Ok(())
+    }
+}
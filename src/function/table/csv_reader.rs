@@ -0,0 +1,485 @@
+use std::io::{self, BufRead};
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, BooleanArray, StringArray};
+use arrow::compute::{cast, filter_record_batch};
+use arrow::datatypes::{DataType, SchemaRef};
+use arrow::record_batch::RecordBatch;
+
+use super::{parse_format, ReadCSVOptions, ISO_DATE_FORMAT};
+use crate::function::{FunctionError, FunctionResult};
+
+/// A record of a CSV file, with the line number it starts at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CSVRecord {
+    pub(crate) line: usize,
+    pub(crate) fields: Vec<String>,
+}
+
+/// A line rejected by a reader with `ignore_errors`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CSVReject {
+    pub(crate) line: usize,
+    pub(crate) reason: String,
+}
+
+/// The state of the tokenizer, kept across the lines of a record with quoted newlines.
+#[derive(Default)]
+struct Tokenizer {
+    fields: Vec<String>,
+    field: Vec<u8>,
+    in_quotes: bool,
+}
+
+impl Tokenizer {
+    /// Consume one line without its line terminator. The escape character escapes a quote or
+    /// itself in quoted values, no escape accepts both a doubled quote and a backslash.
+    fn consume(&mut self, line: &[u8], delimiter: u8, quote: u8, escape: Option<u8>) {
+        let mut idx = 0;
+        while idx < line.len() {
+            let b = line[idx];
+            let next = line.get(idx + 1).copied();
+            if self.in_quotes {
+                let is_escape = match escape {
+                    Some(escape) if escape != quote => {
+                        b == escape && (next == Some(quote) || next == Some(escape))
+                    }
+                    Some(_) => b == quote && next == Some(quote),
+                    None => (b == quote || b == b'\\') && next == Some(quote),
+                };
+                if is_escape {
+                    self.field.push(next.unwrap());
+                    idx += 1;
+                } else if b == quote {
+                    self.in_quotes = false;
+                } else {
+                    self.field.push(b);
+                }
+            } else if b == quote && self.field.is_empty() {
+                self.in_quotes = true;
+            } else if b == delimiter {
+                let field = std::mem::take(&mut self.field);
+                self.fields
+                    .push(String::from_utf8_lossy(&field).to_string());
+            } else {
+                self.field.push(b);
+            }
+            idx += 1;
+        }
+    }
+
+    fn finish(mut self) -> Vec<String> {
+        self.fields
+            .push(String::from_utf8_lossy(&self.field).to_string());
+        self.fields
+    }
+}
+
+/// Split a line into values, the delimiters in quoted values are kept.
+pub fn split_line(line: &str, delimiter: u8, quote: u8, escape: Option<u8>) -> Vec<String> {
+    let mut tokenizer = Tokenizer::default();
+    tokenizer.consume(line.as_bytes(), delimiter, quote, escape);
+    tokenizer.finish()
+}
+
+/// Read the records of a CSV file, quoted values can contain delimiters and newlines. Empty
+/// lines are skipped.
+pub struct CSVRecordReader<R> {
+    reader: R,
+    delimiter: u8,
+    quote: u8,
+    escape: u8,
+    /// The number of lines read so far
+    line: usize,
+    buf: Vec<u8>,
+}
+
+impl<R: BufRead> CSVRecordReader<R> {
+    pub fn new(reader: R, delimiter: u8, quote: u8, escape: u8) -> Self {
+        Self {
+            reader,
+            delimiter,
+            quote,
+            escape,
+            line: 0,
+            buf: vec![],
+        }
+    }
+
+    /// Skip lines without parsing them, used for the leading lines and the header.
+    pub fn skip_lines(&mut self, lines: usize) -> io::Result<()> {
+        for _ in 0..lines {
+            self.buf.clear();
+            if self.reader.read_until(b'\n', &mut self.buf)? == 0 {
+                break;
+            }
+            self.line += 1;
+        }
+        Ok(())
+    }
+
+    pub fn next_record(&mut self) -> io::Result<Option<CSVRecord>> {
+        'record: loop {
+            let mut tokenizer = Tokenizer::default();
+            let start_line = self.line + 1;
+            let mut has_data = false;
+            loop {
+                self.buf.clear();
+                if self.reader.read_until(b'\n', &mut self.buf)? == 0 {
+                    break;
+                }
+                self.line += 1;
+                let mut line = self.buf.as_slice();
+                if let Some(stripped) = line.strip_suffix(b"\n") {
+                    line = stripped;
+                }
+                if let Some(stripped) = line.strip_suffix(b"\r") {
+                    line = stripped;
+                }
+                if !has_data && line.is_empty() {
+                    // an empty line between records
+                    continue 'record;
+                }
+                has_data = true;
+                tokenizer.consume(line, self.delimiter, self.quote, Some(self.escape));
+                if !tokenizer.in_quotes {
+                    break;
+                }
+                tokenizer.field.push(b'\n');
+            }
+            if !has_data {
+                return Ok(None);
+            }
+            return Ok(Some(CSVRecord {
+                line: start_line,
+                fields: tokenizer.finish(),
+            }));
+        }
+    }
+}
+
+/// The quote state of the tokenizer, without the values.
+#[derive(Default)]
+struct QuoteState {
+    in_quotes: bool,
+    /// Whether the current value has a byte, a quote only starts a quoted value at its start
+    value_started: bool,
+    /// An escape byte in a quoted value, it escapes the next byte if it's a quote or an escape
+    pending_escape: Option<u8>,
+}
+
+impl QuoteState {
+    /// Consume a byte, returns whether it's the newline ending a record.
+    fn consume(&mut self, b: u8, delimiter: u8, quote: u8, escape: u8) -> bool {
+        if let Some(pending) = self.pending_escape.take() {
+            if b == quote || b == escape {
+                self.value_started = true;
+                return false;
+            }
+            if pending == quote {
+                self.in_quotes = false;
+            } else {
+                self.value_started = true;
+            }
+        }
+        if self.in_quotes {
+            if b == escape {
+                self.pending_escape = Some(b);
+            } else if b == quote {
+                self.in_quotes = false;
+            } else {
+                self.value_started = true;
+            }
+            false
+        } else if b == b'\n' {
+            self.value_started = false;
+            true
+        } else {
+            if b == quote && !self.value_started {
+                self.in_quotes = true;
+            } else {
+                self.value_started = b != delimiter;
+            }
+            false
+        }
+    }
+}
+
+/// Find where the records of a CSV file end. The quotes are tracked like the tokenizer does from
+/// the start of the data, so a newline in a quoted value doesn't end a record.
+pub struct CSVRecordBoundaries<R> {
+    reader: R,
+    delimiter: u8,
+    quote: u8,
+    escape: u8,
+    /// The position in the file of the next byte of the reader
+    pos: u64,
+    state: QuoteState,
+}
+
+impl<R: BufRead> CSVRecordBoundaries<R> {
+    /// The reader starts at the position `pos` of the file, at the start of a record.
+    pub fn new(reader: R, pos: u64, delimiter: u8, quote: u8, escape: u8) -> Self {
+        Self {
+            reader,
+            delimiter,
+            quote,
+            escape,
+            pos,
+            state: QuoteState::default(),
+        }
+    }
+
+    /// The position after the first record ending at or after `pos`, None if the data ends
+    /// before.
+    pub fn next_record_start(&mut self, pos: u64) -> io::Result<Option<u64>> {
+        loop {
+            let buf = self.reader.fill_buf()?;
+            if buf.is_empty() {
+                return Ok(None);
+            }
+            let mut found = None;
+            let mut consumed = 0;
+            for b in buf.iter() {
+                consumed += 1;
+                let record_end = self
+                    .state
+                    .consume(*b, self.delimiter, self.quote, self.escape);
+                if record_end && self.pos + consumed as u64 >= pos {
+                    found = Some(self.pos + consumed as u64);
+                    break;
+                }
+            }
+            self.reader.consume(consumed);
+            self.pos += consumed as u64;
+            if found.is_some() {
+                return Ok(found);
+            }
+        }
+    }
+}
+
+/// Parse the records of a CSV file into batches of the columns of the schema. Values equal to
+/// the null string are nulls, the other values are cast to the type of their column. A record
+/// with a wrong number of values or a value that can't be cast is an error, or is skipped and
+/// reported in the rejects with `ignore_errors`.
+pub struct CSVReader<R> {
+    records: CSVRecordReader<R>,
+    schema: SchemaRef,
+    projection: Vec<usize>,
+    options: ReadCSVOptions,
+    /// The number of records to skip, and the maximum number of records to read after them
+    bounds: Option<(usize, usize)>,
+    records_read: usize,
+    rejects: Vec<CSVReject>,
+}
+
+impl<R: BufRead> CSVReader<R> {
+    pub fn new(
+        reader: R,
+        schema: SchemaRef,
+        projection: Option<Vec<usize>>,
+        options: ReadCSVOptions,
+        bounds: Option<(usize, usize)>,
+    ) -> Self {
+        let records =
+            CSVRecordReader::new(reader, options.delimiter, options.quote, options.escape);
+        let projection = projection.unwrap_or_else(|| (0..schema.fields().len()).collect());
+        Self {
+            records,
+            schema,
+            projection,
+            options,
+            bounds,
+            records_read: 0,
+            rejects: vec![],
+        }
+    }
+
+    /// Skip the leading lines and the header, the reader of a range in the middle of a file
+    /// starts at a record.
+    pub fn skip_header(&mut self) -> io::Result<()> {
+        let lines = self.options.skip + self.options.has_header as usize;
+        self.records.skip_lines(lines)
+    }
+
+    /// The bounds left for the next file once this reader is exhausted.
+    pub fn remaining_bounds(&self) -> Option<(usize, usize)> {
+        self.bounds.map(|(offset, limit)| {
+            let skipped = self.records_read.min(offset);
+            (offset - skipped, limit - (self.records_read - skipped))
+        })
+    }
+
+    pub fn take_rejects(&mut self) -> Vec<CSVReject> {
+        std::mem::take(&mut self.rejects)
+    }
+
+    fn reject(&mut self, line: usize, reason: String) -> FunctionResult<()> {
+        if self.options.ignore_errors {
+            self.rejects.push(CSVReject { line, reason });
+            Ok(())
+        } else {
+            Err(FunctionError::InternalError(format!(
+                "line {}: {}",
+                line, reason
+            )))
+        }
+    }
+
+    fn read_records(&mut self) -> FunctionResult<Vec<CSVRecord>> {
+        let (offset, limit) = self.bounds.unwrap_or((0, usize::MAX));
+        let mut records = vec![];
+        while records.len() < self.options.read_batch_size
+            && self.records_read < offset.saturating_add(limit)
+        {
+            let record = match self.records.next_record()? {
+                Some(record) => record,
+                None => break,
+            };
+            if record.fields.len() != self.schema.fields().len() {
+                self.reject(
+                    record.line,
+                    format!(
+                        "expected {} values, but found {}",
+                        self.schema.fields().len(),
+                        record.fields.len()
+                    ),
+                )?;
+                continue;
+            }
+            self.records_read += 1;
+            if self.records_read > offset {
+                records.push(record);
+            }
+        }
+        Ok(records)
+    }
+
+    fn convert_column(&self, records: &[CSVRecord], col_idx: usize) -> (StringArray, DataType) {
+        let data_type = self.schema.field(col_idx).data_type().clone();
+        let date_format = self
+            .options
+            .date_format
+            .as_deref()
+            .filter(|f| data_type == DataType::Date32 && *f != ISO_DATE_FORMAT);
+        let strings = records
+            .iter()
+            .map(|record| {
+                let value = &record.fields[col_idx];
+                if *value == self.options.null_str {
+                    return None;
+                }
+                match date_format.and_then(|f| parse_format(value, f)) {
+                    Some(parts) => Some(parts.to_iso_date()),
+                    None => Some(value.clone()),
+                }
+            })
+            .collect::<StringArray>();
+        (strings, data_type)
+    }
+
+    fn build_batch(&mut self, records: Vec<CSVRecord>) -> FunctionResult<RecordBatch> {
+        let mut valid = vec![true; records.len()];
+        let mut columns: Vec<ArrayRef> = Vec::with_capacity(self.projection.len());
+        for col_idx in self.projection.clone() {
+            let (strings, data_type) = self.convert_column(&records, col_idx);
+            let array = cast(&(Arc::new(strings.clone()) as ArrayRef), &data_type)?;
+            // the cast returns nulls for the values it can't parse
+            for row in 0..records.len() {
+                if valid[row] && strings.is_valid(row) && array.is_null(row) {
+                    valid[row] = false;
+                    let reason = format!(
+                        "could not convert '{}' to {} in column {}",
+                        strings.value(row),
+                        data_type,
+                        self.schema.field(col_idx).name()
+                    );
+                    self.reject(records[row].line, reason)?;
+                }
+            }
+            columns.push(array);
+        }
+        let schema = Arc::new(self.schema.project(&self.projection)?);
+        let batch = RecordBatch::try_new(schema, columns)?;
+        if valid.iter().all(|v| *v) {
+            Ok(batch)
+        } else {
+            Ok(filter_record_batch(&batch, &BooleanArray::from(valid))?)
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for CSVReader<R> {
+    type Item = FunctionResult<RecordBatch>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let records = match self.read_records() {
+            Ok(records) => records,
+            Err(e) => return Some(Err(e)),
+        };
+        if records.is_empty() {
+            return None;
+        }
+        Some(self.build_batch(records))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn test_split_line_with_quotes() {
+        assert_eq!(
+            split_line(r#"1,"a, b","c""d",'e'"#, b',', b'"', None),
+            vec!["1", "a, b", "c\"d", "'e'"]
+        );
+        assert_eq!(
+            split_line(r#"1;'a\'b'"#, b';', b'\'', None),
+            vec!["1", "a'b"]
+        );
+        assert_eq!(
+            split_line(r#"1,"a\"b""#, b',', b'"', Some(b'"')),
+            vec!["1", "a\\b\""]
+        );
+    }
+
+    #[test]
+    fn test_record_reader_with_quoted_newlines() {
+        let data = "1,\"a\nb\"\n\n2,c\r\n";
+        let mut reader = CSVRecordReader::new(Cursor::new(data), b',', b'"', b'"');
+        assert_eq!(
+            reader.next_record().unwrap(),
+            Some(CSVRecord {
+                line: 1,
+                fields: vec!["1".to_string(), "a\nb".to_string()],
+            })
+        );
+        assert_eq!(
+            reader.next_record().unwrap(),
+            Some(CSVRecord {
+                line: 4,
+                fields: vec!["2".to_string(), "c".to_string()],
+            })
+        );
+        assert_eq!(reader.next_record().unwrap(), None);
+    }
+
+    #[test]
+    fn test_record_boundaries_with_quoted_newlines() {
+        let data = "1,\"a\nb\"\n2,\"c\"\"\n\"\n3,x\n";
+        let mut boundaries = CSVRecordBoundaries::new(Cursor::new(data), 0, b',', b'"', b'"');
+        assert_eq!(boundaries.next_record_start(0).unwrap(), Some(8));
+        assert_eq!(boundaries.next_record_start(9).unwrap(), Some(17));
+        assert_eq!(boundaries.next_record_start(18).unwrap(), Some(21));
+        assert_eq!(boundaries.next_record_start(22).unwrap(), None);
+
+        let data = "1,\"a\\\"\nb\"\n2,c\n";
+        let mut boundaries = CSVRecordBoundaries::new(Cursor::new(data), 0, b',', b'"', b'\\');
+        assert_eq!(boundaries.next_record_start(1).unwrap(), Some(10));
+        assert_eq!(boundaries.next_record_start(11).unwrap(), Some(14));
+    }
+}
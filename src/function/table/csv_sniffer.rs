@@ -0,0 +1,464 @@
+use std::fmt;
+use std::io::BufRead;
+use std::sync::Arc;
+
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+
+use super::{split_line, FileCompression};
+use crate::function::FunctionError;
+
+/// The default number of rows the sniffer uses to detect the dialect and the column types.
+const SNIFF_SAMPLE_ROWS: usize = 1024;
+/// The candidate delimiters, in the order of preference when several dialects fit equally well.
+const DELIMITER_CANDIDATES: [u8; 4] = [b',', b'\t', b';', b'|'];
+/// The candidate quote characters.
+const QUOTE_CANDIDATES: [u8; 2] = [b'"', b'\''];
+pub(crate) const ISO_DATE_FORMAT: &str = "%Y-%m-%d";
+const DATE_FORMATS: [&str; 4] = [ISO_DATE_FORMAT, "%Y/%m/%d", "%d/%m/%Y", "%m/%d/%Y"];
+const TIMESTAMP_FORMATS: [&str; 4] = [
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y/%m/%d %H:%M:%S",
+    "%d/%m/%Y %H:%M:%S",
+];
+
+/// The column type detected by the sniffer. Dates and timestamps keep the format their values
+/// are written in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SniffedType {
+    Boolean,
+    Bigint,
+    Double,
+    Date(String),
+    Timestamp(String),
+    Varchar,
+}
+
+impl SniffedType {
+    /// The arrow type used to read the column. sqlrs has no timestamp type yet, so timestamps
+    /// are read as strings.
+    pub fn to_arrow_type(&self) -> DataType {
+        match self {
+            SniffedType::Boolean => DataType::Boolean,
+            SniffedType::Bigint => DataType::Int64,
+            SniffedType::Double => DataType::Float64,
+            SniffedType::Date(_) => DataType::Date32,
+            SniffedType::Timestamp(_) | SniffedType::Varchar => DataType::Utf8,
+        }
+    }
+
+    fn format(&self) -> Option<&str> {
+        match self {
+            SniffedType::Date(format) | SniffedType::Timestamp(format) => Some(format),
+            _ => None,
+        }
+    }
+
+    /// The candidate types of a value, from the most to the least specific. Only the given
+    /// formats are tried for dates and timestamps.
+    fn candidates(date_format: Option<&str>, timestamp_format: Option<&str>) -> Vec<SniffedType> {
+        let mut candidates = vec![
+            SniffedType::Boolean,
+            SniffedType::Bigint,
+            SniffedType::Double,
+        ];
+        let date_formats = date_format.map_or(DATE_FORMATS.to_vec(), |f| vec![f]);
+        let timestamp_formats = timestamp_format.map_or(TIMESTAMP_FORMATS.to_vec(), |f| vec![f]);
+        candidates.extend(
+            date_formats
+                .iter()
+                .map(|f| SniffedType::Date(f.to_string())),
+        );
+        candidates.extend(
+            timestamp_formats
+                .iter()
+                .map(|f| SniffedType::Timestamp(f.to_string())),
+        );
+        candidates
+    }
+
+    fn matches(&self, value: &str) -> bool {
+        match self {
+            SniffedType::Boolean => {
+                value.eq_ignore_ascii_case("true") || value.eq_ignore_ascii_case("false")
+            }
+            SniffedType::Bigint => value.parse::<i64>().is_ok(),
+            SniffedType::Double => value.parse::<f64>().is_ok(),
+            SniffedType::Date(format) | SniffedType::Timestamp(format) => {
+                parse_format(value, format).is_some()
+            }
+            SniffedType::Varchar => true,
+        }
+    }
+}
+
+impl fmt::Display for SniffedType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SniffedType::Boolean => write!(f, "BOOLEAN"),
+            SniffedType::Bigint => write!(f, "BIGINT"),
+            SniffedType::Double => write!(f, "DOUBLE"),
+            SniffedType::Date(_) => write!(f, "DATE"),
+            SniffedType::Timestamp(_) => write!(f, "TIMESTAMP"),
+            SniffedType::Varchar => write!(f, "VARCHAR"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SniffedColumn {
+    pub(crate) name: String,
+    pub(crate) ty: SniffedType,
+}
+
+/// The dialect and the columns of a CSV file detected by the sniffer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SniffResult {
+    pub(crate) delimiter: u8,
+    pub(crate) quote: u8,
+    /// The escape character of quotes in quoted values, equal to the quote when quotes are
+    /// escaped by doubling them.
+    pub(crate) escape: u8,
+    pub(crate) has_header: bool,
+    pub(crate) columns: Vec<SniffedColumn>,
+}
+
+impl SniffResult {
+    pub fn schema(&self) -> SchemaRef {
+        let fields = self
+            .columns
+            .iter()
+            .map(|c| Field::new(&c.name, c.ty.to_arrow_type(), true))
+            .collect::<Vec<_>>();
+        Arc::new(Schema::new(fields))
+    }
+
+    /// The format of the first date column, if any.
+    pub fn date_format(&self) -> Option<&str> {
+        self.columns
+            .iter()
+            .find(|c| matches!(c.ty, SniffedType::Date(_)))
+            .and_then(|c| c.ty.format())
+    }
+
+    /// The format of the first timestamp column, if any.
+    pub fn timestamp_format(&self) -> Option<&str> {
+        self.columns
+            .iter()
+            .find(|c| matches!(c.ty, SniffedType::Timestamp(_)))
+            .and_then(|c| c.ty.format())
+    }
+}
+
+/// The sniffer detects the dialect, the header and the column types of a CSV file from a sample
+/// of its first rows. Options given by the user are kept as they are.
+///
+/// Rows are split by newlines before they are parsed, so quoted values containing newlines are
+/// not supported by the detection.
+#[derive(Debug, Clone)]
+pub struct CSVSniffer {
+    pub(crate) delimiter: Option<u8>,
+    pub(crate) quote: Option<u8>,
+    pub(crate) escape: Option<u8>,
+    pub(crate) has_header: Option<bool>,
+    /// The number of lines to skip before the header or the first row
+    pub(crate) skip: usize,
+    pub(crate) sample_rows: usize,
+    pub(crate) date_format: Option<String>,
+    pub(crate) timestamp_format: Option<String>,
+}
+
+impl Default for CSVSniffer {
+    fn default() -> Self {
+        Self {
+            delimiter: None,
+            quote: None,
+            escape: None,
+            has_header: None,
+            skip: 0,
+            sample_rows: SNIFF_SAMPLE_ROWS,
+            date_format: None,
+            timestamp_format: None,
+        }
+    }
+}
+
+impl CSVSniffer {
+    pub fn sniff(
+        &self,
+        filename: &str,
+        compression: FileCompression,
+    ) -> Result<SniffResult, FunctionError> {
+        let lines = self.read_sample(filename, compression)?;
+        let (delimiter, quote, rows) = self.detect_dialect(&lines);
+        let escape = self
+            .escape
+            .unwrap_or_else(|| Self::detect_escape(&lines, quote));
+        Ok(self.detect_columns(delimiter, quote, escape, &rows))
+    }
+
+    fn read_sample(
+        &self,
+        filename: &str,
+        compression: FileCompression,
+    ) -> Result<Vec<String>, FunctionError> {
+        let reader = compression.open(filename)?;
+        let mut lines = vec![];
+        for line in reader
+            .split(b'\n')
+            .skip(self.skip)
+            .take(self.sample_rows + 1)
+        {
+            let line = String::from_utf8_lossy(&line?)
+                .trim_end_matches('\r')
+                .to_string();
+            if !line.is_empty() {
+                lines.push(line);
+            }
+        }
+        Ok(lines)
+    }
+
+    /// Try every candidate dialect on the sample. The dialect which splits the most rows into the
+    /// same number of columns wins, ties are broken by the number of columns.
+    fn detect_dialect(&self, lines: &[String]) -> (u8, u8, Vec<Vec<String>>) {
+        let delimiters = match self.delimiter {
+            Some(delimiter) => vec![delimiter],
+            None => DELIMITER_CANDIDATES.to_vec(),
+        };
+        let quotes = match self.quote {
+            Some(quote) => vec![quote],
+            None => QUOTE_CANDIDATES.to_vec(),
+        };
+        let mut best: Option<(usize, usize, u8, u8, Vec<Vec<String>>)> = None;
+        for delimiter in delimiters.iter().copied() {
+            for quote in quotes.iter().copied() {
+                let rows = lines
+                    .iter()
+                    .map(|line| split_line(line, delimiter, quote, self.escape))
+                    .collect::<Vec<_>>();
+                let columns = rows.first().map_or(0, |r| r.len());
+                let consistent = rows.iter().take_while(|r| r.len() == columns).count();
+                let better = match &best {
+                    Some((best_consistent, best_columns, ..)) => {
+                        (consistent, columns) > (*best_consistent, *best_columns)
+                    }
+                    None => true,
+                };
+                if better {
+                    best = Some((consistent, columns, delimiter, quote, rows));
+                }
+            }
+        }
+        match best {
+            Some((_, columns, delimiter, quote, rows))
+                if columns > 1 || self.delimiter.is_some() =>
+            {
+                (delimiter, quote, rows)
+            }
+            // a single column file, fall back to the default dialect
+            _ => {
+                let quote = self.quote.unwrap_or(b'"');
+                let rows = lines
+                    .iter()
+                    .map(|line| split_line(line, b',', quote, self.escape))
+                    .collect();
+                (b',', quote, rows)
+            }
+        }
+    }
+
+    fn detect_escape(lines: &[String], quote: u8) -> u8 {
+        let escaped_quote = format!("\\{}", quote as char);
+        if lines.iter().any(|line| line.contains(&escaped_quote)) {
+            b'\\'
+        } else {
+            quote
+        }
+    }
+
+    /// Detect the type of every column from the rows after the first one. The first row is a
+    /// header if one of its values doesn't fit the type of its column, or if all the columns are
+    /// strings.
+    fn detect_columns(
+        &self,
+        delimiter: u8,
+        quote: u8,
+        escape: u8,
+        rows: &[Vec<String>],
+    ) -> SniffResult {
+        let columns = rows.first().map_or(0, |r| r.len());
+        let body = rows.iter().skip(1).collect::<Vec<_>>();
+        let body_types = (0..columns)
+            .map(|idx| self.detect_type(body.iter().filter_map(|r| r.get(idx))))
+            .collect::<Vec<_>>();
+
+        let has_header = self.has_header.unwrap_or_else(|| match rows.first() {
+            Some(first) => {
+                body_types.iter().all(|ty| *ty == SniffedType::Varchar)
+                    || first
+                        .iter()
+                        .zip(body_types.iter())
+                        .any(|(value, ty)| !value.is_empty() && !ty.matches(value))
+            }
+            None => false,
+        });
+
+        let (names, types) = if let (true, Some(first)) = (has_header, rows.first()) {
+            let names = first
+                .iter()
+                .enumerate()
+                .map(|(idx, name)| {
+                    if name.is_empty() {
+                        format!("column{}", idx)
+                    } else {
+                        name.to_lowercase()
+                    }
+                })
+                .collect::<Vec<_>>();
+            (names, body_types)
+        } else {
+            let names = (0..columns).map(|idx| format!("column{}", idx)).collect();
+            let types = (0..columns)
+                .map(|idx| self.detect_type(rows.iter().filter_map(|r| r.get(idx))))
+                .collect();
+            (names, types)
+        };
+        let columns = names
+            .into_iter()
+            .zip(types.into_iter())
+            .map(|(name, ty)| SniffedColumn { name, ty })
+            .collect();
+        SniffResult {
+            delimiter,
+            quote,
+            escape,
+            has_header,
+            columns,
+        }
+    }
+
+    /// The most specific type all the non empty values fit, empty values are nulls.
+    fn detect_type<'a>(&self, values: impl Iterator<Item = &'a String>) -> SniffedType {
+        let mut candidates = SniffedType::candidates(
+            self.date_format.as_deref(),
+            self.timestamp_format.as_deref(),
+        );
+        let mut has_value = false;
+        for value in values.filter(|v| !v.is_empty()) {
+            has_value = true;
+            candidates.retain(|ty| ty.matches(value));
+            if candidates.is_empty() {
+                break;
+            }
+        }
+        match candidates.into_iter().next() {
+            Some(ty) if has_value => ty,
+            _ => SniffedType::Varchar,
+        }
+    }
+}
+
+/// The fields of a date or a timestamp parsed by `parse_format`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DateTimeParts {
+    pub(crate) year: u32,
+    pub(crate) month: u32,
+    pub(crate) day: u32,
+    pub(crate) hour: u32,
+    pub(crate) minute: u32,
+    pub(crate) second: u32,
+}
+
+impl DateTimeParts {
+    /// The date in the ISO format, which is the format the arrow cast kernels parse.
+    pub fn to_iso_date(self) -> String {
+        format!("{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// Parse a value written in the strftime style format, only the %Y, %m, %d, %H, %M and %S
+/// specifiers are supported.
+pub fn parse_format(value: &str, format: &str) -> Option<DateTimeParts> {
+    let value = value.as_bytes();
+    let format = format.as_bytes();
+    let mut parts = DateTimeParts::default();
+    let (mut v, mut f) = (0, 0);
+    while f < format.len() {
+        if format[f] == b'%' && f + 1 < format.len() {
+            let (min_digits, max_digits, range, part) = match format[f + 1] {
+                b'Y' => (4, 4, 0..=9999, &mut parts.year),
+                b'm' => (1, 2, 1..=12, &mut parts.month),
+                b'd' => (1, 2, 1..=31, &mut parts.day),
+                b'H' => (1, 2, 0..=23, &mut parts.hour),
+                b'M' => (1, 2, 0..=59, &mut parts.minute),
+                b'S' => (1, 2, 0..=59, &mut parts.second),
+                _ => return None,
+            };
+            let digits = value[v..]
+                .iter()
+                .take(max_digits)
+                .take_while(|b| b.is_ascii_digit())
+                .count();
+            if digits < min_digits {
+                return None;
+            }
+            let number = std::str::from_utf8(&value[v..v + digits])
+                .ok()?
+                .parse::<u32>()
+                .ok()?;
+            if !range.contains(&number) {
+                return None;
+            }
+            *part = number;
+            v += digits;
+            f += 2;
+        } else {
+            if v >= value.len() || value[v] != format[f] {
+                return None;
+            }
+            v += 1;
+            f += 1;
+        }
+    }
+    (v == value.len()).then_some(parts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_type() {
+        let sniffer = CSVSniffer::default();
+        let detect = |values: &[&str]| {
+            let values = values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+            sniffer.detect_type(values.iter())
+        };
+        assert_eq!(detect(&["1", "", "-2"]), SniffedType::Bigint);
+        assert_eq!(detect(&["1", "2.5"]), SniffedType::Double);
+        assert_eq!(detect(&["TRUE", "false"]), SniffedType::Boolean);
+        assert_eq!(
+            detect(&["2022-01-31", "2022-12-01"]),
+            SniffedType::Date("%Y-%m-%d".to_string())
+        );
+        assert_eq!(
+            detect(&["31/01/2022", "13/12/2022"]),
+            SniffedType::Date("%d/%m/%Y".to_string())
+        );
+        assert_eq!(
+            detect(&["2022-01-31 10:00:00"]),
+            SniffedType::Timestamp("%Y-%m-%d %H:%M:%S".to_string())
+        );
+        assert_eq!(detect(&["2022-13-31"]), SniffedType::Varchar);
+        assert_eq!(detect(&[""]), SniffedType::Varchar);
+    }
+
+    #[test]
+    fn test_parse_format() {
+        let parts = parse_format("31/01/2022", "%d/%m/%Y").unwrap();
+        assert_eq!(parts.to_iso_date(), "2022-01-31");
+        assert!(parse_format("2022-01-31 1", "%Y-%m-%d").is_none());
+        assert!(parse_format("2022-1-31", "%Y-%m-%d").is_some());
+    }
+}
@@ -0,0 +1,158 @@
+use std::io::Write;
+
+use arrow::array::{Array, ArrayRef, StringArray};
+use arrow::compute::cast;
+use arrow::datatypes::DataType;
+use arrow::record_batch::RecordBatch;
+use derive_builder::Builder;
+
+use crate::function::FunctionResult;
+
+#[derive(Builder, Debug, Clone)]
+pub struct CSVWriterOptions {
+    #[builder(default = "b','")]
+    pub(crate) delimiter: u8,
+    #[builder(default = "b'\"'")]
+    pub(crate) quote: u8,
+    #[builder(default = "b'\"'")]
+    pub(crate) escape: u8,
+    #[builder(default = "true")]
+    pub(crate) header: bool,
+    /// The string of null values
+    #[builder(default = "String::new()")]
+    pub(crate) null_str: String,
+}
+
+/// Write record batches as CSV. A value is quoted when it contains the delimiter, the quote or a
+/// newline, or when it could be read back as a null value.
+pub struct CSVWriter<W: Write> {
+    writer: W,
+    /// The column names of the header
+    names: Vec<String>,
+    options: CSVWriterOptions,
+    header_written: bool,
+}
+
+impl<W: Write> CSVWriter<W> {
+    pub fn new(writer: W, names: Vec<String>, options: CSVWriterOptions) -> Self {
+        Self {
+            writer,
+            names,
+            options,
+            header_written: false,
+        }
+    }
+
+    fn write_header(&mut self) -> FunctionResult<()> {
+        if self.header_written || !self.options.header {
+            return Ok(());
+        }
+        self.header_written = true;
+        let names = self.names.clone();
+        let names = names.iter().map(|n| Some(n.as_str())).collect::<Vec<_>>();
+        self.write_record(&names)
+    }
+
+    fn write_value(&mut self, value: &str) -> FunctionResult<()> {
+        let CSVWriterOptions {
+            delimiter,
+            quote,
+            escape,
+            ..
+        } = self.options;
+        let needs_quotes = value.is_empty()
+            || value == self.options.null_str
+            || value
+                .bytes()
+                .any(|b| b == delimiter || b == quote || b == b'\n' || b == b'\r');
+        if !needs_quotes {
+            self.writer.write_all(value.as_bytes())?;
+            return Ok(());
+        }
+        let mut quoted = Vec::with_capacity(value.len() + 2);
+        quoted.push(quote);
+        for b in value.bytes() {
+            if b == quote || (b == escape && escape != quote) {
+                quoted.push(escape);
+            }
+            quoted.push(b);
+        }
+        quoted.push(quote);
+        self.writer.write_all(&quoted)?;
+        Ok(())
+    }
+
+    fn write_record(&mut self, values: &[Option<&str>]) -> FunctionResult<()> {
+        for (idx, value) in values.iter().enumerate() {
+            if idx > 0 {
+                self.writer.write_all(&[self.options.delimiter])?;
+            }
+            match value {
+                Some(value) => self.write_value(value)?,
+                None => self.writer.write_all(self.options.null_str.as_bytes())?,
+            }
+        }
+        self.writer.write_all(b"\n")?;
+        Ok(())
+    }
+
+    pub fn write(&mut self, batch: &RecordBatch) -> FunctionResult<()> {
+        self.write_header()?;
+        let columns = batch
+            .columns()
+            .iter()
+            .map(|column| cast(column, &DataType::Utf8))
+            .collect::<Result<Vec<ArrayRef>, _>>()?;
+        let columns = columns
+            .iter()
+            .map(|column| column.as_any().downcast_ref::<StringArray>().unwrap())
+            .collect::<Vec<_>>();
+        for row in 0..batch.num_rows() {
+            let values = columns
+                .iter()
+                .map(|column| column.is_valid(row).then(|| column.value(row)))
+                .collect::<Vec<_>>();
+            self.write_record(&values)?;
+        }
+        Ok(())
+    }
+
+    /// Write the header of a file without rows, and flush the writer.
+    pub fn finish(mut self) -> FunctionResult<W> {
+        self.write_header()?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::array::Int32Array;
+    use arrow::datatypes::{Field, Schema};
+
+    use super::*;
+
+    #[test]
+    fn test_write_quoted_values() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, true),
+            Field::new("name", DataType::Utf8, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![Some(1), Some(2), None])),
+                Arc::new(StringArray::from(vec![Some("a,\"b\""), Some(""), None])),
+            ],
+        )
+        .unwrap();
+        let options = CSVWriterOptionsBuilder::default().build().unwrap();
+        let names = vec!["id".to_string(), "name".to_string()];
+        let mut writer = CSVWriter::new(vec![], names, options);
+        writer.write(&batch).unwrap();
+        let output = String::from_utf8(writer.finish().unwrap()).unwrap();
+        assert_eq!(output, "id,name\n1,\"a,\"\"b\"\"\"\n2,\"\"\n,\n");
+    }
+}
@@ -0,0 +1,87 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+use flate2::read::MultiGzDecoder;
+
+use crate::function::FunctionError;
+
+/// The compression of an input file, the file is decompressed while it's read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileCompression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl FileCompression {
+    /// Detect the compression from the extension of the file, like `t1.csv.gz`.
+    pub fn from_extension(filename: &str) -> Self {
+        match Path::new(filename).extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("gz") => FileCompression::Gzip,
+            Some(ext) if ext.eq_ignore_ascii_case("zst") => FileCompression::Zstd,
+            _ => FileCompression::None,
+        }
+    }
+
+    /// Parse the `compression` option, `auto` detects the compression from the extension.
+    pub fn parse_option(value: &str) -> Result<Option<Self>, FunctionError> {
+        match value.to_lowercase().as_str() {
+            "auto" => Ok(None),
+            "none" => Ok(Some(FileCompression::None)),
+            "gzip" => Ok(Some(FileCompression::Gzip)),
+            "zstd" => Ok(Some(FileCompression::Zstd)),
+            other => Err(FunctionError::InternalError(format!(
+                "unsupported compression: {}",
+                other
+            ))),
+        }
+    }
+
+    /// The file name without the extension of its compression, `t1.csv.gz` is `t1.csv`.
+    pub fn strip_extension(filename: &str) -> &str {
+        match Self::from_extension(filename) {
+            FileCompression::None => filename,
+            _ => &filename[..filename.rfind('.').unwrap()],
+        }
+    }
+
+    pub fn is_compressed(&self) -> bool {
+        *self != FileCompression::None
+    }
+
+    /// Open the file with a reader returning the decompressed bytes. The gzip reader reads all
+    /// the members of files made by concatenating gzip files.
+    pub fn open(&self, filename: &str) -> io::Result<Box<dyn BufRead + Send>> {
+        let file = File::open(filename)?;
+        Ok(match self {
+            FileCompression::None => Box::new(BufReader::new(file)),
+            FileCompression::Gzip => Box::new(BufReader::new(MultiGzDecoder::new(file))),
+            FileCompression::Zstd => Box::new(BufReader::new(zstd::Decoder::new(file)?)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_compression() {
+        assert_eq!(
+            FileCompression::from_extension("data/t1.csv.gz"),
+            FileCompression::Gzip
+        );
+        assert_eq!(
+            FileCompression::from_extension("t1.csv.ZST"),
+            FileCompression::Zstd
+        );
+        assert_eq!(
+            FileCompression::from_extension("t1.csv"),
+            FileCompression::None
+        );
+        assert_eq!(FileCompression::strip_extension("t1.csv.gz"), "t1.csv");
+        assert_eq!(FileCompression::strip_extension("t1.csv"), "t1.csv");
+        assert!(FileCompression::parse_option("bzip2").is_err());
+    }
+}
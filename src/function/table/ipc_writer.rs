@@ -0,0 +1,34 @@
+use std::io::Write;
+
+use arrow::datatypes::SchemaRef;
+use arrow::ipc::writer::FileWriter;
+use arrow::record_batch::RecordBatch;
+
+use crate::function::FunctionResult;
+
+/// Write record batches as an arrow IPC file, which is also the feather v2 format.
+pub struct IpcWriter<W: Write> {
+    writer: FileWriter<W>,
+    schema: SchemaRef,
+}
+
+impl<W: Write> IpcWriter<W> {
+    pub fn try_new(writer: W, schema: SchemaRef) -> FunctionResult<Self> {
+        let writer = FileWriter::try_new(writer, &schema)?;
+        Ok(Self { writer, schema })
+    }
+
+    pub fn write(&mut self, batch: &RecordBatch) -> FunctionResult<()> {
+        // the batches are written with the schema of the file, including the field names
+        let batch = RecordBatch::try_new(self.schema.clone(), batch.columns().to_vec())?;
+        self.writer.write(&batch)?;
+        Ok(())
+    }
+
+    /// Write the footer of the file.
+    pub fn finish(self) -> FunctionResult<W> {
+        let mut writer = self.writer.into_inner()?;
+        writer.flush()?;
+        Ok(writer)
+    }
+}
@@ -0,0 +1,31 @@
+use std::io::Write;
+
+use arrow::json::writer::record_batches_to_json_rows;
+use arrow::record_batch::RecordBatch;
+
+use crate::function::FunctionResult;
+
+/// Write record batches as newline delimited json, one object per row. The NULL values are left
+/// out of the objects.
+pub struct JsonWriter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> JsonWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    pub fn write(&mut self, batch: &RecordBatch) -> FunctionResult<()> {
+        for row in record_batches_to_json_rows(std::slice::from_ref(batch))? {
+            serde_json::to_writer(&mut self.writer, &row)?;
+            self.writer.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> FunctionResult<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
@@ -1,10 +1,40 @@
+mod arrow_scan;
+mod csv_reader;
+mod csv_sniffer;
+mod csv_writer;
+mod file_compression;
+mod ipc_writer;
+mod json_writer;
+mod multi_file_reader;
+mod parquet_statistics;
+mod parquet_writer;
+mod read_arrow;
 mod read_csv;
+mod read_json;
+mod read_parquet;
 mod seq_table_scan;
+mod sniff_csv;
+mod sqlrs_column_stats;
 mod sqlrs_columns;
 mod sqlrs_tables;
 mod table_function;
+pub use arrow_scan::*;
+pub use csv_reader::*;
+pub use csv_sniffer::*;
+pub use csv_writer::*;
+pub use file_compression::*;
+pub use ipc_writer::*;
+pub use json_writer::*;
+pub use multi_file_reader::*;
+pub use parquet_statistics::*;
+pub use parquet_writer::*;
+pub use read_arrow::*;
 pub use read_csv::*;
+pub use read_json::*;
+pub use read_parquet::*;
 pub use seq_table_scan::*;
+pub use sniff_csv::*;
+pub use sqlrs_column_stats::*;
 pub use sqlrs_columns::*;
 pub use sqlrs_tables::*;
 pub use table_function::*;
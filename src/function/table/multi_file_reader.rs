@@ -0,0 +1,491 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use arrow::array::{new_null_array, Array, ArrayRef, StringArray};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use arrow::record_batch::{RecordBatch, RecordBatchOptions};
+use sqlparser::ast::{Expr, FunctionArgExpr};
+
+use crate::function::{FunctionError, FunctionResult};
+use crate::planner_v2::SqlparserResolver;
+use crate::types_v2::{LogicalType, ScalarValue};
+
+/// The name of the column with the file each row was read from.
+pub const FILENAME_COLUMN: &str = "filename";
+/// The value of a NULL partition key in the name of a hive partition directory.
+pub const HIVE_DEFAULT_PARTITION: &str = "__HIVE_DEFAULT_PARTITION__";
+
+/// The options of the table functions reading a list of files.
+#[derive(Debug, Clone, Default)]
+pub struct MultiFileOptions {
+    /// Add a column with the file each row was read from
+    pub(crate) filename: bool,
+    /// Align the columns of the files by name, the columns missing in a file are NULL
+    pub(crate) union_by_name: bool,
+    /// Read the `key=value` directories of the paths as columns, detected when it's not set
+    pub(crate) hive_partitioning: Option<bool>,
+}
+
+/// Resolve the files of a table function and align their schemas.
+pub struct MultiFileReader;
+
+impl MultiFileReader {
+    /// Parse a file name or glob pattern, or a list of them like `['a.csv', 'dir/*.csv']`.
+    pub fn parse_patterns(arg: &FunctionArgExpr) -> Result<Vec<String>, FunctionError> {
+        match arg {
+            FunctionArgExpr::Expr(Expr::Array(array)) => {
+                let patterns = array
+                    .elem
+                    .iter()
+                    .map(SqlparserResolver::resolve_expr_to_string)
+                    .collect::<Result<Vec<_>, _>>()?;
+                if patterns.is_empty() {
+                    return Err(FunctionError::InternalError(
+                        "the list of files is empty".to_string(),
+                    ));
+                }
+                Ok(patterns)
+            }
+            _ => Ok(vec![SqlparserResolver::resolve_func_arg_expr_to_string(
+                arg,
+            )?]),
+        }
+    }
+
+    /// Parse the named args of the multi-file options, returns false for the other args.
+    pub fn parse_option(
+        options: &mut MultiFileOptions,
+        name: &str,
+        arg: &FunctionArgExpr,
+    ) -> Result<bool, FunctionError> {
+        match name {
+            "filename" => {
+                options.filename = SqlparserResolver::resolve_func_arg_expr_to_bool(arg)?;
+            }
+            "union_by_name" => {
+                options.union_by_name = SqlparserResolver::resolve_func_arg_expr_to_bool(arg)?;
+            }
+            "hive_partitioning" => {
+                let v = SqlparserResolver::resolve_func_arg_expr_to_bool(arg)?;
+                options.hive_partitioning = Some(v);
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// The values of the columns following the columns of a file: its partition values and its
+    /// name.
+    pub fn constant_columns(
+        filename: &str,
+        partition_values: &[ScalarValue],
+        filename_column: bool,
+    ) -> Vec<ScalarValue> {
+        let mut values = partition_values.to_vec();
+        if filename_column {
+            values.push(ScalarValue::Utf8(Some(filename.to_string())));
+        }
+        values
+    }
+
+    /// The names and types of the columns of the scan returned by the bind function.
+    pub fn column_names_types(
+        schema: &SchemaRef,
+    ) -> Result<(Vec<String>, Vec<LogicalType>), FunctionError> {
+        let mut col_names = vec![];
+        let mut col_types = vec![];
+        for field in schema.fields() {
+            col_names.push(field.name().to_string().to_lowercase());
+            col_types.push(field.data_type().try_into()?);
+        }
+        Ok((col_names, col_types))
+    }
+
+    fn is_glob(pattern: &str) -> bool {
+        pattern.contains(|c| matches!(c, '*' | '?' | '['))
+    }
+
+    /// Expand the glob patterns into the files they match, sorted by name. A name without glob
+    /// characters is kept as is, so a missing file is reported when it's opened.
+    pub fn expand_patterns(patterns: &[String]) -> Result<Vec<String>, FunctionError> {
+        let mut files = vec![];
+        for pattern in patterns {
+            if !Self::is_glob(pattern) {
+                files.push(pattern.clone());
+                continue;
+            }
+            let paths = glob::glob(pattern).map_err(|e| {
+                FunctionError::InternalError(format!("invalid glob pattern '{}': {}", pattern, e))
+            })?;
+            let mut matched = vec![];
+            for path in paths {
+                let path = path.map_err(|e| FunctionError::IoError(e.into_error()))?;
+                if path.is_file() {
+                    matched.push(path.to_string_lossy().to_string());
+                }
+            }
+            if matched.is_empty() {
+                return Err(FunctionError::InternalError(format!(
+                    "no files found that match the pattern '{}'",
+                    pattern
+                )));
+            }
+            matched.sort();
+            files.extend(matched);
+        }
+        Ok(files)
+    }
+
+    /// The columns of the scan: the columns of the files by name in the order they first appear,
+    /// followed by the partition keys and the filename column. A column with different types in
+    /// the files gets a type both can be cast to, a column named as a partition key is replaced
+    /// by the key.
+    pub fn scan_schema(
+        schemas: &[SchemaRef],
+        partitioning: &HivePartitioning,
+        options: &MultiFileOptions,
+    ) -> Result<SchemaRef, FunctionError> {
+        let mut fields: Vec<Field> = vec![];
+        for schema in schemas {
+            for field in schema.fields() {
+                if partitioning.names.contains(field.name()) {
+                    continue;
+                }
+                match fields.iter_mut().find(|f| f.name() == field.name()) {
+                    Some(f) => {
+                        let data_type = Self::common_type(f.data_type(), field.data_type());
+                        *f = Field::new(field.name(), data_type, true);
+                    }
+                    None => fields.push(Field::new(field.name(), field.data_type().clone(), true)),
+                }
+            }
+        }
+        for (name, data_type) in partitioning.names.iter().zip(partitioning.types.iter()) {
+            fields.push(Field::new(name, data_type.clone(), true));
+        }
+        if options.filename {
+            if fields.iter().any(|f| f.name() == FILENAME_COLUMN) {
+                return Err(FunctionError::InternalError(format!(
+                    "the files already have a column named {}",
+                    FILENAME_COLUMN
+                )));
+            }
+            fields.push(Field::new(FILENAME_COLUMN, DataType::Utf8, false));
+        }
+        Ok(Arc::new(Schema::new(fields)))
+    }
+
+    fn common_type(left: &DataType, right: &DataType) -> DataType {
+        match (left, right) {
+            (l, r) if l == r => l.clone(),
+            (DataType::Int32, DataType::Int64) | (DataType::Int64, DataType::Int32) => {
+                DataType::Int64
+            }
+            (DataType::Int32 | DataType::Int64, DataType::Float64)
+            | (DataType::Float64, DataType::Int32 | DataType::Int64) => DataType::Float64,
+            _ => DataType::Utf8,
+        }
+    }
+}
+
+/// The hive partition keys of the files laid out like `year=2024/month=5/data_0.csv`. Each
+/// `key=value` directory is a column with the same value for all the rows of its files.
+#[derive(Debug, Clone, Default)]
+pub struct HivePartitioning {
+    /// The partition keys, in the order of the directories
+    pub(crate) names: Vec<String>,
+    pub(crate) types: Vec<DataType>,
+}
+
+impl HivePartitioning {
+    /// The keys and values of the `key=value` directories of the path, decoded.
+    pub fn parse_path(filename: &str) -> Vec<(String, String)> {
+        let dir = match Path::new(filename).parent() {
+            Some(dir) => dir,
+            None => return vec![],
+        };
+        dir.components()
+            .filter_map(|c| {
+                let (key, value) = c.as_os_str().to_str()?.split_once('=')?;
+                (!key.is_empty()).then(|| {
+                    let key = Self::unescape_path_part(key).to_lowercase();
+                    (key, Self::unescape_path_part(value))
+                })
+            })
+            .collect()
+    }
+
+    /// Percent-encode a key or a value for the name of a partition directory like Hive, so it
+    /// can't contain a path separator or the `=` of the directory name.
+    pub fn escape_path_part(part: &str) -> String {
+        let mut escaped = String::with_capacity(part.len());
+        for c in part.chars() {
+            let special = matches!(
+                c,
+                '"' | '#' | '%' | '\'' | '*' | '/' | ':' | '=' | '?' | '\\' | '{' | '[' | ']' | '^'
+            );
+            if special || c.is_ascii_control() {
+                escaped.push_str(&format!("%{:02X}", c as u32));
+            } else {
+                escaped.push(c);
+            }
+        }
+        escaped
+    }
+
+    /// Decode a percent-encoded key or value of a partition directory, a `%` without two hex
+    /// digits is kept as it is.
+    pub fn unescape_path_part(part: &str) -> String {
+        let bytes = part.as_bytes();
+        let mut decoded = Vec::with_capacity(bytes.len());
+        let mut idx = 0;
+        while idx < bytes.len() {
+            let hex = bytes
+                .get(idx + 1..idx + 3)
+                .filter(|hex| bytes[idx] == b'%' && hex.iter().all(|b| b.is_ascii_hexdigit()));
+            match hex {
+                Some(hex) => {
+                    let hex = std::str::from_utf8(hex).unwrap();
+                    decoded.push(u8::from_str_radix(hex, 16).unwrap());
+                    idx += 3;
+                }
+                None => {
+                    decoded.push(bytes[idx]);
+                    idx += 1;
+                }
+            }
+        }
+        String::from_utf8_lossy(&decoded).to_string()
+    }
+
+    /// Detect the partition keys and their types, and parse the partition values of each file.
+    /// The files are partitioned when all of them have the same keys, unless `enabled` is set.
+    pub fn detect(
+        filenames: &[String],
+        enabled: Option<bool>,
+    ) -> Result<(Self, Vec<Vec<ScalarValue>>), FunctionError> {
+        let not_partitioned = (Self::default(), vec![vec![]; filenames.len()]);
+        if enabled == Some(false) || filenames.is_empty() {
+            return Ok(not_partitioned);
+        }
+        let partitions = filenames
+            .iter()
+            .map(|f| Self::parse_path(f))
+            .collect::<Vec<_>>();
+        let names = partitions[0]
+            .iter()
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+        let same_keys = partitions
+            .iter()
+            .all(|p| p.iter().map(|(key, _)| key).eq(names.iter()));
+        if names.is_empty() || !same_keys {
+            if enabled == Some(true) {
+                return Err(FunctionError::InternalError(
+                    "hive partitioning requires the same partition keys in the paths of all files"
+                        .to_string(),
+                ));
+            }
+            return Ok(not_partitioned);
+        }
+
+        let mut types = vec![];
+        let mut columns = vec![];
+        for key_idx in 0..names.len() {
+            let strings = partitions
+                .iter()
+                .map(|p| {
+                    let value = p[key_idx].1.as_str();
+                    (value != HIVE_DEFAULT_PARTITION).then_some(value)
+                })
+                .collect::<StringArray>();
+            let (data_type, column) = Self::infer_type(Arc::new(strings))?;
+            types.push(data_type);
+            columns.push(column);
+        }
+        let values = (0..filenames.len())
+            .map(|file_idx| {
+                columns
+                    .iter()
+                    .map(|column| ScalarValue::try_from_array(column, file_idx))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok((Self { names, types }, values))
+    }
+
+    /// The values are BIGINT, DOUBLE or DATE when all of them can be cast to it, or VARCHAR.
+    fn infer_type(strings: ArrayRef) -> Result<(DataType, ArrayRef), FunctionError> {
+        for data_type in [DataType::Int64, DataType::Float64, DataType::Date32] {
+            let column = cast(&strings, &data_type)?;
+            if column.null_count() == strings.null_count() {
+                return Ok((data_type, column));
+            }
+        }
+        Ok((DataType::Utf8, strings))
+    }
+}
+
+/// Where a column of the scan comes from.
+#[derive(Debug, Clone)]
+enum ColumnSource {
+    /// The index of the column in the batches read from the file
+    File(usize),
+    /// The column is missing in the file
+    Null,
+    /// A partition key or the filename, the value is the same for all the rows of the file
+    Constant(ScalarValue),
+}
+
+/// Map the columns read from one file to the projected columns of the scan.
+#[derive(Debug, Clone)]
+pub struct FileColumnMapping {
+    /// The columns to read from the file, it reads one column at least so the batches keep their
+    /// number of rows
+    pub(crate) file_projection: Vec<usize>,
+    sources: Vec<ColumnSource>,
+    schema: SchemaRef,
+}
+
+impl FileColumnMapping {
+    /// Map the columns of the file by name, the last columns of `scan_schema` have the values of
+    /// `constants`.
+    pub fn new(
+        file_schema: &SchemaRef,
+        scan_schema: &SchemaRef,
+        projection: Option<&[usize]>,
+        constants: Vec<ScalarValue>,
+    ) -> Self {
+        let projection = projection
+            .map(|p| p.to_vec())
+            .unwrap_or_else(|| (0..scan_schema.fields().len()).collect());
+        let first_constant = scan_schema.fields().len() - constants.len();
+        let mut file_projection = vec![];
+        let mut sources = vec![];
+        for idx in projection.iter() {
+            let name = scan_schema.field(*idx).name();
+            let source = if *idx >= first_constant {
+                ColumnSource::Constant(constants[*idx - first_constant].clone())
+            } else {
+                match file_schema.index_of(name) {
+                    Ok(file_idx) => {
+                        file_projection.push(file_idx);
+                        ColumnSource::File(file_projection.len() - 1)
+                    }
+                    Err(_) => ColumnSource::Null,
+                }
+            };
+            sources.push(source);
+        }
+        if file_projection.is_empty() {
+            file_projection.push(0);
+        }
+        Self {
+            file_projection,
+            sources,
+            schema: Arc::new(scan_schema.project(&projection).unwrap()),
+        }
+    }
+
+    pub fn map_batch(&self, batch: RecordBatch) -> FunctionResult<RecordBatch> {
+        let rows = batch.num_rows();
+        let mut columns: Vec<ArrayRef> = Vec::with_capacity(self.sources.len());
+        for (source, field) in self.sources.iter().zip(self.schema.fields()) {
+            let column = match source {
+                ColumnSource::File(idx) if batch.column(*idx).data_type() == field.data_type() => {
+                    batch.column(*idx).clone()
+                }
+                ColumnSource::File(idx) => cast(batch.column(*idx), field.data_type())?,
+                ColumnSource::Null => new_null_array(field.data_type(), rows),
+                ColumnSource::Constant(value) if value.is_null() => {
+                    new_null_array(field.data_type(), rows)
+                }
+                ColumnSource::Constant(value) => value.to_array_of_size(rows),
+            };
+            columns.push(column);
+        }
+        let options = RecordBatchOptions::new().with_row_count(Some(rows));
+        Ok(RecordBatch::try_new_with_options(
+            self.schema.clone(),
+            columns,
+            &options,
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_schema_by_name() {
+        let a = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, true),
+            Field::new("name", DataType::Utf8, true),
+        ]));
+        let b = Arc::new(Schema::new(vec![
+            Field::new("score", DataType::Float64, true),
+            Field::new("id", DataType::Float64, true),
+        ]));
+        let partitioning = HivePartitioning {
+            names: vec!["name".to_string()],
+            types: vec![DataType::Int64],
+        };
+        let options = MultiFileOptions {
+            filename: true,
+            ..Default::default()
+        };
+        let schema = MultiFileReader::scan_schema(&[a, b], &partitioning, &options).unwrap();
+        assert_eq!(
+            schema.fields(),
+            &vec![
+                Field::new("id", DataType::Float64, true),
+                Field::new("score", DataType::Float64, true),
+                Field::new("name", DataType::Int64, true),
+                Field::new(FILENAME_COLUMN, DataType::Utf8, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_escape_partition_path() {
+        for value in ["a/b", "..", "k=v", "50%", "%2F", "plain value"] {
+            let escaped = HivePartitioning::escape_path_part(value);
+            assert!(!escaped.contains('/') && !escaped.contains('='));
+            assert_eq!(HivePartitioning::unescape_path_part(&escaped), value);
+        }
+        assert_eq!(HivePartitioning::escape_path_part("a/b=c"), "a%2Fb%3Dc");
+        assert_eq!(HivePartitioning::unescape_path_part("100%"), "100%");
+
+        let filename = format!(
+            "data/{}={}/data_0.csv",
+            HivePartitioning::escape_path_part("K=ey"),
+            HivePartitioning::escape_path_part("../x")
+        );
+        assert_eq!(
+            HivePartitioning::parse_path(&filename),
+            vec![("k=ey".to_string(), "../x".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_detect_hive_partitioning() {
+        let filenames = vec![
+            "data/year=2023/month=12/data_0.csv".to_string(),
+            format!("data/year=2024/month={}/data_0.csv", HIVE_DEFAULT_PARTITION),
+        ];
+        let (partitioning, values) = HivePartitioning::detect(&filenames, None).unwrap();
+        assert_eq!(partitioning.names, vec!["year", "month"]);
+        assert_eq!(partitioning.types, vec![DataType::Int64, DataType::Int64]);
+        assert_eq!(
+            values[1],
+            vec![ScalarValue::Int64(Some(2024)), ScalarValue::Int64(None)]
+        );
+
+        let filenames = vec!["a/k=x/f.csv".to_string(), "b/f.csv".to_string()];
+        let (partitioning, _) = HivePartitioning::detect(&filenames, None).unwrap();
+        assert!(partitioning.names.is_empty());
+        assert!(HivePartitioning::detect(&filenames, Some(true)).is_err());
+    }
+}
@@ -0,0 +1,175 @@
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+use arrow::array::{
+    ArrayRef, BooleanArray, Float32Array, Float64Array, Int32Array, Int64Array, StringArray,
+};
+use arrow::compute::cast;
+use arrow::datatypes::DataType;
+use derive_new::new;
+use parquet::file::metadata::RowGroupMetaData;
+use parquet::file::statistics::Statistics;
+
+use crate::types_v2::ScalarValue;
+
+/// The comparison of a column with a constant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComparisonOp {
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+}
+
+impl ComparisonOp {
+    /// The operator of the comparison function, like `eq` for `=`.
+    pub fn from_function_name(name: &str) -> Option<Self> {
+        match name {
+            "eq" => Some(ComparisonOp::Eq),
+            "neq" => Some(ComparisonOp::NotEq),
+            "lt" => Some(ComparisonOp::Lt),
+            "lt_eq" => Some(ComparisonOp::LtEq),
+            "gt" => Some(ComparisonOp::Gt),
+            "gt_eq" => Some(ComparisonOp::GtEq),
+            _ => None,
+        }
+    }
+
+    /// The operator with swapped sides, `1 < a` is `a > 1`.
+    pub fn flip(self) -> Self {
+        match self {
+            ComparisonOp::Lt => ComparisonOp::Gt,
+            ComparisonOp::LtEq => ComparisonOp::GtEq,
+            ComparisonOp::Gt => ComparisonOp::Lt,
+            ComparisonOp::GtEq => ComparisonOp::LtEq,
+            op => op,
+        }
+    }
+}
+
+/// A filter `column op value` on a column of a scan, the value has the type the column is
+/// compared as.
+#[derive(new, Debug, Clone)]
+pub struct ColumnPredicate {
+    /// The index of the column in the scan
+    pub(crate) column_idx: usize,
+    pub(crate) op: ComparisonOp,
+    pub(crate) value: ScalarValue,
+}
+
+impl ColumnPredicate {
+    /// Whether a row group with the values between min and max may have rows passing the
+    /// predicate.
+    fn may_pass(&self, min: &ScalarValue, max: &ScalarValue) -> bool {
+        let v = &self.value;
+        let cmp = |a: &ScalarValue, b: &ScalarValue| a.partial_cmp(b);
+        let result = match self.op {
+            ComparisonOp::Eq => cmp(min, v)
+                .zip(cmp(v, max))
+                .map(|(l, r)| l != Ordering::Greater && r != Ordering::Greater),
+            ComparisonOp::NotEq => cmp(min, v)
+                .zip(cmp(max, v))
+                .map(|(l, r)| l != Ordering::Equal || r != Ordering::Equal),
+            ComparisonOp::Lt => cmp(min, v).map(|o| o == Ordering::Less),
+            ComparisonOp::LtEq => cmp(min, v).map(|o| o != Ordering::Greater),
+            ComparisonOp::Gt => cmp(max, v).map(|o| o == Ordering::Greater),
+            ComparisonOp::GtEq => cmp(max, v).map(|o| o != Ordering::Less),
+        };
+        // values that can't be compared can't prune the row group
+        result.unwrap_or(true)
+    }
+}
+
+/// Prune the row groups of a parquet file by the min/max statistics of their columns.
+pub struct RowGroupPruner;
+
+impl RowGroupPruner {
+    /// The min and max values of the statistics as an array of two values of the physical type.
+    fn min_max_array(statistics: &Statistics) -> Option<ArrayRef> {
+        if !statistics.has_min_max_set() {
+            return None;
+        }
+        let array: ArrayRef = match statistics {
+            Statistics::Boolean(s) => Arc::new(BooleanArray::from(vec![*s.min(), *s.max()])),
+            Statistics::Int32(s) => Arc::new(Int32Array::from(vec![*s.min(), *s.max()])),
+            Statistics::Int64(s) => Arc::new(Int64Array::from(vec![*s.min(), *s.max()])),
+            Statistics::Float(s) => Arc::new(Float32Array::from(vec![*s.min(), *s.max()])),
+            Statistics::Double(s) => Arc::new(Float64Array::from(vec![*s.min(), *s.max()])),
+            Statistics::ByteArray(s) => {
+                let min = s.min().as_utf8().ok()?;
+                let max = s.max().as_utf8().ok()?;
+                Arc::new(StringArray::from(vec![min, max]))
+            }
+            _ => return None,
+        };
+        Some(array)
+    }
+
+    /// The min and max values of a column of the row group, cast to the type of the column in
+    /// the file and then to the type it's compared as.
+    fn min_max(
+        row_group: &RowGroupMetaData,
+        leaf_idx: usize,
+        column_type: &DataType,
+        compare_type: &DataType,
+    ) -> Option<(ScalarValue, ScalarValue)> {
+        let array = Self::min_max_array(row_group.column(leaf_idx).statistics()?)?;
+        if *array.data_type() == DataType::Utf8 && *column_type != DataType::Utf8 {
+            // a binary column
+            return None;
+        }
+        let array = cast(&cast(&array, column_type).ok()?, compare_type).ok()?;
+        let min = ScalarValue::try_from_array(&array, 0).ok()?;
+        let max = ScalarValue::try_from_array(&array, 1).ok()?;
+        if min.is_null() || max.is_null() {
+            return None;
+        }
+        Some((min, max))
+    }
+
+    /// Whether the row group may have rows passing all the predicates. `columns` has the leaf
+    /// index and the type of the column of each predicate in the file, or None when the column
+    /// isn't in the file.
+    pub fn may_pass(
+        row_group: &RowGroupMetaData,
+        predicates: &[ColumnPredicate],
+        columns: &[Option<(usize, DataType)>],
+    ) -> bool {
+        predicates
+            .iter()
+            .zip(columns.iter())
+            .all(|(predicate, column)| {
+                let (leaf_idx, column_type) = match column {
+                    Some(column) => column,
+                    None => return true,
+                };
+                let compare_type = predicate.value.get_datatype();
+                match Self::min_max(row_group, *leaf_idx, column_type, &compare_type) {
+                    Some((min, max)) => predicate.may_pass(&min, &max),
+                    None => true,
+                }
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_predicate_may_pass() {
+        let (min, max) = (ScalarValue::Int64(Some(10)), ScalarValue::Int64(Some(20)));
+        let predicate = |op, v| ColumnPredicate::new(0, op, ScalarValue::Int64(Some(v)));
+        assert!(predicate(ComparisonOp::Eq, 15).may_pass(&min, &max));
+        assert!(!predicate(ComparisonOp::Eq, 21).may_pass(&min, &max));
+        assert!(!predicate(ComparisonOp::Lt, 10).may_pass(&min, &max));
+        assert!(predicate(ComparisonOp::LtEq, 10).may_pass(&min, &max));
+        assert!(!predicate(ComparisonOp::Gt, 20).may_pass(&min, &max));
+        assert!(predicate(ComparisonOp::GtEq, 20).may_pass(&min, &max));
+        assert!(predicate(ComparisonOp::NotEq, 10).may_pass(&min, &max));
+        let same = ScalarValue::Int64(Some(10));
+        assert!(!predicate(ComparisonOp::NotEq, 10).may_pass(&same, &same));
+    }
+}
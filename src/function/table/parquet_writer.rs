@@ -0,0 +1,71 @@
+use std::io::Write;
+
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use derive_builder::Builder;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::{WriterProperties, DEFAULT_MAX_ROW_GROUP_SIZE};
+
+use crate::function::{FunctionError, FunctionResult};
+
+#[derive(Builder, Debug, Clone)]
+pub struct ParquetWriterOptions {
+    #[builder(default = "Compression::SNAPPY")]
+    pub(crate) compression: Compression,
+    /// The maximum number of rows of a row group
+    #[builder(default = "DEFAULT_MAX_ROW_GROUP_SIZE")]
+    pub(crate) row_group_size: usize,
+}
+
+impl ParquetWriterOptions {
+    /// Parse the `COMPRESSION` option of COPY.
+    pub fn parse_compression(value: &str) -> FunctionResult<Compression> {
+        match value.to_lowercase().as_str() {
+            "uncompressed" => Ok(Compression::UNCOMPRESSED),
+            "snappy" => Ok(Compression::SNAPPY),
+            "gzip" => Ok(Compression::GZIP),
+            "zstd" => Ok(Compression::ZSTD),
+            "lz4" => Ok(Compression::LZ4),
+            "brotli" => Ok(Compression::BROTLI),
+            other => Err(FunctionError::InternalError(format!(
+                "unsupported parquet compression: {}",
+                other
+            ))),
+        }
+    }
+}
+
+/// Write record batches as a parquet file. The rows are buffered until a row group is full.
+pub struct ParquetWriter<W: Write> {
+    writer: ArrowWriter<W>,
+    schema: SchemaRef,
+}
+
+impl<W: Write> ParquetWriter<W> {
+    pub fn try_new(
+        writer: W,
+        schema: SchemaRef,
+        options: &ParquetWriterOptions,
+    ) -> FunctionResult<Self> {
+        let props = WriterProperties::builder()
+            .set_compression(options.compression)
+            .set_max_row_group_size(options.row_group_size.max(1))
+            .build();
+        let writer = ArrowWriter::try_new(writer, schema.clone(), Some(props))?;
+        Ok(Self { writer, schema })
+    }
+
+    pub fn write(&mut self, batch: &RecordBatch) -> FunctionResult<()> {
+        // the writer requires the batches to have its schema, including the field names
+        let batch = RecordBatch::try_new(self.schema.clone(), batch.columns().to_vec())?;
+        self.writer.write(&batch)?;
+        Ok(())
+    }
+
+    /// Write the buffered rows and the footer of the file.
+    pub fn finish(self) -> FunctionResult<()> {
+        self.writer.close()?;
+        Ok(())
+    }
+}
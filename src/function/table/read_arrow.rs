@@ -0,0 +1,287 @@
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::sync::Arc;
+
+use arrow::datatypes::SchemaRef;
+use arrow::ipc::reader::{FileReader, StreamReader};
+use arrow::record_batch::RecordBatch;
+use derive_builder::Builder;
+use futures::stream::BoxStream;
+use sqlparser::ast::FunctionArg;
+
+use super::{
+    FileColumnMapping, HivePartitioning, MultiFileOptions, MultiFileReader, TableFunction,
+    TableFunctionBindInput, TableFunctionInput,
+};
+use crate::function::{BuiltinFunctions, FunctionData, FunctionError, FunctionResult};
+use crate::main_entry::ClientContext;
+use crate::types_v2::{LogicalType, ScalarValue};
+
+/// The magic bytes at the start of an arrow IPC file, the stream format doesn't have them.
+const ARROW_FILE_MAGIC: &[u8; 6] = b"ARROW1";
+
+pub struct ReadArrow;
+
+#[derive(Builder, Debug, Clone)]
+pub struct ReadArrowInputData {
+    pub(crate) files: Vec<ReadArrowFile>,
+    /// The columns of the scan: the columns of the files, followed by the partition columns and
+    /// the filename column
+    pub(crate) schema: SchemaRef,
+    #[builder(default = "0")]
+    pub(crate) partition_columns: usize,
+    #[builder(default = "false")]
+    pub(crate) filename_column: bool,
+    #[builder(default = "None")]
+    pub(crate) projection: Option<Vec<usize>>,
+}
+
+impl ReadArrowInputData {
+    /// The number of columns read from the files, the others have the same value for all the
+    /// rows of a file.
+    pub fn file_columns(&self) -> usize {
+        self.schema.fields().len() - self.partition_columns - self.filename_column as usize
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ReadArrowFile {
+    pub(crate) filename: String,
+    /// The values of the hive partition keys in the path of the file
+    pub(crate) partition_values: Vec<ScalarValue>,
+}
+
+/// A reader of the arrow IPC file format, or of the stream format.
+enum IpcReader {
+    File(FileReader<BufReader<File>>),
+    Stream(StreamReader<BufReader<File>>),
+}
+
+impl IpcReader {
+    /// Open the file with the reader of its format. The batches are decoded into buffers of
+    /// their own, the arrow version we use can't map them to the file: its `FileReader` reads
+    /// every block into a new buffer, and it has no decoder for the blocks of a mapped file.
+    fn try_new(filename: &str, projection: Option<Vec<usize>>) -> FunctionResult<Self> {
+        let mut magic = [0; ARROW_FILE_MAGIC.len()];
+        let is_file = match File::open(filename)?.read_exact(&mut magic) {
+            Ok(_) => magic == *ARROW_FILE_MAGIC,
+            Err(_) => false,
+        };
+        let reader = BufReader::new(File::open(filename)?);
+        Ok(if is_file {
+            IpcReader::File(FileReader::try_new(reader, projection)?)
+        } else {
+            IpcReader::Stream(StreamReader::try_new(reader, projection)?)
+        })
+    }
+
+    fn schema(&self) -> SchemaRef {
+        match self {
+            IpcReader::File(reader) => reader.schema(),
+            IpcReader::Stream(reader) => reader.schema(),
+        }
+    }
+
+    fn next_batch(&mut self) -> FunctionResult<Option<RecordBatch>> {
+        let batch = match self {
+            IpcReader::File(reader) => reader.next(),
+            IpcReader::Stream(reader) => reader.next(),
+        };
+        Ok(batch.transpose()?)
+    }
+}
+
+impl ReadArrow {
+    fn parse_func_args(
+        args: &[FunctionArg],
+    ) -> Result<(Vec<String>, MultiFileOptions), FunctionError> {
+        let patterns = match args.first() {
+            Some(FunctionArg::Unnamed(e)) => MultiFileReader::parse_patterns(e)?,
+            _ => {
+                return Err(FunctionError::InternalError(
+                    "filename is required".to_string(),
+                ))
+            }
+        };
+        let filenames = MultiFileReader::expand_patterns(&patterns)?;
+        let mut options = MultiFileOptions::default();
+        for each in args.iter().skip(1) {
+            match each {
+                FunctionArg::Named { name, arg } => {
+                    if !MultiFileReader::parse_option(&mut options, &name.value, arg)? {
+                        return Err(FunctionError::InternalError(format!(
+                            "unexpected arg: {}",
+                            name.value
+                        )));
+                    }
+                }
+                _ => {
+                    return Err(FunctionError::InternalError(
+                        "expected named arg".to_string(),
+                    ))
+                }
+            }
+        }
+        Ok((filenames, options))
+    }
+
+    /// Open the file with the projected columns. The columns of the files are mapped by name,
+    /// so the files can have their columns in any order.
+    fn create_reader(
+        file: &ReadArrowFile,
+        data: &ReadArrowInputData,
+    ) -> FunctionResult<(IpcReader, FileColumnMapping, Vec<usize>)> {
+        let file_schema = IpcReader::try_new(&file.filename, None)?.schema();
+        let constants = MultiFileReader::constant_columns(
+            &file.filename,
+            &file.partition_values,
+            data.filename_column,
+        );
+        let mapping = FileColumnMapping::new(
+            &file_schema,
+            &data.schema,
+            data.projection.as_deref(),
+            constants,
+        );
+        // the projected columns are read in the order of the file
+        let mut file_columns = mapping.file_projection.clone();
+        file_columns.sort_unstable();
+        file_columns.dedup();
+        let positions = mapping
+            .file_projection
+            .iter()
+            .map(|idx| file_columns.binary_search(idx).unwrap())
+            .collect::<Vec<_>>();
+        let reader = IpcReader::try_new(&file.filename, Some(file_columns))?;
+        Ok((reader, mapping, positions))
+    }
+
+    fn bind_func(
+        _context: Arc<ClientContext>,
+        input: TableFunctionBindInput,
+        return_types: &mut Vec<LogicalType>,
+        return_names: &mut Vec<String>,
+    ) -> Result<Option<FunctionData>, FunctionError> {
+        if let Some(args) = input.func_args {
+            let (filenames, options) = Self::parse_func_args(args.as_slice())?;
+            let schemas = if options.union_by_name {
+                filenames
+                    .iter()
+                    .map(|f| Ok(IpcReader::try_new(f, None)?.schema()))
+                    .collect::<FunctionResult<Vec<_>>>()?
+            } else {
+                // the files are expected to have the columns of the first one
+                vec![IpcReader::try_new(&filenames[0], None)?.schema()]
+            };
+            let (partitioning, partition_values) =
+                HivePartitioning::detect(&filenames, options.hive_partitioning)?;
+            let files = filenames
+                .into_iter()
+                .zip(partition_values)
+                .map(|(filename, partition_values)| ReadArrowFile {
+                    filename,
+                    partition_values,
+                })
+                .collect::<Vec<_>>();
+            let schema = MultiFileReader::scan_schema(&schemas, &partitioning, &options)?;
+            let (col_names, col_types) = MultiFileReader::column_names_types(&schema)?;
+            return_types.extend(col_types);
+            return_names.extend(col_names);
+            let input_data = ReadArrowInputDataBuilder::default()
+                .files(files)
+                .schema(schema)
+                .partition_columns(partitioning.names.len())
+                .filename_column(options.filename)
+                .build()
+                .unwrap();
+            Ok(Some(FunctionData::ReadArrowInputData(Box::new(input_data))))
+        } else {
+            Err(FunctionError::InternalError(
+                "unexpected bind data type".to_string(),
+            ))
+        }
+    }
+
+    fn scan_func(
+        _context: Arc<ClientContext>,
+        input: TableFunctionInput,
+    ) -> FunctionResult<BoxStream<'static, FunctionResult<RecordBatch>>> {
+        if let Some(FunctionData::ReadArrowInputData(data)) = input.bind_data {
+            let stream = Box::pin(async_stream::try_stream! {
+                for file in data.files.iter() {
+                    let (mut reader, mapping, positions) = Self::create_reader(file, &data)?;
+                    while let Some(batch) = reader.next_batch()? {
+                        yield mapping.map_batch(batch.project(&positions)?)?;
+                    }
+                }
+            });
+            Ok(stream)
+        } else {
+            Err(FunctionError::InternalError(
+                "unexpected bind data type".to_string(),
+            ))
+        }
+    }
+
+    pub fn register_function(set: &mut BuiltinFunctions) -> Result<(), FunctionError> {
+        let mut function = TableFunction::new(
+            "read_arrow".to_string(),
+            Some(Self::bind_func),
+            Self::scan_func,
+        );
+        function.projection_pushdown = true;
+        set.add_table_functions(function)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use arrow::array::{Int32Array, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::ipc::writer::{FileWriter, StreamWriter};
+
+    use super::*;
+
+    #[test]
+    fn test_read_file_and_stream_formats() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Utf8, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2])),
+                Arc::new(StringArray::from(vec!["x", "y"])),
+            ],
+        )
+        .unwrap();
+        fs::create_dir_all("target/read_arrow").unwrap();
+        let file_path = "target/read_arrow/file.arrow";
+        let mut writer = FileWriter::try_new(File::create(file_path).unwrap(), &schema).unwrap();
+        writer.write(&batch).unwrap();
+        writer.finish().unwrap();
+        let stream_path = "target/read_arrow/stream.arrow";
+        let mut writer =
+            StreamWriter::try_new(File::create(stream_path).unwrap(), &schema).unwrap();
+        writer.write(&batch).unwrap();
+        writer.finish().unwrap();
+
+        let mut reader = IpcReader::try_new(file_path, Some(vec![1])).unwrap();
+        assert!(matches!(reader, IpcReader::File(_)));
+        assert_eq!(
+            reader.next_batch().unwrap().unwrap(),
+            batch.project(&[1]).unwrap()
+        );
+        assert!(reader.next_batch().unwrap().is_none());
+
+        let mut reader = IpcReader::try_new(stream_path, None).unwrap();
+        assert!(matches!(reader, IpcReader::Stream(_)));
+        assert_eq!(reader.schema(), schema);
+        assert_eq!(reader.next_batch().unwrap().unwrap(), batch);
+        assert!(reader.next_batch().unwrap().is_none());
+    }
+}
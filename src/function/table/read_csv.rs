@@ -1,50 +1,127 @@
 use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Take};
 use std::sync::Arc;
 
-use arrow::csv::{reader, Reader};
-use arrow::datatypes::SchemaRef;
+use arrow::array::{Int64Array, StringArray};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
 use arrow::record_batch::RecordBatch;
 use derive_builder::Builder;
 use futures::stream::BoxStream;
-use sqlparser::ast::FunctionArg;
+use sqlparser::ast::{FunctionArg, FunctionArgExpr};
+use sqlparser::dialect::PostgreSqlDialect;
+use sqlparser::parser::Parser;
+use tokio::sync::mpsc;
 
-use super::{TableFunction, TableFunctionBindInput, TableFunctionInput};
+use super::{
+    CSVReader, CSVRecordBoundaries, CSVReject, CSVSniffer, FileColumnMapping, FileCompression,
+    HivePartitioning, MultiFileOptions, MultiFileReader, SniffResult, TableFunction,
+    TableFunctionBindInput, TableFunctionInput,
+};
+use crate::catalog_v2::{Catalog, ColumnDefinition, DataTable, DataTableInfo, DEFAULT_SCHEMA};
+use crate::execution::SchemaUtil;
 use crate::function::{BuiltinFunctions, FunctionData, FunctionError, FunctionResult};
 use crate::main_entry::ClientContext;
-use crate::planner_v2::SqlparserResolver;
-use crate::types_v2::LogicalType;
+use crate::planner_v2::{BindError, SqlparserResolver};
+use crate::storage_v2::LocalStorage;
+use crate::types_v2::{LogicalType, ScalarValue};
+
+/// Files smaller than this are read by one reader, splitting them costs more than it saves.
+const MIN_RANGE_BYTES: u64 = 4 * 1024 * 1024;
+/// The number of parsed batches a range reader can produce ahead of the consumer.
+const RANGE_READER_BUFFER: usize = 2;
+/// The table the lines rejected by `ignore_errors` are appended to.
+const REJECT_ERRORS_TABLE: &str = "reject_errors";
 
 pub struct ReadCSV;
 
 #[derive(Builder, Debug, Clone)]
 pub struct ReadCSVInputData {
-    pub(crate) filename: String,
-    pub(crate) option: ReadCSVOptions,
+    pub(crate) files: Vec<ReadCSVFile>,
+    /// The columns of the scan: the columns of the files, followed by the partition columns and
+    /// the filename column
     pub(crate) schema: SchemaRef,
+    #[builder(default = "0")]
+    pub(crate) partition_columns: usize,
+    #[builder(default = "false")]
+    pub(crate) filename_column: bool,
     #[builder(default = "None")]
     pub(crate) bounds: Option<(usize, usize)>,
     #[builder(default = "None")]
     pub(crate) projection: Option<Vec<usize>>,
 }
 
+/// A file of the scan with its dialect and columns. The files have the ones of the first file,
+/// unless they are sniffed one by one for `union_by_name`.
+#[derive(Debug, Clone)]
+pub struct ReadCSVFile {
+    pub(crate) filename: String,
+    pub(crate) option: ReadCSVOptions,
+    pub(crate) schema: SchemaRef,
+    pub(crate) compression: FileCompression,
+    /// The values of the hive partition keys in the path of the file
+    pub(crate) partition_values: Vec<ScalarValue>,
+}
+
+impl ReadCSVFile {
+    /// The values of the columns following the columns of the file.
+    fn constant_columns(&self, filename_column: bool) -> Vec<ScalarValue> {
+        MultiFileReader::constant_columns(&self.filename, &self.partition_values, filename_column)
+    }
+}
+
 #[derive(Builder, Debug, Clone)]
 pub struct ReadCSVOptions {
-    #[builder(default = "1024")]
-    pub(crate) infer_schema_max_rows: usize,
     #[builder(default = "1024")]
     pub(crate) read_batch_size: usize,
     #[builder(default = "true")]
     pub(crate) has_header: bool,
     #[builder(default = "b','")]
     pub(crate) delimiter: u8,
+    #[builder(default = "b'\"'")]
+    pub(crate) quote: u8,
+    #[builder(default = "b'\"'")]
+    pub(crate) escape: u8,
+    /// The string of null values
+    #[builder(default = "String::new()")]
+    pub(crate) null_str: String,
+    /// The number of lines to skip before the header or the first row
+    #[builder(default = "0")]
+    pub(crate) skip: usize,
     #[builder(default = "None")]
-    pub(crate) datetime_format: Option<String>,
+    pub(crate) date_format: Option<String>,
+    /// Skip the lines which can't be parsed and append them to the reject_errors table
+    #[builder(default = "false")]
+    pub(crate) ignore_errors: bool,
+}
+
+/// The options of read_csv given by the user. The dialect options which are not given are
+/// detected by the sniffer.
+#[derive(Debug, Clone, Default)]
+pub struct ReadCSVArgs {
+    pub(crate) sniffer: CSVSniffer,
+    /// The names and types of the columns, overriding the detected ones
+    pub(crate) columns: Option<Vec<ColumnDefinition>>,
+    pub(crate) null_str: String,
+    pub(crate) all_varchar: bool,
+    pub(crate) ignore_errors: bool,
+    pub(crate) multi_file: MultiFileOptions,
+    /// The compression of the files, detected from their extensions when it's not set
+    pub(crate) compression: Option<FileCompression>,
+}
+
+impl ReadCSVArgs {
+    pub(crate) fn file_compression(&self, filename: &str) -> FileCompression {
+        self.compression
+            .unwrap_or_else(|| FileCompression::from_extension(filename))
+    }
 }
 
 impl ReadCSV {
-    fn parse_filename(args: &[FunctionArg]) -> Result<String, FunctionError> {
+    /// Parse the file names and glob patterns, and expand them into the files to read.
+    fn parse_filenames(args: &[FunctionArg]) -> Result<Vec<String>, FunctionError> {
         if let FunctionArg::Unnamed(e) = &args[0] {
-            return Ok(SqlparserResolver::resolve_func_arg_expr_to_string(e)?);
+            let patterns = MultiFileReader::parse_patterns(e)?;
+            return MultiFileReader::expand_patterns(&patterns);
         }
         Err(FunctionError::InternalError(format!(
             "unexpected filename arg: {}",
@@ -52,95 +129,345 @@ impl ReadCSV {
         )))
     }
 
-    fn parse_func_args(args: &[FunctionArg]) -> Result<(String, ReadCSVOptions), FunctionError> {
+    fn parse_byte_arg(name: &str, arg: &FunctionArgExpr) -> Result<u8, FunctionError> {
+        let string = SqlparserResolver::resolve_func_arg_expr_to_string(arg)?;
+        let bytes = string.as_bytes();
+        if bytes.len() != 1 {
+            return Err(FunctionError::InternalError(format!(
+                "{} must be a single byte",
+                name
+            )));
+        }
+        Ok(bytes[0])
+    }
+
+    /// Parse column definitions like `a INT, b VARCHAR`.
+    fn parse_columns_arg(arg: &FunctionArgExpr) -> Result<Vec<ColumnDefinition>, FunctionError> {
+        let string = SqlparserResolver::resolve_func_arg_expr_to_string(arg)?;
+        let dialect = PostgreSqlDialect {};
+        let column_defs = Parser::new(&dialect)
+            .try_with_sql(&string)
+            .and_then(|mut parser| parser.parse_comma_separated(Parser::parse_column_def))
+            .map_err(BindError::from)?;
+        let columns = column_defs
+            .iter()
+            .map(SqlparserResolver::column_def_to_column_definition)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(columns)
+    }
+
+    /// Parse the files and the options given by the user.
+    pub(crate) fn parse_func_args(
+        args: &[FunctionArg],
+    ) -> Result<(Vec<String>, ReadCSVArgs), FunctionError> {
         if args.is_empty() {
-            Err(FunctionError::InternalError(
+            return Err(FunctionError::InternalError(
                 "filename is required".to_string(),
-            ))
-        } else {
-            let filename = Self::parse_filename(args)?;
-            let mut options = ReadCSVOptionsBuilder::default();
-            for each in args.iter().skip(1) {
-                if let FunctionArg::Named { name, arg } = each {
-                    match name.value.as_str() {
-                        "delim" => {
-                            let string = SqlparserResolver::resolve_func_arg_expr_to_string(arg)?;
-                            let bytes = string.as_bytes();
-                            if bytes.len() != 1 {
-                                return Err(FunctionError::InternalError(
-                                    "delimiter must be a single byte".to_string(),
-                                ));
-                            }
-                            options.delimiter(bytes[0]);
-                        }
-                        "header" => {
-                            let v = SqlparserResolver::resolve_func_arg_expr_to_bool(arg)?;
-                            options.has_header(v);
-                        }
-                        other => {
-                            return Err(FunctionError::InternalError(format!(
-                                "unexpected arg: {}",
-                                other
-                            )))
-                        }
+            ));
+        }
+        let filenames = Self::parse_filenames(args)?;
+        let mut csv_args = ReadCSVArgs::default();
+        for each in args.iter().skip(1) {
+            if let FunctionArg::Named { name, arg } = each {
+                let name = name.value.as_str();
+                if MultiFileReader::parse_option(&mut csv_args.multi_file, name, arg)? {
+                    continue;
+                }
+                let sniffer = &mut csv_args.sniffer;
+                match name {
+                    "delim" => sniffer.delimiter = Some(Self::parse_byte_arg("delimiter", arg)?),
+                    "quote" => sniffer.quote = Some(Self::parse_byte_arg("quote", arg)?),
+                    "escape" => sniffer.escape = Some(Self::parse_byte_arg("escape", arg)?),
+                    "header" => {
+                        let v = SqlparserResolver::resolve_func_arg_expr_to_bool(arg)?;
+                        sniffer.has_header = Some(v);
+                    }
+                    "skip" => {
+                        sniffer.skip = SqlparserResolver::resolve_func_arg_expr_to_usize(arg)?
+                    }
+                    "sample_size" => {
+                        let v = SqlparserResolver::resolve_func_arg_expr_to_usize(arg)?;
+                        sniffer.sample_rows = v.max(1);
+                    }
+                    "dateformat" => {
+                        let v = SqlparserResolver::resolve_func_arg_expr_to_string(arg)?;
+                        sniffer.date_format = Some(v);
+                    }
+                    "timestampformat" => {
+                        let v = SqlparserResolver::resolve_func_arg_expr_to_string(arg)?;
+                        sniffer.timestamp_format = Some(v);
+                    }
+                    "nullstr" => {
+                        csv_args.null_str =
+                            SqlparserResolver::resolve_func_arg_expr_to_string(arg)?;
+                    }
+                    "columns" => csv_args.columns = Some(Self::parse_columns_arg(arg)?),
+                    "all_varchar" => {
+                        csv_args.all_varchar =
+                            SqlparserResolver::resolve_func_arg_expr_to_bool(arg)?;
+                    }
+                    "ignore_errors" => {
+                        csv_args.ignore_errors =
+                            SqlparserResolver::resolve_func_arg_expr_to_bool(arg)?;
+                    }
+                    "compression" => {
+                        let v = SqlparserResolver::resolve_func_arg_expr_to_string(arg)?;
+                        csv_args.compression = FileCompression::parse_option(&v)?;
+                    }
+                    other => {
+                        return Err(FunctionError::InternalError(format!(
+                            "unexpected arg: {}",
+                            other
+                        )))
                     }
-                } else {
-                    return Err(FunctionError::InternalError(
-                        "expected named arg".to_string(),
-                    ));
                 }
+            } else {
+                return Err(FunctionError::InternalError(
+                    "expected named arg".to_string(),
+                ));
             }
-            Ok((filename, options.build().unwrap()))
         }
+        if csv_args.multi_file.union_by_name && csv_args.columns.is_some() {
+            return Err(FunctionError::InternalError(
+                "columns can't be used with union_by_name".to_string(),
+            ));
+        }
+        Ok((filenames, csv_args))
     }
 
-    fn infer_arrow_schema(
-        filepath: String,
-        option: &ReadCSVOptions,
-    ) -> Result<SchemaRef, FunctionError> {
-        let mut file = File::open(filepath)?;
-        let (schema, _) = reader::infer_reader_schema(
-            &mut file,
-            option.delimiter,
-            Some(option.infer_schema_max_rows),
-            option.has_header,
+    /// The schema of the file, the columns given by the user replace the detected ones.
+    fn build_schema(args: &ReadCSVArgs, sniffed: &SniffResult) -> SchemaRef {
+        let fields = match &args.columns {
+            Some(columns) => columns
+                .iter()
+                .map(|c| Field::new(&c.name, c.ty.clone().into(), true))
+                .collect::<Vec<_>>(),
+            None if args.all_varchar => sniffed
+                .columns
+                .iter()
+                .map(|c| Field::new(&c.name, DataType::Utf8, true))
+                .collect::<Vec<_>>(),
+            None => return sniffed.schema(),
+        };
+        Arc::new(Schema::new(fields))
+    }
+
+    /// Sniff the file and build the dialect and the columns to read it with.
+    fn bind_file(filename: &str, args: &ReadCSVArgs) -> Result<ReadCSVFile, FunctionError> {
+        let compression = args.file_compression(filename);
+        let sniffed = args.sniffer.sniff(filename, compression)?;
+        let date_format = args
+            .sniffer
+            .date_format
+            .clone()
+            .or_else(|| sniffed.date_format().map(|f| f.to_string()));
+        let option = ReadCSVOptionsBuilder::default()
+            .delimiter(sniffed.delimiter)
+            .quote(sniffed.quote)
+            .escape(sniffed.escape)
+            .has_header(sniffed.has_header)
+            .skip(args.sniffer.skip)
+            .null_str(args.null_str.clone())
+            .date_format(date_format)
+            .ignore_errors(args.ignore_errors)
+            .build()
+            .unwrap();
+        Ok(ReadCSVFile {
+            filename: filename.to_string(),
+            option,
+            schema: Self::build_schema(args, &sniffed),
+            compression,
+            partition_values: vec![],
+        })
+    }
+
+    fn create_reader(
+        file: &ReadCSVFile,
+        projection: Vec<usize>,
+        bounds: Option<(usize, usize)>,
+    ) -> Result<CSVReader<Box<dyn BufRead + Send>>, FunctionError> {
+        let mut reader = CSVReader::new(
+            file.compression.open(&file.filename)?,
+            file.schema.clone(),
+            Some(projection),
+            file.option.clone(),
+            bounds,
+        );
+        reader.skip_header()?;
+        Ok(reader)
+    }
+
+    /// Read the byte range of the file, the range must start at the beginning of a record and
+    /// can't contain the header.
+    fn create_range_reader(
+        file: ReadCSVFile,
+        projection: Vec<usize>,
+        (start, end): (u64, u64),
+    ) -> Result<CSVReader<BufReader<Take<File>>>, FunctionError> {
+        let mut reader = File::open(&file.filename)?;
+        reader.seek(SeekFrom::Start(start))?;
+        let reader = CSVReader::new(
+            BufReader::new(reader.take(end - start)),
+            file.schema,
+            Some(projection),
+            file.option,
+            None,
+        );
+        Ok(reader)
+    }
+
+    /// Append the rejected lines to the reject_errors table, the table is created by the first
+    /// rejected line.
+    fn store_rejects(
+        context: Arc<ClientContext>,
+        filename: &str,
+        rejects: Vec<CSVReject>,
+    ) -> FunctionResult<()> {
+        if rejects.is_empty() {
+            return Ok(());
+        }
+        let names = vec!["file".to_string(), "line".to_string(), "reason".to_string()];
+        let types = vec![
+            LogicalType::Varchar,
+            LogicalType::Bigint,
+            LogicalType::Varchar,
+        ];
+        let table = match Catalog::get_table(
+            context.clone(),
+            DEFAULT_SCHEMA.to_string(),
+            REJECT_ERRORS_TABLE.to_string(),
+        ) {
+            Ok(entry) => entry.storage,
+            Err(_) => {
+                let columns = names
+                    .iter()
+                    .zip(types.iter())
+                    .map(|(name, ty)| ColumnDefinition::new(name.clone(), ty.clone()))
+                    .collect();
+                let data_table = DataTable::new(
+                    DataTableInfo::new(DEFAULT_SCHEMA.to_string(), REJECT_ERRORS_TABLE.to_string()),
+                    columns,
+                );
+                Catalog::create_table(
+                    context.clone(),
+                    DEFAULT_SCHEMA.to_string(),
+                    REJECT_ERRORS_TABLE.to_string(),
+                    data_table.clone(),
+                )?;
+                LocalStorage::init_table(context.clone(), &data_table)?;
+                data_table
+            }
+        };
+        let schema = SchemaUtil::new_schema_ref(&names, &types);
+        let files = StringArray::from(vec![filename; rejects.len()]);
+        let lines = Int64Array::from_iter_values(rejects.iter().map(|r| r.line as i64));
+        let reasons = StringArray::from_iter_values(rejects.iter().map(|r| r.reason.as_str()));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![Arc::new(files), Arc::new(lines), Arc::new(reasons)],
         )?;
-        Ok(Arc::new(schema))
+        LocalStorage::append(context, &table, batch)?;
+        Ok(())
     }
 
-    fn create_reader(input: ReadCSVInputData) -> Result<Reader<File>, FunctionError> {
-        let file = File::open(input.filename)?;
-        // convert bounds into csv bounds concept: (min line, max line)
-        let new_bounds = input.bounds.map(|(offset, limit)| {
-            if limit == usize::MAX {
-                (offset, limit)
-            } else {
-                (offset, offset + limit + 1)
+    /// Parse the byte range on a blocking thread, the batches are sent through a bounded channel
+    /// so the parser doesn't run too far ahead of the consumer.
+    fn spawn_range_reader(
+        file: ReadCSVFile,
+        projection: Vec<usize>,
+        range: (u64, u64),
+    ) -> mpsc::Receiver<FunctionResult<RecordBatch>> {
+        let (tx, rx) = mpsc::channel(RANGE_READER_BUFFER);
+        tokio::task::spawn_blocking(move || {
+            match Self::create_range_reader(file, projection, range) {
+                Ok(reader) => {
+                    for batch in reader {
+                        // the receiver is dropped when the consumer stops early
+                        if tx.blocking_send(batch).is_err() {
+                            break;
+                        }
+                    }
+                }
+                Err(e) => {
+                    let _ = tx.blocking_send(Err(e));
+                }
             }
         });
-        let reader = Reader::new(
-            file,
-            input.schema,
-            input.option.has_header,
-            Some(input.option.delimiter),
-            input.option.read_batch_size,
-            new_bounds,
-            input.projection,
-            input.option.datetime_format,
+        rx
+    }
+
+    /// Split the data of the file into at most `parts` byte ranges, each range starts at the
+    /// beginning of a record. The records are scanned from the start of the data to find where
+    /// they end, so the newlines in quoted values don't split them.
+    fn split_file_ranges(
+        filename: &str,
+        option: &ReadCSVOptions,
+        parts: usize,
+        min_range_bytes: u64,
+    ) -> Result<Vec<(u64, u64)>, FunctionError> {
+        let mut file = File::open(filename)?;
+        let file_len = file.metadata()?.len();
+        let mut data_start = 0;
+        for _ in 0..option.skip + option.has_header as usize {
+            data_start = Self::next_line_start(&mut file, data_start, file_len)?;
+        }
+        let data_len = file_len - data_start;
+        let parts = (data_len / min_range_bytes.max(1)).clamp(1, parts.max(1) as u64);
+        let range_len = data_len / parts;
+
+        file.seek(SeekFrom::Start(data_start))?;
+        let mut boundaries = CSVRecordBoundaries::new(
+            BufReader::new(file),
+            data_start,
+            option.delimiter,
+            option.quote,
+            option.escape,
         );
-        Ok(reader)
+        let mut ranges = vec![];
+        let mut start = data_start;
+        for i in 1..parts {
+            let candidate = data_start + i * range_len;
+            if candidate <= start {
+                continue;
+            }
+            let end = match boundaries.next_record_start(candidate)? {
+                Some(end) if end < file_len => end,
+                _ => break,
+            };
+            ranges.push((start, end));
+            start = end;
+        }
+        if start < file_len {
+            ranges.push((start, file_len));
+        }
+        Ok(ranges)
     }
 
-    fn parse_col_names_types(
-        schema: &SchemaRef,
-    ) -> Result<(Vec<String>, Vec<LogicalType>), FunctionError> {
-        let mut col_names = vec![];
-        let mut col_types = vec![];
-        for field in schema.fields() {
-            col_names.push(field.name().to_string().to_lowercase());
-            col_types.push(field.data_type().try_into()?);
+    /// The position after the first newline at or after `pos`, or the end of the file. The
+    /// leading lines and the header are skipped as lines, like the reader does.
+    fn next_line_start(file: &mut File, pos: u64, file_len: u64) -> io::Result<u64> {
+        file.seek(SeekFrom::Start(pos))?;
+        let mut buf = [0u8; 8192];
+        let mut offset = pos;
+        loop {
+            let n = file.read(&mut buf)?;
+            if n == 0 {
+                return Ok(file_len);
+            }
+            if let Some(idx) = buf[..n].iter().position(|b| *b == b'\n') {
+                return Ok(offset + idx as u64 + 1);
+            }
+            offset += n as u64;
         }
-        Ok((col_names, col_types))
+    }
+
+    fn parallelism(context: &ClientContext) -> usize {
+        context
+            .db
+            .config
+            .read()
+            .map(|config| config.threads)
+            .unwrap_or(1)
     }
 
     fn bind_func(
@@ -150,15 +477,44 @@ impl ReadCSV {
         return_names: &mut Vec<String>,
     ) -> Result<Option<FunctionData>, FunctionError> {
         if let Some(args) = input.func_args {
-            let (filename, option) = Self::parse_func_args(args.as_slice())?;
-            let schema = Self::infer_arrow_schema(filename.clone(), &option)?;
-            let (col_names, col_types) = Self::parse_col_names_types(&schema)?;
+            let (filenames, args) = Self::parse_func_args(args.as_slice())?;
+            let files = if args.multi_file.union_by_name {
+                filenames
+                    .iter()
+                    .map(|filename| Self::bind_file(filename, &args))
+                    .collect::<Result<Vec<_>, _>>()?
+            } else {
+                // the files are expected to have the dialect and the columns of the first one
+                let first = Self::bind_file(&filenames[0], &args)?;
+                filenames
+                    .iter()
+                    .map(|filename| ReadCSVFile {
+                        filename: filename.clone(),
+                        compression: args.file_compression(filename),
+                        ..first.clone()
+                    })
+                    .collect()
+            };
+            let (partitioning, partition_values) =
+                HivePartitioning::detect(&filenames, args.multi_file.hive_partitioning)?;
+            let files = files
+                .into_iter()
+                .zip(partition_values)
+                .map(|(file, partition_values)| ReadCSVFile {
+                    partition_values,
+                    ..file
+                })
+                .collect::<Vec<_>>();
+            let schemas = files.iter().map(|f| f.schema.clone()).collect::<Vec<_>>();
+            let schema = MultiFileReader::scan_schema(&schemas, &partitioning, &args.multi_file)?;
+            let (col_names, col_types) = MultiFileReader::column_names_types(&schema)?;
             return_types.extend(col_types);
             return_names.extend(col_names);
             let input_data = ReadCSVInputDataBuilder::default()
-                .filename(filename)
+                .files(files)
                 .schema(schema)
-                .option(option)
+                .partition_columns(partitioning.names.len())
+                .filename_column(args.multi_file.filename)
                 .build()
                 .unwrap();
             Ok(Some(FunctionData::ReadCSVInputData(Box::new(input_data))))
@@ -170,14 +526,64 @@ impl ReadCSV {
     }
 
     fn scan_func(
-        _context: Arc<ClientContext>,
+        context: Arc<ClientContext>,
         input: TableFunctionInput,
     ) -> FunctionResult<BoxStream<'static, FunctionResult<RecordBatch>>> {
         if let Some(FunctionData::ReadCSVInputData(data)) = input.bind_data {
-            let mut reader = Self::create_reader(*data)?;
+            let parallelism = Self::parallelism(&context);
             let stream = Box::pin(async_stream::try_stream! {
-                while let Some(batch) = reader.next().transpose()? {
-                    yield batch;
+                // the bounds count the rows of all the files, in the order of the files
+                let mut bounds = data.bounds;
+                for file in data.files.iter() {
+                    if matches!(bounds, Some((_, 0))) {
+                        break;
+                    }
+                    let mapping = FileColumnMapping::new(
+                        &file.schema,
+                        &data.schema,
+                        data.projection.as_deref(),
+                        file.constant_columns(data.filename_column),
+                    );
+                    // the bounds and the line numbers of the rejects count from the start of the
+                    // file, and compressed files can only be read from the start, so they need
+                    // one reader
+                    let splittable =
+                        !file.option.ignore_errors && !file.compression.is_compressed();
+                    let ranges = if bounds.is_none() && splittable {
+                        Self::split_file_ranges(
+                            &file.filename,
+                            &file.option,
+                            parallelism,
+                            MIN_RANGE_BYTES,
+                        )?
+                    } else {
+                        vec![]
+                    };
+                    if ranges.len() <= 1 {
+                        let mut reader =
+                            Self::create_reader(file, mapping.file_projection.clone(), bounds)?;
+                        while let Some(batch) = reader.next().transpose()? {
+                            yield mapping.map_batch(batch)?;
+                        }
+                        bounds = reader.remaining_bounds();
+                        let rejects = reader.take_rejects();
+                        Self::store_rejects(context.clone(), &file.filename, rejects)?;
+                        continue;
+                    }
+
+                    // all ranges are parsed in parallel, the batches are returned in the file order
+                    let receivers = ranges
+                        .into_iter()
+                        .map(|range| {
+                            let projection = mapping.file_projection.clone();
+                            Self::spawn_range_reader(file.clone(), projection, range)
+                        })
+                        .collect::<Vec<_>>();
+                    for mut receiver in receivers {
+                        while let Some(batch) = receiver.recv().await {
+                            yield mapping.map_batch(batch?)?;
+                        }
+                    }
                 }
             });
             Ok(stream)
@@ -189,11 +595,82 @@ impl ReadCSV {
     }
 
     pub fn register_function(set: &mut BuiltinFunctions) -> Result<(), FunctionError> {
-        set.add_table_functions(TableFunction::new(
+        let mut function = TableFunction::new(
             "read_csv".to_string(),
             Some(Self::bind_func),
             Self::scan_func,
-        ))?;
+        );
+        function.projection_pushdown = true;
+        set.add_table_functions(function)?;
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn test_split_file_ranges_at_line_boundaries() {
+        let filename = "tests/csv/employee.csv";
+        let content = fs::read(filename).unwrap();
+        let header_len = content.iter().position(|b| *b == b'\n').unwrap() as u64 + 1;
+
+        let option = ReadCSVOptionsBuilder::default().build().unwrap();
+        let ranges = ReadCSV::split_file_ranges(filename, &option, 4, 1).unwrap();
+        assert!(ranges.len() > 1 && ranges.len() <= 4);
+        assert_eq!(ranges[0].0, header_len);
+        assert_eq!(ranges.last().unwrap().1, content.len() as u64);
+        for window in ranges.windows(2) {
+            assert_eq!(window[0].1, window[1].0);
+            assert_eq!(content[window[1].0 as usize - 1], b'\n');
+        }
+
+        let ranges = ReadCSV::split_file_ranges(filename, &option, 4, MIN_RANGE_BYTES).unwrap();
+        assert_eq!(ranges, vec![(header_len, content.len() as u64)]);
+    }
+
+    #[test]
+    fn test_split_file_ranges_with_quoted_newlines() {
+        fs::create_dir_all("target/read_csv").unwrap();
+        let filename = "target/read_csv/quoted_newlines.csv";
+        let quoted = "a \"\"quoted\"\" value,\nwith newlines\n\n";
+        let value = "a \"quoted\" value,\nwith newlines\n\n";
+        // the data is larger than two ranges, most of its newlines are in quoted values
+        let rows = 2 * MIN_RANGE_BYTES as usize / quoted.len();
+        let mut content = "id,value\n".to_string();
+        for id in 0..rows {
+            content.push_str(&format!("{},\"{}\"\n", id, quoted));
+        }
+        fs::write(filename, content).unwrap();
+
+        let option = ReadCSVOptionsBuilder::default().build().unwrap();
+        let ranges = ReadCSV::split_file_ranges(filename, &option, 4, MIN_RANGE_BYTES).unwrap();
+        assert!(ranges.len() > 1);
+        let file = ReadCSVFile {
+            filename: filename.to_string(),
+            option,
+            schema: Arc::new(Schema::new(vec![
+                Field::new("id", DataType::Int64, true),
+                Field::new("value", DataType::Utf8, true),
+            ])),
+            compression: FileCompression::None,
+            partition_values: vec![],
+        };
+        let mut ids = vec![];
+        for range in ranges {
+            for batch in ReadCSV::create_range_reader(file.clone(), vec![0, 1], range).unwrap() {
+                let batch = batch.unwrap();
+                let id_array = batch.column(0).as_any().downcast_ref::<Int64Array>();
+                let value_array = batch.column(1).as_any().downcast_ref::<StringArray>();
+                for row in 0..batch.num_rows() {
+                    ids.push(id_array.unwrap().value(row));
+                    assert_eq!(value_array.unwrap().value(row), value);
+                }
+            }
+        }
+        assert_eq!(ids, (0..rows as i64).collect::<Vec<_>>());
+    }
+}
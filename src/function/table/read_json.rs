@@ -0,0 +1,363 @@
+use std::io::{self, BufRead};
+use std::sync::Arc;
+
+use arrow::datatypes::{Field, Schema, SchemaRef};
+use arrow::error::ArrowError;
+use arrow::json::reader::{infer_json_schema_from_iterator, Decoder, DecoderOptions};
+use arrow::record_batch::RecordBatch;
+use derive_builder::Builder;
+use futures::stream::BoxStream;
+use serde_json::Value;
+use sqlparser::ast::FunctionArg;
+
+use super::{
+    FileColumnMapping, FileCompression, HivePartitioning, MultiFileOptions, MultiFileReader,
+    TableFunction, TableFunctionBindInput, TableFunctionInput,
+};
+use crate::function::{BuiltinFunctions, FunctionData, FunctionError, FunctionResult};
+use crate::main_entry::ClientContext;
+use crate::planner_v2::SqlparserResolver;
+use crate::types_v2::{LogicalType, ScalarValue};
+
+/// The number of rows of the batches read from the files.
+const READ_BATCH_SIZE: usize = 1024;
+/// The number of records read to infer the columns by default.
+const DEFAULT_SAMPLE_SIZE: usize = 1024;
+
+/// How the records are laid out in a json file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsonFormat {
+    /// Detected from the first character of the file
+    Auto,
+    /// The records are the elements of a top-level array
+    Array,
+    /// One record per line
+    NewlineDelimited,
+}
+
+impl JsonFormat {
+    /// Parse the `format` arg.
+    pub fn parse_option(value: &str) -> FunctionResult<Self> {
+        match value.to_lowercase().as_str() {
+            "auto" => Ok(JsonFormat::Auto),
+            "array" => Ok(JsonFormat::Array),
+            "newline_delimited" => Ok(JsonFormat::NewlineDelimited),
+            other => Err(FunctionError::InternalError(format!(
+                "unsupported json format: {}",
+                other
+            ))),
+        }
+    }
+}
+
+/// The json records of a file.
+type JsonRecords = Box<dyn Iterator<Item = Result<Value, ArrowError>> + Send>;
+
+pub struct ReadJSON;
+
+#[derive(Builder, Debug, Clone)]
+pub struct ReadJSONInputData {
+    pub(crate) files: Vec<ReadJSONFile>,
+    /// The columns of the scan: the fields of the records, followed by the partition columns and
+    /// the filename column
+    pub(crate) schema: SchemaRef,
+    /// The fields of the records inferred from the sample
+    pub(crate) json_schema: SchemaRef,
+    pub(crate) format: JsonFormat,
+    #[builder(default = "0")]
+    pub(crate) partition_columns: usize,
+    #[builder(default = "false")]
+    pub(crate) filename_column: bool,
+    #[builder(default = "None")]
+    pub(crate) projection: Option<Vec<usize>>,
+}
+
+impl ReadJSONInputData {
+    /// The number of columns read from the files, the others have the same value for all the
+    /// rows of a file.
+    pub fn file_columns(&self) -> usize {
+        self.schema.fields().len() - self.partition_columns - self.filename_column as usize
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ReadJSONFile {
+    pub(crate) filename: String,
+    /// The values of the hive partition keys in the path of the file
+    pub(crate) partition_values: Vec<ScalarValue>,
+}
+
+/// The args of read_json.
+struct ReadJSONArgs {
+    format: JsonFormat,
+    sample_size: usize,
+    multi_file: MultiFileOptions,
+}
+
+impl ReadJSON {
+    fn parse_func_args(
+        args: &[FunctionArg],
+        format: JsonFormat,
+    ) -> Result<(Vec<String>, ReadJSONArgs), FunctionError> {
+        let patterns = match args.first() {
+            Some(FunctionArg::Unnamed(e)) => MultiFileReader::parse_patterns(e)?,
+            _ => {
+                return Err(FunctionError::InternalError(
+                    "filename is required".to_string(),
+                ))
+            }
+        };
+        let filenames = MultiFileReader::expand_patterns(&patterns)?;
+        let mut json_args = ReadJSONArgs {
+            format,
+            sample_size: DEFAULT_SAMPLE_SIZE,
+            multi_file: MultiFileOptions::default(),
+        };
+        for each in args.iter().skip(1) {
+            if let FunctionArg::Named { name, arg } = each {
+                let name = name.value.as_str();
+                if MultiFileReader::parse_option(&mut json_args.multi_file, name, arg)? {
+                    continue;
+                }
+                match name {
+                    "format" => {
+                        let v = SqlparserResolver::resolve_func_arg_expr_to_string(arg)?;
+                        json_args.format = JsonFormat::parse_option(&v)?;
+                    }
+                    "sample_size" => {
+                        let v = SqlparserResolver::resolve_func_arg_expr_to_usize(arg)?;
+                        json_args.sample_size = v.max(1);
+                    }
+                    other => {
+                        return Err(FunctionError::InternalError(format!(
+                            "unexpected arg: {}",
+                            other
+                        )))
+                    }
+                }
+            } else {
+                return Err(FunctionError::InternalError(
+                    "expected named arg".to_string(),
+                ));
+            }
+        }
+        Ok((filenames, json_args))
+    }
+
+    /// The first byte of the reader that is not a whitespace, it's not consumed.
+    fn first_byte(reader: &mut dyn BufRead) -> io::Result<Option<u8>> {
+        loop {
+            let buf = reader.fill_buf()?;
+            if buf.is_empty() {
+                return Ok(None);
+            }
+            match buf.iter().position(|b| !b.is_ascii_whitespace()) {
+                Some(pos) => {
+                    let byte = buf[pos];
+                    reader.consume(pos);
+                    return Ok(Some(byte));
+                }
+                None => {
+                    let len = buf.len();
+                    reader.consume(len);
+                }
+            }
+        }
+    }
+
+    /// Read the records of the file, a file of the array format is parsed at once.
+    fn read_records(filename: &str, format: JsonFormat) -> FunctionResult<JsonRecords> {
+        let mut reader = FileCompression::from_extension(filename).open(filename)?;
+        let format = match format {
+            JsonFormat::Auto if Self::first_byte(&mut reader)? == Some(b'[') => JsonFormat::Array,
+            JsonFormat::Auto => JsonFormat::NewlineDelimited,
+            format => format,
+        };
+        if format == JsonFormat::Array {
+            return match serde_json::from_reader::<_, Value>(reader)? {
+                Value::Array(records) => Ok(Box::new(records.into_iter().map(Ok))),
+                _ => Err(FunctionError::InternalError(format!(
+                    "expected a json array in {}",
+                    filename
+                ))),
+            };
+        }
+        let records = serde_json::Deserializer::from_reader(reader)
+            .into_iter::<Value>()
+            .map(|r| r.map_err(|e| ArrowError::JsonError(e.to_string())));
+        Ok(Box::new(records))
+    }
+
+    /// The type of a column of the records. The fields that are always null are read as VARCHAR,
+    /// and the nested types are rebuilt so they match the types of the scan.
+    fn column_type(ty: LogicalType) -> LogicalType {
+        match ty {
+            LogicalType::SqlNull => LogicalType::Varchar,
+            LogicalType::Struct(fields) => LogicalType::Struct(
+                fields
+                    .into_iter()
+                    .map(|(name, ty)| (name, Self::column_type(ty)))
+                    .collect(),
+            ),
+            LogicalType::List(ty) => LogicalType::List(Box::new(Self::column_type(*ty))),
+            ty => ty,
+        }
+    }
+
+    /// Infer the fields of the records from the first `sample_size` records of the files.
+    fn infer_schema(filenames: &[String], args: &ReadJSONArgs) -> FunctionResult<SchemaRef> {
+        let mut sample = Vec::with_capacity(args.sample_size);
+        for filename in filenames {
+            let records = Self::read_records(filename, args.format)?;
+            for record in records.take(args.sample_size - sample.len()) {
+                sample.push(record?);
+            }
+            if sample.len() == args.sample_size {
+                break;
+            }
+        }
+        let inferred = infer_json_schema_from_iterator(sample.into_iter().map(Ok))?;
+        if inferred.fields().is_empty() {
+            return Err(FunctionError::InternalError(
+                "no fields found in the json records".to_string(),
+            ));
+        }
+        let fields = inferred
+            .fields()
+            .iter()
+            .map(|field| {
+                let ty = Self::column_type(field.data_type().try_into()?);
+                Ok(Field::new(field.name(), ty.into(), true))
+            })
+            .collect::<FunctionResult<Vec<_>>>()?;
+        Ok(Arc::new(Schema::new(fields)))
+    }
+
+    fn bind(
+        input: TableFunctionBindInput,
+        format: JsonFormat,
+        return_types: &mut Vec<LogicalType>,
+        return_names: &mut Vec<String>,
+    ) -> Result<Option<FunctionData>, FunctionError> {
+        if let Some(args) = input.func_args {
+            let (filenames, json_args) = Self::parse_func_args(args.as_slice(), format)?;
+            let json_schema = Self::infer_schema(&filenames, &json_args)?;
+            let options = &json_args.multi_file;
+            let (partitioning, partition_values) =
+                HivePartitioning::detect(&filenames, options.hive_partitioning)?;
+            let files = filenames
+                .into_iter()
+                .zip(partition_values)
+                .map(|(filename, partition_values)| ReadJSONFile {
+                    filename,
+                    partition_values,
+                })
+                .collect::<Vec<_>>();
+            let schema =
+                MultiFileReader::scan_schema(&[json_schema.clone()], &partitioning, options)?;
+            let (col_names, col_types) = MultiFileReader::column_names_types(&schema)?;
+            return_types.extend(col_types);
+            return_names.extend(col_names);
+            let input_data = ReadJSONInputDataBuilder::default()
+                .files(files)
+                .schema(schema)
+                .json_schema(json_schema)
+                .format(json_args.format)
+                .partition_columns(partitioning.names.len())
+                .filename_column(options.filename)
+                .build()
+                .unwrap();
+            Ok(Some(FunctionData::ReadJSONInputData(Box::new(input_data))))
+        } else {
+            Err(FunctionError::InternalError(
+                "unexpected bind data type".to_string(),
+            ))
+        }
+    }
+
+    fn bind_func(
+        _context: Arc<ClientContext>,
+        input: TableFunctionBindInput,
+        return_types: &mut Vec<LogicalType>,
+        return_names: &mut Vec<String>,
+    ) -> Result<Option<FunctionData>, FunctionError> {
+        Self::bind(input, JsonFormat::Auto, return_types, return_names)
+    }
+
+    fn bind_ndjson_func(
+        _context: Arc<ClientContext>,
+        input: TableFunctionBindInput,
+        return_types: &mut Vec<LogicalType>,
+        return_names: &mut Vec<String>,
+    ) -> Result<Option<FunctionData>, FunctionError> {
+        Self::bind(
+            input,
+            JsonFormat::NewlineDelimited,
+            return_types,
+            return_names,
+        )
+    }
+
+    /// The decoder of the projected fields of the records, and the mapping of the decoded
+    /// columns to the columns of the scan.
+    fn create_decoder(
+        file: &ReadJSONFile,
+        data: &ReadJSONInputData,
+    ) -> FunctionResult<(Decoder, FileColumnMapping)> {
+        let constants = MultiFileReader::constant_columns(
+            &file.filename,
+            &file.partition_values,
+            data.filename_column,
+        );
+        let mapping = FileColumnMapping::new(
+            &data.json_schema,
+            &data.schema,
+            data.projection.as_deref(),
+            constants,
+        );
+        // the decoder only builds the fields of its schema, in the order of the mapping
+        let schema = data.json_schema.project(&mapping.file_projection)?;
+        let options = DecoderOptions::new().with_batch_size(READ_BATCH_SIZE);
+        Ok((Decoder::new(Arc::new(schema), options), mapping))
+    }
+
+    fn scan_func(
+        _context: Arc<ClientContext>,
+        input: TableFunctionInput,
+    ) -> FunctionResult<BoxStream<'static, FunctionResult<RecordBatch>>> {
+        if let Some(FunctionData::ReadJSONInputData(data)) = input.bind_data {
+            let stream = Box::pin(async_stream::try_stream! {
+                for file in data.files.iter() {
+                    let (decoder, mapping) = Self::create_decoder(file, &data)?;
+                    let mut records = Self::read_records(&file.filename, data.format)?;
+                    while let Some(batch) = decoder.next_batch(&mut records)? {
+                        yield mapping.map_batch(batch)?;
+                    }
+                }
+            });
+            Ok(stream)
+        } else {
+            Err(FunctionError::InternalError(
+                "unexpected bind data type".to_string(),
+            ))
+        }
+    }
+
+    pub fn register_function(set: &mut BuiltinFunctions) -> Result<(), FunctionError> {
+        let mut function = TableFunction::new(
+            "read_json".to_string(),
+            Some(Self::bind_func),
+            Self::scan_func,
+        );
+        function.projection_pushdown = true;
+        set.add_table_functions(function)?;
+        let mut function = TableFunction::new(
+            "read_ndjson".to_string(),
+            Some(Self::bind_ndjson_func),
+            Self::scan_func,
+        );
+        function.projection_pushdown = true;
+        set.add_table_functions(function)?;
+        Ok(())
+    }
+}
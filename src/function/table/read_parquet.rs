@@ -0,0 +1,248 @@
+use std::fs::File;
+use std::sync::Arc;
+
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use derive_builder::Builder;
+use futures::stream::BoxStream;
+use parquet::arrow::arrow_reader::{ParquetRecordBatchReader, ParquetRecordBatchReaderBuilder};
+use parquet::arrow::ProjectionMask;
+use parquet::file::metadata::ParquetMetaData;
+use sqlparser::ast::FunctionArg;
+
+use super::{
+    ColumnPredicate, FileColumnMapping, HivePartitioning, MultiFileOptions, MultiFileReader,
+    RowGroupPruner, TableFunction, TableFunctionBindInput, TableFunctionInput,
+};
+use crate::function::{BuiltinFunctions, FunctionData, FunctionError, FunctionResult};
+use crate::main_entry::ClientContext;
+use crate::types_v2::{LogicalType, ScalarValue};
+
+/// The number of rows of the batches read from the files.
+const READ_BATCH_SIZE: usize = 1024;
+
+pub struct ReadParquet;
+
+#[derive(Builder, Debug, Clone)]
+pub struct ReadParquetInputData {
+    pub(crate) files: Vec<ReadParquetFile>,
+    /// The columns of the scan: the columns of the files, followed by the partition columns and
+    /// the filename column
+    pub(crate) schema: SchemaRef,
+    #[builder(default = "0")]
+    pub(crate) partition_columns: usize,
+    #[builder(default = "false")]
+    pub(crate) filename_column: bool,
+    #[builder(default = "None")]
+    pub(crate) projection: Option<Vec<usize>>,
+    /// The filters on the columns of the files, the row groups whose statistics can't pass them
+    /// are skipped
+    #[builder(default = "vec![]")]
+    pub(crate) predicates: Vec<ColumnPredicate>,
+}
+
+impl ReadParquetInputData {
+    /// The number of columns read from the files, the others have the same value for all the
+    /// rows of a file.
+    pub fn file_columns(&self) -> usize {
+        self.schema.fields().len() - self.partition_columns - self.filename_column as usize
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ReadParquetFile {
+    pub(crate) filename: String,
+    /// The values of the hive partition keys in the path of the file
+    pub(crate) partition_values: Vec<ScalarValue>,
+}
+
+impl ReadParquet {
+    fn parse_func_args(
+        args: &[FunctionArg],
+    ) -> Result<(Vec<String>, MultiFileOptions), FunctionError> {
+        let patterns = match args.first() {
+            Some(FunctionArg::Unnamed(e)) => MultiFileReader::parse_patterns(e)?,
+            _ => {
+                return Err(FunctionError::InternalError(
+                    "filename is required".to_string(),
+                ))
+            }
+        };
+        let filenames = MultiFileReader::expand_patterns(&patterns)?;
+        let mut options = MultiFileOptions::default();
+        for each in args.iter().skip(1) {
+            match each {
+                FunctionArg::Named { name, arg } => {
+                    if !MultiFileReader::parse_option(&mut options, &name.value, arg)? {
+                        return Err(FunctionError::InternalError(format!(
+                            "unexpected arg: {}",
+                            name.value
+                        )));
+                    }
+                }
+                _ => {
+                    return Err(FunctionError::InternalError(
+                        "expected named arg".to_string(),
+                    ))
+                }
+            }
+        }
+        Ok((filenames, options))
+    }
+
+    /// Read the schema of the file from its footer.
+    fn read_schema(filename: &str) -> FunctionResult<SchemaRef> {
+        let builder = ParquetRecordBatchReaderBuilder::try_new(File::open(filename)?)?;
+        Ok(builder.schema().clone())
+    }
+
+    /// The number of rows of the file, from its footer.
+    pub fn read_row_count(filename: &str) -> FunctionResult<usize> {
+        let builder = ParquetRecordBatchReaderBuilder::try_new(File::open(filename)?)?;
+        Ok(builder.metadata().file_metadata().num_rows() as usize)
+    }
+
+    /// The row groups of the file which may have rows passing the predicates.
+    fn prune_row_groups(
+        metadata: &ParquetMetaData,
+        file_schema: &SchemaRef,
+        data: &ReadParquetInputData,
+    ) -> Vec<usize> {
+        let leaves = metadata.file_metadata().schema_descr().columns();
+        let columns = data
+            .predicates
+            .iter()
+            .map(|p| {
+                let name = data.schema.field(p.column_idx).name();
+                let field = file_schema.field_with_name(name).ok()?;
+                let leaf_idx = leaves.iter().position(|c| c.path().string() == *name)?;
+                Some((leaf_idx, field.data_type().clone()))
+            })
+            .collect::<Vec<_>>();
+        (0..metadata.num_row_groups())
+            .filter(|idx| {
+                RowGroupPruner::may_pass(metadata.row_group(*idx), &data.predicates, &columns)
+            })
+            .collect()
+    }
+
+    /// Open the file with the projected columns and the row groups which may pass the
+    /// predicates. The columns of the files are mapped by name, so the files can have their
+    /// columns in any order.
+    fn create_reader(
+        file: &ReadParquetFile,
+        data: &ReadParquetInputData,
+    ) -> FunctionResult<(ParquetRecordBatchReader, FileColumnMapping, Vec<usize>)> {
+        let builder = ParquetRecordBatchReaderBuilder::try_new(File::open(&file.filename)?)?;
+        let file_schema = builder.schema().clone();
+        let constants = MultiFileReader::constant_columns(
+            &file.filename,
+            &file.partition_values,
+            data.filename_column,
+        );
+        let mapping = FileColumnMapping::new(
+            &file_schema,
+            &data.schema,
+            data.projection.as_deref(),
+            constants,
+        );
+        // the reader returns the projected columns in the order of the file
+        let mut file_columns = mapping.file_projection.clone();
+        file_columns.sort_unstable();
+        file_columns.dedup();
+        let positions = mapping
+            .file_projection
+            .iter()
+            .map(|idx| file_columns.binary_search(idx).unwrap())
+            .collect::<Vec<_>>();
+        let row_groups = Self::prune_row_groups(builder.metadata(), &file_schema, data);
+        let mask = ProjectionMask::roots(builder.parquet_schema(), file_columns);
+        let reader = builder
+            .with_projection(mask)
+            .with_row_groups(row_groups)
+            .with_batch_size(READ_BATCH_SIZE)
+            .build()?;
+        Ok((reader, mapping, positions))
+    }
+
+    fn bind_func(
+        _context: Arc<ClientContext>,
+        input: TableFunctionBindInput,
+        return_types: &mut Vec<LogicalType>,
+        return_names: &mut Vec<String>,
+    ) -> Result<Option<FunctionData>, FunctionError> {
+        if let Some(args) = input.func_args {
+            let (filenames, options) = Self::parse_func_args(args.as_slice())?;
+            let schemas = if options.union_by_name {
+                filenames
+                    .iter()
+                    .map(|f| Self::read_schema(f))
+                    .collect::<Result<Vec<_>, _>>()?
+            } else {
+                // the files are expected to have the columns of the first one
+                vec![Self::read_schema(&filenames[0])?]
+            };
+            let (partitioning, partition_values) =
+                HivePartitioning::detect(&filenames, options.hive_partitioning)?;
+            let files = filenames
+                .into_iter()
+                .zip(partition_values)
+                .map(|(filename, partition_values)| ReadParquetFile {
+                    filename,
+                    partition_values,
+                })
+                .collect::<Vec<_>>();
+            let schema = MultiFileReader::scan_schema(&schemas, &partitioning, &options)?;
+            let (col_names, col_types) = MultiFileReader::column_names_types(&schema)?;
+            return_types.extend(col_types);
+            return_names.extend(col_names);
+            let input_data = ReadParquetInputDataBuilder::default()
+                .files(files)
+                .schema(schema)
+                .partition_columns(partitioning.names.len())
+                .filename_column(options.filename)
+                .build()
+                .unwrap();
+            Ok(Some(FunctionData::ReadParquetInputData(Box::new(
+                input_data,
+            ))))
+        } else {
+            Err(FunctionError::InternalError(
+                "unexpected bind data type".to_string(),
+            ))
+        }
+    }
+
+    fn scan_func(
+        _context: Arc<ClientContext>,
+        input: TableFunctionInput,
+    ) -> FunctionResult<BoxStream<'static, FunctionResult<RecordBatch>>> {
+        if let Some(FunctionData::ReadParquetInputData(data)) = input.bind_data {
+            let stream = Box::pin(async_stream::try_stream! {
+                for file in data.files.iter() {
+                    let (reader, mapping, positions) = Self::create_reader(file, &data)?;
+                    for batch in reader {
+                        let batch = batch?.project(&positions)?;
+                        yield mapping.map_batch(batch)?;
+                    }
+                }
+            });
+            Ok(stream)
+        } else {
+            Err(FunctionError::InternalError(
+                "unexpected bind data type".to_string(),
+            ))
+        }
+    }
+
+    pub fn register_function(set: &mut BuiltinFunctions) -> Result<(), FunctionError> {
+        let mut function = TableFunction::new(
+            "read_parquet".to_string(),
+            Some(Self::bind_func),
+            Self::scan_func,
+        );
+        function.projection_pushdown = true;
+        set.add_table_functions(function)?;
+        Ok(())
+    }
+}
@@ -45,7 +45,7 @@ impl SeqTableScan {
         if let Some(FunctionData::SeqTableScanInputData(data)) = input.bind_data {
             let mut reader = LocalStorage::create_reader(&data.bind_table.storage);
             let stream = Box::pin(async_stream::try_stream! {
-                while let Some(batch) = reader.next_batch(context.clone()){
+                while let Some(batch) = reader.next_batch(context.clone())? {
                     yield batch;
                 }
             });
@@ -0,0 +1,120 @@
+use std::sync::Arc;
+
+use arrow::record_batch::RecordBatch;
+use derive_new::new;
+use futures::stream::BoxStream;
+use itertools::Itertools;
+
+use super::{ReadCSV, SniffResult, TableFunction, TableFunctionBindInput, TableFunctionInput};
+use crate::execution::SchemaUtil;
+use crate::function::{BuiltinFunctions, FunctionData, FunctionError, FunctionResult};
+use crate::main_entry::ClientContext;
+use crate::types_v2::{LogicalType, ScalarValue};
+
+/// The sniff_csv function returns the dialect and the columns read_csv detects for a file.
+pub struct SniffCSVFunc;
+
+#[derive(new, Debug, Clone)]
+pub struct SniffCSVData {
+    pub(crate) result: SniffResult,
+    pub(crate) return_types: Vec<LogicalType>,
+    pub(crate) return_names: Vec<String>,
+}
+
+impl SniffCSVFunc {
+    fn generate_sniff_csv_names() -> Vec<String> {
+        vec![
+            "delimiter".to_string(),
+            "quote".to_string(),
+            "escape".to_string(),
+            "has_header".to_string(),
+            "columns".to_string(),
+            "date_format".to_string(),
+            "timestamp_format".to_string(),
+        ]
+    }
+
+    fn generate_sniff_csv_types() -> Vec<LogicalType> {
+        vec![
+            LogicalType::Varchar,
+            LogicalType::Varchar,
+            LogicalType::Varchar,
+            LogicalType::Boolean,
+            LogicalType::Varchar,
+            LogicalType::Varchar,
+            LogicalType::Varchar,
+        ]
+    }
+
+    fn bind_func(
+        _context: Arc<ClientContext>,
+        input: TableFunctionBindInput,
+        return_types: &mut Vec<LogicalType>,
+        return_names: &mut Vec<String>,
+    ) -> FunctionResult<Option<FunctionData>> {
+        if let Some(args) = input.func_args {
+            let (filenames, args) = ReadCSV::parse_func_args(args.as_slice())?;
+            let data = SniffCSVData::new(
+                args.sniffer
+                    .sniff(&filenames[0], args.file_compression(&filenames[0]))?,
+                Self::generate_sniff_csv_types(),
+                Self::generate_sniff_csv_names(),
+            );
+            return_types.extend(data.return_types.clone());
+            return_names.extend(data.return_names.clone());
+            Ok(Some(FunctionData::SniffCSVData(Box::new(data))))
+        } else {
+            Err(FunctionError::InternalError(
+                "unexpected bind data type".to_string(),
+            ))
+        }
+    }
+
+    fn sniff_csv_func(
+        _context: Arc<ClientContext>,
+        input: TableFunctionInput,
+    ) -> FunctionResult<BoxStream<'static, FunctionResult<RecordBatch>>> {
+        if let Some(FunctionData::SniffCSVData(data)) = input.bind_data {
+            let schema = SchemaUtil::new_schema_ref(&data.return_names, &data.return_types);
+            let result = &data.result;
+            let columns = result
+                .columns
+                .iter()
+                .map(|c| format!("{} {}", c.name, c.ty))
+                .join(", ");
+            let values = vec![
+                ScalarValue::Utf8(Some((result.delimiter as char).to_string())),
+                ScalarValue::Utf8(Some((result.quote as char).to_string())),
+                ScalarValue::Utf8(Some((result.escape as char).to_string())),
+                ScalarValue::Boolean(Some(result.has_header)),
+                ScalarValue::Utf8(Some(columns)),
+                ScalarValue::Utf8(result.date_format().map(|f| f.to_string())),
+                ScalarValue::Utf8(result.timestamp_format().map(|f| f.to_string())),
+            ];
+            let mut cols = vec![];
+            for (value, ty) in values.iter().zip(data.return_types.iter()) {
+                let mut builder = ScalarValue::new_builder(ty)?;
+                ScalarValue::append_for_builder(value, &mut builder)?;
+                cols.push(builder.finish());
+            }
+            let batch = RecordBatch::try_new(schema, cols)?;
+            let stream = Box::pin(async_stream::try_stream! {
+                yield batch;
+            });
+            Ok(stream)
+        } else {
+            Err(FunctionError::InternalError(
+                "unexpected global state type".to_string(),
+            ))
+        }
+    }
+
+    pub fn register_function(set: &mut BuiltinFunctions) -> Result<(), FunctionError> {
+        set.add_table_functions(TableFunction::new(
+            "sniff_csv".to_string(),
+            Some(Self::bind_func),
+            Self::sniff_csv_func,
+        ))?;
+        Ok(())
+    }
+}
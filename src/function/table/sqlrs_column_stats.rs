@@ -0,0 +1,116 @@
+use std::sync::Arc;
+
+use arrow::record_batch::RecordBatch;
+use derive_new::new;
+use futures::stream::BoxStream;
+
+use super::{TableFunction, TableFunctionBindInput, TableFunctionInput};
+use crate::catalog_v2::{Catalog, CatalogEntry, DEFAULT_SCHEMA};
+use crate::execution::SchemaUtil;
+use crate::function::{BuiltinFunctions, FunctionData, FunctionError, FunctionResult};
+use crate::main_entry::ClientContext;
+use crate::types_v2::{LogicalType, ScalarValue};
+
+pub struct SqlrsColumnStatsFunc;
+
+#[derive(new, Debug, Clone)]
+pub struct SqlrsColumnStatsData {
+    pub(crate) entries: Vec<CatalogEntry>,
+    pub(crate) return_types: Vec<LogicalType>,
+    pub(crate) return_names: Vec<String>,
+}
+
+impl SqlrsColumnStatsFunc {
+    fn generate_sqlrs_column_stats_names() -> Vec<String> {
+        vec![
+            "table_name".to_string(),
+            "column_name".to_string(),
+            "row_count".to_string(),
+            "null_fraction".to_string(),
+            "distinct_count".to_string(),
+            "histogram".to_string(),
+        ]
+    }
+
+    fn generate_sqlrs_column_stats_types() -> Vec<LogicalType> {
+        vec![
+            LogicalType::Varchar,
+            LogicalType::Varchar,
+            LogicalType::Bigint,
+            LogicalType::Double,
+            LogicalType::Bigint,
+            LogicalType::Varchar,
+        ]
+    }
+
+    fn bind_func(
+        context: Arc<ClientContext>,
+        _input: TableFunctionBindInput,
+        return_types: &mut Vec<LogicalType>,
+        return_names: &mut Vec<String>,
+    ) -> FunctionResult<Option<FunctionData>> {
+        let entries = Catalog::scan_entries(context, DEFAULT_SCHEMA.to_string(), &|entry| {
+            matches!(entry, CatalogEntry::TableCatalogEntry(_))
+        })?;
+        let data = SqlrsColumnStatsData::new(
+            entries,
+            Self::generate_sqlrs_column_stats_types(),
+            Self::generate_sqlrs_column_stats_names(),
+        );
+        return_types.extend(data.return_types.clone());
+        return_names.extend(data.return_names.clone());
+        Ok(Some(FunctionData::SqlrsColumnStatsData(Box::new(data))))
+    }
+
+    fn column_stats_func(
+        _context: Arc<ClientContext>,
+        input: TableFunctionInput,
+    ) -> FunctionResult<BoxStream<'static, FunctionResult<RecordBatch>>> {
+        if let Some(FunctionData::SqlrsColumnStatsData(data)) = input.bind_data {
+            let schema = SchemaUtil::new_schema_ref(&data.return_names, &data.return_types);
+            let mut builders = data
+                .return_types
+                .iter()
+                .map(ScalarValue::new_builder)
+                .collect::<Result<Vec<_>, _>>()?;
+            for entry in data.entries.iter() {
+                if let CatalogEntry::TableCatalogEntry(table) = entry {
+                    let stats = &table.stats;
+                    for (idx, column) in table.columns.iter().enumerate() {
+                        let histogram = stats.histogram(idx).map(|h| h.to_string());
+                        let values = vec![
+                            ScalarValue::Utf8(Some(table.base.name.clone())),
+                            ScalarValue::Utf8(Some(column.name.clone())),
+                            ScalarValue::Int64(Some(stats.row_count as i64)),
+                            ScalarValue::Float64(Some(stats.null_fraction(idx))),
+                            ScalarValue::Int64(stats.distinct_count(idx).map(|v| v as i64)),
+                            ScalarValue::Utf8(histogram),
+                        ];
+                        for (value, builder) in values.iter().zip(builders.iter_mut()) {
+                            ScalarValue::append_for_builder(value, builder)?;
+                        }
+                    }
+                }
+            }
+            let cols = builders.iter_mut().map(|b| b.finish()).collect::<Vec<_>>();
+            let batch = RecordBatch::try_new(schema, cols)?;
+            let stream = Box::pin(async_stream::try_stream! {
+                yield batch;
+            });
+            Ok(stream)
+        } else {
+            Err(FunctionError::InternalError(
+                "unexpected global state type".to_string(),
+            ))
+        }
+    }
+
+    pub fn register_function(set: &mut BuiltinFunctions) -> Result<(), FunctionError> {
+        set.add_table_functions(TableFunction::new(
+            "sqlrs_column_stats".to_string(),
+            Some(Self::bind_func),
+            Self::column_stats_func,
+        ))?;
+        Ok(())
+    }
+}
@@ -46,6 +46,10 @@ pub struct TableFunction {
     pub(crate) bind: Option<TableFunctionBindFunc>,
     /// The main function
     pub(crate) function: TableFunc,
+    /// Whether or not the table function supports projection pushdown. If not supported a
+    /// projection will be added that filters out unused columns.
+    #[new(value = "false")]
+    pub(crate) projection_pushdown: bool,
 }
 
 impl Debug for TableFunction {
@@ -8,6 +8,7 @@
 extern crate lazy_static;
 
 pub mod binder;
+pub mod capi;
 pub mod catalog;
 pub mod catalog_v2;
 pub mod cli;
@@ -15,10 +16,13 @@ pub mod common;
 pub mod db;
 pub mod execution;
 pub mod executor;
+pub mod flight_sql;
 pub mod function;
 pub mod main_entry;
 pub mod optimizer;
+pub mod optimizer_v2;
 pub mod parser;
+pub mod pg_server;
 pub mod planner;
 pub mod planner_v2;
 pub mod storage;
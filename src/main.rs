@@ -1,13 +1,21 @@
 use std::sync::Arc;
 
-use anyhow::Result;
-use sqlrs::main_entry::{ClientContext, DatabaseInstance};
+use anyhow::{anyhow, bail, Error, Result};
+use sqlrs::flight_sql::FlightSqlServer;
+use sqlrs::main_entry::{self, ClientContext, DatabaseInstance};
+use sqlrs::pg_server::PgServer;
 use sqlrs::{cli, Database};
+use tokio::net::TcpListener;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     env_logger::init();
 
+    let args = std::env::args().collect::<Vec<_>>();
+    if args.get(1).map(String::as_str) == Some("serve") {
+        return serve(&args[2..]).await;
+    }
+
     let db = Database::new_on_csv();
     create_csv_table(&db, "employee")?;
     create_csv_table(&db, "department")?;
@@ -23,6 +31,55 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+/// `sqlrs serve [--pg-port <port>] [--flight-port <port>]` serves an in-memory database to
+/// postgres and Flight SQL clients on the local host. Without a port, the postgres protocol is
+/// served on 5432.
+async fn serve(args: &[String]) -> Result<()> {
+    let mut pg_port = None;
+    let mut flight_port = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let port = match arg.as_str() {
+            "--pg-port" => &mut pg_port,
+            "--flight-port" => &mut flight_port,
+            other => bail!("unknown argument of serve: {}", other),
+        };
+        let value = args.next().ok_or_else(|| anyhow!("{} needs a port", arg))?;
+        *port = Some(value.parse::<u16>()?);
+    }
+    if pg_port.is_none() && flight_port.is_none() {
+        pg_port = Some(5432);
+    }
+
+    let database = main_entry::Database::open()?;
+    let mut servers = vec![];
+    if let Some(port) = pg_port {
+        let listener = TcpListener::bind(("127.0.0.1", port)).await?;
+        println!(
+            "sqlrs listens to postgres clients on {}",
+            listener.local_addr()?
+        );
+        let server = PgServer::new(database.clone());
+        servers.push(tokio::spawn(async move {
+            server.serve(listener).await.map_err(Error::from)
+        }));
+    }
+    if let Some(port) = flight_port {
+        let listener = TcpListener::bind(("127.0.0.1", port)).await?;
+        println!(
+            "sqlrs listens to Flight SQL clients on {}",
+            listener.local_addr()?
+        );
+        let server = FlightSqlServer::new(database);
+        servers.push(tokio::spawn(async move {
+            server.serve(listener).await.map_err(Error::from)
+        }));
+    }
+    // the servers run until one of them fails
+    let (result, _, _) = futures::future::select_all(servers).await;
+    result?
+}
+
 fn create_csv_table(db: &Database, table_name: &str) -> Result<()> {
     let table_name = table_name.to_string();
     let filepath = format!("./tests/csv/{}.csv", table_name);
@@ -0,0 +1,276 @@
+use std::sync::Arc;
+
+use arrow::array::ArrayBuilder;
+use arrow::datatypes::{DataType, SchemaRef};
+use arrow::record_batch::RecordBatch;
+
+use super::{ClientContext, DatabaseError};
+use crate::catalog_v2::{Catalog, CatalogError, TableCatalogEntry, TableStatistics};
+use crate::execution::SchemaUtil;
+use crate::storage_v2::LocalStorage;
+use crate::types_v2::ScalarValue;
+
+/// The number of rows buffered by an appender before they are flushed into the table.
+pub const APPENDER_FLUSH_ROWS: usize = 8192;
+
+/// The Appender inserts rows into a table without parsing and binding SQL. The rows are buffered
+/// into arrow builders, and appended to the storage of the table as one batch when the buffer is
+/// full, or on `flush` and `close`. The rows still buffered when the appender is dropped are
+/// lost.
+pub struct Appender {
+    client_context: Arc<ClientContext>,
+    table: TableCatalogEntry,
+    /// The schema of the batches appended to the table
+    schema: SchemaRef,
+    builders: Vec<Box<dyn ArrayBuilder>>,
+    /// The number of rows in the builders
+    buffered_rows: usize,
+}
+
+impl Appender {
+    pub fn try_new(
+        client_context: Arc<ClientContext>,
+        schema: &str,
+        table: &str,
+    ) -> Result<Self, DatabaseError> {
+        let table = Catalog::get_table(
+            client_context.clone(),
+            schema.to_string(),
+            table.to_string(),
+        )?;
+        let names = table
+            .columns
+            .iter()
+            .map(|c| c.name.clone())
+            .collect::<Vec<_>>();
+        let types = table
+            .columns
+            .iter()
+            .map(|c| c.ty.clone())
+            .collect::<Vec<_>>();
+        let builders = types
+            .iter()
+            .map(ScalarValue::new_builder)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| DatabaseError::InternalError(e.to_string()))?;
+        Ok(Self {
+            client_context,
+            schema: SchemaUtil::new_schema_ref(&names, &types),
+            table,
+            builders,
+            buffered_rows: 0,
+        })
+    }
+
+    /// Append a row, the values must have the types of the columns of the table. A NULL value
+    /// can be given as `ScalarValue::Null`.
+    pub fn append_row(&mut self, row: Vec<ScalarValue>) -> Result<(), DatabaseError> {
+        if row.len() != self.table.columns.len() {
+            return Err(DatabaseError::InternalError(format!(
+                "the row has {} values, but table {} has {} columns",
+                row.len(),
+                self.table.base.name,
+                self.table.columns.len()
+            )));
+        }
+        // check the whole row first, so an invalid row is not partially appended
+        let mut values = Vec::with_capacity(row.len());
+        for (column, value) in self.table.columns.iter().zip(row) {
+            let value = match value {
+                ScalarValue::Null => {
+                    ScalarValue::new_none_value(&DataType::from(column.ty.clone()))
+                        .map_err(|e| DatabaseError::InternalError(e.to_string()))?
+                }
+                value => value,
+            };
+            if value.get_logical_type() != column.ty {
+                return Err(DatabaseError::InternalError(format!(
+                    "expected a value of type {} for column {}, but got {}",
+                    column.ty,
+                    column.name,
+                    value.get_logical_type()
+                )));
+            }
+            if column.not_null && value.is_null() {
+                return Err(CatalogError::NotNullConstraintViolation(format!(
+                    "{}.{}",
+                    self.table.base.name, column.name
+                ))
+                .into());
+            }
+            values.push(value);
+        }
+        for (builder, value) in self.builders.iter_mut().zip(values.iter()) {
+            ScalarValue::append_for_builder(value, builder)
+                .map_err(|e| DatabaseError::InternalError(e.to_string()))?;
+        }
+        self.buffered_rows += 1;
+        if self.buffered_rows >= APPENDER_FLUSH_ROWS {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Append the rows of a batch, its columns must have the types of the columns of the table.
+    /// The buffered rows are flushed first, so the rows keep the order they were appended in.
+    pub fn append_batch(&mut self, batch: RecordBatch) -> Result<(), DatabaseError> {
+        let batch_types = batch
+            .schema()
+            .fields()
+            .iter()
+            .map(|f| f.data_type().clone());
+        if !batch_types.eq(self.schema.fields().iter().map(|f| f.data_type().clone())) {
+            return Err(DatabaseError::InternalError(format!(
+                "the batch doesn't match the columns of table {}",
+                self.table.base.name
+            )));
+        }
+        self.flush()?;
+        let batch = RecordBatch::try_new(self.schema.clone(), batch.columns().to_vec())?;
+        self.append_to_storage(batch)
+    }
+
+    /// Append the buffered rows to the table.
+    pub fn flush(&mut self) -> Result<(), DatabaseError> {
+        if self.buffered_rows == 0 {
+            return Ok(());
+        }
+        let columns = self.builders.iter_mut().map(|b| b.finish()).collect();
+        self.buffered_rows = 0;
+        let batch = RecordBatch::try_new(self.schema.clone(), columns)?;
+        self.append_to_storage(batch)
+    }
+
+    /// Flush the buffered rows, and close the appender.
+    pub fn close(mut self) -> Result<(), DatabaseError> {
+        self.flush()
+    }
+
+    fn append_to_storage(&self, batch: RecordBatch) -> Result<(), DatabaseError> {
+        if batch.num_rows() == 0 {
+            return Ok(());
+        }
+        let storage = &self.table.storage;
+        storage.verify_append_constraints(&batch)?;
+        let mut appended_stats = TableStatistics::new(self.table.columns.len());
+        appended_stats
+            .update(&batch)
+            .map_err(|e| DatabaseError::InternalError(e.to_string()))?;
+        // keep the statistics of the table current for the optimizer, like an insert does
+        LocalStorage::append_with_statistics(
+            self.client_context.clone(),
+            storage,
+            vec![batch],
+            &appended_stats,
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{Int32Array, StringArray};
+
+    use super::*;
+    use crate::main_entry::Database;
+
+    #[tokio::test]
+    async fn test_append_rows_and_batches() {
+        let db = Database::open().unwrap();
+        let conn = db.connect();
+        conn.query("create table t1(a int not null, b varchar)")
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+
+        let mut appender = conn.appender("t1").unwrap();
+        appender
+            .append_row(vec![
+                ScalarValue::Int32(Some(1)),
+                ScalarValue::Utf8(Some("x".to_string())),
+            ])
+            .unwrap();
+        appender
+            .append_row(vec![ScalarValue::Int32(Some(2)), ScalarValue::Null])
+            .unwrap();
+        // the values must have the types of the columns, and satisfy their constraints
+        assert!(appender
+            .append_row(vec![ScalarValue::Int32(Some(3))])
+            .is_err());
+        assert!(appender
+            .append_row(vec![ScalarValue::Int64(Some(3)), ScalarValue::Null])
+            .is_err());
+        assert!(appender
+            .append_row(vec![ScalarValue::Null, ScalarValue::Null])
+            .is_err());
+        let batch = RecordBatch::try_from_iter(vec![
+            ("a", Arc::new(Int32Array::from(vec![3])) as _),
+            ("b", Arc::new(StringArray::from(vec!["z"])) as _),
+        ])
+        .unwrap();
+        appender.append_batch(batch).unwrap();
+        let batch = RecordBatch::try_from_iter(vec![
+            ("a", Arc::new(Int32Array::from(vec![None])) as _),
+            ("b", Arc::new(StringArray::from(vec!["z"])) as _),
+        ])
+        .unwrap();
+        assert!(appender.append_batch(batch).is_err());
+        appender.close().unwrap();
+
+        let result = conn.query("select a from t1").await.unwrap();
+        let result = result.collect().await.unwrap();
+        let values = result
+            .batches()
+            .iter()
+            .flat_map(|b| {
+                let column = b.column(0).as_any().downcast_ref::<Int32Array>().unwrap();
+                column.iter().flatten().collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(values, vec![1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_append_rows_over_several_flushes() {
+        let db = Database::open().unwrap();
+        let conn = db.connect();
+        conn.query("create table t1(a int)")
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+
+        // two full flushes, and the rest of the rows flushed by close
+        let count = APPENDER_FLUSH_ROWS * 2 + 10;
+        let mut appender = conn.appender("t1").unwrap();
+        for i in 0..count {
+            appender
+                .append_row(vec![ScalarValue::Int32(Some(i as i32))])
+                .unwrap();
+        }
+        appender.close().unwrap();
+
+        let result = conn.query("select a from t1").await.unwrap();
+        let result = result.collect().await.unwrap();
+        let values = result
+            .batches()
+            .iter()
+            .flat_map(|b| {
+                let column = b.column(0).as_any().downcast_ref::<Int32Array>().unwrap();
+                column.iter().flatten().collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(values, (0..count as i32).collect::<Vec<_>>());
+
+        let table = Catalog::get_table(
+            conn.client_context().clone(),
+            "main".to_string(),
+            "t1".to_string(),
+        )
+        .unwrap();
+        assert_eq!(table.stats.row_count, count);
+    }
+}
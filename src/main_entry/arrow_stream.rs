@@ -0,0 +1,179 @@
+use arrow::datatypes::SchemaRef;
+use arrow::error::ArrowError;
+use arrow::ffi_stream::{ArrowArrayStreamReader, FFI_ArrowArrayStream};
+use arrow::record_batch::{RecordBatch, RecordBatchReader};
+use futures::executor::{block_on_stream, BlockingStream};
+use futures::stream::BoxStream;
+
+use super::{DatabaseError, QueryResult};
+
+/// Read the batches of a query result synchronously, as the reader of an exported C stream. A
+/// batch still being executed blocks the reading thread until it's produced, so a streamed
+/// result must not be read from a thread of the runtime executing the query.
+pub struct QueryResultReader {
+    schema: SchemaRef,
+    batches: BlockingStream<BoxStream<'static, Result<RecordBatch, DatabaseError>>>,
+}
+
+impl QueryResultReader {
+    pub fn new(result: QueryResult) -> Self {
+        Self {
+            schema: result.base().schema(),
+            batches: block_on_stream(result.into_stream()),
+        }
+    }
+}
+
+impl Iterator for QueryResultReader {
+    type Item = Result<RecordBatch, ArrowError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let batch = self.batches.next()?;
+        // the batches are exported with the names of the result
+        Some(
+            batch
+                .map_err(|e| ArrowError::ExternalError(e.to_string().into()))
+                .and_then(|b| RecordBatch::try_new(self.schema.clone(), b.columns().to_vec())),
+        )
+    }
+}
+
+impl RecordBatchReader for QueryResultReader {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+impl QueryResult {
+    /// Export the result as an arrow C stream, its batches are handed to the consumer without
+    /// being copied or serialized. The stream must be released by its consumer.
+    pub fn into_arrow_stream(self) -> FFI_ArrowArrayStream {
+        FFI_ArrowArrayStream::new(Box::new(QueryResultReader::new(self)))
+    }
+}
+
+/// An arrow C stream imported from another library of the process, it's read by the
+/// `arrow_scan` table function.
+pub struct ImportedArrowStream {
+    reader: ArrowArrayStreamReader,
+}
+
+// SAFETY: the callbacks of a C stream can be called from any thread as long as they are not
+// called concurrently, the stream is owned and read by one scan at a time.
+unsafe impl Send for ImportedArrowStream {}
+
+impl ImportedArrowStream {
+    pub fn try_new(stream: FFI_ArrowArrayStream) -> Result<Self, ArrowError> {
+        Ok(Self {
+            reader: ArrowArrayStreamReader::try_new(stream)?,
+        })
+    }
+
+    pub fn schema(&self) -> SchemaRef {
+        self.reader.schema()
+    }
+
+    pub fn next_batch(&mut self) -> Result<Option<RecordBatch>, ArrowError> {
+        self.reader.next().transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::array::{Int32Array, StringArray};
+    use arrow::ffi_stream::ArrowArrayStreamReader;
+    use arrow::record_batch::{RecordBatch, RecordBatchReader};
+
+    use crate::main_entry::{Database, QueryResult};
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_export_and_import_arrow_stream() {
+        let db = Database::open().unwrap();
+        let conn = db.connect();
+        conn.query("create table t1(a int, b varchar); insert into t1 values (1, 'x'), (2, 'y');")
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+
+        // a streamed result is read by the consumer while the query is executed
+        let result = conn.query("select a as c, b from t1").await.unwrap();
+        let stream = result.into_arrow_stream();
+        let batches = tokio::task::spawn_blocking(move || {
+            let reader = ArrowArrayStreamReader::try_new(stream).unwrap();
+            assert_eq!(reader.schema().field(0).name(), "c");
+            reader.collect::<Result<Vec<_>, _>>().unwrap()
+        })
+        .await
+        .unwrap();
+        let rows = batches.iter().map(|b| b.num_rows()).sum::<usize>();
+        assert_eq!(rows, 2);
+
+        // the result of one connection is scanned by another one
+        let result = conn.query("select a, b from t1 where a > 1").await.unwrap();
+        let result = QueryResult::MaterializedQueryResult(result.collect().await.unwrap());
+        let other = db.connect();
+        other
+            .client_context()
+            .register_arrow_stream("s", result.into_arrow_stream())
+            .unwrap();
+        let result = other
+            .query("select b, a from arrow_scan('s')")
+            .await
+            .unwrap();
+        assert_eq!(result.names(), &["b".to_string(), "a".to_string()]);
+        let result = result.collect().await.unwrap();
+        let batch = &result.batches()[0];
+        assert_eq!(
+            batch.column(0).as_any().downcast_ref::<StringArray>(),
+            Some(&StringArray::from(vec!["y"]))
+        );
+        assert_eq!(
+            batch.column(1).as_any().downcast_ref::<Int32Array>(),
+            Some(&Int32Array::from(vec![2]))
+        );
+        // the stream is consumed by the scan
+        assert!(other.query("select * from arrow_scan('s')").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_scan_exported_stream_result() {
+        let db = Database::open().unwrap();
+        let conn = db.connect();
+        let batches = (0..10)
+            .map(|i| {
+                let values = Int32Array::from_iter_values(i * 1000..(i + 1) * 1000);
+                RecordBatch::try_from_iter(vec![("x", Arc::new(values) as _)]).unwrap()
+            })
+            .collect::<Vec<_>>();
+        conn.client_context()
+            .register_batches("t1", batches[0].schema(), batches)
+            .unwrap();
+
+        // the exported result is still executed by this runtime while another query scans it
+        let result = conn
+            .query("select x as y from t1 where x >= 0")
+            .await
+            .unwrap();
+        assert!(matches!(result, QueryResult::StreamQueryResult(_)));
+        let other = db.connect();
+        other
+            .client_context()
+            .register_arrow_stream("s", result.into_arrow_stream())
+            .unwrap();
+        let result = other.query("select y from arrow_scan('s')").await.unwrap();
+        let result = result.collect().await.unwrap();
+        let values = result
+            .batches()
+            .iter()
+            .flat_map(|b| {
+                let column = b.column(0).as_any().downcast_ref::<Int32Array>().unwrap();
+                column.iter().flatten().collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(values, (0..10000).collect::<Vec<_>>());
+    }
+}
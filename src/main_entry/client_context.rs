@@ -1,18 +1,32 @@
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
 
-use arrow::record_batch::RecordBatch;
+use arrow::array::StringArray;
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use arrow::error::ArrowError;
+use arrow::ffi_stream::FFI_ArrowArrayStream;
+use arrow::record_batch::{RecordBatch, RecordBatchReader};
 use futures::lock::Mutex;
-use sqlparser::ast::Statement;
+use sqlparser::ast::{Expr, Statement};
 
 use super::query_context::ActiveQueryContext;
 use super::{
-    DatabaseError, DatabaseInstance, PendingQueryResult, PreparedStatementData, QueryResult,
+    BaseQueryResult, ClientConfigOption, DatabaseError, DatabaseInstance, ImportedArrowStream,
+    MaterializedQueryResult, PendingQueryResult, PreparedStatement, PreparedStatementData,
+    QueryResult,
 };
-use crate::execution::{PhysicalPlanGenerator, VolcanoExecutor};
+use crate::catalog_v2::{Catalog, ColumnDefinition, DataTable, DataTableInfo, DEFAULT_SCHEMA};
+use crate::execution::{ExpressionExecutor, PhysicalPlanGenerator, PipelineExecutor};
+use crate::optimizer_v2::Optimizer;
 use crate::parser::Sqlparser;
-use crate::planner_v2::Planner;
-use crate::util::pretty_batches_with;
+use crate::planner_v2::{
+    BindError, Binder, ExpressionBinder, LogicalOperator, Planner, PlannerError,
+    StatementReturnType,
+};
+use crate::storage_v2::LocalStorage;
+use crate::types_v2::{LogicalType, ScalarValue};
 
 /// The ClientContext holds information relevant to the current client session during execution
 pub struct ClientContext {
@@ -20,6 +34,12 @@ pub struct ClientContext {
     pub(crate) db: Arc<DatabaseInstance>,
     pub(crate) active_query: Mutex<ActiveQueryContext>,
     pub(crate) interrupted: AtomicBool,
+    /// The `statement_timeout` of the session in milliseconds, 0 disables the timeout
+    statement_timeout_ms: AtomicU64,
+    /// The statements prepared by `PREPARE name AS ...`
+    pub(crate) prepared_statements: RwLock<HashMap<String, Arc<PreparedStatement>>>,
+    /// The arrow C streams registered by `register_arrow_stream`, and not scanned yet
+    arrow_streams: std::sync::Mutex<HashMap<String, ImportedArrowStream>>,
 }
 
 impl ClientContext {
@@ -28,41 +48,270 @@ impl ClientContext {
             db,
             active_query: Mutex::new(ActiveQueryContext::default()),
             interrupted: AtomicBool::new(false),
+            statement_timeout_ms: AtomicU64::new(0),
+            prepared_statements: RwLock::new(HashMap::new()),
+            arrow_streams: std::sync::Mutex::new(HashMap::new()),
         })
     }
 
+    /// Run the statements of the sql, the result of each statement is materialized.
+    pub async fn run(
+        self: &Arc<Self>,
+        sql: &str,
+    ) -> Result<Vec<MaterializedQueryResult>, DatabaseError> {
+        let mut results = vec![];
+        for stat in Self::parse_statements(sql)?.iter() {
+            let result = self.execute_statement(stat, false).await?;
+            results.push(result.collect().await?);
+        }
+        Ok(results)
+    }
+
+    /// Run the statements of the sql, returns the batches of all their results.
     pub async fn query(self: &Arc<Self>, sql: String) -> Result<Vec<RecordBatch>, DatabaseError> {
-        let statements = Sqlparser::parse(sql.clone())?;
+        let results = self.run(&sql).await?;
+        Ok(results
+            .into_iter()
+            .flat_map(|res| res.into_batches())
+            .collect())
+    }
+
+    /// Run the statements of the sql, the result of the last statement is streamed while it is
+    /// executed. The statements before it are executed to their end.
+    pub async fn query_stream(self: &Arc<Self>, sql: &str) -> Result<QueryResult, DatabaseError> {
+        let statements = Self::parse_statements(sql)?;
+        let (last, others) = statements.split_last().unwrap();
+        for stat in others.iter() {
+            self.execute_statement(stat, false).await?.collect().await?;
+        }
+        self.execute_statement(last, true).await
+    }
+
+    /// Prepare the statement of the sql, it's bound once and executed with the values of its
+    /// parameters.
+    pub fn prepare(self: &Arc<Self>, sql: &str) -> Result<PreparedStatement, DatabaseError> {
+        let mut statements = Self::parse_statements(sql)?;
+        if statements.len() > 1 {
+            return Err(DatabaseError::InternalError(
+                "only one statement can be prepared".to_string(),
+            ));
+        }
+        PreparedStatement::try_new(self.clone(), statements.remove(0), vec![])
+    }
+
+    fn parse_statements(sql: &str) -> Result<Vec<Statement>, DatabaseError> {
+        let statements = Sqlparser::parse(sql.to_string())?;
         if statements.is_empty() {
             return Err(DatabaseError::InternalError(
                 "invalid statement".to_string(),
             ));
         }
+        Ok(statements)
+    }
 
-        let mut collection_result = vec![];
-
-        for stat in statements.iter() {
-            let result = self.pending_query(stat).await?;
-            match result {
-                QueryResult::MaterializedQueryResult(res) => {
-                    pretty_batches_with(&res.collection, &res.base.names, &res.base.types);
-                    collection_result.extend(res.collection);
-                }
+    /// Register the batches as a table of the main schema. The table shares the arrays of the
+    /// batches, so nothing is copied, and it's scanned like the other tables with only the
+    /// columns the query uses.
+    pub fn register_batches(
+        self: &Arc<Self>,
+        name: &str,
+        schema: SchemaRef,
+        batches: Vec<RecordBatch>,
+    ) -> Result<(), DatabaseError> {
+        let columns = Self::schema_columns(&schema)?;
+        for batch in batches.iter() {
+            let batch_schema = batch.schema();
+            let batch_types = batch_schema.fields().iter().map(|f| f.data_type());
+            if !batch_types.eq(schema.fields().iter().map(|f| f.data_type())) {
+                return Err(DatabaseError::InternalError(format!(
+                    "the batches of {} don't match its schema",
+                    name
+                )));
             }
         }
+        let data_table = DataTable::new(
+            DataTableInfo::new(DEFAULT_SCHEMA.to_string(), name.to_string()),
+            columns,
+        );
+        Catalog::create_table(
+            self.clone(),
+            DEFAULT_SCHEMA.to_string(),
+            name.to_string(),
+            data_table.clone(),
+        )?;
+        let row_count = batches.iter().map(|b| b.num_rows()).sum();
+        LocalStorage::register(self.clone(), &data_table, batches)?;
+        Catalog::update_table_statistics(
+            self.clone(),
+            DEFAULT_SCHEMA.to_string(),
+            name.to_string(),
+            |stats| stats.row_count = row_count,
+        )?;
+        Ok(())
+    }
 
-        Ok(collection_result)
+    /// Register the batches of the reader as a table, the reader is read to its end.
+    pub fn register_batch_reader(
+        self: &Arc<Self>,
+        name: &str,
+        reader: impl RecordBatchReader,
+    ) -> Result<(), DatabaseError> {
+        let schema = reader.schema();
+        let batches = reader.collect::<Result<Vec<_>, ArrowError>>()?;
+        self.register_batches(name, schema, batches)
     }
 
-    async fn pending_query(
+    /// Register an arrow C stream imported from another library of the process, it's scanned by
+    /// `select * from arrow_scan('name')`. The stream is read once, by the first query scanning
+    /// it.
+    pub fn register_arrow_stream(
+        self: &Arc<Self>,
+        name: &str,
+        stream: FFI_ArrowArrayStream,
+    ) -> Result<(), DatabaseError> {
+        let stream = ImportedArrowStream::try_new(stream)?;
+        Self::schema_columns(&stream.schema())?;
+        let mut streams = self.arrow_streams.lock().unwrap();
+        if streams.contains_key(name) {
+            return Err(DatabaseError::InternalError(format!(
+                "arrow stream {} is already registered",
+                name
+            )));
+        }
+        streams.insert(name.to_string(), stream);
+        Ok(())
+    }
+
+    /// The schema of a registered arrow stream not read yet.
+    pub(crate) fn arrow_stream_schema(&self, name: &str) -> Option<SchemaRef> {
+        let streams = self.arrow_streams.lock().unwrap();
+        streams.get(name).map(|stream| stream.schema())
+    }
+
+    /// Take a registered arrow stream to read it, it can't be scanned again.
+    pub(crate) fn take_arrow_stream(&self, name: &str) -> Option<ImportedArrowStream> {
+        self.arrow_streams.lock().unwrap().remove(name)
+    }
+
+    /// The columns of a table with the schema, the scans build the batches with the arrow types
+    /// of the logical types, so the types of the schema must be the ones of logical types.
+    fn schema_columns(schema: &SchemaRef) -> Result<Vec<ColumnDefinition>, DatabaseError> {
+        let mut columns = vec![];
+        for field in schema.fields() {
+            let ty = LogicalType::try_from(field.data_type())
+                .ok()
+                .filter(|ty| DataType::from(ty.clone()) == *field.data_type())
+                .ok_or_else(|| {
+                    DatabaseError::InternalError(format!(
+                        "unsupported type {} of column {}",
+                        field.data_type(),
+                        field.name()
+                    ))
+                })?;
+            columns.push(ColumnDefinition::new(field.name().to_lowercase(), ty));
+        }
+        Ok(columns)
+    }
+
+    /// Drop a table of the main schema, like the ones registered by `register_batches`.
+    pub fn unregister(self: &Arc<Self>, name: &str) -> Result<(), DatabaseError> {
+        let data_table =
+            Catalog::drop_table(self.clone(), DEFAULT_SCHEMA.to_string(), name.to_string())?;
+        LocalStorage::drop_table(self.clone(), &data_table)?;
+        Ok(())
+    }
+
+    pub(crate) async fn execute_statement(
         self: &Arc<Self>,
         statement: &Statement,
+        stream: bool,
     ) -> Result<QueryResult, DatabaseError> {
-        let pending_query = self
-            .pending_statement_or_prepared_statement(statement)
-            .await?;
-        let result = pending_query.execute().await?;
-        Ok(result)
+        match statement {
+            Statement::Prepare {
+                name,
+                data_types,
+                statement,
+            } => {
+                let declared_types = data_types
+                    .iter()
+                    .map(|ty| LogicalType::try_from(ty.clone()))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|e| PlannerError::from(BindError::from(e)))?;
+                let prepared =
+                    PreparedStatement::try_new(self.clone(), *statement.clone(), declared_types)?;
+                let mut prepared_statements = self.prepared_statements.write().unwrap();
+                if prepared_statements.contains_key(&name.value) {
+                    return Err(DatabaseError::InternalError(format!(
+                        "prepared statement {} already exists",
+                        name.value
+                    )));
+                }
+                prepared_statements.insert(name.value.clone(), Arc::new(prepared));
+                Self::success_result(format!("PREPARE {}", name.value))
+            }
+            Statement::Deallocate { name, .. } => {
+                self.get_prepared_statement(&name.value)?;
+                self.prepared_statements
+                    .write()
+                    .unwrap()
+                    .remove(&name.value);
+                Self::success_result(format!("DEALLOCATE {}", name.value))
+            }
+            _ => {
+                let pending_query = self
+                    .pending_statement_or_prepared_statement(statement)
+                    .await?;
+                if stream {
+                    pending_query.execute_stream().await
+                } else {
+                    pending_query.execute().await
+                }
+            }
+        }
+    }
+
+    fn success_result(message: String) -> Result<QueryResult, DatabaseError> {
+        let fields = vec![Field::new("success", DataType::Utf8, false)];
+        let batch = RecordBatch::try_new(
+            Arc::new(Schema::new(fields)),
+            vec![Arc::new(StringArray::from(vec![message]))],
+        )?;
+        let base = BaseQueryResult::new(
+            vec![LogicalType::Varchar],
+            vec!["success".to_string()],
+            StatementReturnType::Nothing,
+        );
+        Ok(QueryResult::MaterializedQueryResult(
+            MaterializedQueryResult::new(base, vec![batch]),
+        ))
+    }
+
+    fn get_prepared_statement(&self, name: &str) -> Result<Arc<PreparedStatement>, DatabaseError> {
+        self.prepared_statements
+            .read()
+            .unwrap()
+            .get(name)
+            .cloned()
+            .ok_or_else(|| {
+                DatabaseError::InternalError(format!("prepared statement {} does not exist", name))
+            })
+    }
+
+    /// Evaluate the parameters of `EXECUTE name(...)`, they must be constant expressions.
+    fn bind_parameter_values(
+        self: &Arc<Self>,
+        parameters: &[Expr],
+    ) -> Result<Vec<ScalarValue>, DatabaseError> {
+        let mut binder = Binder::new(self.clone());
+        let mut expr_binder = ExpressionBinder::new(&mut binder);
+        let mut values = vec![];
+        for parameter in parameters.iter() {
+            let expr = expr_binder
+                .bind_expression(parameter, &mut vec![], &mut vec![])
+                .map_err(PlannerError::from)?;
+            values.push(ExpressionExecutor::execute_scalar(&expr)?);
+        }
+        Ok(values)
     }
 
     async fn pending_statement_or_prepared_statement(
@@ -73,10 +322,37 @@ impl ClientContext {
 
         self.active_query.lock().await.query = Some(statement.to_string());
         // prepare the query for execution
-        let prepared = self.create_prepared_statement(statement).await?;
+        let prepared = match statement {
+            Statement::Execute { name, parameters } => {
+                let values = self.bind_parameter_values(parameters)?;
+                self.get_prepared_statement(&name.value)?
+                    .create_plan(values)?
+            }
+            _ => self.create_prepared_statement(statement).await?,
+        };
+        self.pending_prepared(prepared).await
+    }
+
+    pub(crate) async fn pending_prepared_statement(
+        self: &Arc<Self>,
+        statement: &PreparedStatement,
+        parameters: Vec<ScalarValue>,
+    ) -> Result<Arc<PendingQueryResult>, DatabaseError> {
+        self.initial_cleanup().await;
+
+        self.active_query.lock().await.query = Some(statement.statement.to_string());
+        let prepared = statement.create_plan(parameters)?;
+        self.pending_prepared(prepared).await
+    }
+
+    async fn pending_prepared(
+        self: &Arc<Self>,
+        prepared: PreparedStatementData,
+    ) -> Result<Arc<PendingQueryResult>, DatabaseError> {
         self.active_query.lock().await.prepared = Some(prepared);
-        // set volcano executor
-        let executor = VolcanoExecutor::new();
+        // set pipeline executor
+        let threads = self.threads()?;
+        let executor = PipelineExecutor::new(threads);
         self.active_query.lock().await.executor = Some(executor);
         // return pending query result
         let pending_query_result = Arc::new(PendingQueryResult::new(self.clone()));
@@ -90,15 +366,72 @@ impl ClientContext {
     ) -> Result<PreparedStatementData, DatabaseError> {
         let mut planner = Planner::new(self.clone());
         planner.create_plan(statement)?;
+        if !planner.parameter_types.unwrap().is_empty() {
+            return Err(DatabaseError::InternalError(
+                "a statement with parameters must be prepared".to_string(),
+            ));
+        }
         let logical_plan = planner.plan.unwrap();
         let names = planner.names.unwrap();
         let types = planner.types.unwrap();
+        let return_type = planner.return_type.unwrap();
+        Ok(self.create_physical_plan(statement.clone(), logical_plan, names, types, return_type))
+    }
+
+    pub(crate) fn create_physical_plan(
+        self: &Arc<Self>,
+        statement: Statement,
+        logical_plan: LogicalOperator,
+        names: Vec<String>,
+        types: Vec<LogicalType>,
+        return_type: StatementReturnType,
+    ) -> PreparedStatementData {
+        let mut optimizer = Optimizer::new(self.clone());
+        let logical_plan = optimizer.optimize(logical_plan);
 
         let physical_planner = PhysicalPlanGenerator::new(self.clone());
         let physical_plan = physical_planner.create_plan(logical_plan);
 
-        let result = PreparedStatementData::new(statement.clone(), physical_plan, names, types);
-        Ok(result)
+        PreparedStatementData::new(statement, physical_plan, names, types, return_type)
+    }
+
+    /// Interrupt the running query of this client, it fails with an interrupted error at the
+    /// next batch it produces. A query started after the call is not interrupted.
+    pub fn interrupt(&self) {
+        self.interrupted.store(true, Ordering::Release);
+    }
+
+    pub fn is_interrupted(&self) -> bool {
+        self.interrupted.load(Ordering::Acquire)
+    }
+
+    /// The timeout of the queries of this client, set by `SET statement_timeout`. It's not shared
+    /// with the other clients of the database.
+    pub(crate) fn statement_timeout(&self) -> Option<Duration> {
+        match self.statement_timeout_ms.load(Ordering::Acquire) {
+            0 => None,
+            ms => Some(Duration::from_millis(ms)),
+        }
+    }
+
+    /// Apply an option of the session set by the SET statement, the other clients of the
+    /// database keep their own value.
+    pub(crate) fn apply_config(&self, option: &ClientConfigOption) {
+        match option {
+            ClientConfigOption::StatementTimeout(timeout) => {
+                let ms = timeout.map_or(0, |t| (t.as_millis() as u64).max(1));
+                self.statement_timeout_ms.store(ms, Ordering::Release);
+            }
+        }
+    }
+
+    fn threads(&self) -> Result<usize, DatabaseError> {
+        match self.db.config.read() {
+            Ok(config) => Ok(config.threads),
+            Err(_) => Err(DatabaseError::InternalError(
+                "database config lock poisoned".to_string(),
+            )),
+        }
     }
 
     async fn initial_cleanup(self: &Arc<Self>) {
@@ -122,3 +455,107 @@ impl ClientContext {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{Int32Array, Int64Array, StringArray};
+    use arrow::datatypes::{Field, Schema};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_register_and_unregister_batches() {
+        let db = Arc::new(DatabaseInstance::default());
+        db.initialize().unwrap();
+        let context = ClientContext::new(db);
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("Name", DataType::Utf8, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2])),
+                Arc::new(StringArray::from(vec![Some("a"), None])),
+            ],
+        )
+        .unwrap();
+        context
+            .register_batches("t", schema.clone(), vec![batch.clone()])
+            .unwrap();
+        assert!(context
+            .register_batches("t", schema.clone(), vec![batch])
+            .is_err());
+
+        let result = context
+            .query("select name from t where id = 1".to_string())
+            .await
+            .unwrap();
+        let names = result[0].column(0).as_any().downcast_ref::<StringArray>();
+        assert_eq!(names, Some(&StringArray::from(vec!["a"])));
+
+        context.unregister("t").unwrap();
+        assert!(context.query("select * from t".to_string()).await.is_err());
+        assert!(context.unregister("t").is_err());
+
+        // the batches must have the types of the schema
+        let other =
+            RecordBatch::try_from_iter(vec![("id", Arc::new(Int64Array::from(vec![1])) as _)])
+                .unwrap();
+        assert!(context.register_batches("u", schema, vec![other]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_insert_into_registered_large_batch() {
+        let db = Arc::new(DatabaseInstance::default());
+        db.initialize().unwrap();
+        let context = ClientContext::new(db);
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int32, false)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int32Array::from_iter_values(0..3000))],
+        )
+        .unwrap();
+        context.register_batches("t", schema, vec![batch]).unwrap();
+
+        // the registered batch is split into row groups, so the insert appends to a full one
+        context
+            .query("insert into t values (3000)".to_string())
+            .await
+            .unwrap();
+        let result = context.query("select id from t".to_string()).await.unwrap();
+        let rows = result.iter().map(|batch| batch.num_rows()).sum::<usize>();
+        assert_eq!(rows, 3001);
+    }
+
+    #[tokio::test]
+    async fn test_failed_insert_appends_no_rows() {
+        let db = Arc::new(DatabaseInstance::default());
+        db.initialize().unwrap();
+        let context = ClientContext::new(db);
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int32, true)]));
+        let values = (0..3000).map(|v| if v == 2999 { None } else { Some(v) });
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int32Array::from_iter(values))],
+        )
+        .unwrap();
+        context.register_batches("t", schema, vec![batch]).unwrap();
+        context
+            .query("create table t2(id int not null)".to_string())
+            .await
+            .unwrap();
+
+        // the null is in the last row group, after the others are already read
+        let result = context
+            .query("insert into t2 select id from t".to_string())
+            .await;
+        assert!(result.is_err());
+        let result = context
+            .query("select id from t2".to_string())
+            .await
+            .unwrap();
+        let rows = result.iter().map(|batch| batch.num_rows()).sum::<usize>();
+        assert_eq!(rows, 0);
+    }
+}
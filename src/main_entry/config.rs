@@ -0,0 +1,76 @@
+use std::fmt;
+use std::time::Duration;
+
+/// The configuration of a database instance, options are changed by the SET statement.
+#[derive(Debug, Clone)]
+pub struct DBConfig {
+    /// The number of worker threads used to execute a pipeline
+    pub(crate) threads: usize,
+}
+
+impl Default for DBConfig {
+    fn default() -> Self {
+        let threads = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1);
+        Self { threads }
+    }
+}
+
+impl DBConfig {
+    pub fn apply(&mut self, option: &DBConfigOption) {
+        match option {
+            DBConfigOption::Threads(threads) => self.threads = *threads,
+        }
+    }
+}
+
+/// An option changed by the SET statement, either of the database or of the session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigOption {
+    Database(DBConfigOption),
+    Client(ClientConfigOption),
+}
+
+impl fmt::Display for ConfigOption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigOption::Database(option) => write!(f, "{}", option),
+            ConfigOption::Client(option) => write!(f, "{}", option),
+        }
+    }
+}
+
+/// A database option with its new value, it's shared by all the sessions of the database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DBConfigOption {
+    Threads(usize),
+}
+
+impl fmt::Display for DBConfigOption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DBConfigOption::Threads(threads) => write!(f, "threads = {}", threads),
+        }
+    }
+}
+
+/// A session option with its new value, it's applied to the client context of the session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClientConfigOption {
+    /// Queries of the session running longer than the timeout are cancelled, none disables the
+    /// timeout.
+    StatementTimeout(Option<Duration>),
+}
+
+impl fmt::Display for ClientConfigOption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientConfigOption::StatementTimeout(timeout) => write!(
+                f,
+                "statement_timeout = {}",
+                timeout.map_or(0, |t| t.as_millis())
+            ),
+        }
+    }
+}
@@ -0,0 +1,244 @@
+use std::sync::Arc;
+
+use super::{
+    Appender, ClientContext, DatabaseError, DatabaseInstance, PreparedStatement, QueryResult,
+};
+use crate::catalog_v2::DEFAULT_SCHEMA;
+
+/// An in-memory database, the entry point of sqlrs used as a library. The tables live as long
+/// as the database, and are shared by all its connections.
+#[derive(Clone)]
+pub struct Database {
+    instance: Arc<DatabaseInstance>,
+}
+
+impl Database {
+    pub fn open() -> Result<Self, DatabaseError> {
+        let instance = Arc::new(DatabaseInstance::default());
+        instance.initialize()?;
+        Ok(Self { instance })
+    }
+
+    /// Create a connection to the database, each connection has its own client session.
+    pub fn connect(&self) -> Connection {
+        Connection {
+            context: ClientContext::new(self.instance.clone()),
+        }
+    }
+}
+
+pub struct Connection {
+    context: Arc<ClientContext>,
+}
+
+impl Connection {
+    /// Run the statements of the sql and return the result of the last one. The result has the
+    /// names and types of its columns, and its batches are streamed while the query is executed.
+    pub async fn query(&self, sql: &str) -> Result<QueryResult, DatabaseError> {
+        self.context.query_stream(sql).await
+    }
+
+    /// Prepare a statement with `$1` or `?` parameters, it's executed with the values of the
+    /// parameters by `PreparedStatement::execute`.
+    pub fn prepare(&self, sql: &str) -> Result<PreparedStatement, DatabaseError> {
+        self.context.prepare(sql)
+    }
+
+    /// Create an appender of a table of the main schema, to insert rows without SQL.
+    pub fn appender(&self, table: &str) -> Result<Appender, DatabaseError> {
+        Appender::try_new(self.context.clone(), DEFAULT_SCHEMA, table)
+    }
+
+    /// Cancel the running query of the connection, its result ends with an interrupted error.
+    pub fn interrupt(&self) {
+        self.context.interrupt();
+    }
+
+    pub fn client_context(&self) -> &Arc<ClientContext> {
+        &self.context
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use arrow::array::Int32Array;
+    use arrow::record_batch::RecordBatch;
+    use futures::StreamExt;
+
+    use super::*;
+    use crate::planner_v2::StatementReturnType;
+    use crate::types_v2::LogicalType;
+
+    #[tokio::test]
+    async fn test_query_stream_result() {
+        let db = Database::open().unwrap();
+        let conn = db.connect();
+        conn.query("create table t1(a int, b varchar); insert into t1 values (1, 'x'), (2, 'y');")
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+
+        let result = conn
+            .query("select a as c, b from t1 where a > 0;")
+            .await
+            .unwrap();
+        assert!(matches!(result, QueryResult::StreamQueryResult(_)));
+        assert_eq!(result.names(), &["c".to_string(), "b".to_string()]);
+        assert_eq!(
+            result.types(),
+            &[LogicalType::Integer, LogicalType::Varchar]
+        );
+        let mut stream = result.into_stream();
+        let mut values = vec![];
+        while let Some(batch) = stream.next().await {
+            let batch = batch.unwrap();
+            assert_eq!(batch.schema().field(0).name(), "c");
+            let column = batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<Int32Array>()
+                .unwrap();
+            values.extend(column.iter().flatten());
+        }
+        assert_eq!(values, vec![1, 2]);
+
+        // the tables are shared by the connections of the database
+        let other = db.connect();
+        let result = other.query("select * from t1").await.unwrap();
+        let result = result.collect().await.unwrap();
+        let rows = result.batches().iter().map(|b| b.num_rows()).sum::<usize>();
+        assert_eq!(rows, 2);
+        assert!(other.query("select * from t2").await.is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_interrupt_and_statement_timeout() {
+        let db = Database::open().unwrap();
+        let conn = db.connect();
+        let context = conn.client_context();
+        let batches = (0..1000)
+            .map(|i| {
+                let values = Int32Array::from_iter_values(i * 1000..(i + 1) * 1000);
+                RecordBatch::try_from_iter(vec![("x", Arc::new(values) as _)]).unwrap()
+            })
+            .collect::<Vec<_>>();
+        let schema = batches[0].schema();
+        context
+            .register_batches("a", schema.clone(), batches.clone())
+            .unwrap();
+        context.register_batches("b", schema, batches).unwrap();
+        // the cross product has 10^12 rows, and the filter removes all of them
+        let sql = "select a.x from a, b where a.x < b.x and a.x > b.x";
+
+        let result = conn.query(sql).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        conn.interrupt();
+        let err = result.collect().await.err().unwrap();
+        assert!(err.to_string().contains("user request"), "{}", err);
+
+        // the flag is cleared by the next query
+        let result = conn.query("select 1").await.unwrap();
+        assert!(result.collect().await.is_ok());
+
+        conn.query("set statement_timeout = 100")
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        let err = conn
+            .query(sql)
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("statement timeout"), "{}", err);
+
+        // the timeout is a setting of the session, the other connections don't have it
+        let other = db.connect();
+        assert!(other.client_context().statement_timeout().is_none());
+        let result = other.query("select 1").await.unwrap();
+        assert!(result.collect().await.is_ok());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_drop_partially_read_stream_result() {
+        let db = Database::open().unwrap();
+        let conn = db.connect();
+        let context = conn.client_context().clone();
+        let batches = (0..1000)
+            .map(|i| {
+                let values = Int32Array::from_iter_values(i * 1000..(i + 1) * 1000);
+                RecordBatch::try_from_iter(vec![("x", Arc::new(values) as _)]).unwrap()
+            })
+            .collect::<Vec<_>>();
+        let schema = batches[0].schema();
+        context
+            .register_batches("a", schema.clone(), batches.clone())
+            .unwrap();
+        context
+            .register_batches("b", schema, batches[..1].to_vec())
+            .unwrap();
+        conn.query("select 1")
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        let baseline = Arc::strong_count(&context);
+
+        // the cross product has 10^9 rows, only its first batch is read
+        let result = conn
+            .query("select a.x as ax, b.x as bx from a, b")
+            .await
+            .unwrap();
+        let mut stream = result.into_stream();
+        assert!(stream.next().await.unwrap().is_ok());
+        drop(stream);
+
+        // the execution stops, and releases the client context
+        let start = Instant::now();
+        while Arc::strong_count(&context) > baseline {
+            assert!(
+                start.elapsed() < Duration::from_secs(10),
+                "the query is still running"
+            );
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let result = conn.query("select 1").await.unwrap();
+        assert!(result.collect().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_affected_rows() {
+        let db = Database::open().unwrap();
+        let conn = db.connect();
+        let result = conn.query("create table t1(a int)").await.unwrap();
+        assert_eq!(result.affected_rows().await.unwrap(), 0);
+        let result = conn
+            .query("insert into t1 values (1), (2), (3)")
+            .await
+            .unwrap();
+        assert_eq!(result.return_type(), StatementReturnType::ChangedRows);
+        assert_eq!(result.affected_rows().await.unwrap(), 3);
+        let result = conn
+            .query("create table t2 as select a from t1")
+            .await
+            .unwrap();
+        assert_eq!(result.affected_rows().await.unwrap(), 3);
+
+        // a query writes no rows, whatever the shape of its result
+        let result = conn
+            .query("select cast(3 as bigint) as \"Count\"")
+            .await
+            .unwrap();
+        assert_eq!(result.return_type(), StatementReturnType::QueryResult);
+        assert_eq!(result.affected_rows().await.unwrap(), 0);
+    }
+}
@@ -1,6 +1,6 @@
 use std::sync::{Arc, RwLock};
 
-use super::{ClientContext, DatabaseError};
+use super::{ClientContext, DBConfig, DatabaseError};
 use crate::catalog_v2::{Catalog, CatalogError, DEFAULT_SCHEMA};
 use crate::function::BuiltinFunctions;
 use crate::storage_v2::LocalStorage;
@@ -9,6 +9,7 @@ use crate::storage_v2::LocalStorage;
 pub struct DatabaseInstance {
     pub(crate) storage: RwLock<LocalStorage>,
     pub(crate) catalog: Arc<RwLock<Catalog>>,
+    pub(crate) config: RwLock<DBConfig>,
 }
 
 impl DatabaseInstance {
@@ -21,14 +22,10 @@ impl DatabaseInstance {
     }
 
     fn init_default_schema(self: &Arc<Self>) -> Result<(), DatabaseError> {
-        let mut catalog = match self.catalog.try_write() {
-            Ok(c) => c,
-            Err(_) => {
-                return Err(DatabaseError::CatalogError(
-                    CatalogError::CatalogLockedError,
-                ))
-            }
-        };
+        let mut catalog = self
+            .catalog
+            .write()
+            .map_err(|_| CatalogError::CatalogPoisonedError)?;
         catalog.create_schema(DEFAULT_SCHEMA.to_string()).unwrap();
         Ok(())
     }
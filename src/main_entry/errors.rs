@@ -5,6 +5,7 @@ use crate::catalog_v2::CatalogError;
 use crate::execution::ExecutorError;
 use crate::function::FunctionError;
 use crate::planner_v2::PlannerError;
+use crate::storage_v2::StorageError;
 
 #[derive(thiserror::Error, Debug)]
 pub enum DatabaseError {
@@ -44,6 +45,15 @@ pub enum DatabaseError {
         #[from]
         FunctionError,
     ),
+    #[error("storage error: {0}")]
+    StorageError(
+        #[source]
+        #[from]
+        StorageError,
+    ),
+    /// Binding a prepared statement again changed the types that were described to a client
+    #[error("cached plan must not change result type")]
+    PreparedStatementTypesChanged,
     #[error("Internal error: {0}")]
     InternalError(String),
 }
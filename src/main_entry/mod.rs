@@ -1,14 +1,24 @@
+mod appender;
+mod arrow_stream;
 mod client_context;
+mod config;
+mod connection;
 mod db;
 mod errors;
 mod pending_query_result;
+mod prepared_statement;
 mod prepared_statement_data;
 mod query_context;
 mod query_result;
 
+pub use appender::*;
+pub use arrow_stream::*;
 pub use client_context::*;
+pub use config::*;
+pub use connection::*;
 pub use db::*;
 pub use errors::*;
 pub use pending_query_result::*;
+pub use prepared_statement::*;
 pub use prepared_statement_data::*;
 pub use query_result::*;
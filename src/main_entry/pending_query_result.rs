@@ -1,8 +1,13 @@
 use std::sync::Arc;
 
+use arrow::record_batch::RecordBatch;
 use derive_new::new;
+use futures::StreamExt;
 
-use super::{BaseQueryResult, ClientContext, DatabaseError, MaterializedQueryResult, QueryResult};
+use super::{
+    BaseQueryResult, ClientContext, DatabaseError, MaterializedQueryResult, QueryResult,
+    StreamQueryResult,
+};
 use crate::execution::ExecutionContext;
 
 #[derive(new)]
@@ -24,7 +29,7 @@ impl PendingQueryResult {
             .await?;
         // set query result
         let materialized_query_result = MaterializedQueryResult::new(
-            BaseQueryResult::new(prepared.types, prepared.names),
+            BaseQueryResult::new(prepared.types, prepared.names, prepared.return_type),
             collection,
         );
         Ok(QueryResult::MaterializedQueryResult(
@@ -32,6 +37,32 @@ impl PendingQueryResult {
         ))
     }
 
+    /// Start the execution of the query, the batches of the result are streamed while the query
+    /// is executed on a background task.
+    pub async fn execute_stream(&self) -> Result<QueryResult, DatabaseError> {
+        self.check_executable_internal().await?;
+
+        let mut active_query_context = self.client_context.active_query.lock().await;
+        let executor = active_query_context.executor.take().unwrap();
+        let prepared = active_query_context.prepared.take().unwrap();
+        let execution_context = Arc::new(ExecutionContext::new(self.client_context.clone()));
+        let base = BaseQueryResult::new(prepared.types, prepared.names, prepared.return_type);
+        // the batches are returned with the names of the result
+        let schema = base.schema();
+        let stream = executor
+            .execute_stream(prepared.plan, execution_context)
+            .map(move |batch| -> Result<RecordBatch, DatabaseError> {
+                Ok(RecordBatch::try_new(
+                    schema.clone(),
+                    batch?.columns().to_vec(),
+                )?)
+            })
+            .boxed();
+        Ok(QueryResult::StreamQueryResult(StreamQueryResult::new(
+            base, stream,
+        )))
+    }
+
     async fn check_executable_internal(&self) -> Result<(), DatabaseError> {
         // whether the current pending query is active or not
         let invalidated = !self.client_context.is_active_request(self).await;
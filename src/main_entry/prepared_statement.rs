@@ -0,0 +1,244 @@
+use std::sync::{Arc, Mutex};
+
+use sqlparser::ast::Statement;
+
+use super::{ClientContext, DatabaseError, PreparedStatementData, QueryResult};
+use crate::catalog_v2::Catalog;
+use crate::planner_v2::{
+    LogicalOperator, ParameterBinder, Planner, PlannerError, StatementReturnType,
+};
+use crate::types_v2::{LogicalType, ScalarValue};
+
+/// A statement bound once and executed many times with different values of its parameters, such
+/// as `select * from t1 where a = $1`. The bound plan is kept until the catalog changes, then the
+/// statement is bound again.
+pub struct PreparedStatement {
+    client_context: Arc<ClientContext>,
+    /// The statement that was prepared
+    pub(crate) statement: Statement,
+    /// The declared types of the parameters, such as `PREPARE s(INT) AS ...`
+    declared_types: Vec<LogicalType>,
+    /// Whether the types were described to a client, which keeps them until the statement is
+    /// closed. Binding the statement again must not change them then.
+    fixed_types: bool,
+    bound: Mutex<BoundPreparedStatement>,
+}
+
+/// The bound plan of a prepared statement, the plan is optimized once the values of the
+/// parameters are known.
+struct BoundPreparedStatement {
+    plan: LogicalOperator,
+    names: Vec<String>,
+    types: Vec<LogicalType>,
+    parameter_types: Vec<LogicalType>,
+    return_type: StatementReturnType,
+    /// The catalog version the statement was bound at
+    catalog_version: usize,
+}
+
+impl PreparedStatement {
+    pub(crate) fn try_new(
+        client_context: Arc<ClientContext>,
+        statement: Statement,
+        declared_types: Vec<LogicalType>,
+    ) -> Result<Self, DatabaseError> {
+        let bound = Self::bind(&client_context, &statement, &declared_types)?;
+        Ok(Self {
+            client_context,
+            statement,
+            declared_types,
+            fixed_types: false,
+            bound: Mutex::new(bound),
+        })
+    }
+
+    /// Fail the executions of the statement once binding it again changes the types of its
+    /// parameters or of its result, for the protocols whose clients cache the described types.
+    pub(crate) fn with_fixed_types(mut self) -> Self {
+        self.fixed_types = true;
+        self
+    }
+
+    fn bind(
+        client_context: &Arc<ClientContext>,
+        statement: &Statement,
+        declared_types: &[LogicalType],
+    ) -> Result<BoundPreparedStatement, DatabaseError> {
+        let catalog_version = Catalog::catalog_version(client_context.clone())?;
+        let mut planner = Planner::new(client_context.clone());
+        planner.set_parameter_types(declared_types.to_vec());
+        planner.create_plan(statement)?;
+        Ok(BoundPreparedStatement {
+            plan: planner.plan.unwrap(),
+            names: planner.names.unwrap(),
+            types: planner.types.unwrap(),
+            parameter_types: planner.parameter_types.unwrap(),
+            return_type: planner.return_type.unwrap(),
+            catalog_version,
+        })
+    }
+
+    /// The types of the parameters, the type of `$1` is the first one.
+    pub fn parameter_types(&self) -> Vec<LogicalType> {
+        self.bound.lock().unwrap().parameter_types.clone()
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.bound.lock().unwrap().names.clone()
+    }
+
+    pub fn types(&self) -> Vec<LogicalType> {
+        self.bound.lock().unwrap().types.clone()
+    }
+
+    /// Execute the statement with the values of its parameters, the values are cast to the
+    /// types of the parameters. The result is streamed like the result of a query.
+    pub async fn execute(
+        &self,
+        parameters: Vec<ScalarValue>,
+    ) -> Result<QueryResult, DatabaseError> {
+        let pending_query = self
+            .client_context
+            .pending_prepared_statement(self, parameters)
+            .await?;
+        pending_query.execute_stream().await
+    }
+
+    /// Plan the statement with the values of its parameters. The statement is bound again when
+    /// the catalog changed since it was bound, e.g. a table it reads was dropped.
+    pub(crate) fn create_plan(
+        &self,
+        parameters: Vec<ScalarValue>,
+    ) -> Result<PreparedStatementData, DatabaseError> {
+        let mut bound = self.bound.lock().unwrap();
+        if bound.catalog_version != Catalog::catalog_version(self.client_context.clone())? {
+            let rebound = Self::bind(&self.client_context, &self.statement, &self.declared_types)?;
+            if self.fixed_types
+                && (rebound.parameter_types != bound.parameter_types
+                    || rebound.types != bound.types)
+            {
+                return Err(DatabaseError::PreparedStatementTypesChanged);
+            }
+            *bound = rebound;
+        }
+        if parameters.len() != bound.parameter_types.len() {
+            return Err(DatabaseError::InternalError(format!(
+                "expected {} parameters, but got {}",
+                bound.parameter_types.len(),
+                parameters.len()
+            )));
+        }
+        let mut plan = bound.plan.clone();
+        ParameterBinder::bind_values(&mut plan, parameters).map_err(PlannerError::from)?;
+        Ok(self.client_context.create_physical_plan(
+            self.statement.clone(),
+            plan,
+            bound.names.clone(),
+            bound.types.clone(),
+            bound.return_type,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{Int32Array, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::record_batch::RecordBatch;
+
+    use crate::main_entry::{Database, DatabaseError};
+    use crate::types_v2::{LogicalType, ScalarValue};
+
+    fn batch_of(values: Vec<i32>) -> RecordBatch {
+        let schema = Schema::new(vec![Field::new("a", DataType::Int32, false)]);
+        RecordBatch::try_new(
+            schema.into(),
+            vec![std::sync::Arc::new(Int32Array::from(values))],
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_prepared_statement_is_bound_again() {
+        let db = Database::open().unwrap();
+        let conn = db.connect();
+        let context = conn.client_context();
+        let batch = batch_of(vec![1, 2, 3]);
+        context
+            .register_batches("t", batch.schema(), vec![batch])
+            .unwrap();
+
+        let stmt = conn.prepare("select a from t where a >= ?").unwrap();
+        assert_eq!(stmt.parameter_types(), vec![LogicalType::Integer]);
+        assert_eq!(stmt.names(), vec!["a".to_string()]);
+        let result = stmt.execute(vec![ScalarValue::Int32(Some(2))]).await;
+        let result = result.unwrap().collect().await.unwrap();
+        let column = result.batches()[0].column(0);
+        assert_eq!(
+            column.as_any().downcast_ref::<Int32Array>(),
+            Some(&Int32Array::from(vec![2, 3]))
+        );
+        assert!(stmt.execute(vec![]).await.is_err());
+
+        // the table is replaced by one with another type of column
+        context.unregister("t").unwrap();
+        let schema = Schema::new(vec![Field::new("a", DataType::Utf8, false)]);
+        let batch = RecordBatch::try_new(
+            schema.into(),
+            vec![std::sync::Arc::new(StringArray::from(vec!["x", "y"]))],
+        )
+        .unwrap();
+        context
+            .register_batches("t", batch.schema(), vec![batch])
+            .unwrap();
+        let result = stmt.execute(vec![ScalarValue::Utf8(Some("y".to_string()))]);
+        let result = result.await.unwrap().collect().await.unwrap();
+        assert_eq!(stmt.parameter_types(), vec![LogicalType::Varchar]);
+        assert_eq!(result.base().types(), &[LogicalType::Varchar]);
+        assert_eq!(result.batches()[0].num_rows(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_prepared_statement_with_fixed_types() {
+        let db = Database::open().unwrap();
+        let conn = db.connect();
+        let context = conn.client_context();
+        let batch = batch_of(vec![1, 2, 3]);
+        context
+            .register_batches("t", batch.schema(), vec![batch])
+            .unwrap();
+        let stmt = conn
+            .prepare("select a from t where a >= ?")
+            .unwrap()
+            .with_fixed_types();
+
+        // the table is replaced by one with the same types
+        context.unregister("t").unwrap();
+        let batch = batch_of(vec![4, 5]);
+        context
+            .register_batches("t", batch.schema(), vec![batch])
+            .unwrap();
+        let result = stmt.execute(vec![ScalarValue::Int32(Some(5))]);
+        let result = result.await.unwrap().collect().await.unwrap();
+        assert_eq!(result.batches()[0].num_rows(), 1);
+
+        // the table is replaced by one with another type of column
+        context.unregister("t").unwrap();
+        let schema = Schema::new(vec![Field::new("a", DataType::Utf8, false)]);
+        let batch = RecordBatch::try_new(
+            schema.into(),
+            vec![std::sync::Arc::new(StringArray::from(vec!["x", "y"]))],
+        )
+        .unwrap();
+        context
+            .register_batches("t", batch.schema(), vec![batch])
+            .unwrap();
+        let result = stmt.execute(vec![ScalarValue::Int32(Some(5))]).await;
+        assert!(matches!(
+            result,
+            Err(DatabaseError::PreparedStatementTypesChanged)
+        ));
+        assert_eq!(stmt.parameter_types(), vec![LogicalType::Integer]);
+        assert_eq!(stmt.types(), vec![LogicalType::Integer]);
+    }
+}
@@ -2,6 +2,7 @@ use derive_new::new;
 use sqlparser::ast::Statement;
 
 use crate::execution::PhysicalOperator;
+use crate::planner_v2::StatementReturnType;
 use crate::types_v2::LogicalType;
 
 #[derive(new)]
@@ -15,4 +16,5 @@ pub struct PreparedStatementData {
     pub(crate) names: Vec<String>,
     /// The result types
     pub(crate) types: Vec<LogicalType>,
+    pub(crate) return_type: StatementReturnType,
 }
@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use super::{PendingQueryResult, PreparedStatementData};
-use crate::execution::VolcanoExecutor;
+use crate::execution::PipelineExecutor;
 
 #[derive(Default)]
 pub struct ActiveQueryContext {
@@ -12,7 +12,7 @@ pub struct ActiveQueryContext {
     /// Prepared statement data
     pub(crate) prepared: Option<PreparedStatementData>,
     /// The query executor
-    pub(crate) executor: Option<VolcanoExecutor>,
+    pub(crate) executor: Option<PipelineExecutor>,
 }
 
 impl ActiveQueryContext {
@@ -1,14 +1,44 @@
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use arrow::array::Int64Array;
+use arrow::datatypes::SchemaRef;
 use arrow::record_batch::RecordBatch;
 use derive_new::new;
+use futures::stream::{self, BoxStream};
+use futures::{Stream, StreamExt, TryStreamExt};
 
+use super::DatabaseError;
+use crate::execution::SchemaUtil;
+use crate::planner_v2::StatementReturnType;
 use crate::types_v2::LogicalType;
 
-#[derive(new, Debug)]
+#[derive(new, Debug, Clone)]
 pub struct BaseQueryResult {
     /// The SQL types of the result
     pub(crate) types: Vec<LogicalType>,
     /// The names of the result
     pub(crate) names: Vec<String>,
+    pub(crate) return_type: StatementReturnType,
+}
+
+impl BaseQueryResult {
+    pub fn types(&self) -> &[LogicalType] {
+        &self.types
+    }
+
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    pub fn return_type(&self) -> StatementReturnType {
+        self.return_type
+    }
+
+    /// The arrow schema of the batches of the result.
+    pub fn schema(&self) -> SchemaRef {
+        SchemaUtil::new_schema_ref(&self.names, &self.types)
+    }
 }
 
 #[derive(new)]
@@ -17,6 +47,106 @@ pub struct MaterializedQueryResult {
     pub(crate) collection: Vec<RecordBatch>,
 }
 
+impl MaterializedQueryResult {
+    pub fn base(&self) -> &BaseQueryResult {
+        &self.base
+    }
+
+    pub fn batches(&self) -> &[RecordBatch] {
+        &self.collection
+    }
+
+    pub fn into_batches(self) -> Vec<RecordBatch> {
+        self.collection
+    }
+}
+
+/// The result of a query still being executed, its batches are returned as soon as they are
+/// produced.
+#[derive(new)]
+pub struct StreamQueryResult {
+    pub(crate) base: BaseQueryResult,
+    pub(crate) stream: BoxStream<'static, Result<RecordBatch, DatabaseError>>,
+}
+
+impl StreamQueryResult {
+    pub fn base(&self) -> &BaseQueryResult {
+        &self.base
+    }
+
+    /// Read the remaining batches of the result.
+    pub async fn collect(self) -> Result<MaterializedQueryResult, DatabaseError> {
+        let collection = self.stream.try_collect().await?;
+        Ok(MaterializedQueryResult::new(self.base, collection))
+    }
+}
+
+impl Stream for StreamQueryResult {
+    type Item = Result<RecordBatch, DatabaseError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.stream.poll_next_unpin(cx)
+    }
+}
+
 pub enum QueryResult {
     MaterializedQueryResult(MaterializedQueryResult),
+    StreamQueryResult(StreamQueryResult),
+}
+
+impl QueryResult {
+    pub fn base(&self) -> &BaseQueryResult {
+        match self {
+            QueryResult::MaterializedQueryResult(res) => &res.base,
+            QueryResult::StreamQueryResult(res) => &res.base,
+        }
+    }
+
+    pub fn types(&self) -> &[LogicalType] {
+        self.base().types()
+    }
+
+    pub fn names(&self) -> &[String] {
+        self.base().names()
+    }
+
+    pub fn return_type(&self) -> StatementReturnType {
+        self.base().return_type()
+    }
+
+    /// The count of the rows written by the statement, such as an insert or a copy. It's 0 for
+    /// the statements which write no rows. The result is read to its end, so the statement is
+    /// completed.
+    pub async fn affected_rows(self) -> Result<i64, DatabaseError> {
+        let changed_rows = self.return_type() == StatementReturnType::ChangedRows;
+        let mut count = 0;
+        let mut stream = self.into_stream();
+        while let Some(batch) = stream.next().await {
+            let batch = batch?;
+            if !changed_rows {
+                continue;
+            }
+            let array = batch.column(0).as_any().downcast_ref::<Int64Array>();
+            count += array.map_or(0, |a| a.iter().flatten().sum::<i64>());
+        }
+        Ok(count)
+    }
+
+    /// The batches of the result as a stream, whether the result is materialized or not.
+    pub fn into_stream(self) -> BoxStream<'static, Result<RecordBatch, DatabaseError>> {
+        match self {
+            QueryResult::MaterializedQueryResult(res) => {
+                stream::iter(res.collection.into_iter().map(Ok)).boxed()
+            }
+            QueryResult::StreamQueryResult(res) => res.stream,
+        }
+    }
+
+    /// Read all the batches of the result.
+    pub async fn collect(self) -> Result<MaterializedQueryResult, DatabaseError> {
+        match self {
+            QueryResult::MaterializedQueryResult(res) => Ok(res),
+            QueryResult::StreamQueryResult(res) => res.collect().await,
+        }
+    }
 }
@@ -0,0 +1,83 @@
+use crate::binder::{BoundExpr, JoinCondition, JoinType};
+use crate::optimizer::{PlanNodeType, PlanRef};
+
+/// The number of rows of a table, the v1 catalog doesn't collect table statistics.
+const DEFAULT_ROW_COUNT: f64 = 1000.0;
+/// The fraction of rows that pass a predicate.
+const DEFAULT_SELECTIVITY: f64 = 0.2;
+/// The cost to insert a row into a hash table, relative to probing it with a row.
+const HASH_BUILD_FACTOR: f64 = 2.0;
+
+/// The cost model estimates the number of rows of logical plans and the cost of physical plans.
+/// The cost of an operator is the number of rows it processes, children costs are added by the
+/// optimizer.
+#[derive(Default)]
+pub struct CostModel;
+
+impl CostModel {
+    /// Estimate the number of rows of a logical plan node from the rows of its children.
+    pub fn estimate_rows(&self, plan: &PlanRef, children_rows: &[f64]) -> f64 {
+        match plan.node_type() {
+            PlanNodeType::LogicalTableScan => {
+                let scan = plan.as_logical_table_scan().unwrap();
+                match scan.bounds() {
+                    Some((_, limit)) => DEFAULT_ROW_COUNT.min(limit as f64),
+                    None => DEFAULT_ROW_COUNT,
+                }
+            }
+            PlanNodeType::LogicalFilter => children_rows[0] * DEFAULT_SELECTIVITY,
+            PlanNodeType::LogicalAgg => {
+                let agg = plan.as_logical_agg().unwrap();
+                if agg.group_by().is_empty() {
+                    1.0
+                } else {
+                    (children_rows[0] * DEFAULT_SELECTIVITY).max(1.0)
+                }
+            }
+            PlanNodeType::LogicalLimit => {
+                let limit = plan.as_logical_limit().unwrap();
+                match limit.limit() {
+                    Some(BoundExpr::Constant(v)) => match v.as_usize() {
+                        Some(v) => children_rows[0].min(v as f64),
+                        None => children_rows[0],
+                    },
+                    _ => children_rows[0],
+                }
+            }
+            PlanNodeType::LogicalJoin => {
+                let join = plan.as_logical_join().unwrap();
+                let (left, right) = (children_rows[0], children_rows[1]);
+                match (join.join_type(), join.join_condition()) {
+                    (JoinType::Cross, _) | (_, JoinCondition::None) => left * right,
+                    (_, JoinCondition::On { on, .. }) if on.is_empty() => {
+                        left * right * DEFAULT_SELECTIVITY
+                    }
+                    (_, JoinCondition::On { .. }) => left.max(right),
+                }
+            }
+            _ => children_rows.first().copied().unwrap_or(1.0),
+        }
+    }
+
+    /// Estimate the cost of a physical plan node, excluding the cost of its children.
+    pub fn operator_cost(&self, plan: &PlanRef, output_rows: f64, children_rows: &[f64]) -> f64 {
+        match plan.node_type() {
+            // a limit stops reading its child once it has enough rows
+            PlanNodeType::PhysicalTableScan | PlanNodeType::PhysicalLimit => output_rows,
+            PlanNodeType::PhysicalOrder => {
+                let rows = children_rows[0].max(1.0);
+                rows * rows.log2().max(1.0)
+            }
+            // the hash table is built on the left side and probed with the right side
+            PlanNodeType::PhysicalHashJoin => {
+                children_rows[0] * HASH_BUILD_FACTOR + children_rows[1]
+            }
+            PlanNodeType::PhysicalCrossJoin => children_rows[0] * children_rows[1],
+            PlanNodeType::PhysicalProject
+            | PlanNodeType::PhysicalFilter
+            | PlanNodeType::PhysicalSimpleAgg
+            | PlanNodeType::PhysicalHashAgg => children_rows[0],
+            _ => 0.0,
+        }
+    }
+}
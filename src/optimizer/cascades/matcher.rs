@@ -0,0 +1,66 @@
+use crate::optimizer::core::{
+    GroupId, Memo, OptExpr, OptExprNode, Pattern, PatternChildrenPredicate, PatternMatcher,
+};
+
+/// Use pattern to determines whether a rule can be applied to an expression of a memo group.
+pub struct MemoMatcher<'a, 'b> {
+    pub pattern: &'a Pattern,
+    pub group_id: GroupId,
+    pub expr_idx: usize,
+    pub memo: &'b Memo,
+}
+
+impl<'a, 'b> MemoMatcher<'a, 'b> {
+    pub fn new(pattern: &'a Pattern, group_id: GroupId, expr_idx: usize, memo: &'b Memo) -> Self {
+        Self {
+            pattern,
+            group_id,
+            expr_idx,
+            memo,
+        }
+    }
+}
+
+impl PatternMatcher for MemoMatcher<'_, '_> {
+    fn match_opt_expr(&self) -> Option<OptExpr> {
+        let expr = &self.memo.group(self.group_id).exprs[self.expr_idx];
+        // check the root node predicate
+        if !(self.pattern.predicate)(&expr.plan) {
+            return None;
+        }
+        // check the children's predicate
+        let opt_expr = match &self.pattern.children {
+            PatternChildrenPredicate::MatchedRecursive => OptExpr::new_from_plan_ref(&expr.plan),
+            PatternChildrenPredicate::Predicate(children_patterns) => {
+                let mut children_opt_exprs = vec![];
+                for (idx, child_pattern) in children_patterns.iter().enumerate() {
+                    // the predicates order should match the children groups order, the first
+                    // matched expression of the child group is used.
+                    let child_id = expr.children[idx];
+                    let child_group = self.memo.group(child_id);
+                    let matched = (0..child_group.exprs.len()).find_map(|child_expr_idx| {
+                        MemoMatcher::new(child_pattern, child_id, child_expr_idx, self.memo)
+                            .match_opt_expr()
+                    });
+                    match matched {
+                        Some(opt_expr) => children_opt_exprs.push(opt_expr),
+                        // if one of the children doesn't match, the whole pattern doesn't match
+                        None => return None,
+                    }
+                }
+                OptExpr::new(OptExprNode::PlanRef(expr.plan.clone()), children_opt_exprs)
+            }
+            PatternChildrenPredicate::None => {
+                // we don't care the children in rule logic, so they are collected as
+                // OptExprNode::OptExpr which refer to the children groups.
+                let children_opt_exprs = expr
+                    .children
+                    .iter()
+                    .map(|id| OptExpr::new(OptExprNode::OptExpr(*id), vec![]))
+                    .collect::<Vec<_>>();
+                OptExpr::new(OptExprNode::PlanRef(expr.plan.clone()), children_opt_exprs)
+            }
+        };
+        Some(opt_expr)
+    }
+}
@@ -0,0 +1,5 @@
+mod cost;
+mod matcher;
+mod optimizer;
+pub use cost::*;
+pub use optimizer::*;
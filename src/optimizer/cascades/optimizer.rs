@@ -0,0 +1,265 @@
+use super::cost::CostModel;
+use super::matcher::MemoMatcher;
+use crate::optimizer::core::{GroupId, Memo, PatternMatcher, Rule, Substitute, Winner};
+use crate::optimizer::rules::RuleImpl;
+use crate::optimizer::PlanRef;
+
+/// A Cascades-style optimizer. Unlike `HepOptimizer` which replaces the plan with the result of
+/// each rule, it keeps all the equivalent alternatives in a memo and returns the cheapest plan.
+///
+/// - Exploration: transformation rules add logically equivalent expressions to the groups.
+/// - Implementation: implementation rules add the physical expressions of the logical ones.
+/// - Costing: the cheapest physical expression of each group is chosen bottom up.
+pub struct CascadesOptimizer {
+    transformation_rules: Vec<RuleImpl>,
+    implementation_rules: Vec<RuleImpl>,
+    cost_model: CostModel,
+    memo: Memo,
+}
+
+impl CascadesOptimizer {
+    pub fn new(
+        transformation_rules: Vec<RuleImpl>,
+        implementation_rules: Vec<RuleImpl>,
+        root: PlanRef,
+    ) -> Self {
+        Self {
+            transformation_rules,
+            implementation_rules,
+            cost_model: CostModel::default(),
+            memo: Memo::new(&root),
+        }
+    }
+
+    pub fn find_best(&mut self) -> Result<PlanRef, OptimizerError> {
+        let root = self.memo.root();
+        if self.optimize_group(root).is_none() {
+            return Err(OptimizerError::NoPhysicalPlan);
+        }
+        Ok(self.build_best_plan(root))
+    }
+
+    /// Apply the transformation rules to all the expressions of the group, including the ones
+    /// added by the rules. The children groups are explored first, so the patterns spanning
+    /// several levels can match their alternatives.
+    fn explore_group(&mut self, group_id: GroupId) {
+        if self.memo.group(group_id).explored {
+            return;
+        }
+        self.memo.group_mut(group_id).explored = true;
+
+        let mut expr_idx = 0;
+        while expr_idx < self.memo.group(group_id).exprs.len() {
+            let children = self.memo.group(group_id).exprs[expr_idx].children.clone();
+            for child in children {
+                self.explore_group(child);
+            }
+            for rule in self.transformation_rules.clone() {
+                self.apply_rule(&rule, group_id, expr_idx);
+            }
+            expr_idx += 1;
+        }
+    }
+
+    /// Find the cheapest physical expression of the group, return its cost including the
+    /// children, or None if the group can not be implemented.
+    fn optimize_group(&mut self, group_id: GroupId) -> Option<f64> {
+        if let Some(winner) = self.memo.group(group_id).winner {
+            return Some(winner.cost);
+        }
+        self.explore_group(group_id);
+
+        let logical_exprs = self.memo.group(group_id).exprs.len();
+        for expr_idx in 0..logical_exprs {
+            if !self.memo.group(group_id).exprs[expr_idx]
+                .plan
+                .is_logical_plan()
+            {
+                continue;
+            }
+            for rule in self.implementation_rules.clone() {
+                self.apply_rule(&rule, group_id, expr_idx);
+            }
+        }
+
+        let output_rows = self.group_rows(group_id);
+        let mut winner: Option<Winner> = None;
+        let exprs = self.memo.group(group_id).exprs.clone();
+        'exprs: for (expr_idx, expr) in exprs.into_iter().enumerate() {
+            if expr.plan.is_logical_plan() {
+                continue;
+            }
+            let children_rows = expr
+                .children
+                .iter()
+                .map(|child| self.group_rows(*child))
+                .collect::<Vec<_>>();
+            let mut cost = self
+                .cost_model
+                .operator_cost(&expr.plan, output_rows, &children_rows);
+            for child in expr.children.iter() {
+                match self.optimize_group(*child) {
+                    Some(child_cost) => cost += child_cost,
+                    None => continue 'exprs,
+                }
+            }
+            if winner.map_or(true, |w| cost < w.cost) {
+                winner = Some(Winner { expr_idx, cost });
+            }
+        }
+        self.memo.group_mut(group_id).winner = winner;
+        winner.map(|w| w.cost)
+    }
+
+    /// The estimated number of rows of the group, derived from its first logical expression.
+    fn group_rows(&mut self, group_id: GroupId) -> f64 {
+        if let Some(rows) = self.memo.group(group_id).row_count {
+            return rows;
+        }
+        let expr = self.memo.group(group_id).exprs[0].clone();
+        let children_rows = expr
+            .children
+            .iter()
+            .map(|child| self.group_rows(*child))
+            .collect::<Vec<_>>();
+        let rows = self.cost_model.estimate_rows(&expr.plan, &children_rows);
+        self.memo.group_mut(group_id).row_count = Some(rows);
+        rows
+    }
+
+    fn apply_rule(&mut self, rule: &RuleImpl, group_id: GroupId, expr_idx: usize) {
+        let matcher = MemoMatcher::new(rule.pattern(), group_id, expr_idx, &self.memo);
+        if let Some(opt_expr) = matcher.match_opt_expr() {
+            let mut substitute = Substitute::default();
+            rule.apply(opt_expr, &mut substitute);
+            for opt_expr in substitute.opt_exprs {
+                self.memo.insert_opt_expr(opt_expr, Some(group_id));
+            }
+        }
+    }
+
+    fn build_best_plan(&self, group_id: GroupId) -> PlanRef {
+        let group = self.memo.group(group_id);
+        let winner = group.winner.expect("group should be optimized");
+        let expr = &group.exprs[winner.expr_idx];
+        let children = expr
+            .children
+            .iter()
+            .map(|child| self.build_best_plan(*child))
+            .collect::<Vec<_>>();
+        expr.plan.clone_with_children(children)
+    }
+}
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum OptimizerError {
+    #[error("no physical plan found for the plan tree, missing implementation rules")]
+    NoPhysicalPlan,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::datatypes::DataType;
+    use sqlparser::ast::BinaryOperator;
+
+    use super::{CascadesOptimizer, OptimizerError};
+    use crate::binder::test_util::*;
+    use crate::binder::{BoundBinaryOp, BoundExpr, JoinCondition, JoinType};
+    use crate::optimizer::{
+        ExtractJoinConditionToFilter, ImplementPhysicalOperator, LogicalFilter, LogicalJoin,
+        LogicalTableScan, PlanNodeType, PlanRef, PushPredicateIntoCrossJoin,
+    };
+
+    fn build_logical_table_scan(table_id: &str) -> PlanRef {
+        Arc::new(LogicalTableScan::new(
+            table_id.to_string(),
+            None,
+            vec![
+                build_column_catalog(table_id, "c1"),
+                build_column_catalog(table_id, "c2"),
+            ],
+            None,
+            None,
+        ))
+    }
+
+    fn build_optimizer(root: PlanRef) -> CascadesOptimizer {
+        CascadesOptimizer::new(
+            vec![
+                PushPredicateIntoCrossJoin::create(),
+                ExtractJoinConditionToFilter::create(),
+            ],
+            vec![ImplementPhysicalOperator::create()],
+            root,
+        )
+    }
+
+    #[test]
+    fn test_cascades_optimizer_choose_hash_join() {
+        // select * from t1, t2 where t1.c1 = t2.c1
+        let join = Arc::new(LogicalJoin::new(
+            build_logical_table_scan("t1"),
+            build_logical_table_scan("t2"),
+            JoinType::Cross,
+            JoinCondition::None,
+        ));
+        let root = Arc::new(LogicalFilter::new(
+            BoundExpr::BinaryOp(BoundBinaryOp {
+                op: BinaryOperator::Eq,
+                left: build_bound_column_ref_box("t1", "c1"),
+                right: build_bound_column_ref_box("t2", "c1"),
+                return_type: Some(DataType::Boolean),
+            }),
+            join,
+        ));
+        let mut optimizer = build_optimizer(root);
+        let plan = optimizer.find_best().unwrap();
+        assert_eq!(plan.node_type(), PlanNodeType::PhysicalHashJoin);
+        assert_eq!(
+            plan.as_physical_hash_join().unwrap().join_condition(),
+            build_join_condition_eq("t1", "c1", "t2", "c1")
+        );
+        for child in plan.children() {
+            assert_eq!(child.node_type(), PlanNodeType::PhysicalTableScan);
+        }
+    }
+
+    #[test]
+    fn test_cascades_optimizer_keep_cross_join_without_equi_condition() {
+        // select * from t1, t2 where t1.c1 > t2.c1
+        let join = Arc::new(LogicalJoin::new(
+            build_logical_table_scan("t1"),
+            build_logical_table_scan("t2"),
+            JoinType::Cross,
+            JoinCondition::None,
+        ));
+        let root = Arc::new(LogicalFilter::new(
+            BoundExpr::BinaryOp(BoundBinaryOp {
+                op: BinaryOperator::Gt,
+                left: build_bound_column_ref_box("t1", "c1"),
+                right: build_bound_column_ref_box("t2", "c1"),
+                return_type: Some(DataType::Boolean),
+            }),
+            join,
+        ));
+        let mut optimizer = build_optimizer(root);
+        let plan = optimizer.find_best().unwrap();
+        assert_eq!(plan.node_type(), PlanNodeType::PhysicalFilter);
+        assert_eq!(
+            plan.children()[0].node_type(),
+            PlanNodeType::PhysicalCrossJoin
+        );
+    }
+
+    #[test]
+    fn test_cascades_optimizer_without_implementation_rules() {
+        let root = build_logical_table_scan("t1");
+        let mut optimizer = CascadesOptimizer::new(vec![], vec![], root);
+        assert_eq!(
+            optimizer.find_best().unwrap_err(),
+            OptimizerError::NoPhysicalPlan
+        );
+    }
+}
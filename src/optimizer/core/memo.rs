@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+
+use super::{OptExpr, OptExprNode};
+use crate::optimizer::{PlanNodeType, PlanRef};
+
+/// GroupId is used in memo to identify a group.
+pub type GroupId = usize;
+
+/// An expression in a memo group. Its children are groups, so one group expression represents all
+/// the plans that combine it with any expression of the child groups.
+#[derive(Clone, Debug)]
+pub struct GroupExpr {
+    /// The plan node, its children are the plans of the first expressions of the child groups.
+    pub plan: PlanRef,
+    pub children: Vec<GroupId>,
+}
+
+/// The cheapest physical expression of a group found by the search.
+#[derive(Clone, Copy, Debug)]
+pub struct Winner {
+    pub expr_idx: usize,
+    pub cost: f64,
+}
+
+/// A group of logically equivalent expressions. The first expression is always the logical one
+/// the group was created from, it is used to derive the group properties.
+#[derive(Clone, Debug, Default)]
+pub struct Group {
+    pub exprs: Vec<GroupExpr>,
+    /// Whether the transformation rules have been applied to all the expressions of the group.
+    pub explored: bool,
+    /// The estimated number of rows the group produces.
+    pub row_count: Option<f64>,
+    pub winner: Option<Winner>,
+}
+
+/// The memo stores the search space of the optimizer. Equivalent expressions are collected in the
+/// same group and every expression is stored only once, so rules can be applied to the whole
+/// space without enumerating the plans.
+#[derive(Debug)]
+pub struct Memo {
+    groups: Vec<Group>,
+    root: GroupId,
+    /// The expressions of the memo by their node type and children groups, as the group and the
+    /// index of the expression in the group. Only the expressions with the same key can be equal.
+    expr_index: HashMap<(PlanNodeType, Vec<GroupId>), Vec<(GroupId, usize)>>,
+}
+
+impl Memo {
+    pub fn new(root: &PlanRef) -> Self {
+        let mut memo = Self {
+            groups: vec![],
+            root: GroupId::default(),
+            expr_index: HashMap::new(),
+        };
+        memo.root = memo.insert_opt_expr(OptExpr::new_from_plan_ref(root), None);
+        memo
+    }
+
+    pub fn root(&self) -> GroupId {
+        self.root
+    }
+
+    pub fn group(&self, id: GroupId) -> &Group {
+        &self.groups[id]
+    }
+
+    pub fn group_mut(&mut self, id: GroupId) -> &mut Group {
+        &mut self.groups[id]
+    }
+
+    /// The plan of the first expression of the group, used as the representative of the group
+    /// when a plan node needs its children.
+    pub fn group_plan(&self, id: GroupId) -> PlanRef {
+        self.groups[id].exprs[0].plan.clone()
+    }
+
+    /// Insert an `OptExpr` tree into the memo, the root is added to the target group, or to a new
+    /// group if there is no target. `OptExprNode::OptExpr` nodes refer to existing groups.
+    ///
+    /// Returns the group of the root. An expression which already exists in the memo is not
+    /// added again, its existing group is returned instead, even if it is not the target group.
+    pub fn insert_opt_expr(&mut self, opt_expr: OptExpr, target: Option<GroupId>) -> GroupId {
+        let plan = match opt_expr.root {
+            OptExprNode::OptExpr(id) => return id,
+            OptExprNode::PlanRef(plan) => plan,
+        };
+        let children = opt_expr
+            .children
+            .into_iter()
+            .map(|child| self.insert_opt_expr(child, None))
+            .collect::<Vec<_>>();
+        let children_plans = children
+            .iter()
+            .map(|id| self.group_plan(*id))
+            .collect::<Vec<_>>();
+        let expr = GroupExpr {
+            plan: plan.clone_with_children(children_plans),
+            children,
+        };
+
+        let key = (expr.plan.node_type(), expr.children.clone());
+        if let Some(id) = self.find_group(&key, &expr) {
+            return id;
+        }
+        let id = match target {
+            Some(id) => id,
+            None => {
+                self.groups.push(Group::default());
+                self.groups.len() - 1
+            }
+        };
+        self.expr_index
+            .entry(key)
+            .or_default()
+            .push((id, self.groups[id].exprs.len()));
+        self.groups[id].exprs.push(expr);
+        id
+    }
+
+    /// Find the group which contains the expression. The children plans of two expressions with
+    /// the same children groups are the same, so comparing the plans only compares the nodes.
+    fn find_group(&self, key: &(PlanNodeType, Vec<GroupId>), expr: &GroupExpr) -> Option<GroupId> {
+        self.expr_index.get(key).and_then(|exprs| {
+            exprs
+                .iter()
+                .find(|(id, expr_idx)| self.groups[*id].exprs[*expr_idx].plan == expr.plan)
+                .map(|(id, _)| *id)
+        })
+    }
+}
@@ -1,6 +1,8 @@
+mod memo;
 mod opt_expr;
 mod pattern;
 mod rule;
+pub use memo::*;
 pub use opt_expr::*;
 pub use pattern::*;
 pub use rule::*;
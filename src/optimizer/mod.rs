@@ -1,3 +1,4 @@
+mod cascades;
 mod core;
 mod expr_rewriter;
 mod expr_visitor;
@@ -9,6 +10,7 @@ mod plan_rewriter;
 mod plan_visitor;
 mod rules;
 
+pub use cascades::*;
 pub use expr_visitor::*;
 pub use heuristic::*;
 pub use input_ref_rewriter::*;
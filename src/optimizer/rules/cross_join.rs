@@ -0,0 +1,188 @@
+use std::sync::Arc;
+
+use arrow::datatypes::DataType;
+use sqlparser::ast::BinaryOperator;
+
+use super::util::{is_subset_cols, reduce_conjunctive_predicate};
+use super::RuleImpl;
+use crate::binder::{BoundBinaryOp, BoundExpr, JoinCondition, JoinType};
+use crate::optimizer::core::*;
+use crate::optimizer::{Dummy, LogicalFilter, LogicalJoin, PlanNodeType};
+
+lazy_static! {
+    static ref PUSH_PREDICATE_INTO_CROSS_JOIN: Pattern = {
+        Pattern {
+            predicate: |p| p.node_type() == PlanNodeType::LogicalFilter,
+            children: PatternChildrenPredicate::Predicate(vec![Pattern {
+                predicate: |p| {
+                    p.node_type() == PlanNodeType::LogicalJoin
+                        && p.as_logical_join().unwrap().join_type() == JoinType::Cross
+                },
+                children: PatternChildrenPredicate::None,
+            }]),
+        }
+    };
+    static ref EXTRACT_JOIN_CONDITION_TO_FILTER: Pattern = {
+        Pattern {
+            predicate: |p| {
+                p.node_type() == PlanNodeType::LogicalJoin
+                    && p.as_logical_join().unwrap().join_type() == JoinType::Inner
+            },
+            children: PatternChildrenPredicate::None,
+        }
+    };
+}
+
+fn split_conjunctive_predicates(expr: &BoundExpr) -> Vec<BoundExpr> {
+    match expr {
+        BoundExpr::BinaryOp(BoundBinaryOp {
+            op: BinaryOperator::And,
+            left,
+            right,
+            ..
+        }) => [
+            split_conjunctive_predicates(left),
+            split_conjunctive_predicates(right),
+        ]
+        .concat(),
+        _ => vec![expr.clone()],
+    }
+}
+
+/// Turns a `Filter` over a `CROSS JOIN` into an `INNER JOIN` when the filter contains equi-join
+/// predicates between the two sides, so the join can be executed as a hash join. It is the
+/// inverse of `ExtractJoinConditionToFilter`, the memo optimizer keeps both alternatives.
+#[derive(Clone)]
+pub struct PushPredicateIntoCrossJoin;
+
+impl PushPredicateIntoCrossJoin {
+    pub fn create() -> RuleImpl {
+        Self {}.into()
+    }
+}
+
+impl Rule for PushPredicateIntoCrossJoin {
+    fn pattern(&self) -> &Pattern {
+        &PUSH_PREDICATE_INTO_CROSS_JOIN
+    }
+
+    fn apply(&self, opt_expr: OptExpr, result: &mut Substitute) {
+        let join_opt_expr = opt_expr.children[0].clone();
+        let join_node = join_opt_expr.root.get_plan_ref().as_logical_join().unwrap();
+        let left_output_cols = join_node.left().output_columns();
+        let right_output_cols = join_node.right().output_columns();
+
+        let filter_expr = opt_expr
+            .root
+            .get_plan_ref()
+            .as_logical_filter()
+            .unwrap()
+            .expr();
+
+        let mut on = vec![];
+        let mut filters = vec![];
+        for expr in split_conjunctive_predicates(&filter_expr) {
+            if let BoundExpr::BinaryOp(BoundBinaryOp {
+                op: BinaryOperator::Eq,
+                left,
+                right,
+                ..
+            }) = &expr
+            {
+                if let (BoundExpr::ColumnRef(l), BoundExpr::ColumnRef(r)) = (&**left, &**right) {
+                    let l_cols = [l.column_catalog.clone()];
+                    let r_cols = [r.column_catalog.clone()];
+                    // reorder left and right join keys to pattern: (left, right)
+                    if is_subset_cols(&l_cols, &left_output_cols)
+                        && is_subset_cols(&r_cols, &right_output_cols)
+                    {
+                        on.push((*left.clone(), *right.clone()));
+                        continue;
+                    }
+                    if is_subset_cols(&r_cols, &left_output_cols)
+                        && is_subset_cols(&l_cols, &right_output_cols)
+                    {
+                        on.push((*right.clone(), *left.clone()));
+                        continue;
+                    }
+                }
+            }
+            filters.push(expr);
+        }
+        // a join without equi-join keys can't be executed as a hash join
+        if on.is_empty() {
+            return;
+        }
+
+        let join_condition = JoinCondition::On {
+            on,
+            filter: reduce_conjunctive_predicate(filters),
+        };
+        let new_join_root = OptExprNode::PlanRef(Arc::new(LogicalJoin::new(
+            Dummy::new_ref(),
+            Dummy::new_ref(),
+            JoinType::Inner,
+            join_condition,
+        )));
+        let res = OptExpr::new(new_join_root, join_opt_expr.children);
+        result.opt_exprs.push(res);
+    }
+}
+
+/// Turns an `INNER JOIN` into a `Filter` over a `CROSS JOIN` with the join condition as the
+/// filter predicate. The memo optimizer uses it to cost a cross join plus filter against a hash
+/// join.
+#[derive(Clone)]
+pub struct ExtractJoinConditionToFilter;
+
+impl ExtractJoinConditionToFilter {
+    pub fn create() -> RuleImpl {
+        Self {}.into()
+    }
+}
+
+impl Rule for ExtractJoinConditionToFilter {
+    fn pattern(&self) -> &Pattern {
+        &EXTRACT_JOIN_CONDITION_TO_FILTER
+    }
+
+    fn apply(&self, opt_expr: OptExpr, result: &mut Substitute) {
+        let join_node = opt_expr.root.get_plan_ref().as_logical_join().unwrap();
+        let (on, filter) = match join_node.join_condition() {
+            JoinCondition::On { on, filter } => (on, filter),
+            JoinCondition::None => return,
+        };
+
+        let predicates = on
+            .into_iter()
+            .map(|(left, right)| {
+                BoundExpr::BinaryOp(BoundBinaryOp {
+                    op: BinaryOperator::Eq,
+                    left: Box::new(left),
+                    right: Box::new(right),
+                    return_type: Some(DataType::Boolean),
+                })
+            })
+            .chain(filter.iter().flat_map(split_conjunctive_predicates))
+            .collect::<Vec<_>>();
+        let predicate = match reduce_conjunctive_predicate(predicates) {
+            Some(predicate) => predicate,
+            None => return,
+        };
+
+        let cross_join_opt_expr = OptExpr::new(
+            OptExprNode::PlanRef(Arc::new(LogicalJoin::new(
+                Dummy::new_ref(),
+                Dummy::new_ref(),
+                JoinType::Cross,
+                JoinCondition::None,
+            ))),
+            opt_expr.children,
+        );
+        let res = OptExpr::new(
+            OptExprNode::PlanRef(Arc::new(LogicalFilter::new(predicate, Dummy::new_ref()))),
+            vec![cross_join_opt_expr],
+        );
+        result.opt_exprs.push(res);
+    }
+}
@@ -1,5 +1,6 @@
 mod column_pruning;
 mod combine_operators;
+mod cross_join;
 mod physical_rewrite;
 mod pushdown_limit;
 mod pushdown_predicates;
@@ -9,6 +10,7 @@ use std::fmt::Debug;
 
 pub use column_pruning::*;
 pub use combine_operators::*;
+pub use cross_join::*;
 use enum_dispatch::enum_dispatch;
 pub use physical_rewrite::*;
 pub use pushdown_limit::*;
@@ -38,8 +40,12 @@ pub enum RuleImpl {
     CombineFilter,
     // Simplification
     SimplifyCasts,
+    // Cross join alternatives
+    PushPredicateIntoCrossJoin,
+    ExtractJoinConditionToFilter,
     // Rewrite physical plan
     PhysicalRewriteRule,
+    ImplementPhysicalOperator,
 }
 
 impl Debug for RuleImpl {
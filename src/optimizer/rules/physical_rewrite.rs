@@ -1,6 +1,13 @@
+use std::sync::Arc;
+
 use super::RuleImpl;
+use crate::binder::JoinType;
 use crate::optimizer::core::*;
-use crate::optimizer::{PhysicalRewriter, PlanRewriter};
+use crate::optimizer::{
+    PhysicalCrossJoin, PhysicalFilter, PhysicalHashAgg, PhysicalHashJoin, PhysicalLimit,
+    PhysicalOrder, PhysicalProject, PhysicalRewriter, PhysicalSimpleAgg, PhysicalTableScan,
+    PlanNodeType, PlanRef, PlanRewriter,
+};
 
 lazy_static! {
     static ref PATTERN: Pattern = {
@@ -9,6 +16,12 @@ lazy_static! {
             children: PatternChildrenPredicate::MatchedRecursive,
         }
     };
+    static ref IMPLEMENT_PHYSICAL_OPERATOR: Pattern = {
+        Pattern {
+            predicate: |p| p.is_logical_plan(),
+            children: PatternChildrenPredicate::None,
+        }
+    };
 }
 
 #[derive(Clone)]
@@ -34,3 +47,61 @@ impl Rule for PhysicalRewriteRule {
         result.opt_exprs.push(res);
     }
 }
+
+/// Implementation rule of the memo optimizer, implements a logical node with the physical node
+/// that executes it. Unlike `PhysicalRewriteRule` it only rewrites the root node, the children
+/// are implemented in their own groups.
+#[derive(Clone)]
+pub struct ImplementPhysicalOperator;
+
+impl ImplementPhysicalOperator {
+    pub fn create() -> RuleImpl {
+        Self {}.into()
+    }
+}
+
+impl Rule for ImplementPhysicalOperator {
+    fn pattern(&self) -> &Pattern {
+        &IMPLEMENT_PHYSICAL_OPERATOR
+    }
+
+    fn apply(&self, opt_expr: OptExpr, result: &mut Substitute) {
+        let plan = opt_expr.root.get_plan_ref();
+        let physical: PlanRef = match plan.node_type() {
+            PlanNodeType::LogicalTableScan => Arc::new(PhysicalTableScan::new(
+                plan.as_logical_table_scan().unwrap().clone(),
+            )),
+            PlanNodeType::LogicalProject => Arc::new(PhysicalProject::new(
+                plan.as_logical_project().unwrap().clone(),
+            )),
+            PlanNodeType::LogicalFilter => Arc::new(PhysicalFilter::new(
+                plan.as_logical_filter().unwrap().clone(),
+            )),
+            PlanNodeType::LogicalAgg => {
+                let agg = plan.as_logical_agg().unwrap().clone();
+                if agg.group_by().is_empty() {
+                    Arc::new(PhysicalSimpleAgg::new(agg))
+                } else {
+                    Arc::new(PhysicalHashAgg::new(agg))
+                }
+            }
+            PlanNodeType::LogicalLimit => {
+                Arc::new(PhysicalLimit::new(plan.as_logical_limit().unwrap().clone()))
+            }
+            PlanNodeType::LogicalOrder => {
+                Arc::new(PhysicalOrder::new(plan.as_logical_order().unwrap().clone()))
+            }
+            PlanNodeType::LogicalJoin => {
+                let join = plan.as_logical_join().unwrap().clone();
+                if join.join_type() == JoinType::Cross {
+                    Arc::new(PhysicalCrossJoin::new(join))
+                } else {
+                    Arc::new(PhysicalHashJoin::new(join))
+                }
+            }
+            _ => return,
+        };
+        let res = OptExpr::new(OptExprNode::PlanRef(physical), opt_expr.children);
+        result.opt_exprs.push(res);
+    }
+}
@@ -0,0 +1,80 @@
+use super::rule::{
+    BooleanSimplificationRule, CastSimplificationRule, ComparisonSimplificationRule,
+    ConstantFoldingRule, ExpressionRule, ExpressionRuleImpl, RuleUtil,
+};
+use crate::planner_v2::{
+    BoundExpression, LogicalEmptyResult, LogicalOperator, LogicalOperatorVisitor,
+};
+
+/// The ExpressionRewriter applies the expression rules on all expressions of the plan until none
+/// of the rules can be applied. It also removes the filters that fold to a constant.
+pub struct ExpressionRewriter {
+    rules: Vec<ExpressionRuleImpl>,
+}
+
+impl Default for ExpressionRewriter {
+    fn default() -> Self {
+        Self {
+            rules: vec![
+                CastSimplificationRule::create(),
+                ConstantFoldingRule::create(),
+                ComparisonSimplificationRule::create(),
+                BooleanSimplificationRule::create(),
+            ],
+        }
+    }
+}
+
+impl ExpressionRewriter {
+    fn apply_rules(&self, expr: &mut BoundExpression) -> bool {
+        for rule in self.rules.iter() {
+            if let Some(new_expr) = rule.apply(expr) {
+                *expr = new_expr;
+                return true;
+            }
+        }
+        false
+    }
+
+    fn rewrite_filter(op: &mut LogicalOperator) {
+        if let LogicalOperator::LogicalFilter(filter) = op {
+            // constant TRUE predicates always pass
+            filter
+                .base
+                .expressioins
+                .retain(|e| RuleUtil::as_boolean_constant(e) != Some(Some(true)));
+            let always_false = filter.base.expressioins.iter().any(|e| {
+                matches!(
+                    RuleUtil::as_boolean_constant(e),
+                    Some(Some(false)) | Some(None)
+                )
+            });
+            if always_false {
+                // the filter never passes any row, so replace the whole subtree with an empty
+                // result
+                let child = filter.base.children.remove(0);
+                *op = LogicalOperator::LogicalEmptyResult(LogicalEmptyResult::new(child));
+            } else if filter.base.expressioins.is_empty() {
+                let child = filter.base.children.remove(0);
+                *op = child;
+            }
+        }
+    }
+}
+
+impl LogicalOperatorVisitor for ExpressionRewriter {
+    fn visit_operator(&mut self, op: &mut LogicalOperator) {
+        self.visit_operator_children(op);
+        self.visit_operator_expressions(op);
+        Self::rewrite_filter(op);
+    }
+
+    fn visit_expression(&self, expr: &mut BoundExpression) {
+        // rewrite the children first, so constant subtrees are folded from bottom to top
+        self.visit_expression_children(expr);
+        if self.apply_rules(expr) {
+            // the rewritten expression may be simplified further
+            self.visit_expression(expr);
+        }
+    }
+}
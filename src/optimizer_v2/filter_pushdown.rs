@@ -0,0 +1,78 @@
+use crate::planner_v2::{
+    BoundExpression, ExpressionIterator, LogicalFilter, LogicalOperator, LogicalOperatorBase,
+    LogicalProjection,
+};
+
+/// The FilterPushdown collects the predicates of filters and pushes them down as far as possible.
+/// The predicates are re-added on top of the first operator that blocks the pushdown.
+#[derive(Default)]
+pub struct FilterPushdown {
+    filters: Vec<BoundExpression>,
+}
+
+impl FilterPushdown {
+    pub fn rewrite(&mut self, op: LogicalOperator) -> LogicalOperator {
+        match op {
+            LogicalOperator::LogicalFilter(op) => self.pushdown_filter(op),
+            LogicalOperator::LogicalProjection(op) => self.pushdown_projection(op),
+            other => self.finish_pushdown(other),
+        }
+    }
+
+    fn pushdown_filter(&mut self, mut op: LogicalFilter) -> LogicalOperator {
+        // the predicates are already split by AND, so we can add them separately
+        self.filters.extend(op.base.expressioins);
+        let child = op.base.children.remove(0);
+        self.rewrite(child)
+    }
+
+    fn pushdown_projection(&mut self, mut op: LogicalProjection) -> LogicalOperator {
+        // replace the column references of the filters with the projection expressions, so the
+        // filters can be evaluated below the projection
+        let filters = std::mem::take(&mut self.filters)
+            .into_iter()
+            .map(|mut filter| {
+                Self::replace_projection_bindings(&mut filter, &op);
+                filter
+            })
+            .collect::<Vec<_>>();
+        let mut child_pushdown = FilterPushdown { filters };
+        let child = op.base.children.remove(0);
+        op.base.children.push(child_pushdown.rewrite(child));
+        LogicalOperator::LogicalProjection(op)
+    }
+
+    fn finish_pushdown(&mut self, mut op: LogicalOperator) -> LogicalOperator {
+        // the pushdown is blocked by this operator, so start a new pushdown for each child
+        let children = op
+            .take_children()
+            .into_iter()
+            .map(|child| FilterPushdown::default().rewrite(child))
+            .collect::<Vec<_>>();
+        for child in children {
+            op.add_child(child);
+        }
+        if self.filters.is_empty() {
+            return op;
+        }
+        // add the remaining filters on top of the operator
+        let filters = std::mem::take(&mut self.filters);
+        LogicalOperator::LogicalFilter(LogicalFilter::new(LogicalOperatorBase::new(
+            vec![op],
+            filters,
+            vec![],
+        )))
+    }
+
+    fn replace_projection_bindings(expr: &mut BoundExpression, proj: &LogicalProjection) {
+        if let BoundExpression::BoundColumnRefExpression(e) = expr {
+            if e.binding.table_idx == proj.table_idx {
+                *expr = proj.base.expressioins[e.binding.column_idx].clone();
+            }
+            return;
+        }
+        ExpressionIterator::enumerate_children(expr, |child| {
+            Self::replace_projection_bindings(child, proj)
+        });
+    }
+}
@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+
+use derive_new::new;
+
+use crate::catalog_v2::TableStatistics;
+use crate::function::{FunctionData, ReadParquet};
+use crate::planner_v2::{BoundColumnRefExpression, BoundExpression, LogicalGet, LogicalOperator};
+use crate::types_v2::ScalarValue;
+
+/// The selectivity of a predicate we know nothing about.
+pub const DEFAULT_SELECTIVITY: f64 = 0.2;
+/// The cardinality of a table function that can not estimate its own size.
+pub const DEFAULT_CARDINALITY: f64 = 1000.0;
+/// The average width of a csv column, used to estimate the number of rows of a csv file.
+const CSV_BYTES_PER_COLUMN: u64 = 8;
+
+/// The CardinalityEstimator estimates the number of rows of the relations in a join region, and
+/// the selectivity of the predicates between them. Estimates are driven by the row counts and
+/// distinct value counts and histograms in the table statistics.
+#[derive(new)]
+pub struct CardinalityEstimator<'a> {
+    relations: &'a [LogicalOperator],
+    /// Mapping of table index to the relation it belongs to
+    table_to_relation: &'a HashMap<usize, usize>,
+}
+
+impl CardinalityEstimator<'_> {
+    /// Estimate the number of rows of a relation, without the filters of the join region.
+    pub fn estimate_relation(&self, relation: usize) -> f64 {
+        Self::estimate_operator(&self.relations[relation])
+    }
+
+    pub fn estimate_operator(op: &LogicalOperator) -> f64 {
+        match op {
+            LogicalOperator::LogicalGet(get) => Self::estimate_get(get),
+            LogicalOperator::LogicalFilter(filter) => {
+                let child = Self::estimate_operator(&filter.base.children[0]);
+                child * DEFAULT_SELECTIVITY.powi(filter.base.expressioins.len() as i32)
+            }
+            LogicalOperator::LogicalLimit(limit) => {
+                let child = Self::estimate_operator(&limit.base.children[0]);
+                match limit.limit {
+                    Some(_) => child.min(limit.limit_value as f64),
+                    None => child,
+                }
+            }
+            LogicalOperator::LogicalCrossProduct(op) => op
+                .base
+                .children
+                .iter()
+                .map(Self::estimate_operator)
+                .product(),
+            LogicalOperator::LogicalExpressionGet(op) => op.expressions.len() as f64,
+            LogicalOperator::LogicalDummyScan(_) => 1.0,
+            LogicalOperator::LogicalEmptyResult(_) => 0.0,
+            other => other
+                .children()
+                .first()
+                .map(Self::estimate_operator)
+                .unwrap_or(DEFAULT_CARDINALITY),
+        }
+    }
+
+    fn estimate_get(get: &LogicalGet) -> f64 {
+        match &get.bind_data {
+            Some(FunctionData::SeqTableScanInputData(data)) => {
+                data.bind_table.stats.row_count as f64
+            }
+            Some(FunctionData::ReadCSVInputData(data)) => {
+                let row_width = CSV_BYTES_PER_COLUMN * data.schema.fields().len().max(1) as u64;
+                let bytes = data
+                    .files
+                    .iter()
+                    .map(|file| std::fs::metadata(&file.filename).map(|m| m.len()))
+                    .sum::<Result<u64, _>>();
+                match bytes {
+                    Ok(bytes) => (bytes / row_width) as f64,
+                    Err(_) => DEFAULT_CARDINALITY,
+                }
+            }
+            Some(FunctionData::ReadParquetInputData(data)) => {
+                // the footers have the row counts, the pruned partitions are already removed
+                let rows = data
+                    .files
+                    .iter()
+                    .map(|file| ReadParquet::read_row_count(&file.filename))
+                    .sum::<Result<usize, _>>();
+                match rows {
+                    Ok(rows) => rows as f64,
+                    Err(_) => DEFAULT_CARDINALITY,
+                }
+            }
+            _ => DEFAULT_CARDINALITY,
+        }
+    }
+
+    /// Estimate the fraction of rows that pass the predicate. Equality predicates use the number
+    /// of distinct values of their columns, range predicates against a constant use the column
+    /// histogram, other predicates use a default selectivity.
+    pub fn estimate_selectivity(&self, expr: &BoundExpression) -> f64 {
+        if let BoundExpression::BoundComparisonExpression(e) = expr {
+            let left = Self::as_column_ref(&e.left);
+            let right = Self::as_column_ref(&e.right);
+            if e.function.name == "eq" {
+                let distinct_count = match (left, right) {
+                    (Some(l), Some(r)) => self.distinct_count(l).max(self.distinct_count(r)),
+                    (Some(c), None) | (None, Some(c)) => self.distinct_count(c),
+                    (None, None) => return DEFAULT_SELECTIVITY,
+                };
+                return 1.0 / distinct_count.max(1.0);
+            }
+            let range = match (left, right, e.left.as_ref(), e.right.as_ref()) {
+                (Some(c), None, _, BoundExpression::BoundConstantExpression(v)) => {
+                    Some((c, &v.value, e.function.name.as_str()))
+                }
+                // flip the comparison so the column is on the left side
+                (None, Some(c), BoundExpression::BoundConstantExpression(v), _) => {
+                    let name = match e.function.name.as_str() {
+                        "lt" => "gt",
+                        "lt_eq" => "gt_eq",
+                        "gt" => "lt",
+                        "gt_eq" => "lt_eq",
+                        other => other,
+                    };
+                    Some((c, &v.value, name))
+                }
+                _ => None,
+            };
+            if let Some((column, value, name)) = range {
+                if let Some(selectivity) = self.range_selectivity(column, value, name) {
+                    return selectivity;
+                }
+            }
+        }
+        DEFAULT_SELECTIVITY
+    }
+
+    fn range_selectivity(
+        &self,
+        column: &BoundColumnRefExpression,
+        value: &ScalarValue,
+        name: &str,
+    ) -> Option<f64> {
+        let stats = self.table_statistics(column)?;
+        let column_idx = column.binding.column_idx;
+        let histogram = stats.histogram(column_idx)?;
+        let non_null = 1.0 - stats.null_fraction(column_idx);
+        let fraction = match name {
+            "lt" => histogram.fraction_less_than(value, false)?,
+            "lt_eq" => histogram.fraction_less_than(value, true)?,
+            "gt" => 1.0 - histogram.fraction_less_than(value, true)?,
+            "gt_eq" => 1.0 - histogram.fraction_less_than(value, false)?,
+            _ => return None,
+        };
+        Some(fraction * non_null)
+    }
+
+    fn as_column_ref(expr: &BoundExpression) -> Option<&BoundColumnRefExpression> {
+        match expr {
+            BoundExpression::BoundColumnRefExpression(e) => Some(e),
+            BoundExpression::BoundCastExpression(e) => Self::as_column_ref(&e.child),
+            _ => None,
+        }
+    }
+
+    /// The number of distinct values of a column. When the statistics don't know it, the column
+    /// is assumed to be unique, which is the common case for join keys.
+    fn distinct_count(&self, column: &BoundColumnRefExpression) -> f64 {
+        if let Some(count) = self
+            .table_statistics(column)
+            .and_then(|stats| stats.distinct_count(column.binding.column_idx))
+        {
+            return count as f64;
+        }
+        let table_idx = column.binding.table_idx;
+        let relation = match self.table_to_relation.get(&table_idx) {
+            Some(relation) => &self.relations[*relation],
+            None => return DEFAULT_CARDINALITY,
+        };
+        match Self::find_get(relation, table_idx) {
+            Some(get) => Self::estimate_get(get),
+            None => Self::estimate_operator(relation),
+        }
+    }
+
+    /// The statistics of the table a column is read from, if it is a base table.
+    fn table_statistics(&self, column: &BoundColumnRefExpression) -> Option<&TableStatistics> {
+        let table_idx = column.binding.table_idx;
+        let relation = &self.relations[*self.table_to_relation.get(&table_idx)?];
+        match &Self::find_get(relation, table_idx)?.bind_data {
+            Some(FunctionData::SeqTableScanInputData(data)) => Some(&data.bind_table.stats),
+            _ => None,
+        }
+    }
+
+    fn find_get(op: &LogicalOperator, table_idx: usize) -> Option<&LogicalGet> {
+        if let LogicalOperator::LogicalGet(get) = op {
+            if get.table_idx == table_idx {
+                return Some(get);
+            }
+        }
+        op.children()
+            .iter()
+            .find_map(|child| Self::find_get(child, table_idx))
+    }
+}
@@ -0,0 +1,216 @@
+mod cardinality_estimator;
+mod plan_enumerator;
+mod query_graph;
+
+use std::collections::HashMap;
+
+pub use cardinality_estimator::*;
+pub use plan_enumerator::*;
+pub use query_graph::*;
+
+use crate::planner_v2::{
+    BoundExpression, LogicalCrossProduct, LogicalFilter, LogicalOperator, LogicalOperatorBase,
+};
+
+/// The JoinOrderOptimizer reorders the cross products and the join predicates above them into
+/// the join tree with the lowest estimated cost. Each region of cross products and filters is
+/// flattened into relations and predicates, the relations are ordered by the PlanEnumerator, and
+/// every predicate is placed on the lowest node of the new tree that covers its relations.
+#[derive(Default)]
+pub struct JoinOrderOptimizer;
+
+impl JoinOrderOptimizer {
+    pub fn optimize(&mut self, mut op: LogicalOperator) -> LogicalOperator {
+        if Self::is_join_region(&op) {
+            return self.optimize_join_region(op);
+        }
+        let children = op
+            .take_children()
+            .into_iter()
+            .map(|child| self.optimize(child))
+            .collect::<Vec<_>>();
+        for child in children {
+            op.add_child(child);
+        }
+        op
+    }
+
+    fn is_join_region(op: &LogicalOperator) -> bool {
+        match op {
+            LogicalOperator::LogicalCrossProduct(_) => true,
+            LogicalOperator::LogicalFilter(filter) => {
+                Self::is_join_region(&filter.base.children[0])
+            }
+            _ => false,
+        }
+    }
+
+    fn extract_join_relations(
+        &mut self,
+        op: LogicalOperator,
+        relations: &mut Vec<LogicalOperator>,
+        filters: &mut Vec<BoundExpression>,
+    ) {
+        match op {
+            LogicalOperator::LogicalCrossProduct(op) => {
+                for child in op.base.children {
+                    self.extract_join_relations(child, relations, filters);
+                }
+            }
+            LogicalOperator::LogicalFilter(mut op)
+                if Self::is_join_region(&op.base.children[0]) =>
+            {
+                filters.extend(op.base.expressioins);
+                let child = op.base.children.remove(0);
+                self.extract_join_relations(child, relations, filters);
+            }
+            other => relations.push(self.optimize(other)),
+        }
+    }
+
+    fn optimize_join_region(&mut self, op: LogicalOperator) -> LogicalOperator {
+        let mut relations = vec![];
+        let mut filters = vec![];
+        self.extract_join_relations(op, &mut relations, &mut filters);
+        if relations.len() > MAX_RELATION_COUNT {
+            // too many relations to reorder, keep the original order
+            let root = relations
+                .into_iter()
+                .reduce(LogicalCrossProduct::create)
+                .unwrap();
+            return Self::push_filter(root, filters);
+        }
+
+        let mut table_to_relation = HashMap::new();
+        for (idx, relation) in relations.iter().enumerate() {
+            for binding in relation.get_column_bindings() {
+                table_to_relation.insert(binding.table_idx, idx);
+            }
+        }
+
+        // classify the filters by the relations they reference
+        let estimator = CardinalityEstimator::new(&relations, &table_to_relation);
+        let mut relation_cardinalities = (0..relations.len())
+            .map(|idx| estimator.estimate_relation(idx))
+            .collect::<Vec<_>>();
+        let mut relation_filters = vec![vec![]; relations.len()];
+        let mut join_filters = vec![];
+        let mut constant_filters = vec![];
+        for filter in filters {
+            let referenced = Self::referenced_relations(&filter, &table_to_relation);
+            let selectivity = estimator.estimate_selectivity(&filter);
+            match RelationSetUtil::count(referenced) {
+                0 => constant_filters.push(filter),
+                1 => {
+                    let relation = RelationSetUtil::lowest(referenced);
+                    relation_cardinalities[relation] *= selectivity;
+                    relation_filters[relation].push(filter);
+                }
+                _ => join_filters.push(FilterInfo::new(filter, referenced, selectivity)),
+            }
+        }
+
+        // push the single relation filters into the relations
+        let mut relations = relations
+            .into_iter()
+            .zip(relation_filters.into_iter())
+            .zip(relation_cardinalities.iter())
+            .map(|((relation, filters), cardinality)| {
+                let mut relation = Self::push_filter(relation, filters);
+                relation.set_estimated_cardinality(cardinality.ceil() as usize);
+                Some(relation)
+            })
+            .collect::<Vec<_>>();
+
+        let mut graph = QueryGraph::new(relations.len());
+        for filter in join_filters.iter() {
+            graph.add_predicate(filter.relations);
+        }
+        graph.connect_components();
+
+        let join_predicates = join_filters
+            .iter()
+            .map(|f| (f.relations, f.selectivity))
+            .collect::<Vec<_>>();
+        let plans = PlanEnumerator::new(&graph, relation_cardinalities, join_predicates).solve();
+
+        let root_set = RelationSetUtil::lower_or_equal(relations.len() - 1);
+        let mut join_filters = join_filters.into_iter().map(Some).collect::<Vec<_>>();
+        let root = Self::build_join_tree(root_set, &plans, &mut relations, &mut join_filters);
+        Self::push_filter(root, constant_filters)
+    }
+
+    /// Rebuild the join tree of the set, placing each join predicate on top of the lowest node
+    /// that covers its relations.
+    fn build_join_tree(
+        set: RelationSet,
+        plans: &HashMap<RelationSet, JoinNode>,
+        relations: &mut [Option<LogicalOperator>],
+        join_filters: &mut [Option<FilterInfo>],
+    ) -> LogicalOperator {
+        let node = &plans[&set];
+        let (left_set, right_set) = match node.children {
+            Some(children) => children,
+            None => return relations[RelationSetUtil::lowest(set)].take().unwrap(),
+        };
+        let left = Self::build_join_tree(left_set, plans, relations, join_filters);
+        let right = Self::build_join_tree(right_set, plans, relations, join_filters);
+        let mut root = LogicalCrossProduct::create(left, right);
+        let product = plans[&left_set].cardinality * plans[&right_set].cardinality;
+        root.set_estimated_cardinality(product.ceil() as usize);
+
+        let filters = join_filters
+            .iter_mut()
+            .filter(|f| {
+                f.as_ref()
+                    .map_or(false, |f| RelationSetUtil::is_subset(f.relations, set))
+            })
+            .map(|f| f.take().unwrap().filter)
+            .collect::<Vec<_>>();
+        if !filters.is_empty() {
+            root = Self::push_filter(root, filters);
+            root.set_estimated_cardinality(node.cardinality.ceil() as usize);
+        }
+        root
+    }
+
+    fn push_filter(op: LogicalOperator, filters: Vec<BoundExpression>) -> LogicalOperator {
+        if filters.is_empty() {
+            return op;
+        }
+        LogicalOperator::LogicalFilter(LogicalFilter::new(LogicalOperatorBase::new(
+            vec![op],
+            filters,
+            vec![],
+        )))
+    }
+
+    fn referenced_relations(
+        expr: &BoundExpression,
+        table_to_relation: &HashMap<usize, usize>,
+    ) -> RelationSet {
+        match expr {
+            BoundExpression::BoundColumnRefExpression(e) => table_to_relation
+                .get(&e.binding.table_idx)
+                .map_or(0, |r| RelationSetUtil::single(*r)),
+            BoundExpression::BoundConstantExpression(_)
+            | BoundExpression::BoundReferenceExpression(_)
+            | BoundExpression::BoundParameterExpression(_) => 0,
+            BoundExpression::BoundCastExpression(e) => {
+                Self::referenced_relations(&e.child, table_to_relation)
+            }
+            BoundExpression::BoundFunctionExpression(e) => e.children.iter().fold(0, |set, c| {
+                set | Self::referenced_relations(c, table_to_relation)
+            }),
+            BoundExpression::BoundComparisonExpression(e) => {
+                Self::referenced_relations(&e.left, table_to_relation)
+                    | Self::referenced_relations(&e.right, table_to_relation)
+            }
+            BoundExpression::BoundConjunctionExpression(e) => {
+                e.children.iter().fold(0, |set, c| {
+                    set | Self::referenced_relations(c, table_to_relation)
+                })
+            }
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+
+use derive_new::new;
+
+use super::{QueryGraph, RelationSet, RelationSetUtil};
+
+/// The maximum number of relations that are ordered with dynamic programming. Larger join graphs
+/// fall back to the greedy enumerator.
+pub const DP_RELATION_THRESHOLD: usize = 12;
+
+/// A node of the join tree, either a single relation or the join of two sets of relations.
+#[derive(new, Debug, Clone)]
+pub struct JoinNode {
+    /// The (left, right) sets of the join, None for a single relation
+    pub(crate) children: Option<(RelationSet, RelationSet)>,
+    /// The estimated number of rows produced by this node
+    pub(crate) cardinality: f64,
+    /// The estimated cost of this node, the sum of all intermediate result sizes (C_out)
+    pub(crate) cost: f64,
+}
+
+/// The PlanEnumerator finds the cheapest join tree of a query graph. Small graphs are solved with
+/// DPccp, which enumerates each connected subgraph and complement pair exactly once, larger
+/// graphs use greedy operator ordering (GOO).
+pub struct PlanEnumerator<'a> {
+    graph: &'a QueryGraph,
+    /// The estimated cardinality of each relation
+    relation_cardinalities: Vec<f64>,
+    /// The (relations, selectivity) of each predicate between multiple relations
+    join_predicates: Vec<(RelationSet, f64)>,
+    /// The best plan found for each set of relations
+    plans: HashMap<RelationSet, JoinNode>,
+}
+
+impl<'a> PlanEnumerator<'a> {
+    pub fn new(
+        graph: &'a QueryGraph,
+        relation_cardinalities: Vec<f64>,
+        join_predicates: Vec<(RelationSet, f64)>,
+    ) -> Self {
+        let mut plans = HashMap::new();
+        for (relation, cardinality) in relation_cardinalities.iter().enumerate() {
+            let set = RelationSetUtil::single(relation);
+            plans.insert(set, JoinNode::new(None, *cardinality, 0.0));
+        }
+        Self {
+            graph,
+            relation_cardinalities,
+            join_predicates,
+            plans,
+        }
+    }
+
+    /// Find the best join tree, returns the plans so the tree can be rebuilt from the root set.
+    pub fn solve(mut self) -> HashMap<RelationSet, JoinNode> {
+        if self.graph.relation_count() <= DP_RELATION_THRESHOLD {
+            self.solve_exact();
+        } else {
+            self.solve_greedy();
+        }
+        self.plans
+    }
+
+    /// The estimated cardinality of the join of a set of relations, the product of the relation
+    /// cardinalities and the selectivities of the predicates that are covered by the set.
+    pub fn cardinality(&self, set: RelationSet) -> f64 {
+        let mut cardinality: f64 = RelationSetUtil::relations(set)
+            .iter()
+            .map(|r| self.relation_cardinalities[*r])
+            .product();
+        for (relations, selectivity) in self.join_predicates.iter() {
+            if RelationSetUtil::is_subset(*relations, set) {
+                cardinality *= selectivity;
+            }
+        }
+        cardinality
+    }
+
+    fn emit_pair(&mut self, left: RelationSet, right: RelationSet) {
+        let left_plan = &self.plans[&left];
+        let right_plan = &self.plans[&right];
+        // the right side of a cross product is materialized, keep the smaller side there
+        let children = if left_plan.cardinality >= right_plan.cardinality {
+            (left, right)
+        } else {
+            (right, left)
+        };
+        let set = left | right;
+        let cardinality = self.cardinality(set);
+        let cost = cardinality + left_plan.cost + right_plan.cost;
+        let is_better = self.plans.get(&set).map_or(true, |plan| cost < plan.cost);
+        if is_better {
+            let node = JoinNode::new(Some(children), cardinality, cost);
+            self.plans.insert(set, node);
+        }
+    }
+
+    fn solve_exact(&mut self) {
+        let mut pairs = vec![];
+        self.enumerate_csg_cmp_pairs(&mut pairs);
+        // the plans of both sides must be known before a pair is emitted, so smaller sets go first
+        pairs.sort_by_key(|(left, right)| RelationSetUtil::count(left | right));
+        for (left, right) in pairs {
+            self.emit_pair(left, right);
+        }
+    }
+
+    fn enumerate_csg_cmp_pairs(&self, pairs: &mut Vec<(RelationSet, RelationSet)>) {
+        for relation in (0..self.graph.relation_count()).rev() {
+            let start = RelationSetUtil::single(relation);
+            self.emit_csg(start, pairs);
+            self.enumerate_csg_rec(start, RelationSetUtil::lower_or_equal(relation), pairs);
+        }
+    }
+
+    /// Emit all complements of the connected subgraph.
+    fn emit_csg(&self, csg: RelationSet, pairs: &mut Vec<(RelationSet, RelationSet)>) {
+        let excluded = csg | RelationSetUtil::lower_or_equal(RelationSetUtil::lowest(csg));
+        let neighbors = self.graph.neighbors(csg) & !excluded;
+        for relation in RelationSetUtil::relations(neighbors).into_iter().rev() {
+            let cmp = RelationSetUtil::single(relation);
+            pairs.push((csg, cmp));
+            let cmp_excluded = excluded | (neighbors & RelationSetUtil::lower_or_equal(relation));
+            self.enumerate_cmp_rec(csg, cmp, cmp_excluded, pairs);
+        }
+    }
+
+    /// Extend the connected subgraph with its neighbors.
+    fn enumerate_csg_rec(
+        &self,
+        csg: RelationSet,
+        excluded: RelationSet,
+        pairs: &mut Vec<(RelationSet, RelationSet)>,
+    ) {
+        let neighbors = self.graph.neighbors(csg) & !excluded;
+        let subsets = RelationSetUtil::subsets(neighbors);
+        for subset in subsets.iter() {
+            self.emit_csg(csg | subset, pairs);
+        }
+        for subset in subsets.iter() {
+            self.enumerate_csg_rec(csg | subset, excluded | neighbors, pairs);
+        }
+    }
+
+    /// Extend the complement with its neighbors.
+    fn enumerate_cmp_rec(
+        &self,
+        csg: RelationSet,
+        cmp: RelationSet,
+        excluded: RelationSet,
+        pairs: &mut Vec<(RelationSet, RelationSet)>,
+    ) {
+        let neighbors = self.graph.neighbors(cmp) & !excluded;
+        let subsets = RelationSetUtil::subsets(neighbors);
+        for subset in subsets.iter() {
+            pairs.push((csg, cmp | subset));
+        }
+        for subset in subsets.iter() {
+            self.enumerate_cmp_rec(csg, cmp | subset, excluded | neighbors, pairs);
+        }
+    }
+
+    /// Repeatedly join the two connected sets with the smallest result, until one set is left.
+    fn solve_greedy(&mut self) {
+        let mut sets = (0..self.graph.relation_count())
+            .map(RelationSetUtil::single)
+            .collect::<Vec<_>>();
+        while sets.len() > 1 {
+            let mut best: Option<(usize, usize, f64)> = None;
+            for (i, left) in sets.iter().enumerate() {
+                for (j, right) in sets.iter().enumerate().skip(i + 1) {
+                    if !self.graph.is_connected(*left, *right) {
+                        continue;
+                    }
+                    let cardinality = self.cardinality(left | right);
+                    if best.map_or(true, |(_, _, c)| cardinality < c) {
+                        best = Some((i, j, cardinality));
+                    }
+                }
+            }
+            // the graph is connected, so there is always a pair to join
+            let (i, j, _) = best.expect("join graph should be connected");
+            let right = sets.remove(j);
+            let left = sets.remove(i);
+            self.emit_pair(left, right);
+            sets.push(left | right);
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use derive_new::new;
+
+use crate::planner_v2::BoundExpression;
+
+/// A set of relations, represented as a bitmap of relation indexes.
+pub type RelationSet = u64;
+
+/// The maximum number of relations a RelationSet can hold.
+pub const MAX_RELATION_COUNT: usize = RelationSet::BITS as usize;
+
+pub struct RelationSetUtil;
+
+impl RelationSetUtil {
+    pub fn single(relation: usize) -> RelationSet {
+        1 << relation
+    }
+
+    /// The set of all relations with an index lower or equal to the given relation.
+    pub fn lower_or_equal(relation: usize) -> RelationSet {
+        if relation + 1 >= MAX_RELATION_COUNT {
+            RelationSet::MAX
+        } else {
+            (1 << (relation + 1)) - 1
+        }
+    }
+
+    pub fn is_subset(subset: RelationSet, set: RelationSet) -> bool {
+        subset & !set == 0
+    }
+
+    pub fn count(set: RelationSet) -> usize {
+        set.count_ones() as usize
+    }
+
+    pub fn lowest(set: RelationSet) -> usize {
+        set.trailing_zeros() as usize
+    }
+
+    /// The relation indexes of the set, in ascending order.
+    pub fn relations(set: RelationSet) -> Vec<usize> {
+        (0..MAX_RELATION_COUNT)
+            .filter(|idx| set & Self::single(*idx) != 0)
+            .collect()
+    }
+
+    /// All non-empty subsets of the set.
+    pub fn subsets(set: RelationSet) -> Vec<RelationSet> {
+        let mut result = vec![];
+        let mut subset = set;
+        while subset != 0 {
+            result.push(subset);
+            subset = (subset - 1) & set;
+        }
+        result
+    }
+}
+
+/// A filter of the join region, with the set of relations it references.
+#[derive(new, Debug, Clone)]
+pub struct FilterInfo {
+    pub(crate) filter: BoundExpression,
+    /// The set of relations referenced by the filter
+    pub(crate) relations: RelationSet,
+    /// The estimated fraction of rows that pass the filter
+    pub(crate) selectivity: f64,
+}
+
+/// The QueryGraph keeps track of which relations are connected by join predicates.
+#[derive(Debug)]
+pub struct QueryGraph {
+    /// The neighbors of each relation
+    neighbors: Vec<RelationSet>,
+}
+
+impl QueryGraph {
+    pub fn new(relation_count: usize) -> Self {
+        Self {
+            neighbors: vec![0; relation_count],
+        }
+    }
+
+    pub fn relation_count(&self) -> usize {
+        self.neighbors.len()
+    }
+
+    pub fn add_edge(&mut self, left: usize, right: usize) {
+        if left == right {
+            return;
+        }
+        self.neighbors[left] |= RelationSetUtil::single(right);
+        self.neighbors[right] |= RelationSetUtil::single(left);
+    }
+
+    /// Add an edge between each pair of relations referenced by a predicate. Predicates between
+    /// three or more relations are handled the same way, so the graph can be enumerated as a
+    /// simple graph.
+    pub fn add_predicate(&mut self, relations: RelationSet) {
+        let relations = RelationSetUtil::relations(relations);
+        for (idx, left) in relations.iter().enumerate() {
+            for right in relations.iter().skip(idx + 1) {
+                self.add_edge(*left, *right);
+            }
+        }
+    }
+
+    /// The relations connected to the set, excluding the set itself.
+    pub fn neighbors(&self, set: RelationSet) -> RelationSet {
+        let mut result = 0;
+        for relation in RelationSetUtil::relations(set) {
+            result |= self.neighbors[relation];
+        }
+        result & !set
+    }
+
+    pub fn is_connected(&self, left: RelationSet, right: RelationSet) -> bool {
+        self.neighbors(left) & right != 0
+    }
+
+    /// Connect the disconnected parts of the graph with cross product edges, so every relation is
+    /// reachable and the enumerator can decide where to place the cross products.
+    pub fn connect_components(&mut self) {
+        let mut components: Vec<RelationSet> = vec![];
+        let mut visited: RelationSet = 0;
+        for relation in 0..self.relation_count() {
+            if visited & RelationSetUtil::single(relation) != 0 {
+                continue;
+            }
+            let mut component = RelationSetUtil::single(relation);
+            loop {
+                let neighbors = self.neighbors(component);
+                if neighbors == 0 {
+                    break;
+                }
+                component |= neighbors;
+            }
+            visited |= component;
+            components.push(component);
+        }
+        for (idx, left) in components.iter().enumerate() {
+            for right in components.iter().skip(idx + 1) {
+                for l in RelationSetUtil::relations(*left) {
+                    for r in RelationSetUtil::relations(*right) {
+                        self.add_edge(l, r);
+                    }
+                }
+            }
+        }
+    }
+}
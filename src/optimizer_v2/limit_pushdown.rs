@@ -0,0 +1,37 @@
+use crate::function::FunctionData;
+use crate::planner_v2::{LogicalOperator, LogicalOperatorVisitor};
+
+/// The LimitPushdown bounds the number of rows read by a table scan below a limit. The limit
+/// itself is kept, the scan is only prevented from reading rows that are never returned.
+#[derive(Default)]
+pub struct LimitPushdown;
+
+impl LimitPushdown {
+    fn pushdown_into_get(op: &mut LogicalOperator, max_rows: usize) {
+        match op {
+            LogicalOperator::LogicalProjection(proj) => {
+                Self::pushdown_into_get(&mut proj.base.children[0], max_rows)
+            }
+            LogicalOperator::LogicalGet(get) => {
+                if let Some(FunctionData::ReadCSVInputData(data)) = &mut get.bind_data {
+                    data.bounds = Some((0, max_rows));
+                }
+            }
+            _ => {
+                // other operators could change the number of rows, e.g. filter
+            }
+        }
+    }
+}
+
+impl LogicalOperatorVisitor for LimitPushdown {
+    fn visit_operator(&mut self, op: &mut LogicalOperator) {
+        if let LogicalOperator::LogicalLimit(limit) = op {
+            if limit.limit.is_some() {
+                let max_rows = limit.limit_value.saturating_add(limit.offsert_value) as usize;
+                Self::pushdown_into_get(&mut limit.base.children[0], max_rows);
+            }
+        }
+        self.visit_operator_children(op);
+    }
+}
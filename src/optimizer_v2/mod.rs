@@ -0,0 +1,72 @@
+mod expression_rewriter;
+mod filter_pushdown;
+mod join_order;
+mod limit_pushdown;
+mod partition_pruning;
+mod remove_unused_columns;
+mod rule;
+mod statistics_pushdown;
+
+use std::sync::Arc;
+
+use derive_new::new;
+pub use expression_rewriter::*;
+pub use filter_pushdown::*;
+pub use join_order::*;
+pub use limit_pushdown::*;
+use log::debug;
+pub use partition_pruning::*;
+pub use remove_unused_columns::*;
+pub use rule::*;
+pub use statistics_pushdown::*;
+
+use crate::main_entry::ClientContext;
+use crate::planner_v2::{LogicalOperator, LogicalOperatorVisitor};
+use crate::util::tree_render::TreeRender;
+
+static LOGGING_TARGET: &str = "sqlrs::optimizer";
+
+/// The Optimizer runs a pipeline of rewrite passes over the bound logical plan.
+#[derive(new)]
+pub struct Optimizer {
+    pub(crate) _client_context: Arc<ClientContext>,
+}
+
+impl Optimizer {
+    pub fn optimize(&mut self, mut plan: LogicalOperator) -> LogicalOperator {
+        // first we perform expression rewrites, such as constant folding
+        let mut rewriter = ExpressionRewriter::default();
+        rewriter.visit_operator(&mut plan);
+
+        // perform filter pushdown
+        let mut filter_pushdown = FilterPushdown::default();
+        let mut plan = filter_pushdown.rewrite(plan);
+
+        // skip the files of partitioned scans which can't pass the pushed down filters
+        let mut partition_pruning = PartitionPruning::default();
+        partition_pruning.visit_operator(&mut plan);
+
+        // pass the filters into the parquet scans to skip row groups by their statistics
+        let mut statistics_pushdown = StatisticsPushdown::default();
+        statistics_pushdown.visit_operator(&mut plan);
+
+        // then reorder the joins based on the estimated cardinalities
+        let mut join_order = JoinOrderOptimizer::default();
+        let mut plan = join_order.optimize(plan);
+
+        // then we remove unused columns, and push the projection into the table scan
+        let mut remove_unused_columns = RemoveUnusedColumns::new(true);
+        remove_unused_columns.visit_operator(&mut plan);
+
+        // finally push the limit into the table scan
+        let mut limit_pushdown = LimitPushdown::default();
+        limit_pushdown.visit_operator(&mut plan);
+
+        debug!(
+            target: LOGGING_TARGET,
+            "Optimized Plan:\n{}",
+            TreeRender::logical_plan_tree(&plan),
+        );
+        plan
+    }
+}
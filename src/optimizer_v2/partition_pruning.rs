@@ -0,0 +1,120 @@
+use std::cell::Cell;
+
+use crate::execution::ExpressionExecutor;
+use crate::function::FunctionData;
+use crate::planner_v2::{
+    BoundConstantExpression, BoundExpression, BoundExpressionBase, ExpressionIterator, LogicalGet,
+    LogicalOperator, LogicalOperatorVisitor,
+};
+use crate::types_v2::ScalarValue;
+
+/// The PartitionPruning removes the files of a hive partitioned scan whose partition values can't
+/// pass the filters on the partition columns, so these files are never opened. It runs after the
+/// filter pushdown, which leaves the filters right above the scans.
+#[derive(Default)]
+pub struct PartitionPruning;
+
+impl PartitionPruning {
+    fn prune_files(get: &mut LogicalGet, filters: &[BoundExpression]) {
+        let table_idx = get.table_idx;
+        let may_pass = |first_column: usize, values: &[ScalarValue]| {
+            filters
+                .iter()
+                .all(|filter| Self::may_pass(filter, table_idx, first_column, values))
+        };
+        match &mut get.bind_data {
+            Some(FunctionData::ReadCSVInputData(data)) if data.partition_columns > 0 => {
+                let first_column = data.schema.fields().len()
+                    - data.partition_columns
+                    - data.filename_column as usize;
+                data.files
+                    .retain(|file| may_pass(first_column, &file.partition_values));
+            }
+            Some(FunctionData::ReadParquetInputData(data)) if data.partition_columns > 0 => {
+                let first_column = data.file_columns();
+                data.files
+                    .retain(|file| may_pass(first_column, &file.partition_values));
+            }
+            Some(FunctionData::ReadJSONInputData(data)) if data.partition_columns > 0 => {
+                let first_column = data.file_columns();
+                data.files
+                    .retain(|file| may_pass(first_column, &file.partition_values));
+            }
+            Some(FunctionData::ReadArrowInputData(data)) if data.partition_columns > 0 => {
+                let first_column = data.file_columns();
+                data.files
+                    .retain(|file| may_pass(first_column, &file.partition_values));
+            }
+            _ => {}
+        }
+    }
+
+    /// Evaluate the filter with the partition values of a file, the filters referencing other
+    /// columns or failing to evaluate can't prune the file.
+    fn may_pass(
+        filter: &BoundExpression,
+        table_idx: usize,
+        first_column: usize,
+        values: &[ScalarValue],
+    ) -> bool {
+        let mut expr = filter.clone();
+        Self::replace_partition_columns(&mut expr, table_idx, first_column, values);
+        if Self::has_column_refs(&mut expr) {
+            return true;
+        }
+        !matches!(
+            ExpressionExecutor::execute_scalar(&expr),
+            Ok(ScalarValue::Boolean(Some(false))) | Ok(ScalarValue::Boolean(None))
+        )
+    }
+
+    fn replace_partition_columns(
+        expr: &mut BoundExpression,
+        table_idx: usize,
+        first_column: usize,
+        values: &[ScalarValue],
+    ) {
+        if let BoundExpression::BoundColumnRefExpression(e) = expr {
+            let column_idx = e.binding.column_idx;
+            if e.binding.table_idx == table_idx
+                && column_idx >= first_column
+                && column_idx < first_column + values.len()
+            {
+                let base =
+                    BoundExpressionBase::new(e.base.alias.clone(), e.base.return_type.clone());
+                let value = values[column_idx - first_column].clone();
+                *expr = BoundExpression::BoundConstantExpression(BoundConstantExpression::new(
+                    base, value,
+                ));
+            }
+            return;
+        }
+        ExpressionIterator::enumerate_children(expr, |child| {
+            Self::replace_partition_columns(child, table_idx, first_column, values)
+        });
+    }
+
+    fn has_column_refs(expr: &mut BoundExpression) -> bool {
+        if let BoundExpression::BoundColumnRefExpression(_) = expr {
+            return true;
+        }
+        let found = Cell::new(false);
+        ExpressionIterator::enumerate_children(expr, |child| {
+            if Self::has_column_refs(child) {
+                found.set(true);
+            }
+        });
+        found.get()
+    }
+}
+
+impl LogicalOperatorVisitor for PartitionPruning {
+    fn visit_operator(&mut self, op: &mut LogicalOperator) {
+        if let LogicalOperator::LogicalFilter(filter) = op {
+            if let LogicalOperator::LogicalGet(get) = &mut filter.base.children[0] {
+                Self::prune_files(get, &filter.base.expressioins);
+            }
+        }
+        self.visit_operator_children(op);
+    }
+}
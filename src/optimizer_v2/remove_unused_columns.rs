@@ -0,0 +1,119 @@
+use std::collections::HashSet;
+
+use crate::function::FunctionData;
+use crate::planner_v2::{
+    BoundExpression, ColumnBinding, LogicalGet, LogicalOperator, LogicalOperatorVisitor,
+};
+
+/// The RemoveUnusedColumns removes the columns that are never referenced by the operators above a
+/// table scan, and pushes the remaining columns into the scan.
+pub struct RemoveUnusedColumns {
+    /// Whether or not all the columns of the current operator are referenced by its parent
+    everything_referenced: bool,
+    /// The set of column references
+    column_references: HashSet<ColumnBinding>,
+}
+
+impl RemoveUnusedColumns {
+    pub fn new(is_root: bool) -> Self {
+        Self {
+            everything_referenced: is_root,
+            column_references: HashSet::new(),
+        }
+    }
+
+    fn collect_column_references(&mut self, expr: &BoundExpression) {
+        match expr {
+            BoundExpression::BoundColumnRefExpression(e) => {
+                self.column_references.insert(e.binding.clone());
+            }
+            BoundExpression::BoundConstantExpression(_)
+            | BoundExpression::BoundReferenceExpression(_)
+            | BoundExpression::BoundParameterExpression(_) => {}
+            BoundExpression::BoundCastExpression(e) => self.collect_column_references(&e.child),
+            BoundExpression::BoundFunctionExpression(e) => e
+                .children
+                .iter()
+                .for_each(|c| self.collect_column_references(c)),
+            BoundExpression::BoundComparisonExpression(e) => {
+                self.collect_column_references(&e.left);
+                self.collect_column_references(&e.right);
+            }
+            BoundExpression::BoundConjunctionExpression(e) => e
+                .children
+                .iter()
+                .for_each(|c| self.collect_column_references(c)),
+        }
+    }
+
+    fn remove_unused_get_columns(&self, get: &mut LogicalGet) {
+        if self.everything_referenced {
+            return;
+        }
+        let mut column_ids = self
+            .column_references
+            .iter()
+            .filter(|binding| binding.table_idx == get.table_idx)
+            .map(|binding| binding.column_idx)
+            .collect::<Vec<_>>();
+        column_ids.sort_unstable();
+        if column_ids.is_empty() {
+            // no column is referenced, but we still need one column to know the number of rows
+            column_ids.push(0);
+        }
+        if get.function.projection_pushdown {
+            match &mut get.bind_data {
+                Some(FunctionData::ReadCSVInputData(data)) => {
+                    data.projection = Some(column_ids.clone())
+                }
+                Some(FunctionData::ReadParquetInputData(data)) => {
+                    data.projection = Some(column_ids.clone())
+                }
+                Some(FunctionData::ReadJSONInputData(data)) => {
+                    data.projection = Some(column_ids.clone())
+                }
+                Some(FunctionData::ReadArrowInputData(data)) => {
+                    data.projection = Some(column_ids.clone())
+                }
+                _ => {}
+            }
+        }
+        get.column_ids = column_ids;
+    }
+}
+
+impl LogicalOperatorVisitor for RemoveUnusedColumns {
+    fn visit_operator(&mut self, op: &mut LogicalOperator) {
+        match op {
+            LogicalOperator::LogicalProjection(proj) => {
+                // the projection only needs the columns referenced by its expressions
+                let mut remove = RemoveUnusedColumns::new(false);
+                proj.base
+                    .expressioins
+                    .iter()
+                    .for_each(|e| remove.collect_column_references(e));
+                remove.visit_operator_children(op);
+            }
+            LogicalOperator::LogicalFilter(filter) => {
+                // the filter passes through all the columns of its child
+                filter
+                    .base
+                    .expressioins
+                    .iter()
+                    .for_each(|e| self.collect_column_references(e));
+                self.visit_operator_children(op);
+            }
+            LogicalOperator::LogicalLimit(_) => self.visit_operator_children(op),
+            LogicalOperator::LogicalCrossProduct(_) => {
+                // the cross product passes through all the columns of its children
+                self.visit_operator_children(op)
+            }
+            LogicalOperator::LogicalGet(get) => self.remove_unused_get_columns(get),
+            _ => {
+                // the other operators need all the columns of their children
+                let mut remove = RemoveUnusedColumns::new(true);
+                remove.visit_operator_children(op);
+            }
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use super::{ExpressionRule, ExpressionRuleImpl, RuleUtil};
+use crate::function::ConjunctionType;
+use crate::planner_v2::{BoundConjunctionExpression, BoundExpression};
+
+/// Simplify the boolean identities and double negations:
+///
+/// - `x AND true` -> `x`, `x AND false` -> `false`
+/// - `x OR false` -> `x`, `x OR true` -> `true`
+/// - `NOT NOT x` -> `x`
+#[derive(Clone)]
+pub struct BooleanSimplificationRule;
+
+impl BooleanSimplificationRule {
+    pub fn create() -> ExpressionRuleImpl {
+        Self {}.into()
+    }
+
+    fn simplify_conjunction(expr: &BoundConjunctionExpression) -> Option<BoundExpression> {
+        // the identity value can be removed, the absorbing value decides the result
+        let (identity, absorbing) = match expr.function.ty {
+            ConjunctionType::And => (true, false),
+            ConjunctionType::Or => (false, true),
+        };
+        let mut children = vec![];
+        for child in expr.children.iter() {
+            match RuleUtil::as_boolean_constant(child) {
+                Some(Some(v)) if v == absorbing => {
+                    return Some(RuleUtil::new_boolean_constant(
+                        Some(absorbing),
+                        expr.base.alias.clone(),
+                    ))
+                }
+                Some(Some(v)) if v == identity => continue,
+                _ => children.push(child.clone()),
+            }
+        }
+        if children.len() == expr.children.len() {
+            return None;
+        }
+        match children.len() {
+            0 => Some(RuleUtil::new_boolean_constant(
+                Some(identity),
+                expr.base.alias.clone(),
+            )),
+            1 => Some(children.remove(0)),
+            _ => Some(BoundExpression::BoundConjunctionExpression(
+                BoundConjunctionExpression::new(expr.base.clone(), expr.function.clone(), children),
+            )),
+        }
+    }
+}
+
+impl ExpressionRule for BooleanSimplificationRule {
+    fn apply(&self, expr: &BoundExpression) -> Option<BoundExpression> {
+        match expr {
+            BoundExpression::BoundConjunctionExpression(e) => Self::simplify_conjunction(e),
+            BoundExpression::BoundFunctionExpression(e) if e.function.name == "not" => {
+                match &e.children[0] {
+                    BoundExpression::BoundFunctionExpression(child)
+                        if child.function.name == "not" =>
+                    {
+                        Some(child.children[0].clone())
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use super::{ExpressionRule, ExpressionRuleImpl};
+use crate::planner_v2::BoundExpression;
+
+/// Remove the cast whose child already has the target type.
+#[derive(Clone)]
+pub struct CastSimplificationRule;
+
+impl CastSimplificationRule {
+    pub fn create() -> ExpressionRuleImpl {
+        Self {}.into()
+    }
+}
+
+impl ExpressionRule for CastSimplificationRule {
+    fn apply(&self, expr: &BoundExpression) -> Option<BoundExpression> {
+        match expr {
+            BoundExpression::BoundCastExpression(e)
+                if e.child.return_type() == e.base.return_type =>
+            {
+                Some(*e.child.clone())
+            }
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use sqlparser::ast::BinaryOperator;
+
+use super::{ExpressionRule, ExpressionRuleImpl, RuleUtil};
+use crate::function::{DefaultConjunctionFunctions, IsNotNullFunction, IsNullFunction};
+use crate::planner_v2::{
+    BoundConjunctionExpression, BoundExpression, BoundExpressionBase, BoundFunctionExpression,
+};
+use crate::types_v2::LogicalType;
+
+/// Simplify the comparisons whose result only depends on the nullness of the input:
+///
+/// - `x = NULL` -> `NULL`
+/// - `x = x`, `x <= x`, `x >= x` -> `x IS NOT NULL OR NULL`
+/// - `x <> x`, `x < x`, `x > x` -> `x IS NULL AND NULL`
+#[derive(Clone)]
+pub struct ComparisonSimplificationRule;
+
+impl ComparisonSimplificationRule {
+    pub fn create() -> ExpressionRuleImpl {
+        Self {}.into()
+    }
+
+    fn build_null_check(
+        expr: BoundExpression,
+        is_null: bool,
+        alias: String,
+    ) -> Option<BoundExpression> {
+        let ty = expr.return_type();
+        let (function, op) = if is_null {
+            (IsNullFunction::get_function(ty), BinaryOperator::And)
+        } else {
+            (IsNotNullFunction::get_function(ty), BinaryOperator::Or)
+        };
+        let null_check = BoundExpression::BoundFunctionExpression(BoundFunctionExpression::new(
+            BoundExpressionBase::new("".to_string(), LogicalType::Boolean),
+            function,
+            vec![expr],
+        ));
+        let conjunction_func = DefaultConjunctionFunctions::get_conjunction_function(&op).ok()?;
+        Some(BoundExpression::BoundConjunctionExpression(
+            BoundConjunctionExpression::new(
+                BoundExpressionBase::new(alias, LogicalType::Boolean),
+                conjunction_func,
+                vec![
+                    null_check,
+                    RuleUtil::new_boolean_constant(None, "".to_string()),
+                ],
+            ),
+        ))
+    }
+}
+
+impl ExpressionRule for ComparisonSimplificationRule {
+    fn apply(&self, expr: &BoundExpression) -> Option<BoundExpression> {
+        let e = match expr {
+            BoundExpression::BoundComparisonExpression(e) => e,
+            _ => return None,
+        };
+        if RuleUtil::is_null_constant(&e.left) || RuleUtil::is_null_constant(&e.right) {
+            // comparison with NULL always returns NULL
+            return Some(RuleUtil::new_boolean_constant(None, e.base.alias.clone()));
+        }
+        match (e.left.as_ref(), e.right.as_ref()) {
+            (
+                BoundExpression::BoundColumnRefExpression(l),
+                BoundExpression::BoundColumnRefExpression(r),
+            ) if l.binding == r.binding => {
+                let is_null = match e.function.name.as_str() {
+                    "eq" | "lt_eq" | "gt_eq" => false,
+                    "neq" | "lt" | "gt" => true,
+                    _ => return None,
+                };
+                Self::build_null_check(*e.left.clone(), is_null, e.base.alias.clone())
+            }
+            _ => None,
+        }
+    }
+}
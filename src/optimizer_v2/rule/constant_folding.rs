@@ -0,0 +1,45 @@
+use super::{ExpressionRule, ExpressionRuleImpl};
+use crate::execution::ExpressionExecutor;
+use crate::planner_v2::{BoundConstantExpression, BoundExpression, BoundExpressionBase};
+
+/// Fold the expression whose leaves are all constants into a single constant, such as `1 + 2`.
+#[derive(Clone)]
+pub struct ConstantFoldingRule;
+
+impl ConstantFoldingRule {
+    pub fn create() -> ExpressionRuleImpl {
+        Self {}.into()
+    }
+
+    fn is_foldable(expr: &BoundExpression) -> bool {
+        match expr {
+            BoundExpression::BoundColumnRefExpression(_)
+            | BoundExpression::BoundReferenceExpression(_)
+            | BoundExpression::BoundParameterExpression(_) => false,
+            BoundExpression::BoundConstantExpression(_) => true,
+            BoundExpression::BoundCastExpression(e) => Self::is_foldable(&e.child),
+            BoundExpression::BoundFunctionExpression(e) => e.children.iter().all(Self::is_foldable),
+            BoundExpression::BoundComparisonExpression(e) => {
+                Self::is_foldable(&e.left) && Self::is_foldable(&e.right)
+            }
+            BoundExpression::BoundConjunctionExpression(e) => {
+                e.children.iter().all(Self::is_foldable)
+            }
+        }
+    }
+}
+
+impl ExpressionRule for ConstantFoldingRule {
+    fn apply(&self, expr: &BoundExpression) -> Option<BoundExpression> {
+        if matches!(expr, BoundExpression::BoundConstantExpression(_)) || !Self::is_foldable(expr) {
+            return None;
+        }
+        // if the evaluation fails (e.g. overflow), keep the expression and report the error at
+        // execution time
+        let value = ExpressionExecutor::execute_scalar(expr).ok()?;
+        let base = BoundExpressionBase::new(expr.alias(), expr.return_type());
+        Some(BoundExpression::BoundConstantExpression(
+            BoundConstantExpression::new(base, value),
+        ))
+    }
+}
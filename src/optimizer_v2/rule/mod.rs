@@ -0,0 +1,68 @@
+mod boolean_simplification;
+mod cast_simplification;
+mod comparison_simplification;
+mod constant_folding;
+
+use std::fmt::Debug;
+
+pub use boolean_simplification::*;
+pub use cast_simplification::*;
+pub use comparison_simplification::*;
+pub use constant_folding::*;
+use enum_dispatch::enum_dispatch;
+use strum_macros::AsRefStr;
+
+use crate::planner_v2::{BoundConstantExpression, BoundExpression, BoundExpressionBase};
+use crate::types_v2::{LogicalType, ScalarValue};
+
+/// An expression rule rewrites a bound expression into a simpler but equivalent expression.
+#[enum_dispatch]
+pub trait ExpressionRule {
+    /// Apply the rule to the expression, returns None if the rule can not be applied.
+    fn apply(&self, expr: &BoundExpression) -> Option<BoundExpression>;
+}
+
+#[enum_dispatch(ExpressionRule)]
+#[derive(Clone, AsRefStr)]
+pub enum ExpressionRuleImpl {
+    CastSimplificationRule,
+    ConstantFoldingRule,
+    ComparisonSimplificationRule,
+    BooleanSimplificationRule,
+}
+
+impl Debug for ExpressionRuleImpl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_ref())
+    }
+}
+
+pub struct RuleUtil;
+
+impl RuleUtil {
+    pub fn new_boolean_constant(value: Option<bool>, alias: String) -> BoundExpression {
+        BoundExpression::BoundConstantExpression(BoundConstantExpression::new(
+            BoundExpressionBase::new(alias, LogicalType::Boolean),
+            ScalarValue::Boolean(value),
+        ))
+    }
+
+    /// Returns the boolean value if the expression is a boolean (or NULL) constant.
+    pub fn as_boolean_constant(expr: &BoundExpression) -> Option<Option<bool>> {
+        match expr {
+            BoundExpression::BoundConstantExpression(e) => match &e.value {
+                ScalarValue::Boolean(v) => Some(*v),
+                ScalarValue::Null => Some(None),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    pub fn is_null_constant(expr: &BoundExpression) -> bool {
+        match expr {
+            BoundExpression::BoundConstantExpression(e) => e.value.is_null(),
+            _ => false,
+        }
+    }
+}
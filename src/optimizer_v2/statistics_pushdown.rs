@@ -0,0 +1,91 @@
+use crate::execution::ExpressionExecutor;
+use crate::function::{ColumnPredicate, ComparisonOp, FunctionData};
+use crate::planner_v2::{BoundExpression, LogicalGet, LogicalOperator, LogicalOperatorVisitor};
+
+/// The StatisticsPushdown passes the comparisons between a column and a constant in the filters
+/// right above a parquet scan into the scan, which skips the row groups whose min/max
+/// statistics can't pass them. The filters are kept, the row groups that are read still need
+/// them.
+#[derive(Default)]
+pub struct StatisticsPushdown;
+
+impl StatisticsPushdown {
+    /// The column index of a column reference, a column cast to another numeric type keeps the
+    /// order of its values so it can be compared with the statistics too.
+    fn column_idx(expr: &BoundExpression, table_idx: usize) -> Option<usize> {
+        match expr {
+            BoundExpression::BoundColumnRefExpression(e) if e.binding.table_idx == table_idx => {
+                Some(e.binding.column_idx)
+            }
+            BoundExpression::BoundCastExpression(e)
+                if e.base.return_type.is_numeric() && e.child.return_type().is_numeric() =>
+            {
+                Self::column_idx(&e.child, table_idx)
+            }
+            _ => None,
+        }
+    }
+
+    fn to_predicate(
+        filter: &BoundExpression,
+        table_idx: usize,
+        file_columns: usize,
+    ) -> Option<ColumnPredicate> {
+        let e = match filter {
+            BoundExpression::BoundComparisonExpression(e) => e,
+            _ => return None,
+        };
+        let op = ComparisonOp::from_function_name(&e.function.name)?;
+        let (column_idx, op, constant) = match (
+            Self::column_idx(&e.left, table_idx),
+            Self::column_idx(&e.right, table_idx),
+        ) {
+            (Some(idx), None) => (idx, op, &e.right),
+            (None, Some(idx)) => (idx, op.flip(), &e.left),
+            _ => return None,
+        };
+        if column_idx >= file_columns {
+            // the partition columns are pruned by the PartitionPruning
+            return None;
+        }
+        // the constant folding can leave casts of constants
+        if !Self::is_constant(constant) {
+            return None;
+        }
+        match ExpressionExecutor::execute_scalar(constant) {
+            Ok(value) if !value.is_null() => Some(ColumnPredicate::new(column_idx, op, value)),
+            _ => None,
+        }
+    }
+
+    fn is_constant(expr: &BoundExpression) -> bool {
+        match expr {
+            BoundExpression::BoundConstantExpression(_) => true,
+            BoundExpression::BoundCastExpression(e) => Self::is_constant(&e.child),
+            _ => false,
+        }
+    }
+
+    fn pushdown_into_get(get: &mut LogicalGet, filters: &[BoundExpression]) {
+        let table_idx = get.table_idx;
+        if let Some(FunctionData::ReadParquetInputData(data)) = &mut get.bind_data {
+            let file_columns = data.file_columns();
+            data.predicates.extend(
+                filters
+                    .iter()
+                    .filter_map(|f| Self::to_predicate(f, table_idx, file_columns)),
+            );
+        }
+    }
+}
+
+impl LogicalOperatorVisitor for StatisticsPushdown {
+    fn visit_operator(&mut self, op: &mut LogicalOperator) {
+        if let LogicalOperator::LogicalFilter(filter) = op {
+            if let LogicalOperator::LogicalGet(get) = &mut filter.base.children[0] {
+                Self::pushdown_into_get(get, &filter.base.expressioins);
+            }
+        }
+        self.visit_operator_children(op);
+    }
+}
@@ -1,6 +1,34 @@
-use sqlparser::ast::{Query, Statement};
-use sqlparser::dialect::PostgreSqlDialect;
+use std::ops::Range;
+
+use sqlparser::ast::{Ident, ObjectName, Query, Statement};
+use sqlparser::dialect::{Dialect, PostgreSqlDialect};
+use sqlparser::keywords::Keyword;
 use sqlparser::parser::{Parser, ParserError};
+use sqlparser::tokenizer::{Token, Tokenizer, Whitespace};
+
+/// The placeholder table name of a bare `ANALYZE`, which analyzes all tables.
+const ANALYZE_ALL_TABLES: &str = "*";
+/// The COPY option sqlparser parses in place of the options it doesn't know, like
+/// `PARTITION_BY (a, b)` or `COMPRESSION zstd`. The first column of the option is the quoted
+/// name of the replaced option, the others are its values. It's only valid for COPY FROM CSV
+/// so it can't be confused with a real option of COPY TO.
+pub const COPY_EXTRA_OPTION: &str = "FORCE_NOT_NULL";
+/// The COPY options sqlparser knows.
+const COPY_OPTION_KEYWORDS: [Keyword; 11] = [
+    Keyword::FORMAT,
+    Keyword::FREEZE,
+    Keyword::DELIMITER,
+    Keyword::NULL,
+    Keyword::HEADER,
+    Keyword::QUOTE,
+    Keyword::ESCAPE,
+    Keyword::FORCE_QUOTE,
+    Keyword::FORCE_NOT_NULL,
+    Keyword::FORCE_NULL,
+    Keyword::ENCODING,
+];
+/// The placeholder table names of the queries of `COPY (query) TO`, followed by their indices.
+const COPY_QUERY_PLACEHOLDER: &str = "__sqlrs_copy_query_";
 
 pub fn parse(sql: &str) -> Result<Vec<Statement>, ParserError> {
     let dialect = PostgreSqlDialect {};
@@ -16,8 +44,317 @@ pub struct Sqlparser {}
 impl Sqlparser {
     pub fn parse(sql: String) -> Result<Vec<Statement>, ParserError> {
         let dialect = PostgreSqlDialect {};
+        let sql = Self::rewrite_analyze(&dialect, sql)?;
+        let (sql, copy_queries) = Self::rewrite_copy_query(&dialect, sql)?;
+        let sql = Self::rewrite_copy_options(&dialect, sql)?;
         let stmts = Parser::parse_sql(&dialect, sql.as_str())?;
-        Ok(stmts)
+        Ok(stmts
+            .into_iter()
+            .map(Self::resolve_analyze)
+            .map(|stmt| Self::resolve_copy_query(stmt, &copy_queries))
+            .collect())
+    }
+
+    /// sqlparser only supports `ANALYZE TABLE t`, so `ANALYZE t` and `ANALYZE` are rewritten
+    /// into that form. A bare `ANALYZE` analyzes all tables, it gets a placeholder table name.
+    fn rewrite_analyze(dialect: &dyn Dialect, sql: String) -> Result<String, ParserError> {
+        let tokens = Self::tokenize_with_spans(dialect, &sql)?;
+        let mut edits = vec![];
+        let mut statement_start = true;
+        let mut iter = tokens.iter().peekable();
+        while let Some((token, span)) = iter.next() {
+            match token {
+                Token::SemiColon => {
+                    statement_start = true;
+                    continue;
+                }
+                Token::Word(w) if statement_start && w.keyword == Keyword::ANALYZE => {
+                    match iter.peek().copied() {
+                        Some((Token::Word(w), _)) if w.keyword == Keyword::TABLE => {}
+                        None | Some((Token::SemiColon, _)) => {
+                            let table = Self::quote_ident(ANALYZE_ALL_TABLES);
+                            edits.push((span.end..span.end, format!(" TABLE {}", table)));
+                        }
+                        Some(_) => edits.push((span.end..span.end, " TABLE".to_string())),
+                    }
+                }
+                _ => {}
+            }
+            statement_start = false;
+        }
+        Ok(Self::apply_edits(&sql, edits))
+    }
+
+    /// sqlparser only supports a table in COPY, so the query of `COPY (query) TO` is replaced by
+    /// a placeholder table name, and returned as it's written to be put back after the parsing.
+    fn rewrite_copy_query(
+        dialect: &dyn Dialect,
+        sql: String,
+    ) -> Result<(String, Vec<String>), ParserError> {
+        let tokens = Self::tokenize_with_spans(dialect, &sql)?;
+        let mut queries = vec![];
+        let mut edits = vec![];
+        let mut statement_start = true;
+        let mut iter = tokens.iter().peekable();
+        while let Some((token, _)) = iter.next() {
+            match token {
+                Token::SemiColon => {
+                    statement_start = true;
+                    continue;
+                }
+                Token::Word(w) if statement_start && w.keyword == Keyword::COPY => {
+                    if let Some((Token::LParen, open)) = iter.peek().copied() {
+                        iter.next();
+                        let mut depth = 1;
+                        let close = loop {
+                            match iter.next() {
+                                Some((Token::LParen, _)) => depth += 1,
+                                Some((Token::RParen, span)) => {
+                                    depth -= 1;
+                                    if depth == 0 {
+                                        break span;
+                                    }
+                                }
+                                Some(_) => {}
+                                None => {
+                                    return Err(ParserError::ParserError(
+                                        "expected ) after the query of COPY".to_string(),
+                                    ))
+                                }
+                            }
+                        };
+                        let placeholder = format!("{}{}", COPY_QUERY_PLACEHOLDER, queries.len());
+                        queries.push(sql[open.end..close.start].to_string());
+                        edits.push((open.start..close.end, Self::quote_ident(&placeholder)));
+                    }
+                }
+                _ => {}
+            }
+            statement_start = false;
+        }
+        Ok((Self::apply_edits(&sql, edits), queries))
+    }
+
+    /// sqlparser only knows the COPY options of postgres, the other options like
+    /// `PARTITION_BY (cols)` are rewritten into `FORCE_NOT_NULL ("partition_by", cols)`, which
+    /// the binder reads back.
+    fn rewrite_copy_options(dialect: &dyn Dialect, sql: String) -> Result<String, ParserError> {
+        let tokens = Self::tokenize_with_spans(dialect, &sql)?;
+        let mut edits = vec![];
+        let mut in_copy = false;
+        let mut statement_start = true;
+        // the options follow the target of TO or FROM, the option names follow a `(` or `,` at
+        // the depth of the options
+        let mut after_target = false;
+        let mut depth = 0;
+        let mut option_start = false;
+        let mut iter = tokens.iter().peekable();
+        while let Some((token, span)) = iter.next() {
+            match token {
+                Token::SemiColon => {
+                    in_copy = false;
+                    after_target = false;
+                    depth = 0;
+                    statement_start = true;
+                    continue;
+                }
+                Token::Word(w) if statement_start => in_copy = w.keyword == Keyword::COPY,
+                Token::Word(w)
+                    if in_copy
+                        && depth == 0
+                        && (w.keyword == Keyword::TO || w.keyword == Keyword::FROM) =>
+                {
+                    after_target = true
+                }
+                Token::LParen => depth += 1,
+                Token::RParen => depth -= 1,
+                Token::Word(w)
+                    if in_copy
+                        && after_target
+                        && option_start
+                        && w.quote_style.is_none()
+                        && !COPY_OPTION_KEYWORDS.contains(&w.keyword) =>
+                {
+                    let mut values = vec![Self::quote_ident(&w.value.to_lowercase())];
+                    let mut end = span.end;
+                    let list = matches!(iter.peek().copied(), Some((Token::LParen, _)));
+                    if list {
+                        iter.next();
+                    }
+                    while let Some((next, next_span)) = iter.peek().copied() {
+                        match next {
+                            Token::RParen if list => {
+                                end = next_span.end;
+                                iter.next();
+                                break;
+                            }
+                            Token::RParen | Token::Comma if !list => break,
+                            Token::Comma => {}
+                            Token::Word(v) => values.push(Self::quote_ident(&v.value)),
+                            Token::SingleQuotedString(v) | Token::Number(v, _) => {
+                                values.push(Self::quote_ident(v))
+                            }
+                            other => {
+                                return Err(ParserError::ParserError(format!(
+                                    "unexpected {} in the value of COPY option {}",
+                                    other, w.value
+                                )))
+                            }
+                        }
+                        end = next_span.end;
+                        iter.next();
+                    }
+                    let option = format!("{} ({})", COPY_EXTRA_OPTION, values.join(", "));
+                    edits.push((span.start..end, option));
+                    option_start = false;
+                    continue;
+                }
+                _ => {}
+            }
+            statement_start = false;
+            option_start = in_copy
+                && after_target
+                && depth == 1
+                && matches!(token, Token::LParen | Token::Comma);
+        }
+        Ok(Self::apply_edits(&sql, edits))
+    }
+
+    /// The tokens of the sql with their byte ranges in the text, without the whitespaces and
+    /// the comments. The rewrites replace the ranges of the tokens they change, so the rest of
+    /// the sql is kept as it's written.
+    fn tokenize_with_spans(
+        dialect: &dyn Dialect,
+        sql: &str,
+    ) -> Result<Vec<(Token, Range<usize>)>, ParserError> {
+        let tokens = Tokenizer::new(dialect, sql).tokenize()?;
+        let mut spans = vec![];
+        let mut pos = 0;
+        for token in tokens {
+            if token == Token::EOF {
+                break;
+            }
+            let len = Self::token_len(&token, &sql[pos..]).ok_or_else(|| {
+                ParserError::ParserError(format!("can't find the token {} in the sql", token))
+            })?;
+            if !matches!(token, Token::Whitespace(_)) {
+                spans.push((token, pos..pos + len));
+            }
+            pos += len;
+        }
+        Ok(spans)
+    }
+
+    /// The length of the token at the start of the text. The tokens are written as they are
+    /// displayed, except the quoted ones whose escaped quotes are unescaped by the tokenizer,
+    /// and the newlines which can be `\r\n`.
+    fn token_len(token: &Token, text: &str) -> Option<usize> {
+        match token {
+            Token::SingleQuotedString(_)
+            | Token::NationalStringLiteral(_)
+            | Token::EscapedStringLiteral(_)
+            | Token::HexStringLiteral(_) => Self::quoted_len(text),
+            Token::Word(w) if w.quote_style.is_some() => Self::quoted_len(text),
+            Token::Whitespace(Whitespace::Newline) if text.starts_with("\r\n") => Some(2),
+            Token::Whitespace(Whitespace::Newline) if text.starts_with('\r') => Some(1),
+            _ => {
+                let display = token.to_string();
+                text.starts_with(&display).then_some(display.len())
+            }
+        }
+    }
+
+    /// The length of the quoted string or identifier at the start of the text, with the prefix
+    /// of its kind like `E'...'`. A doubled quote is an escaped quote, and a backslash escapes
+    /// the next character in an escaped string.
+    fn quoted_len(text: &str) -> Option<usize> {
+        let bytes = text.as_bytes();
+        let start = bytes
+            .iter()
+            .take(2)
+            .position(|b| matches!(b, b'\'' | b'"' | b'`' | b'['))?;
+        let backslash_escapes = start == 1 && bytes[0].eq_ignore_ascii_case(&b'e');
+        let close = if bytes[start] == b'[' {
+            b']'
+        } else {
+            bytes[start]
+        };
+        let mut idx = start + 1;
+        while idx < bytes.len() {
+            if backslash_escapes && bytes[idx] == b'\\' {
+                idx += 2;
+            } else if bytes[idx] == close && bytes.get(idx + 1) == Some(&close) {
+                idx += 2;
+            } else if bytes[idx] == close {
+                return Some(idx + 1);
+            } else {
+                idx += 1;
+            }
+        }
+        None
+    }
+
+    /// Replace the ranges of the sql by their texts, the ranges are in order and don't overlap.
+    fn apply_edits(sql: &str, edits: Vec<(Range<usize>, String)>) -> String {
+        let mut result = String::with_capacity(sql.len());
+        let mut pos = 0;
+        for (range, text) in edits {
+            result.push_str(&sql[pos..range.start]);
+            result.push_str(&text);
+            pos = range.end;
+        }
+        result.push_str(&sql[pos..]);
+        result
+    }
+
+    /// A double quoted identifier of the value, its double quotes are doubled.
+    fn quote_ident(value: &str) -> String {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    }
+
+    /// Replace the placeholder table name of a bare `ANALYZE` by an empty name.
+    fn resolve_analyze(mut stmt: Statement) -> Statement {
+        if let Statement::Analyze { table_name, .. } = &mut stmt {
+            if table_name.0 == vec![Ident::with_quote('"', ANALYZE_ALL_TABLES)] {
+                *table_name = ObjectName(vec![]);
+            }
+        }
+        stmt
+    }
+
+    /// Replace the placeholder table name of `COPY (query) TO` by an empty name, the query is
+    /// the only value of the COPY.
+    fn resolve_copy_query(mut stmt: Statement, queries: &[String]) -> Statement {
+        if let Statement::Copy {
+            table_name, values, ..
+        } = &mut stmt
+        {
+            if let [ident] = table_name.0.as_slice() {
+                let query = ident
+                    .value
+                    .strip_prefix(COPY_QUERY_PLACEHOLDER)
+                    .filter(|_| ident.quote_style == Some('"'))
+                    .and_then(|idx| idx.parse::<usize>().ok())
+                    .and_then(|idx| queries.get(idx));
+                if let Some(query) = query {
+                    *table_name = ObjectName(vec![]);
+                    *values = vec![Some(query.clone())];
+                }
+            }
+        }
+        stmt
+    }
+
+    /// The query of a `COPY (query) TO`, which has no table name and the query as its only
+    /// value.
+    pub fn copy_query<'a>(
+        table_name: &ObjectName,
+        values: &'a [Option<String>],
+    ) -> Option<&'a str> {
+        match values {
+            [Some(query)] if table_name.0.is_empty() => Some(query),
+            _ => None,
+        }
     }
 
     pub fn parse_one_stmt(sql: &str) -> Result<Statement, ParserError> {
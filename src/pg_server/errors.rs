@@ -0,0 +1,51 @@
+use std::io;
+
+use crate::catalog_v2::CatalogError;
+use crate::execution::ExecutorError;
+use crate::main_entry::DatabaseError;
+
+#[derive(thiserror::Error, Debug)]
+pub enum PgServerError {
+    #[error("io error: {0}")]
+    IoError(
+        #[source]
+        #[from]
+        io::Error,
+    ),
+    /// A message the server doesn't understand, the connection is closed
+    #[error("protocol error: {0}")]
+    ProtocolError(String),
+    #[error("{0}")]
+    DatabaseError(
+        #[source]
+        #[from]
+        DatabaseError,
+    ),
+    /// An invalid request of the client, such as a parameter value of a wrong format
+    #[error("{0}")]
+    InvalidRequest(String),
+}
+
+impl PgServerError {
+    /// The SQLSTATE code of the error, see
+    /// https://www.postgresql.org/docs/current/errcodes-appendix.html
+    pub fn sqlstate(&self) -> &'static str {
+        match self {
+            PgServerError::IoError(_) | PgServerError::ProtocolError(_) => "08P01",
+            PgServerError::InvalidRequest(_) => "22023",
+            PgServerError::DatabaseError(e) => match e {
+                DatabaseError::ParserError(_) => "42601",
+                DatabaseError::CatalogError(CatalogError::NotNullConstraintViolation(_))
+                | DatabaseError::ExecutorError(ExecutorError::CatalogError(
+                    CatalogError::NotNullConstraintViolation(_),
+                )) => "23502",
+                DatabaseError::CatalogError(_) | DatabaseError::PlannerError(_) => "42000",
+                DatabaseError::PreparedStatementTypesChanged => "0A000",
+                DatabaseError::ExecutorError(
+                    ExecutorError::Interrupted | ExecutorError::StatementTimeout,
+                ) => "57014",
+                _ => "XX000",
+            },
+        }
+    }
+}
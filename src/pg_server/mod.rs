@@ -0,0 +1,166 @@
+//! A server of the postgres wire protocol, for psql, BI tools and the postgres client libraries.
+//! It implements the startup, the simple query and the extended query (parse, bind and execute)
+//! messages without authentication nor encryption, each connection has its own client session.
+mod errors;
+mod protocol;
+mod session;
+mod types;
+
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::{Arc, Mutex};
+
+pub use errors::*;
+use log::debug;
+use protocol::{read_startup, BackendMessage, MessageWriter, StartupMessage, PROTOCOL_VERSION};
+use session::Session;
+use tokio::io::BufReader;
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::main_entry::{ClientContext, Database};
+
+static LOGGING_TARGET: &str = "sqlrs::pg_server";
+
+/// The session of an open connection, its query is canceled by a CancelRequest with the secret
+/// key of the connection.
+struct SessionHandle {
+    secret_key: i32,
+    client_context: Arc<ClientContext>,
+}
+
+pub struct PgServer {
+    database: Database,
+    /// The sessions of the open connections by their process id
+    sessions: Mutex<HashMap<i32, SessionHandle>>,
+    next_process_id: AtomicI32,
+}
+
+impl PgServer {
+    pub fn new(database: Database) -> Arc<Self> {
+        Arc::new(Self {
+            database,
+            sessions: Mutex::new(HashMap::new()),
+            next_process_id: AtomicI32::new(1),
+        })
+    }
+
+    /// Accept the connections of the listener, each connection is served by its own task.
+    pub async fn serve(self: Arc<Self>, listener: TcpListener) -> Result<(), PgServerError> {
+        loop {
+            let (socket, address) = listener.accept().await?;
+            let server = self.clone();
+            tokio::spawn(async move {
+                if let Err(e) = server.handle_connection(socket).await {
+                    debug!(
+                        target: LOGGING_TARGET,
+                        "connection of {} closed: {}", address, e
+                    );
+                }
+            });
+        }
+    }
+
+    async fn handle_connection(&self, socket: TcpStream) -> Result<(), PgServerError> {
+        socket.set_nodelay(true)?;
+        let (reader, writer) = socket.into_split();
+        let mut reader = BufReader::new(reader);
+        let mut writer = MessageWriter::new(writer);
+        loop {
+            match read_startup(&mut reader).await? {
+                // encryption isn't supported, the client continues without it
+                StartupMessage::SslRequest | StartupMessage::GssEncRequest => {
+                    writer.send_byte(b'N').await?;
+                }
+                StartupMessage::CancelRequest {
+                    process_id,
+                    secret_key,
+                } => {
+                    self.cancel(process_id, secret_key);
+                    return Ok(());
+                }
+                StartupMessage::Startup { version, .. } if version != PROTOCOL_VERSION => {
+                    let error = PgServerError::ProtocolError(format!(
+                        "unsupported frontend protocol {}.{}",
+                        version >> 16,
+                        version & 0xffff
+                    ));
+                    writer
+                        .send(BackendMessage::ErrorResponse {
+                            sqlstate: "0A000",
+                            message: error.to_string(),
+                        })
+                        .await?;
+                    writer.flush().await?;
+                    return Err(error);
+                }
+                StartupMessage::Startup { parameters, .. } => {
+                    debug!(
+                        target: LOGGING_TARGET,
+                        "startup of user {:?}",
+                        parameters.get("user")
+                    );
+                    break;
+                }
+            }
+        }
+
+        let connection = self.database.connect();
+        let process_id = self.next_process_id.fetch_add(1, Ordering::Relaxed);
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_i32(process_id);
+        let secret_key = hasher.finish() as i32;
+        writer.send(BackendMessage::AuthenticationOk).await?;
+        let server_version = format!("14.0 (sqlrs {})", env!("CARGO_PKG_VERSION"));
+        for (name, value) in [
+            ("server_version", server_version.as_str()),
+            ("server_encoding", "UTF8"),
+            ("client_encoding", "UTF8"),
+            ("DateStyle", "ISO, MDY"),
+            ("integer_datetimes", "on"),
+            ("standard_conforming_strings", "on"),
+        ] {
+            writer
+                .send(BackendMessage::ParameterStatus {
+                    name: name.to_string(),
+                    value: value.to_string(),
+                })
+                .await?;
+        }
+        writer
+            .send(BackendMessage::BackendKeyData {
+                process_id,
+                secret_key,
+            })
+            .await?;
+        writer.send(BackendMessage::ReadyForQuery(b'I')).await?;
+        writer.flush().await?;
+
+        self.sessions.lock().unwrap().insert(
+            process_id,
+            SessionHandle {
+                secret_key,
+                client_context: connection.client_context().clone(),
+            },
+        );
+        let mut session = Session::new(connection.client_context().clone());
+        let result = session.run(&mut reader, &mut writer).await;
+        self.sessions.lock().unwrap().remove(&process_id);
+        result
+    }
+
+    /// Interrupt the running query of a session, when the secret key is the key of the session.
+    fn cancel(&self, process_id: i32, secret_key: i32) {
+        let sessions = self.sessions.lock().unwrap();
+        match sessions.get(&process_id) {
+            Some(session) if session.secret_key == secret_key => {
+                session.client_context.interrupt();
+            }
+            _ => debug!(
+                target: LOGGING_TARGET,
+                "ignored cancel request of process {}", process_id
+            ),
+        }
+    }
+}
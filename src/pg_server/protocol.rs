@@ -0,0 +1,479 @@
+//! The messages of the postgres frontend/backend protocol version 3, see
+//! https://www.postgresql.org/docs/current/protocol-message-formats.html
+use std::collections::HashMap;
+use std::io::{self, ErrorKind};
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use super::PgServerError;
+
+/// The protocol version 3.0 of the startup message.
+pub const PROTOCOL_VERSION: i32 = 196608;
+const CANCEL_REQUEST_CODE: i32 = 80877102;
+const SSL_REQUEST_CODE: i32 = 80877103;
+const GSSENC_REQUEST_CODE: i32 = 80877104;
+/// The largest message accepted from a client.
+const MAX_MESSAGE_LENGTH: usize = 1 << 30;
+/// The capacity allocated up front to read the body of a message, a larger body grows the
+/// buffer while it's received, so a client can't make the server allocate a length it never sends.
+const READ_BUFFER_SIZE: usize = 8 * 1024;
+/// The size of the buffered messages written to the client once it's reached.
+const WRITE_BUFFER_SIZE: usize = 64 * 1024;
+
+/// The first message of a connection.
+#[derive(Debug)]
+pub enum StartupMessage {
+    Startup {
+        version: i32,
+        parameters: HashMap<String, String>,
+    },
+    /// Request an encrypted connection, it's refused
+    SslRequest,
+    GssEncRequest,
+    /// Cancel the running query of another connection
+    CancelRequest {
+        process_id: i32,
+        secret_key: i32,
+    },
+}
+
+/// Whether a Describe or Close message targets a prepared statement or a portal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    Statement,
+    Portal,
+}
+
+/// A message sent by a client after the startup.
+#[derive(Debug)]
+pub enum FrontendMessage {
+    /// A simple query, the statements of the sql are executed one by one
+    Query(String),
+    Parse {
+        name: String,
+        query: String,
+        /// The declared types of the parameters, 0 leaves the type unspecified
+        parameter_oids: Vec<i32>,
+    },
+    Bind {
+        portal: String,
+        statement: String,
+        parameter_formats: Vec<i16>,
+        /// The values of the parameters, `None` is NULL
+        parameters: Vec<Option<Vec<u8>>>,
+        result_formats: Vec<i16>,
+    },
+    Describe {
+        target: Target,
+        name: String,
+    },
+    Execute {
+        portal: String,
+        /// The limit of rows sent by the message, 0 sends every row
+        max_rows: i32,
+    },
+    Close {
+        target: Target,
+        name: String,
+    },
+    Sync,
+    Flush,
+    Terminate,
+}
+
+/// The description of a column of the rows sent to a client.
+#[derive(Debug, Clone)]
+pub struct FieldDescription {
+    pub name: String,
+    pub type_oid: i32,
+    pub type_size: i16,
+    pub format: i16,
+}
+
+/// A message sent to a client.
+#[derive(Debug)]
+pub enum BackendMessage {
+    AuthenticationOk,
+    ParameterStatus {
+        name: String,
+        value: String,
+    },
+    BackendKeyData {
+        process_id: i32,
+        secret_key: i32,
+    },
+    /// The server is ready for a new query, the status is `I` outside of a transaction
+    ReadyForQuery(u8),
+    RowDescription(Vec<FieldDescription>),
+    ParameterDescription(Vec<i32>),
+    DataRow(Vec<Option<Vec<u8>>>),
+    CommandComplete(String),
+    /// The limit of rows of an Execute message is reached, the portal is resumed by the next
+    PortalSuspended,
+    EmptyQueryResponse,
+    ParseComplete,
+    BindComplete,
+    CloseComplete,
+    NoData,
+    ErrorResponse {
+        sqlstate: &'static str,
+        message: String,
+    },
+}
+
+impl BackendMessage {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        let tag = match self {
+            BackendMessage::AuthenticationOk => b'R',
+            BackendMessage::ParameterStatus { .. } => b'S',
+            BackendMessage::BackendKeyData { .. } => b'K',
+            BackendMessage::ReadyForQuery(_) => b'Z',
+            BackendMessage::RowDescription(_) => b'T',
+            BackendMessage::ParameterDescription(_) => b't',
+            BackendMessage::DataRow(_) => b'D',
+            BackendMessage::CommandComplete(_) => b'C',
+            BackendMessage::PortalSuspended => b's',
+            BackendMessage::EmptyQueryResponse => b'I',
+            BackendMessage::ParseComplete => b'1',
+            BackendMessage::BindComplete => b'2',
+            BackendMessage::CloseComplete => b'3',
+            BackendMessage::NoData => b'n',
+            BackendMessage::ErrorResponse { .. } => b'E',
+        };
+        buf.push(tag);
+        // the length includes itself, it's written once the body is
+        let start = buf.len();
+        buf.extend_from_slice(&[0; 4]);
+        match self {
+            BackendMessage::AuthenticationOk => put_i32(buf, 0),
+            BackendMessage::ParameterStatus { name, value } => {
+                put_cstr(buf, name);
+                put_cstr(buf, value);
+            }
+            BackendMessage::BackendKeyData {
+                process_id,
+                secret_key,
+            } => {
+                put_i32(buf, *process_id);
+                put_i32(buf, *secret_key);
+            }
+            BackendMessage::ReadyForQuery(status) => buf.push(*status),
+            BackendMessage::RowDescription(fields) => {
+                put_i16(buf, fields.len() as i16);
+                for field in fields.iter() {
+                    put_cstr(buf, &field.name);
+                    // the oid of the table and the number of the column
+                    put_i32(buf, 0);
+                    put_i16(buf, 0);
+                    put_i32(buf, field.type_oid);
+                    put_i16(buf, field.type_size);
+                    // the type modifier
+                    put_i32(buf, -1);
+                    put_i16(buf, field.format);
+                }
+            }
+            BackendMessage::ParameterDescription(oids) => {
+                put_i16(buf, oids.len() as i16);
+                for oid in oids.iter() {
+                    put_i32(buf, *oid);
+                }
+            }
+            BackendMessage::DataRow(values) => {
+                put_i16(buf, values.len() as i16);
+                for value in values.iter() {
+                    match value {
+                        Some(value) => {
+                            put_i32(buf, value.len() as i32);
+                            buf.extend_from_slice(value);
+                        }
+                        None => put_i32(buf, -1),
+                    }
+                }
+            }
+            BackendMessage::CommandComplete(tag) => put_cstr(buf, tag),
+            BackendMessage::ErrorResponse { sqlstate, message } => {
+                for (field, value) in [(b'S', "ERROR"), (b'V', "ERROR"), (b'C', *sqlstate)] {
+                    buf.push(field);
+                    put_cstr(buf, value);
+                }
+                buf.push(b'M');
+                put_cstr(buf, message);
+                buf.push(0);
+            }
+            BackendMessage::PortalSuspended
+            | BackendMessage::EmptyQueryResponse
+            | BackendMessage::ParseComplete
+            | BackendMessage::BindComplete
+            | BackendMessage::CloseComplete
+            | BackendMessage::NoData => {}
+        }
+        let len = (buf.len() - start) as i32;
+        buf[start..start + 4].copy_from_slice(&len.to_be_bytes());
+    }
+}
+
+fn put_i16(buf: &mut Vec<u8>, value: i16) {
+    buf.extend_from_slice(&value.to_be_bytes());
+}
+
+fn put_i32(buf: &mut Vec<u8>, value: i32) {
+    buf.extend_from_slice(&value.to_be_bytes());
+}
+
+fn put_cstr(buf: &mut Vec<u8>, value: &str) {
+    // a nul byte would end the string early
+    buf.extend(value.bytes().filter(|b| *b != 0));
+    buf.push(0);
+}
+
+/// The body of a message read from a client.
+struct MessageBody {
+    bytes: Vec<u8>,
+    pos: usize,
+}
+
+impl MessageBody {
+    fn take(&mut self, len: usize) -> Result<&[u8], PgServerError> {
+        if self.bytes.len() - self.pos < len {
+            return Err(PgServerError::ProtocolError(
+                "message is shorter than its content".to_string(),
+            ));
+        }
+        self.pos += len;
+        Ok(&self.bytes[self.pos - len..self.pos])
+    }
+
+    fn u8(&mut self) -> Result<u8, PgServerError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn i16(&mut self) -> Result<i16, PgServerError> {
+        Ok(i16::from_be_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn i32(&mut self) -> Result<i32, PgServerError> {
+        Ok(i32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    /// A list of values prefixed by its length.
+    fn list<T>(
+        &mut self,
+        mut item: impl FnMut(&mut Self) -> Result<T, PgServerError>,
+    ) -> Result<Vec<T>, PgServerError> {
+        let len = self.i16()?;
+        (0..len).map(|_| item(self)).collect()
+    }
+
+    fn cstr(&mut self) -> Result<String, PgServerError> {
+        let len = self.bytes[self.pos..]
+            .iter()
+            .position(|b| *b == 0)
+            .ok_or_else(|| PgServerError::ProtocolError("string is not terminated".to_string()))?;
+        let value = String::from_utf8(self.take(len)?.to_vec())
+            .map_err(|_| PgServerError::ProtocolError("string is not valid UTF-8".to_string()))?;
+        self.pos += 1;
+        Ok(value)
+    }
+
+    fn target(&mut self) -> Result<Target, PgServerError> {
+        match self.u8()? {
+            b'S' => Ok(Target::Statement),
+            b'P' => Ok(Target::Portal),
+            other => Err(PgServerError::ProtocolError(format!(
+                "invalid target {}",
+                other as char
+            ))),
+        }
+    }
+}
+
+/// Read the length of a message and its body.
+async fn read_body<R: AsyncRead + Unpin>(reader: &mut R) -> Result<MessageBody, PgServerError> {
+    let len = reader.read_i32().await?;
+    if !(4..=MAX_MESSAGE_LENGTH as i32).contains(&len) {
+        return Err(PgServerError::ProtocolError(format!(
+            "invalid message length {}",
+            len
+        )));
+    }
+    let len = len as usize - 4;
+    let mut bytes = Vec::with_capacity(len.min(READ_BUFFER_SIZE));
+    (&mut *reader)
+        .take(len as u64)
+        .read_to_end(&mut bytes)
+        .await?;
+    if bytes.len() < len {
+        return Err(io::Error::from(ErrorKind::UnexpectedEof).into());
+    }
+    Ok(MessageBody { bytes, pos: 0 })
+}
+
+pub async fn read_startup<R: AsyncRead + Unpin>(
+    reader: &mut R,
+) -> Result<StartupMessage, PgServerError> {
+    let mut body = read_body(reader).await?;
+    let message = match body.i32()? {
+        SSL_REQUEST_CODE => StartupMessage::SslRequest,
+        GSSENC_REQUEST_CODE => StartupMessage::GssEncRequest,
+        CANCEL_REQUEST_CODE => StartupMessage::CancelRequest {
+            process_id: body.i32()?,
+            secret_key: body.i32()?,
+        },
+        version => {
+            // the parameters are pairs of names and values, ended by an empty name
+            let mut parameters = HashMap::new();
+            loop {
+                let name = body.cstr()?;
+                if name.is_empty() {
+                    break;
+                }
+                parameters.insert(name, body.cstr()?);
+            }
+            StartupMessage::Startup {
+                version,
+                parameters,
+            }
+        }
+    };
+    Ok(message)
+}
+
+/// Read the next message of a client, `None` when the client closed the connection.
+pub async fn read_message<R: AsyncRead + Unpin>(
+    reader: &mut R,
+) -> Result<Option<FrontendMessage>, PgServerError> {
+    let tag = match reader.read_u8().await {
+        Ok(tag) => tag,
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let mut body = read_body(reader).await?;
+    let message = match tag {
+        b'Q' => FrontendMessage::Query(body.cstr()?),
+        b'P' => FrontendMessage::Parse {
+            name: body.cstr()?,
+            query: body.cstr()?,
+            parameter_oids: body.list(MessageBody::i32)?,
+        },
+        b'B' => FrontendMessage::Bind {
+            portal: body.cstr()?,
+            statement: body.cstr()?,
+            parameter_formats: body.list(MessageBody::i16)?,
+            parameters: body.list(|body| {
+                let len = body.i32()?;
+                match len {
+                    -1 => Ok(None),
+                    len if len >= 0 => Ok(Some(body.take(len as usize)?.to_vec())),
+                    len => Err(PgServerError::ProtocolError(format!(
+                        "invalid parameter length {}",
+                        len
+                    ))),
+                }
+            })?,
+            result_formats: body.list(MessageBody::i16)?,
+        },
+        b'D' => FrontendMessage::Describe {
+            target: body.target()?,
+            name: body.cstr()?,
+        },
+        b'E' => FrontendMessage::Execute {
+            portal: body.cstr()?,
+            max_rows: body.i32()?,
+        },
+        b'C' => FrontendMessage::Close {
+            target: body.target()?,
+            name: body.cstr()?,
+        },
+        b'S' => FrontendMessage::Sync,
+        b'H' => FrontendMessage::Flush,
+        b'X' => FrontendMessage::Terminate,
+        other => {
+            return Err(PgServerError::ProtocolError(format!(
+                "unsupported message type {}",
+                other as char
+            )))
+        }
+    };
+    Ok(Some(message))
+}
+
+/// Buffer the messages sent to a client, they are written when the buffer is full or flushed.
+pub struct MessageWriter<W> {
+    writer: W,
+    buf: Vec<u8>,
+}
+
+impl<W: AsyncWrite + Unpin> MessageWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            buf: Vec::with_capacity(WRITE_BUFFER_SIZE),
+        }
+    }
+
+    pub async fn send(&mut self, message: BackendMessage) -> Result<(), PgServerError> {
+        message.encode(&mut self.buf);
+        if self.buf.len() >= WRITE_BUFFER_SIZE {
+            self.flush().await?;
+        }
+        Ok(())
+    }
+
+    /// Write a single byte outside of a message, the answer to an encryption request.
+    pub async fn send_byte(&mut self, byte: u8) -> Result<(), PgServerError> {
+        self.buf.push(byte);
+        self.flush().await
+    }
+
+    pub async fn flush(&mut self) -> Result<(), PgServerError> {
+        self.writer.write_all(&self.buf).await?;
+        self.writer.flush().await?;
+        self.buf.clear();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_read_bind_message() {
+        let mut bytes = vec![b'B', 0, 0, 0, 0];
+        bytes.extend_from_slice(b"p\0s\0");
+        // one binary parameter format, two parameters, no result format
+        bytes.extend_from_slice(&[0, 1, 0, 1, 0, 2]);
+        bytes.extend_from_slice(&[0, 0, 0, 4, 0, 0, 0, 7, 0xff, 0xff, 0xff, 0xff, 0, 0]);
+        let len = (bytes.len() - 1) as i32;
+        bytes[1..5].copy_from_slice(&len.to_be_bytes());
+
+        let message = read_message(&mut bytes.as_slice()).await.unwrap().unwrap();
+        match message {
+            FrontendMessage::Bind {
+                portal,
+                statement,
+                parameter_formats,
+                parameters,
+                result_formats,
+            } => {
+                assert_eq!((portal.as_str(), statement.as_str()), ("p", "s"));
+                assert_eq!(parameter_formats, vec![1]);
+                assert_eq!(parameters, vec![Some(vec![0, 0, 0, 7]), None]);
+                assert!(result_formats.is_empty());
+            }
+            other => panic!("unexpected message {:?}", other),
+        }
+        assert!(read_message(&mut [].as_slice()).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_read_message_shorter_than_its_length() {
+        let mut bytes = vec![b'Q'];
+        bytes.extend_from_slice(&(MAX_MESSAGE_LENGTH as i32).to_be_bytes());
+        bytes.extend_from_slice(b"select 1\0");
+        match read_message(&mut bytes.as_slice()).await {
+            Err(PgServerError::IoError(e)) => assert_eq!(e.kind(), ErrorKind::UnexpectedEof),
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+}
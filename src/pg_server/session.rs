@@ -0,0 +1,590 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow::record_batch::RecordBatch;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use sqlparser::ast::Statement;
+use tokio::io::{AsyncRead, AsyncWrite};
+
+use super::protocol::{
+    read_message, BackendMessage, FieldDescription, FrontendMessage, MessageWriter, Target,
+};
+use super::types::{
+    decode_parameter, encode_value, logical_type_of_oid, type_oid, type_size, TEXT_FORMAT,
+};
+use super::PgServerError;
+use crate::main_entry::{ClientContext, DatabaseError, PreparedStatement, QueryResult};
+use crate::parser::Sqlparser;
+use crate::types_v2::{LogicalType, ScalarValue};
+
+/// The status of ReadyForQuery outside of a transaction, sqlrs has no transaction blocks.
+const IDLE_STATUS: u8 = b'I';
+
+/// How the result of a statement is sent to a client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ResultKind {
+    /// The rows of the result are sent, like the rows of a query
+    Rows(&'static str),
+    /// The result isn't sent, only the tag of the command
+    Command(&'static str),
+}
+
+impl ResultKind {
+    fn of(client_context: &ClientContext, statement: &Statement) -> Self {
+        match statement {
+            Statement::Query(_) => ResultKind::Rows("SELECT"),
+            Statement::Explain { .. } | Statement::ExplainTable { .. } => {
+                ResultKind::Rows("EXPLAIN")
+            }
+            Statement::ShowTables { .. } => ResultKind::Rows("SHOW"),
+            Statement::Insert { .. } => ResultKind::Command("INSERT"),
+            Statement::CreateTable { .. } => ResultKind::Command("CREATE TABLE"),
+            Statement::Copy { .. } => ResultKind::Command("COPY"),
+            Statement::Analyze { .. } => ResultKind::Command("ANALYZE"),
+            Statement::SetVariable { .. } => ResultKind::Command("SET"),
+            Statement::Prepare { .. } => ResultKind::Command("PREPARE"),
+            Statement::Deallocate { .. } => ResultKind::Command("DEALLOCATE"),
+            Statement::Execute { name, .. } => {
+                let prepared_statements = client_context.prepared_statements.read().unwrap();
+                match prepared_statements.get(&name.value) {
+                    Some(prepared) => Self::of(client_context, &prepared.statement),
+                    None => ResultKind::Rows("EXECUTE"),
+                }
+            }
+            _ => ResultKind::Rows("SELECT"),
+        }
+    }
+}
+
+/// A statement prepared by a Parse message.
+struct PgStatement {
+    /// The prepared statement, `None` for an empty query
+    prepared: Option<PreparedStatement>,
+    kind: ResultKind,
+}
+
+/// A prepared statement bound to the values of its parameters by a Bind message.
+struct Portal {
+    statement: Arc<PgStatement>,
+    parameters: Vec<ScalarValue>,
+    /// The formats of the result columns
+    result_formats: Vec<i16>,
+    /// The rows left by an Execute message which reached its limit of rows
+    rows: Option<PortalRows>,
+}
+
+/// The rows of a result sent to a client, a portal keeps them between its Execute messages.
+struct PortalRows {
+    fields: Vec<FieldDescription>,
+    stream: BoxStream<'static, Result<RecordBatch, DatabaseError>>,
+    /// The batch being sent, and the index of its next row
+    batch: Option<RecordBatch>,
+    next_row: usize,
+}
+
+impl PortalRows {
+    fn new(result: QueryResult, formats: &[i16]) -> Self {
+        Self {
+            fields: field_descriptions(result.names(), result.types(), formats),
+            stream: result.into_stream(),
+            batch: None,
+            next_row: 0,
+        }
+    }
+
+    /// Send the rows while the result is streamed, until the limit of rows is reached. Returns
+    /// the count of the sent rows.
+    async fn send<W: AsyncWrite + Unpin>(
+        &mut self,
+        writer: &mut MessageWriter<W>,
+        max_rows: usize,
+    ) -> Result<usize, PgServerError> {
+        let mut rows = 0;
+        while rows < max_rows {
+            let batch = match &self.batch {
+                Some(batch) if self.next_row < batch.num_rows() => batch,
+                _ => match self.stream.next().await {
+                    Some(batch) => {
+                        self.batch = Some(batch?);
+                        self.next_row = 0;
+                        continue;
+                    }
+                    None => break,
+                },
+            };
+            let values = batch
+                .columns()
+                .iter()
+                .zip(self.fields.iter())
+                .map(|(array, field)| {
+                    encode_value(array, self.next_row, field.type_oid, field.format)
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            writer.send(BackendMessage::DataRow(values)).await?;
+            self.next_row += 1;
+            rows += 1;
+        }
+        Ok(rows)
+    }
+}
+
+/// The state of a client connection: its client context, and the prepared statements and portals
+/// of the extended query protocol.
+pub(crate) struct Session {
+    client_context: Arc<ClientContext>,
+    statements: HashMap<String, Arc<PgStatement>>,
+    portals: HashMap<String, Portal>,
+}
+
+impl Session {
+    pub(crate) fn new(client_context: Arc<ClientContext>) -> Self {
+        Self {
+            client_context,
+            statements: HashMap::new(),
+            portals: HashMap::new(),
+        }
+    }
+
+    /// Serve the messages of the client until it terminates the connection.
+    pub(crate) async fn run<R, W>(
+        &mut self,
+        reader: &mut R,
+        writer: &mut MessageWriter<W>,
+    ) -> Result<(), PgServerError>
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        // after an error of the extended query protocol, the messages are ignored until Sync
+        let mut skip_until_sync = false;
+        loop {
+            let message = match read_message(reader).await {
+                Ok(Some(message)) => message,
+                Ok(None) => return Ok(()),
+                Err(e @ PgServerError::ProtocolError(_)) => {
+                    send_error(writer, &e).await?;
+                    writer.flush().await?;
+                    return Err(e);
+                }
+                Err(e) => return Err(e),
+            };
+            if skip_until_sync && !matches!(message, FrontendMessage::Sync) {
+                continue;
+            }
+            let result = match message {
+                FrontendMessage::Query(sql) => {
+                    if let Err(e) = self.simple_query(&sql, writer).await {
+                        report_error(writer, e).await?;
+                    }
+                    writer
+                        .send(BackendMessage::ReadyForQuery(IDLE_STATUS))
+                        .await?;
+                    writer.flush().await?;
+                    continue;
+                }
+                FrontendMessage::Parse {
+                    name,
+                    query,
+                    parameter_oids,
+                } => self.parse(name, &query, &parameter_oids, writer).await,
+                FrontendMessage::Bind {
+                    portal,
+                    statement,
+                    parameter_formats,
+                    parameters,
+                    result_formats,
+                } => {
+                    self.bind(
+                        portal,
+                        &statement,
+                        &parameter_formats,
+                        parameters,
+                        result_formats,
+                        writer,
+                    )
+                    .await
+                }
+                FrontendMessage::Describe { target, name } => {
+                    self.describe(target, &name, writer).await
+                }
+                FrontendMessage::Execute { portal, max_rows } => {
+                    self.execute(&portal, max_rows, writer).await
+                }
+                FrontendMessage::Close { target, name } => {
+                    match target {
+                        Target::Statement => self.statements.remove(&name).is_some(),
+                        Target::Portal => self.portals.remove(&name).is_some(),
+                    };
+                    writer.send(BackendMessage::CloseComplete).await
+                }
+                FrontendMessage::Sync => {
+                    skip_until_sync = false;
+                    self.portals.remove("");
+                    writer
+                        .send(BackendMessage::ReadyForQuery(IDLE_STATUS))
+                        .await?;
+                    writer.flush().await
+                }
+                FrontendMessage::Flush => writer.flush().await,
+                FrontendMessage::Terminate => return Ok(()),
+            };
+            if let Err(e) = result {
+                report_error(writer, e).await?;
+                skip_until_sync = true;
+            }
+        }
+    }
+
+    /// Execute the statements of a simple query one by one, until one of them fails.
+    async fn simple_query<W: AsyncWrite + Unpin>(
+        &self,
+        sql: &str,
+        writer: &mut MessageWriter<W>,
+    ) -> Result<(), PgServerError> {
+        let statements = Sqlparser::parse(sql.to_string()).map_err(DatabaseError::from)?;
+        if statements.is_empty() {
+            return writer.send(BackendMessage::EmptyQueryResponse).await;
+        }
+        for statement in statements.iter() {
+            let kind = ResultKind::of(&self.client_context, statement);
+            let result = self
+                .client_context
+                .execute_statement(statement, true)
+                .await?;
+            send_result(writer, kind, result, &[], true).await?;
+        }
+        Ok(())
+    }
+
+    async fn parse<W: AsyncWrite + Unpin>(
+        &mut self,
+        name: String,
+        query: &str,
+        parameter_oids: &[i32],
+        writer: &mut MessageWriter<W>,
+    ) -> Result<(), PgServerError> {
+        let mut statements = Sqlparser::parse(query.to_string()).map_err(DatabaseError::from)?;
+        let statement = match statements.len() {
+            0 => PgStatement {
+                prepared: None,
+                kind: ResultKind::Command(""),
+            },
+            1 => {
+                let statement = statements.remove(0);
+                let kind = ResultKind::of(&self.client_context, &statement);
+                let declared_types = parameter_oids.iter().map(|oid| logical_type_of_oid(*oid));
+                // the client keeps the types sent by the Describe messages
+                let prepared = PreparedStatement::try_new(
+                    self.client_context.clone(),
+                    statement,
+                    declared_types.collect(),
+                )?
+                .with_fixed_types();
+                PgStatement {
+                    prepared: Some(prepared),
+                    kind,
+                }
+            }
+            _ => {
+                return Err(PgServerError::InvalidRequest(
+                    "cannot insert multiple commands into a prepared statement".to_string(),
+                ))
+            }
+        };
+        self.statements.insert(name, Arc::new(statement));
+        writer.send(BackendMessage::ParseComplete).await
+    }
+
+    async fn bind<W: AsyncWrite + Unpin>(
+        &mut self,
+        portal: String,
+        statement: &str,
+        parameter_formats: &[i16],
+        parameters: Vec<Option<Vec<u8>>>,
+        result_formats: Vec<i16>,
+        writer: &mut MessageWriter<W>,
+    ) -> Result<(), PgServerError> {
+        let statement = self.get_statement(statement)?;
+        let parameter_types = statement
+            .prepared
+            .as_ref()
+            .map_or(vec![], |p| p.parameter_types());
+        if parameters.len() != parameter_types.len() {
+            return Err(PgServerError::InvalidRequest(format!(
+                "bind message supplies {} parameters, but prepared statement requires {}",
+                parameters.len(),
+                parameter_types.len()
+            )));
+        }
+        let parameters = parameters
+            .iter()
+            .zip(parameter_types.iter())
+            .enumerate()
+            .map(|(i, (value, ty))| {
+                let format = format_of(parameter_formats, i);
+                decode_parameter(value.as_deref(), type_oid(ty), format)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        self.portals.insert(
+            portal,
+            Portal {
+                statement,
+                parameters,
+                result_formats,
+                rows: None,
+            },
+        );
+        writer.send(BackendMessage::BindComplete).await
+    }
+
+    async fn describe<W: AsyncWrite + Unpin>(
+        &self,
+        target: Target,
+        name: &str,
+        writer: &mut MessageWriter<W>,
+    ) -> Result<(), PgServerError> {
+        let (statement, result_formats) = match target {
+            Target::Statement => {
+                let statement = self.get_statement(name)?;
+                let parameter_types = statement
+                    .prepared
+                    .as_ref()
+                    .map_or(vec![], |p| p.parameter_types());
+                let oids = parameter_types.iter().map(type_oid).collect();
+                writer
+                    .send(BackendMessage::ParameterDescription(oids))
+                    .await?;
+                // the formats of the result are unknown until the statement is bound
+                (statement, vec![])
+            }
+            Target::Portal => {
+                let portal = self.get_portal(name)?;
+                (portal.statement.clone(), portal.result_formats.clone())
+            }
+        };
+        match (&statement.prepared, statement.kind) {
+            (Some(prepared), ResultKind::Rows(_)) => {
+                let fields =
+                    field_descriptions(&prepared.names(), &prepared.types(), &result_formats);
+                writer.send(BackendMessage::RowDescription(fields)).await
+            }
+            _ => writer.send(BackendMessage::NoData).await,
+        }
+    }
+
+    /// Execute a portal. The rows of a query are sent up to the limit of rows, the portal is
+    /// suspended once the limit is reached, and the next Execute of the portal resumes its rows.
+    async fn execute<W: AsyncWrite + Unpin>(
+        &mut self,
+        portal: &str,
+        max_rows: i32,
+        writer: &mut MessageWriter<W>,
+    ) -> Result<(), PgServerError> {
+        let portal = self.get_portal_mut(portal)?;
+        let prepared = match &portal.statement.prepared {
+            Some(prepared) => prepared,
+            None => return writer.send(BackendMessage::EmptyQueryResponse).await,
+        };
+        let tag = match portal.statement.kind {
+            ResultKind::Rows(tag) => tag,
+            // a command has no rows, the limit of rows doesn't apply to it
+            kind => {
+                let result = prepared.execute(portal.parameters.clone()).await?;
+                return send_result(writer, kind, result, &portal.result_formats, false).await;
+            }
+        };
+        let rows = match portal.rows.take() {
+            Some(rows) => rows,
+            None => {
+                let result = prepared.execute(portal.parameters.clone()).await?;
+                PortalRows::new(result, &portal.result_formats)
+            }
+        };
+        let rows = portal.rows.insert(rows);
+        let max_rows = if max_rows > 0 {
+            max_rows as usize
+        } else {
+            usize::MAX
+        };
+        let count = rows.send(writer, max_rows).await?;
+        if count == max_rows {
+            return writer.send(BackendMessage::PortalSuspended).await;
+        }
+        writer
+            .send(BackendMessage::CommandComplete(command_tag(tag, count)))
+            .await
+    }
+
+    fn get_statement(&self, name: &str) -> Result<Arc<PgStatement>, PgServerError> {
+        self.statements.get(name).cloned().ok_or_else(|| {
+            PgServerError::InvalidRequest(format!("prepared statement \"{}\" does not exist", name))
+        })
+    }
+
+    fn get_portal(&self, name: &str) -> Result<&Portal, PgServerError> {
+        self.portals
+            .get(name)
+            .ok_or_else(|| portal_not_exists(name))
+    }
+
+    fn get_portal_mut(&mut self, name: &str) -> Result<&mut Portal, PgServerError> {
+        self.portals
+            .get_mut(name)
+            .ok_or_else(|| portal_not_exists(name))
+    }
+}
+
+fn portal_not_exists(name: &str) -> PgServerError {
+    PgServerError::InvalidRequest(format!("portal \"{}\" does not exist", name))
+}
+
+/// The format of the value at the index: no format is text, a single format applies to every
+/// value, otherwise each value has its format.
+fn format_of(formats: &[i16], index: usize) -> i16 {
+    match formats {
+        [] => TEXT_FORMAT,
+        [format] => *format,
+        formats => formats.get(index).copied().unwrap_or(TEXT_FORMAT),
+    }
+}
+
+fn field_descriptions(
+    names: &[String],
+    types: &[LogicalType],
+    formats: &[i16],
+) -> Vec<FieldDescription> {
+    names
+        .iter()
+        .zip(types.iter())
+        .enumerate()
+        .map(|(i, (name, ty))| {
+            let type_oid = type_oid(ty);
+            FieldDescription {
+                name: name.clone(),
+                type_oid,
+                type_size: type_size(type_oid),
+                format: format_of(formats, i),
+            }
+        })
+        .collect()
+}
+
+/// Send the result of a statement. The rows are sent while the result is streamed, preceded by
+/// their description in a simple query, and followed by the tag of the completed command.
+async fn send_result<W: AsyncWrite + Unpin>(
+    writer: &mut MessageWriter<W>,
+    kind: ResultKind,
+    result: QueryResult,
+    formats: &[i16],
+    describe: bool,
+) -> Result<(), PgServerError> {
+    let tag = match kind {
+        // the tags of COPY and INSERT carry the count of their rows
+        ResultKind::Command("COPY") => format!("COPY {}", result.affected_rows().await?),
+        ResultKind::Command("INSERT") => format!("INSERT 0 {}", result.affected_rows().await?),
+        ResultKind::Command(tag) => {
+            result.collect().await?;
+            tag.to_string()
+        }
+        ResultKind::Rows(tag) => {
+            let mut rows = PortalRows::new(result, formats);
+            if describe {
+                writer
+                    .send(BackendMessage::RowDescription(rows.fields.clone()))
+                    .await?;
+            }
+            let count = rows.send(writer, usize::MAX).await?;
+            command_tag(tag, count)
+        }
+    };
+    writer.send(BackendMessage::CommandComplete(tag)).await
+}
+
+/// The tag of a completed command which sent rows, a query tag carries the count of its rows.
+fn command_tag(tag: &str, rows: usize) -> String {
+    match tag {
+        "SELECT" => format!("SELECT {}", rows),
+        tag => tag.to_string(),
+    }
+}
+
+async fn send_error<W: AsyncWrite + Unpin>(
+    writer: &mut MessageWriter<W>,
+    error: &PgServerError,
+) -> Result<(), PgServerError> {
+    writer
+        .send(BackendMessage::ErrorResponse {
+            sqlstate: error.sqlstate(),
+            message: error.to_string(),
+        })
+        .await
+}
+
+/// Send an error to the client, except an io error which ends the connection.
+async fn report_error<W: AsyncWrite + Unpin>(
+    writer: &mut MessageWriter<W>,
+    error: PgServerError,
+) -> Result<(), PgServerError> {
+    match error {
+        PgServerError::IoError(_) => Err(error),
+        error => send_error(writer, &error).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::main_entry::DatabaseInstance;
+
+    fn message(tag: u8, body: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![tag];
+        bytes.extend_from_slice(&(body.len() as i32 + 4).to_be_bytes());
+        bytes.extend_from_slice(body);
+        bytes
+    }
+
+    /// The tags of the messages sent to the client.
+    fn message_tags(mut bytes: &[u8]) -> Vec<u8> {
+        let mut tags = vec![];
+        while !bytes.is_empty() {
+            let len = i32::from_be_bytes(bytes[1..5].try_into().unwrap()) as usize;
+            tags.push(bytes[0]);
+            bytes = &bytes[1 + len..];
+        }
+        tags
+    }
+
+    #[tokio::test]
+    async fn test_execute_portal_with_max_rows() {
+        let db = Arc::new(DatabaseInstance::default());
+        db.initialize().unwrap();
+        let client_context = ClientContext::new(db);
+        client_context
+            .query("create table t1(a int)".to_string())
+            .await
+            .unwrap();
+        client_context
+            .query("insert into t1 values (1), (2), (3)".to_string())
+            .await
+            .unwrap();
+
+        let mut input = message(b'P', b"\0select a from t1\0\0\0");
+        // no parameter, every result column in text
+        input.extend(message(b'B', b"\0\0\0\0\0\0\0\0"));
+        // two rows by each Execute
+        input.extend(message(b'E', &[0, 0, 0, 0, 2]));
+        input.extend(message(b'E', &[0, 0, 0, 0, 2]));
+        input.extend(message(b'S', &[]));
+
+        let mut output = vec![];
+        let mut writer = MessageWriter::new(&mut output);
+        let mut session = Session::new(client_context);
+        session
+            .run(&mut input.as_slice(), &mut writer)
+            .await
+            .unwrap();
+        drop(writer);
+        assert_eq!(message_tags(&output), b"12DDsDCZ".to_vec());
+    }
+}
@@ -0,0 +1,241 @@
+use arrow::array::{Array, ArrayRef, BooleanArray};
+use arrow::datatypes::IntervalUnit;
+use arrow::util::display::array_value_to_string;
+
+use super::PgServerError;
+use crate::main_entry::DatabaseError;
+use crate::types_v2::{LogicalType, ScalarValue};
+
+/// The oids of the postgres types sqlrs uses, see `pg_type.dat` of postgres.
+pub const BOOL_OID: i32 = 16;
+pub const INT8_OID: i32 = 20;
+pub const INT2_OID: i32 = 21;
+pub const INT4_OID: i32 = 23;
+pub const TEXT_OID: i32 = 25;
+pub const FLOAT4_OID: i32 = 700;
+pub const FLOAT8_OID: i32 = 701;
+pub const UNKNOWN_OID: i32 = 705;
+pub const VARCHAR_OID: i32 = 1043;
+pub const DATE_OID: i32 = 1082;
+pub const INTERVAL_OID: i32 = 1186;
+pub const NUMERIC_OID: i32 = 1700;
+
+/// The format code of a value in the text format.
+pub const TEXT_FORMAT: i16 = 0;
+
+/// The days between the unix epoch 1970-01-01 and the postgres epoch 2000-01-01.
+const POSTGRES_EPOCH_DAYS: i32 = 10957;
+
+/// The postgres type of a logical type. An unsigned type is mapped to the smallest signed type
+/// holding all its values, and the types postgres has no equivalent of are sent as text.
+pub fn type_oid(ty: &LogicalType) -> i32 {
+    match ty {
+        LogicalType::Boolean => BOOL_OID,
+        LogicalType::Tinyint | LogicalType::UTinyint | LogicalType::Smallint => INT2_OID,
+        LogicalType::USmallint | LogicalType::Integer => INT4_OID,
+        LogicalType::UInteger | LogicalType::Bigint => INT8_OID,
+        LogicalType::UBigint => NUMERIC_OID,
+        LogicalType::Float => FLOAT4_OID,
+        LogicalType::Double => FLOAT8_OID,
+        LogicalType::Varchar => VARCHAR_OID,
+        LogicalType::Date => DATE_OID,
+        LogicalType::Interval(_) => INTERVAL_OID,
+        LogicalType::Invalid
+        | LogicalType::SqlNull
+        | LogicalType::Struct(_)
+        | LogicalType::List(_) => TEXT_OID,
+    }
+}
+
+/// The size of the values of a postgres type, -1 for the types of variable size.
+pub fn type_size(oid: i32) -> i16 {
+    match oid {
+        BOOL_OID => 1,
+        INT2_OID => 2,
+        INT4_OID | FLOAT4_OID | DATE_OID => 4,
+        INT8_OID | FLOAT8_OID => 8,
+        INTERVAL_OID => 16,
+        _ => -1,
+    }
+}
+
+/// The logical type of a parameter type declared by the client. An unspecified or unknown type
+/// is `SqlNull`, and the type of the parameter is inferred from its context.
+pub fn logical_type_of_oid(oid: i32) -> LogicalType {
+    match oid {
+        BOOL_OID => LogicalType::Boolean,
+        INT2_OID => LogicalType::Smallint,
+        INT4_OID => LogicalType::Integer,
+        INT8_OID => LogicalType::Bigint,
+        FLOAT4_OID => LogicalType::Float,
+        FLOAT8_OID => LogicalType::Double,
+        TEXT_OID | VARCHAR_OID => LogicalType::Varchar,
+        DATE_OID => LogicalType::Date,
+        INTERVAL_OID => LogicalType::Interval(IntervalUnit::DayTime),
+        _ => LogicalType::SqlNull,
+    }
+}
+
+/// Encode a value of a result column in the format the client asked for, `None` is NULL.
+pub fn encode_value(
+    array: &ArrayRef,
+    row: usize,
+    oid: i32,
+    format: i16,
+) -> Result<Option<Vec<u8>>, PgServerError> {
+    if array.is_null(row) {
+        return Ok(None);
+    }
+    if format == TEXT_FORMAT || oid == TEXT_OID || oid == VARCHAR_OID {
+        return encode_text(array, row).map(Some);
+    }
+    let value = ScalarValue::try_from_array(array, row)
+        .map_err(|e| DatabaseError::InternalError(e.to_string()))?;
+    let bytes = match value {
+        ScalarValue::Boolean(Some(v)) => vec![v as u8],
+        ScalarValue::Int8(Some(v)) => (v as i16).to_be_bytes().to_vec(),
+        ScalarValue::UInt8(Some(v)) => (v as i16).to_be_bytes().to_vec(),
+        ScalarValue::Int16(Some(v)) => v.to_be_bytes().to_vec(),
+        ScalarValue::UInt16(Some(v)) => (v as i32).to_be_bytes().to_vec(),
+        ScalarValue::Int32(Some(v)) => v.to_be_bytes().to_vec(),
+        ScalarValue::UInt32(Some(v)) => (v as i64).to_be_bytes().to_vec(),
+        ScalarValue::Int64(Some(v)) => v.to_be_bytes().to_vec(),
+        ScalarValue::UInt64(Some(v)) => encode_numeric(v),
+        ScalarValue::Float32(Some(v)) => v.to_be_bytes().to_vec(),
+        ScalarValue::Float64(Some(v)) => v.to_be_bytes().to_vec(),
+        ScalarValue::Date32(Some(v)) => (v - POSTGRES_EPOCH_DAYS).to_be_bytes().to_vec(),
+        ScalarValue::IntervalYearMonth(Some(months)) => encode_interval(0, 0, months),
+        ScalarValue::IntervalDayTime(Some(v)) => {
+            // the days are the high 32 bits, and the milliseconds the low 32 bits
+            let days = (v >> 32) as i32;
+            let millis = v as i32;
+            encode_interval(millis as i64 * 1000, days, 0)
+        }
+        other => {
+            return Err(PgServerError::InvalidRequest(format!(
+                "binary format of {} is not supported",
+                other.get_logical_type()
+            )))
+        }
+    };
+    Ok(Some(bytes))
+}
+
+fn encode_text(array: &ArrayRef, row: usize) -> Result<Vec<u8>, PgServerError> {
+    // postgres writes booleans as t and f
+    if let Some(array) = array.as_any().downcast_ref::<BooleanArray>() {
+        return Ok(if array.value(row) { b"t" } else { b"f" }.to_vec());
+    }
+    let text = array_value_to_string(array, row).map_err(DatabaseError::from)?;
+    Ok(text.into_bytes())
+}
+
+/// The binary format of numeric: the number of digits, the weight of the first digit, the sign
+/// and the display scale, then the digits in base 10000.
+fn encode_numeric(value: u64) -> Vec<u8> {
+    let mut digits = vec![];
+    let mut rest = value;
+    while rest > 0 {
+        digits.push((rest % 10000) as i16);
+        rest /= 10000;
+    }
+    digits.reverse();
+    let weight = digits.len().saturating_sub(1) as i16;
+    // the trailing zero digits are implied by the weight
+    while digits.last() == Some(&0) {
+        digits.pop();
+    }
+    let mut bytes = vec![];
+    bytes.extend_from_slice(&(digits.len() as i16).to_be_bytes());
+    bytes.extend_from_slice(&weight.to_be_bytes());
+    bytes.extend_from_slice(&0_i16.to_be_bytes());
+    bytes.extend_from_slice(&0_i16.to_be_bytes());
+    for digit in digits {
+        bytes.extend_from_slice(&digit.to_be_bytes());
+    }
+    bytes
+}
+
+fn encode_interval(micros: i64, days: i32, months: i32) -> Vec<u8> {
+    let mut bytes = micros.to_be_bytes().to_vec();
+    bytes.extend_from_slice(&days.to_be_bytes());
+    bytes.extend_from_slice(&months.to_be_bytes());
+    bytes
+}
+
+/// Decode the value of a parameter, `None` is NULL. A value in the text format is a string cast
+/// to the type of the parameter when the statement is bound, a value in the binary format is
+/// decoded by the type of the parameter.
+pub fn decode_parameter(
+    value: Option<&[u8]>,
+    oid: i32,
+    format: i16,
+) -> Result<ScalarValue, PgServerError> {
+    let value = match value {
+        Some(value) => value,
+        None => return Ok(ScalarValue::Null),
+    };
+    let invalid = || {
+        PgServerError::InvalidRequest(format!(
+            "invalid binary value of a parameter of type oid {}",
+            oid
+        ))
+    };
+    if format == TEXT_FORMAT || matches!(oid, TEXT_OID | VARCHAR_OID | UNKNOWN_OID) {
+        let text = std::str::from_utf8(value).map_err(|_| {
+            PgServerError::InvalidRequest("parameter value is not valid UTF-8".to_string())
+        })?;
+        return Ok(ScalarValue::Utf8(Some(text.to_string())));
+    }
+    let value = match oid {
+        BOOL_OID => match value {
+            [v] => ScalarValue::Boolean(Some(*v != 0)),
+            _ => return Err(invalid()),
+        },
+        INT2_OID => ScalarValue::Int16(Some(i16::from_be_bytes(
+            value.try_into().map_err(|_| invalid())?,
+        ))),
+        INT4_OID => ScalarValue::Int32(Some(i32::from_be_bytes(
+            value.try_into().map_err(|_| invalid())?,
+        ))),
+        INT8_OID => ScalarValue::Int64(Some(i64::from_be_bytes(
+            value.try_into().map_err(|_| invalid())?,
+        ))),
+        FLOAT4_OID => ScalarValue::Float32(Some(f32::from_be_bytes(
+            value.try_into().map_err(|_| invalid())?,
+        ))),
+        FLOAT8_OID => ScalarValue::Float64(Some(f64::from_be_bytes(
+            value.try_into().map_err(|_| invalid())?,
+        ))),
+        DATE_OID => {
+            let days = i32::from_be_bytes(value.try_into().map_err(|_| invalid())?);
+            ScalarValue::Date32(Some(days + POSTGRES_EPOCH_DAYS))
+        }
+        _ => {
+            return Err(PgServerError::InvalidRequest(format!(
+                "binary format of parameter type oid {} is not supported",
+                oid
+            )))
+        }
+    };
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_numeric() {
+        assert_eq!(encode_numeric(0), vec![0, 0, 0, 0, 0, 0, 0, 0]);
+        // 1_0000_0000 is the digit 1 with the weight 2
+        assert_eq!(
+            encode_numeric(100_000_000),
+            vec![0, 1, 0, 2, 0, 0, 0, 0, 0, 1]
+        );
+        assert_eq!(
+            encode_numeric(12_345),
+            vec![0, 2, 0, 1, 0, 0, 0, 0, 0, 1, 0x09, 0x29]
+        );
+    }
+}
@@ -29,6 +29,16 @@ impl BoundCastExpression {
         if source_type == target_type {
             return Ok(source_expr);
         }
+        let source_expr = match source_expr {
+            BoundExpression::BoundParameterExpression(mut parameter)
+                if parameter.base.return_type == LogicalType::SqlNull =>
+            {
+                // the parameter takes the type it's cast to, its value is cast when it's bound
+                parameter.base.return_type = target_type;
+                return Ok(BoundExpression::BoundParameterExpression(parameter));
+            }
+            other => other,
+        };
         let cast_function = DefaultCastFunctions::get_cast_function(&source_type, &target_type)?;
         let alias = format!("cast({}) as {}", source_expr.alias(), target_type);
         let base = BoundExpressionBase::new(alias, target_type);
@@ -50,4 +50,34 @@ impl ExpressionBinder<'_> {
         result_types.push(bound_function.base.return_type.clone());
         Ok(BoundExpression::BoundFunctionExpression(bound_function))
     }
+
+    pub fn bind_unary_function_expression(
+        &mut self,
+        op: &sqlparser::ast::UnaryOperator,
+        expr: &sqlparser::ast::Expr,
+        result_names: &mut Vec<String>,
+        result_types: &mut Vec<LogicalType>,
+    ) -> Result<BoundExpression, BindError> {
+        let function_name = match op {
+            sqlparser::ast::UnaryOperator::Not => "not",
+            other => {
+                return Err(BindError::UnsupportedExpr(format!(
+                    "unsupported unary operator {}",
+                    other
+                )))
+            }
+        };
+        let function = Catalog::get_scalar_function(
+            self.binder.clone_client_context(),
+            DEFAULT_SCHEMA.to_string(),
+            function_name.to_string(),
+        )?;
+        let mut return_names = vec![];
+        let child = self.bind_expression(expr, &mut return_names, &mut vec![])?;
+        let func_binder = FunctionBinder::new();
+        let bound_function = func_binder.bind_scalar_function(function, vec![child])?;
+        result_names.push(format!("{}({})", function_name, return_names.join(", ")));
+        result_types.push(bound_function.base.return_type.clone());
+        Ok(BoundExpression::BoundFunctionExpression(bound_function))
+    }
 }
@@ -0,0 +1,61 @@
+use derive_new::new;
+
+use super::{BoundExpression, BoundExpressionBase};
+use crate::planner_v2::{BindError, ExpressionBinder};
+use crate::types_v2::LogicalType;
+
+/// A `$1` or `?` placeholder of a prepared statement, it's replaced by the constant of its value
+/// before the plan is optimized.
+#[derive(new, Debug, Clone)]
+pub struct BoundParameterExpression {
+    pub(crate) base: BoundExpressionBase,
+    /// The number of the parameter, starting from 1
+    pub(crate) identifier: usize,
+}
+
+impl BoundParameterExpression {
+    /// The type of a parameter is unknown until it's cast to the type of its context, such as
+    /// the column it's compared to.
+    pub fn is_unresolved(expr: &BoundExpression) -> bool {
+        matches!(expr, BoundExpression::BoundParameterExpression(e)
+            if e.base.return_type == LogicalType::SqlNull)
+    }
+}
+
+impl ExpressionBinder<'_> {
+    pub fn bind_parameter_expr(
+        &mut self,
+        placeholder: &str,
+        result_names: &mut Vec<String>,
+        result_types: &mut Vec<LogicalType>,
+    ) -> Result<BoundExpression, BindError> {
+        let number = placeholder.trim_start_matches(|c| c == '$' || c == '?');
+        let identifier = if number.is_empty() && placeholder.starts_with('?') {
+            // the `?` placeholders are numbered by their position
+            self.binder.parameter_count + 1
+        } else {
+            match number.parse::<usize>() {
+                Ok(n) if n > 0 => n,
+                _ => {
+                    return Err(BindError::UnsupportedExpr(format!(
+                        "invalid parameter {}",
+                        placeholder
+                    )))
+                }
+            }
+        };
+        self.binder.parameter_count = self.binder.parameter_count.max(identifier);
+        let return_type = self
+            .binder
+            .parameter_types
+            .get(identifier - 1)
+            .cloned()
+            .unwrap_or(LogicalType::SqlNull);
+        let base = BoundExpressionBase::new(format!("${}", identifier), return_type);
+        result_names.push(base.alias.clone());
+        result_types.push(base.return_type.clone());
+        Ok(BoundExpression::BoundParameterExpression(
+            BoundParameterExpression::new(base, identifier),
+        ))
+    }
+}
@@ -4,6 +4,7 @@ mod bind_comparison_expression;
 mod bind_conjunction_expression;
 mod bind_constant_expression;
 mod bind_function_expression;
+mod bind_parameter_expression;
 mod bind_reference_expression;
 mod column_binding;
 
@@ -13,6 +14,7 @@ pub use bind_comparison_expression::*;
 pub use bind_conjunction_expression::*;
 pub use bind_constant_expression::*;
 pub use bind_function_expression::*;
+pub use bind_parameter_expression::*;
 pub use bind_reference_expression::*;
 pub use column_binding::*;
 use derive_new::new;
@@ -36,6 +38,7 @@ pub enum BoundExpression {
     BoundFunctionExpression(BoundFunctionExpression),
     BoundComparisonExpression(BoundComparisonExpression),
     BoundConjunctionExpression(BoundConjunctionExpression),
+    BoundParameterExpression(BoundParameterExpression),
 }
 
 impl BoundExpression {
@@ -48,6 +51,7 @@ impl BoundExpression {
             BoundExpression::BoundFunctionExpression(expr) => expr.base.return_type.clone(),
             BoundExpression::BoundComparisonExpression(expr) => expr.base.return_type.clone(),
             BoundExpression::BoundConjunctionExpression(expr) => expr.base.return_type.clone(),
+            BoundExpression::BoundParameterExpression(expr) => expr.base.return_type.clone(),
         }
     }
 
@@ -60,6 +64,7 @@ impl BoundExpression {
             BoundExpression::BoundFunctionExpression(expr) => expr.base.alias.clone(),
             BoundExpression::BoundComparisonExpression(expr) => expr.base.alias.clone(),
             BoundExpression::BoundConjunctionExpression(expr) => expr.base.alias.clone(),
+            BoundExpression::BoundParameterExpression(expr) => expr.base.alias.clone(),
         }
     }
 
@@ -72,6 +77,7 @@ impl BoundExpression {
             BoundExpression::BoundFunctionExpression(expr) => expr.base.alias = alias,
             BoundExpression::BoundComparisonExpression(expr) => expr.base.alias = alias,
             BoundExpression::BoundConjunctionExpression(expr) => expr.base.alias = alias,
+            BoundExpression::BoundParameterExpression(expr) => expr.base.alias = alias,
         }
     }
 }
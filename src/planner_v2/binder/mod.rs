@@ -21,6 +21,7 @@ pub use statement::*;
 pub use tableref::*;
 
 use crate::main_entry::ClientContext;
+use crate::types_v2::LogicalType;
 
 #[derive(Clone)]
 pub struct Binder {
@@ -30,6 +31,14 @@ pub struct Binder {
     bound_tables: usize,
     #[allow(dead_code)]
     parent: Option<Arc<Binder>>,
+    /// The declared types of the parameters of a prepared statement, such as
+    /// `PREPARE s(INT) AS ...`
+    pub(crate) parameter_types: Vec<LogicalType>,
+    /// The highest parameter number bound so far, the `?` parameters are numbered after it
+    pub(crate) parameter_count: usize,
+    /// The types of the table columns an INSERT writes, the parameters of its VALUES list take
+    /// these types
+    pub(crate) expected_values_types: Option<Vec<LogicalType>>,
 }
 
 impl Binder {
@@ -39,6 +48,9 @@ impl Binder {
             bind_context: BindContext::new(),
             bound_tables: 0,
             parent: None,
+            parameter_types: vec![],
+            parameter_count: 0,
+            expected_values_types: None,
         }
     }
 
@@ -47,7 +59,10 @@ impl Binder {
             client_context,
             bind_context: BindContext::new(),
             bound_tables: 0,
-            parent: Some(parent),
+            parent: Some(parent.clone()),
+            parameter_types: parent.parameter_types.clone(),
+            parameter_count: parent.parameter_count,
+            expected_values_types: None,
         }
     }
 
@@ -1,7 +1,4 @@
 use super::BoundSelectNode;
-use crate::planner_v2::BoundTableRef::{
-    BoundBaseTableRef, BoundDummyTableRef, BoundExpressionListRef, BoundTableFunction,
-};
 use crate::planner_v2::{
     BindError, Binder, BoundCastExpression, BoundStatement, LogicalFilter, LogicalOperator,
     LogicalOperatorBase, LogicalProjection,
@@ -13,14 +10,7 @@ impl Binder {
         &mut self,
         node: BoundSelectNode,
     ) -> Result<BoundStatement, BindError> {
-        let mut root = match node.from_table {
-            BoundExpressionListRef(bound_ref) => {
-                self.create_plan_for_expression_list_ref(bound_ref)?
-            }
-            BoundBaseTableRef(bound_ref) => self.create_plan_for_base_tabel_ref(*bound_ref)?,
-            BoundDummyTableRef(bound_ref) => self.create_plan_for_dummy_table_ref(bound_ref)?,
-            BoundTableFunction(bound_func) => self.create_plan_for_table_function(*bound_func)?,
-        };
+        let mut root = self.create_plan_for_table_ref(node.from_table)?;
 
         if let Some(where_clause) = node.where_clause {
             root = LogicalOperator::LogicalFilter(LogicalFilter::new(LogicalOperatorBase::new(
@@ -2,8 +2,8 @@ use std::collections::HashMap;
 
 use itertools::Itertools;
 use sqlparser::ast::{
-    BinaryOperator, ColumnDef, Expr, FunctionArg, FunctionArgExpr, Ident, ObjectName, Query,
-    Select, SelectItem, SetExpr, TableAlias, TableFactor, TableWithJoins, Value,
+    BinaryOperator, ColumnDef, ColumnOption, Expr, FunctionArg, FunctionArgExpr, Ident, ObjectName,
+    Query, Select, SelectItem, SetExpr, TableAlias, TableFactor, TableWithJoins, Value,
     WildcardAdditionalOptions,
 };
 
@@ -31,7 +31,12 @@ impl SqlparserResolver {
     ) -> Result<ColumnDefinition, BindError> {
         let name = column_def.name.value.clone().to_lowercase();
         let ty = column_def.data_type.clone().try_into()?;
-        Ok(ColumnDefinition::new(name, ty))
+        let mut column = ColumnDefinition::new(name, ty);
+        column.not_null = column_def
+            .options
+            .iter()
+            .any(|option| matches!(option.option, ColumnOption::NotNull));
+        Ok(column)
     }
 
     pub fn resolve_expr_idents(
@@ -84,6 +89,18 @@ impl SqlparserResolver {
         }
     }
 
+    pub fn resolve_expr_to_usize(e: &Expr) -> Result<usize, BindError> {
+        match e {
+            Expr::Value(Value::Number(v, _)) => v.parse::<usize>().map_err(|_| {
+                BindError::Internal(format!("excepted unsigned integer, but got: {}", v))
+            }),
+            _ => Err(BindError::Internal(format!(
+                "excepted value expr, but got: {}",
+                e
+            ))),
+        }
+    }
+
     pub fn resolve_func_arg_expr_to_string(arg: &FunctionArgExpr) -> Result<String, BindError> {
         if let FunctionArgExpr::Expr(e) = arg {
             return SqlparserResolver::resolve_expr_to_string(e);
@@ -94,6 +111,16 @@ impl SqlparserResolver {
         )))
     }
 
+    pub fn resolve_func_arg_expr_to_usize(arg: &FunctionArgExpr) -> Result<usize, BindError> {
+        if let FunctionArgExpr::Expr(e) = arg {
+            return SqlparserResolver::resolve_expr_to_usize(e);
+        }
+        Err(BindError::Internal(format!(
+            "expected unsigned integer arg, but got {}",
+            arg
+        )))
+    }
+
     pub fn resolve_func_arg_expr_to_bool(arg: &FunctionArgExpr) -> Result<bool, BindError> {
         if let FunctionArgExpr::Expr(e) = arg {
             return SqlparserResolver::resolve_expr_to_bool(e);
@@ -0,0 +1,45 @@
+use sqlparser::ast::Statement;
+
+use super::{BoundStatement, StatementReturnType};
+use crate::catalog_v2::{Catalog, CatalogEntry, DEFAULT_SCHEMA};
+use crate::planner_v2::{BindError, Binder, LogicalAnalyze, LogicalOperator, SqlparserResolver};
+use crate::types_v2::LogicalType;
+
+impl Binder {
+    pub fn bind_analyze(&mut self, stmt: &Statement) -> Result<BoundStatement, BindError> {
+        match stmt {
+            Statement::Analyze { table_name, .. } => {
+                let tables = if table_name.0.is_empty() {
+                    // a bare ANALYZE analyzes all tables
+                    Catalog::scan_entries(
+                        self.clone_client_context(),
+                        DEFAULT_SCHEMA.to_string(),
+                        &|entry| matches!(entry, CatalogEntry::TableCatalogEntry(_)),
+                    )?
+                    .into_iter()
+                    .filter_map(|entry| match entry {
+                        CatalogEntry::TableCatalogEntry(table) => Some(table),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>()
+                } else {
+                    let (schema, table) =
+                        SqlparserResolver::object_name_to_schema_table(table_name)?;
+                    vec![Catalog::get_table(
+                        self.clone_client_context(),
+                        schema,
+                        table,
+                    )?]
+                };
+                let plan = LogicalOperator::LogicalAnalyze(LogicalAnalyze::new(tables));
+                Ok(BoundStatement::new(
+                    plan,
+                    vec![LogicalType::Varchar],
+                    vec!["success".to_string()],
+                )
+                .with_return_type(StatementReturnType::Nothing))
+            }
+            _ => Err(BindError::UnsupportedStmt(format!("{:?}", stmt))),
+        }
+    }
+}
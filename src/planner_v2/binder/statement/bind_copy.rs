@@ -1,9 +1,14 @@
 use log::debug;
 use sqlparser::ast::{CopyOption, CopyTarget, Ident, ObjectName, Statement};
 
-use super::BoundStatement;
+use super::{BoundStatement, StatementReturnType};
+use crate::function::{CSVWriterOptionsBuilder, ParquetWriterOptions, ParquetWriterOptionsBuilder};
 use crate::parser::Sqlparser;
-use crate::planner_v2::{BindError, Binder, SqlparserResolver, LOGGING_TARGET};
+use crate::planner_v2::{
+    BindError, Binder, CopyToFileFormat, CopyToFileInfo, LogicalCopyToFile, LogicalOperator,
+    LogicalOperatorBase, SqlparserResolver, LOGGING_TARGET,
+};
+use crate::types_v2::LogicalType;
 
 impl Binder {
     /// convert copy from csv into insert statement from csv_read table function
@@ -34,6 +39,11 @@ impl Binder {
         Ok(format!("{} {}", insert_sql, csv_read_sql))
     }
 
+    /// Quote an option value as a sql string literal.
+    fn quote_option(value: impl ToString) -> String {
+        format!("'{}'", value.to_string().replace('\'', "''"))
+    }
+
     fn build_read_csv_sql(
         target: &CopyTarget,
         options: &[CopyOption],
@@ -50,8 +60,11 @@ impl Binder {
         let options_strs = options
             .iter()
             .filter_map(|o| match o {
-                CopyOption::Delimiter(v) => Some(format!("delim=>'{}'", v)),
+                CopyOption::Delimiter(v) => Some(format!("delim=>{}", Self::quote_option(v))),
                 CopyOption::Header(v) => Some(format!("header=>{}", v)),
+                CopyOption::Quote(v) => Some(format!("quote=>{}", Self::quote_option(v))),
+                CopyOption::Escape(v) => Some(format!("escape=>{}", Self::quote_option(v))),
+                CopyOption::Null(v) => Some(format!("nullstr=>{}", Self::quote_option(v))),
                 _ => None,
             })
             .collect::<Vec<_>>();
@@ -63,6 +76,228 @@ impl Binder {
         Ok(format!("read_csv('{}'{})", filename, options_str))
     }
 
+    fn option_byte(name: &str, value: char) -> Result<u8, BindError> {
+        if !value.is_ascii() {
+            return Err(BindError::UnsupportedStmt(format!(
+                "copy option {} must be a single byte",
+                name
+            )));
+        }
+        Ok(value as u8)
+    }
+
+    /// The single value of an option rewritten by the parser.
+    fn option_value<'a>(name: &str, values: &'a [Ident]) -> Result<&'a str, BindError> {
+        match values {
+            [value] => Ok(value.value.as_str()),
+            _ => Err(BindError::UnsupportedStmt(format!(
+                "copy option {} expects one value",
+                name
+            ))),
+        }
+    }
+
+    /// The format of the written files, and the names of the partition columns. The format is
+    /// given by the FORMAT option, or by the extension of the file.
+    fn build_copy_to_options(
+        file_path: &str,
+        options: &[CopyOption],
+    ) -> Result<(CopyToFileFormat, Vec<String>), BindError> {
+        let file_path = file_path.to_lowercase();
+        let mut format = if file_path.ends_with(".parquet") {
+            "parquet".to_string()
+        } else if [".json", ".ndjson", ".jsonl"]
+            .iter()
+            .any(|e| file_path.ends_with(e))
+        {
+            "json".to_string()
+        } else if file_path.ends_with(".arrow") || file_path.ends_with(".feather") {
+            "arrow".to_string()
+        } else {
+            "csv".to_string()
+        };
+        let mut csv = CSVWriterOptionsBuilder::default();
+        let mut parquet = ParquetWriterOptionsBuilder::default();
+        // the options given for each format, to check they match the format
+        let mut csv_options = vec![];
+        let mut parquet_options = vec![];
+        let mut partition_names = vec![];
+        for option in options {
+            match option {
+                CopyOption::Format(f) => format = f.value.to_lowercase(),
+                CopyOption::Delimiter(v) => {
+                    csv.delimiter(Self::option_byte("DELIMITER", *v)?);
+                    csv_options.push("DELIMITER");
+                }
+                CopyOption::Quote(v) => {
+                    csv.quote(Self::option_byte("QUOTE", *v)?);
+                    csv_options.push("QUOTE");
+                }
+                CopyOption::Escape(v) => {
+                    csv.escape(Self::option_byte("ESCAPE", *v)?);
+                    csv_options.push("ESCAPE");
+                }
+                CopyOption::Header(v) => {
+                    csv.header(*v);
+                    csv_options.push("HEADER");
+                }
+                CopyOption::Null(v) => {
+                    csv.null_str(v.clone());
+                    csv_options.push("NULL");
+                }
+                // the options sqlparser doesn't know are rewritten by the parser
+                CopyOption::ForceNotNull(values) if !values.is_empty() => {
+                    let (name, values) = (values[0].value.as_str(), &values[1..]);
+                    match name {
+                        "partition_by" => {
+                            partition_names.extend(values.iter().map(|c| c.value.to_lowercase()))
+                        }
+                        "compression" => {
+                            let value = Self::option_value(name, values)?;
+                            parquet.compression(ParquetWriterOptions::parse_compression(value)?);
+                            parquet_options.push("COMPRESSION");
+                        }
+                        "row_group_size" => {
+                            let value = Self::option_value(name, values)?;
+                            let size = value.parse::<usize>().map_err(|_| {
+                                BindError::UnsupportedStmt(format!(
+                                    "invalid ROW_GROUP_SIZE {}",
+                                    value
+                                ))
+                            })?;
+                            parquet.row_group_size(size);
+                            parquet_options.push("ROW_GROUP_SIZE");
+                        }
+                        other => {
+                            return Err(BindError::UnsupportedStmt(format!(
+                                "unsupported copy to option {}",
+                                other.to_uppercase()
+                            )))
+                        }
+                    }
+                }
+                other => {
+                    return Err(BindError::UnsupportedStmt(format!(
+                        "unsupported copy to option {}",
+                        other
+                    )))
+                }
+            }
+        }
+        let (file_format, other_options) = match format.as_str() {
+            "csv" => (CopyToFileFormat::Csv(csv.build().unwrap()), parquet_options),
+            "parquet" => (
+                CopyToFileFormat::Parquet(parquet.build().unwrap()),
+                csv_options,
+            ),
+            "json" => (
+                CopyToFileFormat::Json,
+                [csv_options, parquet_options].concat(),
+            ),
+            "arrow" => (
+                CopyToFileFormat::Arrow,
+                [csv_options, parquet_options].concat(),
+            ),
+            other => {
+                return Err(BindError::UnsupportedStmt(format!(
+                    "unsupported copy to format {}",
+                    other
+                )))
+            }
+        };
+        if let Some(option) = other_options.first() {
+            return Err(BindError::UnsupportedStmt(format!(
+                "copy option {} is not supported by format {}",
+                option, format
+            )));
+        }
+        Ok((file_format, partition_names))
+    }
+
+    /// The query of `COPY (query) TO`, or a select statement of the columns of the table.
+    fn build_copy_to_query(
+        table_name: &ObjectName,
+        columns: &[Ident],
+        query: Option<&str>,
+    ) -> Result<String, BindError> {
+        if let Some(query) = query {
+            return Ok(query.to_string());
+        }
+        let (schema_name, table_name) = SqlparserResolver::object_name_to_schema_table(table_name)?;
+        let select_cols = if columns.is_empty() {
+            "*".to_string()
+        } else {
+            columns
+                .iter()
+                .map(|c| c.to_string().to_lowercase())
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        Ok(format!(
+            "select {} from {}.{}",
+            select_cols, schema_name, table_name
+        ))
+    }
+
+    /// bind copy to a file, or to a directory partitioned by columns
+    fn bind_copy_to(
+        &mut self,
+        table_name: &ObjectName,
+        columns: &[Ident],
+        query: Option<&str>,
+        target: &CopyTarget,
+        options: &[CopyOption],
+    ) -> Result<BoundStatement, BindError> {
+        let file_path = match target {
+            CopyTarget::File { filename } => filename.clone(),
+            _ => {
+                return Err(BindError::UnsupportedStmt(format!(
+                    "unsupported copy target {:?}",
+                    target
+                )))
+            }
+        };
+        let (format, partition_names) = Self::build_copy_to_options(&file_path, options)?;
+        let select_sql = Self::build_copy_to_query(table_name, columns, query)?;
+        debug!(
+            target: LOGGING_TARGET,
+            "Copy to converted raw sql: {:?}", select_sql
+        );
+        let query = Sqlparser::parse_one_query(&select_sql)?;
+        let bound_query = self.bind_query(&query)?;
+
+        let partition_by = partition_names
+            .iter()
+            .map(|name| {
+                bound_query
+                    .names
+                    .iter()
+                    .position(|n| n == name)
+                    .ok_or_else(|| {
+                        BindError::Internal(format!("partition column {} not found", name))
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if !partition_by.is_empty() && partition_by.len() == bound_query.names.len() {
+            return Err(BindError::Internal(
+                "copy to needs at least one column that is not a partition column".to_string(),
+            ));
+        }
+        let info = CopyToFileInfo::new(
+            file_path,
+            bound_query.names,
+            bound_query.types,
+            partition_by,
+            format,
+        );
+        let base = LogicalOperatorBase::new(vec![bound_query.plan], vec![], vec![]);
+        let plan = LogicalOperator::LogicalCopyToFile(LogicalCopyToFile::new(base, info));
+        Ok(
+            BoundStatement::new(plan, vec![LogicalType::Bigint], vec!["Count".to_string()])
+                .with_return_type(StatementReturnType::ChangedRows),
+        )
+    }
+
     pub fn bind_copy(&mut self, stmt: &Statement) -> Result<BoundStatement, BindError> {
         match stmt {
             Statement::Copy {
@@ -72,11 +307,15 @@ impl Binder {
                 target,
                 options,
                 legacy_options: _,
-                values: _,
+                values,
             } => {
+                let query = Sqlparser::copy_query(table_name, values);
                 if *to {
+                    return self.bind_copy_to(table_name, columns, query, target, options);
+                }
+                if query.is_some() {
                     return Err(BindError::UnsupportedStmt(
-                        "unsupported copy to statement".to_string(),
+                        "copy from can't read into a query".to_string(),
                     ));
                 }
 
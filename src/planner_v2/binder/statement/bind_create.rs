@@ -1,7 +1,7 @@
 use itertools::Itertools;
 use sqlparser::ast::Statement;
 
-use super::BoundStatement;
+use super::{BoundStatement, StatementReturnType};
 use crate::catalog_v2::ColumnDefinition;
 use crate::common::{CreateInfoBase, CreateTableInfo};
 use crate::planner_v2::{
@@ -37,14 +37,26 @@ impl Binder {
                         .try_collect()?;
                     (cols, None)
                 };
+                // create table as returns the count of the inserted rows, like an insert
+                let (types, names, return_type) = if query.is_some() {
+                    let names = vec!["Count".to_string()];
+                    (
+                        vec![LogicalType::Bigint],
+                        names,
+                        StatementReturnType::ChangedRows,
+                    )
+                } else {
+                    let names = vec!["success".to_string()];
+                    (
+                        vec![LogicalType::Varchar],
+                        names,
+                        StatementReturnType::Nothing,
+                    )
+                };
                 let bound_info =
                     BoundCreateTableInfo::new(schema, table, column_definitions, query);
                 let plan = LogicalOperator::LogicalCreateTable(LogicalCreateTable::new(bound_info));
-                Ok(BoundStatement::new(
-                    plan,
-                    vec![LogicalType::Varchar],
-                    vec!["success".to_string()],
-                ))
+                Ok(BoundStatement::new(plan, types, names).with_return_type(return_type))
             }
             _ => Err(BindError::UnsupportedStmt(format!("{:?}", stmt))),
         }
@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use sqlparser::ast::Statement;
 
-use super::BoundStatement;
+use super::{BoundStatement, StatementReturnType};
 use crate::catalog_v2::Catalog;
 use crate::planner_v2::{
     BindError, Binder, LogicalInsert, LogicalOperator, LogicalOperatorBase, SqlparserResolver,
@@ -81,7 +81,10 @@ impl Binder {
                     }
                 }
 
-                let select_node = self.bind_query(source)?;
+                self.expected_values_types = Some(expected_types.clone());
+                let select_node = self.bind_query(source);
+                self.expected_values_types = None;
+                let select_node = select_node?;
                 let expected_columns_cnt = named_column_indices.len();
 
                 let inserted_types = select_node.types;
@@ -101,9 +104,10 @@ impl Binder {
                 );
                 Ok(BoundStatement::new(
                     LogicalOperator::LogicalInsert(root),
-                    vec![LogicalType::Varchar],
-                    vec!["success".to_string()],
-                ))
+                    vec![LogicalType::Bigint],
+                    vec!["Count".to_string()],
+                )
+                .with_return_type(StatementReturnType::ChangedRows))
             }
             _ => Err(BindError::UnsupportedStmt(format!("{:?}", stmt))),
         }
@@ -0,0 +1,93 @@
+use std::time::Duration;
+
+use sqlparser::ast::{Expr, Statement, Value};
+
+use super::{BoundStatement, StatementReturnType};
+use crate::main_entry::{ClientConfigOption, ConfigOption, DBConfigOption};
+use crate::planner_v2::{BindError, Binder, LogicalOperator, LogicalSet};
+use crate::types_v2::LogicalType;
+
+impl Binder {
+    pub fn bind_set(&mut self, stmt: &Statement) -> Result<BoundStatement, BindError> {
+        match stmt {
+            Statement::SetVariable {
+                variable, value, ..
+            } => {
+                let name = variable.to_string().to_lowercase();
+                let option = match name.as_str() {
+                    "threads" => {
+                        ConfigOption::Database(DBConfigOption::Threads(Self::bind_threads(value)?))
+                    }
+                    "statement_timeout" => ConfigOption::Client(
+                        ClientConfigOption::StatementTimeout(Self::bind_statement_timeout(value)?),
+                    ),
+                    _ => {
+                        return Err(BindError::UnsupportedStmt(format!(
+                            "unrecognized configuration parameter: {}",
+                            name
+                        )))
+                    }
+                };
+                let plan = LogicalOperator::LogicalSet(LogicalSet::new(option));
+                Ok(BoundStatement::new(
+                    plan,
+                    vec![LogicalType::Varchar],
+                    vec!["success".to_string()],
+                )
+                .with_return_type(StatementReturnType::Nothing))
+            }
+            _ => Err(BindError::UnsupportedStmt(format!("{:?}", stmt))),
+        }
+    }
+
+    fn bind_threads(value: &[Expr]) -> Result<usize, BindError> {
+        let threads = match value {
+            [Expr::Value(Value::Number(v, _))] => v.parse::<usize>().ok(),
+            _ => None,
+        };
+        match threads {
+            Some(threads) if threads > 0 => Ok(threads),
+            _ => Err(BindError::Internal(format!(
+                "threads must be a positive integer, got: {:?}",
+                value
+            ))),
+        }
+    }
+
+    /// The timeout is given in milliseconds like `1000`, or with a unit like `'5s'`. A timeout of
+    /// zero disables it.
+    fn bind_statement_timeout(value: &[Expr]) -> Result<Option<Duration>, BindError> {
+        let millis = match value {
+            [Expr::Value(Value::Number(v, _))] => v.parse::<u64>().ok(),
+            [Expr::Value(Value::SingleQuotedString(v))] => {
+                let v = v.trim();
+                let unit_start = v.find(|c: char| !c.is_ascii_digit()).unwrap_or(v.len());
+                let (number, unit) = v.split_at(unit_start);
+                let factor = match unit.trim() {
+                    "" | "ms" => Some(1),
+                    "s" => Some(1000),
+                    "min" => Some(60 * 1000),
+                    _ => None,
+                };
+                match (number.parse::<u64>().ok(), factor) {
+                    (Some(n), Some(factor)) => Some(n.checked_mul(factor).ok_or_else(|| {
+                        BindError::Internal(format!(
+                            "statement_timeout is out of range, got: {:?}",
+                            value
+                        ))
+                    })?),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+        match millis {
+            Some(0) => Ok(None),
+            Some(millis) => Ok(Some(Duration::from_millis(millis))),
+            None => Err(BindError::Internal(format!(
+                "statement_timeout must be a non-negative number of milliseconds, got: {:?}",
+                value
+            ))),
+        }
+    }
+}
@@ -1,9 +1,11 @@
+mod bind_analyze;
 mod bind_copy;
 mod bind_create;
 mod bind_explain;
 mod bind_explain_table;
 mod bind_insert;
 mod bind_select;
+mod bind_set;
 mod bind_show_tables;
 
 pub use bind_create::*;
@@ -19,6 +21,26 @@ pub struct BoundStatement {
     pub(crate) plan: LogicalOperator,
     pub(crate) types: Vec<LogicalType>,
     pub(crate) names: Vec<String>,
+    #[new(value = "StatementReturnType::QueryResult")]
+    pub(crate) return_type: StatementReturnType,
+}
+
+impl BoundStatement {
+    pub(crate) fn with_return_type(mut self, return_type: StatementReturnType) -> Self {
+        self.return_type = return_type;
+        self
+    }
+}
+
+/// What the result of a statement holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatementReturnType {
+    /// The rows of a query
+    QueryResult,
+    /// The count of the rows written by the statement, as a single `Count` column
+    ChangedRows,
+    /// Only the success of the statement
+    Nothing,
 }
 
 impl Binder {
@@ -31,6 +53,8 @@ impl Binder {
             Statement::ShowTables { .. } => self.bind_show_tables(statement),
             Statement::ExplainTable { .. } => self.bind_explain_table(statement),
             Statement::Copy { .. } => self.bind_copy(statement),
+            Statement::Analyze { .. } => self.bind_analyze(statement),
+            Statement::SetVariable { .. } => self.bind_set(statement),
             _ => Err(BindError::UnsupportedStmt(format!("{:?}", statement))),
         }
     }
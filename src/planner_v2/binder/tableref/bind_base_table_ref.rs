@@ -6,7 +6,7 @@ use sqlparser::ast::TableFactor;
 
 use super::BoundTableRef;
 use crate::catalog_v2::{Catalog, CatalogEntry, TableCatalogEntry};
-use crate::function::{SeqTableScan, TableFunctionBindInput};
+use crate::function::{FileCompression, SeqTableScan, TableFunctionBindInput};
 use crate::planner_v2::{
     BindError, Binder, LogicalGet, LogicalOperator, LogicalOperatorBase, SqlparserResolver,
     SqlparserTableFactorBuilder,
@@ -96,7 +96,9 @@ impl Binder {
 
     /// Replacement table scans are automatically attempted when a table name cannot be found in the
     /// schema. This allows you to do e.g. SELECT * FROM 'filename.csv', and automatically
-    /// convert this into a CSV scan
+    /// convert this into a CSV scan. Compressed files like 'filename.csv.gz' are scanned too,
+    /// 'filename.parquet' is converted into a parquet scan, 'filename.json' into a json scan and
+    /// 'filename.arrow' into an arrow IPC scan.
     fn bind_replacement_table_factor(
         &mut self,
         table_name: String,
@@ -104,24 +106,34 @@ impl Binder {
     ) -> Option<TableFactor> {
         let table_name = table_name.to_lowercase();
         let mut alias = alias.to_lowercase();
-        if table_name.ends_with(".csv") {
-            if table_name == alias {
-                // which means the alias is not set, so we simply use the filename
-                alias = Path::new(table_name.as_str())
-                    .file_stem()
-                    .unwrap()
-                    .to_str()
-                    .unwrap()
-                    .to_string();
-            }
-            Some(SqlparserTableFactorBuilder::build_table_func(
-                "read_csv",
-                alias,
-                vec![table_name],
-                HashMap::new(),
-            ))
+        let file_name = FileCompression::strip_extension(&table_name);
+        let function_name = if file_name.ends_with(".csv") {
+            "read_csv"
+        } else if table_name.ends_with(".parquet") {
+            "read_parquet"
+        } else if file_name.ends_with(".json") {
+            "read_json"
+        } else if file_name.ends_with(".ndjson") || file_name.ends_with(".jsonl") {
+            "read_ndjson"
+        } else if table_name.ends_with(".arrow") || table_name.ends_with(".feather") {
+            "read_arrow"
         } else {
-            None
+            return None;
+        };
+        if table_name == alias {
+            // which means the alias is not set, so we simply use the filename
+            alias = Path::new(file_name)
+                .file_stem()
+                .unwrap()
+                .to_str()
+                .unwrap()
+                .to_string();
         }
+        Some(SqlparserTableFactorBuilder::build_table_func(
+            function_name,
+            alias,
+            vec![table_name],
+            HashMap::new(),
+        ))
     }
 }
@@ -2,7 +2,8 @@ use derive_new::new;
 use sqlparser::ast::Values;
 
 use crate::planner_v2::{
-    BindError, Binder, BoundCastExpression, BoundExpression, ExpressionBinder,
+    BindError, Binder, BoundCastExpression, BoundExpression, BoundParameterExpression,
+    ExpressionBinder,
 };
 use crate::types_v2::LogicalType;
 
@@ -42,12 +43,22 @@ impl Binder {
         let mut names = vec!["".to_string(); values_cnt];
         let mut types = vec![LogicalType::Invalid; values_cnt];
 
+        let expected_types = self.expected_values_types.take();
         let mut expr_binder = ExpressionBinder::new(self);
 
         for val_expr_list in values.rows.iter() {
             let mut bound_expr_row = vec![];
             for (idx, expr) in val_expr_list.iter().enumerate() {
-                let bound_expr = expr_binder.bind_expression(expr, &mut vec![], &mut vec![])?;
+                let mut bound_expr = expr_binder.bind_expression(expr, &mut vec![], &mut vec![])?;
+                if let Some(ty) = expected_types.as_ref().and_then(|types| types.get(idx)) {
+                    if BoundParameterExpression::is_unresolved(&bound_expr) {
+                        bound_expr = BoundCastExpression::try_add_cast_to_type(
+                            bound_expr,
+                            ty.clone(),
+                            false,
+                        )?;
+                    }
+                }
                 names[idx] = format!("col{}", idx);
                 if types[idx] == LogicalType::Invalid {
                     types[idx] = bound_expr.return_type().clone();
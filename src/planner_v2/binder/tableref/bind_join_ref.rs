@@ -0,0 +1,53 @@
+use derive_new::new;
+use sqlparser::ast::{JoinConstraint, JoinOperator};
+
+use super::BoundTableRef;
+use crate::planner_v2::{BindError, Binder, BoundExpression, ExpressionBinder};
+
+/// Represents a join between two bound table references. Only inner joins and cross products are
+/// supported, so a join is always planned as a cross product with an optional filter on top.
+#[derive(new, Debug)]
+pub struct BoundJoinRef {
+    /// The left hand side of the join
+    pub(crate) left: BoundTableRef,
+    /// The right hand side of the join
+    pub(crate) right: BoundTableRef,
+    /// The join condition, None for a cross product
+    pub(crate) condition: Option<BoundExpression>,
+}
+
+impl Binder {
+    pub fn bind_table_with_joins(
+        &mut self,
+        table: &sqlparser::ast::TableWithJoins,
+    ) -> Result<BoundTableRef, BindError> {
+        let mut result = self.bind_table_factor(table.relation.clone())?;
+        for join in table.joins.iter() {
+            result = self.bind_join_ref(result, join)?;
+        }
+        Ok(result)
+    }
+
+    pub fn bind_join_ref(
+        &mut self,
+        left: BoundTableRef,
+        join: &sqlparser::ast::Join,
+    ) -> Result<BoundTableRef, BindError> {
+        let right = self.bind_table_factor(join.relation.clone())?;
+        let condition = match &join.join_operator {
+            JoinOperator::Inner(JoinConstraint::On(expr)) => {
+                let mut expr_binder = ExpressionBinder::new(self);
+                Some(expr_binder.bind_expression(expr, &mut vec![], &mut vec![])?)
+            }
+            JoinOperator::Inner(JoinConstraint::None) | JoinOperator::CrossJoin => None,
+            other => {
+                return Err(BindError::UnsupportedStmt(format!(
+                    "unsupported join type: {:?}",
+                    other
+                )))
+            }
+        };
+        let bound_join_ref = BoundJoinRef::new(left, right, condition);
+        Ok(BoundTableRef::BoundJoinRef(Box::new(bound_join_ref)))
+    }
+}
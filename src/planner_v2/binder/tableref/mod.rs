@@ -1,22 +1,26 @@
 mod bind_base_table_ref;
 mod bind_dummy_table_ref;
 mod bind_expression_list_ref;
+mod bind_join_ref;
 mod bind_table_function;
 mod plan_base_table_ref;
 mod plan_dummy_table_ref;
 mod plan_expression_list_ref;
+mod plan_join_ref;
 mod plan_table_function;
 
 pub use bind_base_table_ref::*;
 pub use bind_dummy_table_ref::*;
 pub use bind_expression_list_ref::*;
+pub use bind_join_ref::*;
 pub use bind_table_function::*;
 pub use plan_base_table_ref::*;
 pub use plan_dummy_table_ref::*;
 pub use plan_expression_list_ref::*;
+pub use plan_join_ref::*;
 pub use plan_table_function::*;
 
-use super::{BindError, Binder};
+use super::{BindError, Binder, LogicalOperator};
 
 #[derive(Debug)]
 pub enum BoundTableRef {
@@ -24,6 +28,7 @@ pub enum BoundTableRef {
     BoundBaseTableRef(Box<BoundBaseTableRef>),
     BoundDummyTableRef(BoundDummyTableRef),
     BoundTableFunction(Box<BoundTableFunction>),
+    BoundJoinRef(Box<BoundJoinRef>),
 }
 
 impl Binder {
@@ -34,15 +39,46 @@ impl Binder {
         if table_refs.is_empty() {
             return self.bind_dummy_table_ref();
         }
-        let first_table = table_refs[0].clone();
-        match first_table.relation.clone() {
-            sqlparser::ast::TableFactor::Table { .. } => {
-                self.bind_base_table_ref(first_table.relation)
-            }
+        // multiple tables in the FROM clause are bound as cross products
+        let mut result = self.bind_table_with_joins(&table_refs[0])?;
+        for table in table_refs.iter().skip(1) {
+            let right = self.bind_table_with_joins(table)?;
+            result = BoundTableRef::BoundJoinRef(Box::new(BoundJoinRef::new(result, right, None)));
+        }
+        Ok(result)
+    }
+
+    pub fn bind_table_factor(
+        &mut self,
+        table: sqlparser::ast::TableFactor,
+    ) -> Result<BoundTableRef, BindError> {
+        match table {
+            sqlparser::ast::TableFactor::Table { .. } => self.bind_base_table_ref(table),
             other => Err(BindError::Internal(format!(
                 "unexpected table type: {}",
                 other
             ))),
         }
     }
+
+    pub fn create_plan_for_table_ref(
+        &mut self,
+        bound_ref: BoundTableRef,
+    ) -> Result<LogicalOperator, BindError> {
+        match bound_ref {
+            BoundTableRef::BoundExpressionListRef(bound_ref) => {
+                self.create_plan_for_expression_list_ref(bound_ref)
+            }
+            BoundTableRef::BoundBaseTableRef(bound_ref) => {
+                self.create_plan_for_base_tabel_ref(*bound_ref)
+            }
+            BoundTableRef::BoundDummyTableRef(bound_ref) => {
+                self.create_plan_for_dummy_table_ref(bound_ref)
+            }
+            BoundTableRef::BoundTableFunction(bound_func) => {
+                self.create_plan_for_table_function(*bound_func)
+            }
+            BoundTableRef::BoundJoinRef(bound_ref) => self.create_plan_for_join_ref(*bound_ref),
+        }
+    }
 }
@@ -0,0 +1,23 @@
+use super::BoundJoinRef;
+use crate::planner_v2::{
+    BindError, Binder, LogicalCrossProduct, LogicalFilter, LogicalOperator, LogicalOperatorBase,
+};
+
+impl Binder {
+    pub fn create_plan_for_join_ref(
+        &mut self,
+        bound_ref: BoundJoinRef,
+    ) -> Result<LogicalOperator, BindError> {
+        let left = self.create_plan_for_table_ref(bound_ref.left)?;
+        let right = self.create_plan_for_table_ref(bound_ref.right)?;
+        let mut root = LogicalCrossProduct::create(left, right);
+        if let Some(condition) = bound_ref.condition {
+            root = LogicalOperator::LogicalFilter(LogicalFilter::new(LogicalOperatorBase::new(
+                vec![root],
+                vec![condition],
+                vec![],
+            )));
+        }
+        Ok(root)
+    }
+}
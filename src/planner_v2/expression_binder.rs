@@ -36,7 +36,12 @@ impl ExpressionBinder<'_> {
             sqlparser::ast::Expr::BinaryOp { left, op, right } => {
                 self.bind_binary_op_internal(left, op, right, result_names, result_types)
             }
-            sqlparser::ast::Expr::UnaryOp { .. } => todo!(),
+            sqlparser::ast::Expr::UnaryOp { op, expr } => {
+                self.bind_unary_function_expression(op, expr, result_names, result_types)
+            }
+            sqlparser::ast::Expr::Value(sqlparser::ast::Value::Placeholder(p)) => {
+                self.bind_parameter_expr(p, result_names, result_types)
+            }
             sqlparser::ast::Expr::Value(v) => {
                 self.bind_constant_expr(v, result_names, result_types)
             }
@@ -10,7 +10,8 @@ impl ExpressionIterator {
         match expr {
             BoundExpression::BoundColumnRefExpression(_)
             | BoundExpression::BoundConstantExpression(_)
-            | BoundExpression::BoundReferenceExpression(_) => {
+            | BoundExpression::BoundReferenceExpression(_)
+            | BoundExpression::BoundParameterExpression(_) => {
                 // these node types have no children
             }
             BoundExpression::BoundCastExpression(e) => callback(&mut e.child),
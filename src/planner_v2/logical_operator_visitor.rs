@@ -1,7 +1,7 @@
 use super::{
     BoundCastExpression, BoundColumnRefExpression, BoundComparisonExpression,
     BoundConjunctionExpression, BoundConstantExpression, BoundExpression, BoundFunctionExpression,
-    BoundReferenceExpression, ExpressionIterator, LogicalOperator,
+    BoundParameterExpression, BoundReferenceExpression, ExpressionIterator, LogicalOperator,
 };
 
 /// Visitor pattern on logical operators, also includes rewrite expression ability.
@@ -39,6 +39,7 @@ pub trait LogicalOperatorVisitor {
             BoundExpression::BoundFunctionExpression(e) => self.visit_function_expression(e),
             BoundExpression::BoundComparisonExpression(e) => self.visit_comparison_expression(e),
             BoundExpression::BoundConjunctionExpression(e) => self.visit_conjunction_expression(e),
+            BoundExpression::BoundParameterExpression(e) => self.visit_replace_parameter(e),
         };
         if let Some(new_expr) = result {
             *expr = new_expr;
@@ -78,4 +79,7 @@ pub trait LogicalOperatorVisitor {
     ) -> Option<BoundExpression> {
         None
     }
+    fn visit_replace_parameter(&self, _: &BoundParameterExpression) -> Option<BoundExpression> {
+        None
+    }
 }
@@ -6,6 +6,7 @@ mod expression_iterator;
 mod function_binder;
 mod logical_operator_visitor;
 mod operator;
+mod parameter_binder;
 
 use std::sync::Arc;
 
@@ -18,6 +19,7 @@ pub use function_binder::*;
 use log::debug;
 pub use logical_operator_visitor::*;
 pub use operator::*;
+pub use parameter_binder::*;
 use sqlparser::ast::Statement;
 
 use crate::main_entry::ClientContext;
@@ -33,6 +35,9 @@ pub struct Planner {
     pub(crate) plan: Option<LogicalOperator>,
     pub(crate) types: Option<Vec<LogicalType>>,
     pub(crate) names: Option<Vec<String>>,
+    /// The types of the parameters of the statement, such as `$1`
+    pub(crate) parameter_types: Option<Vec<LogicalType>>,
+    pub(crate) return_type: Option<StatementReturnType>,
 }
 
 impl Planner {
@@ -43,16 +48,25 @@ impl Planner {
             plan: None,
             types: None,
             names: None,
+            parameter_types: None,
+            return_type: None,
         }
     }
 
+    /// Declare the types of the parameters, the other parameters get the types inferred from
+    /// their context.
+    pub fn set_parameter_types(&mut self, parameter_types: Vec<LogicalType>) {
+        self.binder.parameter_types = parameter_types;
+    }
+
     pub fn create_plan(&mut self, statement: &Statement) -> Result<(), PlannerError> {
         debug!(
             target: LOGGING_TARGET,
             "Planner raw statement: {:?}", statement
         );
 
-        let bound_statement = self.binder.bind(statement)?;
+        let mut bound_statement = self.binder.bind(statement)?;
+        let parameter_types = ParameterBinder::parameter_types(&mut bound_statement.plan)?;
 
         debug!(
             target: LOGGING_TARGET,
@@ -68,6 +82,8 @@ plan:
         self.plan = Some(bound_statement.plan);
         self.names = Some(bound_statement.names);
         self.types = Some(bound_statement.types);
+        self.parameter_types = Some(parameter_types);
+        self.return_type = Some(bound_statement.return_type);
         Ok(())
     }
 }
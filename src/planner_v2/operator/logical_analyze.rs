@@ -0,0 +1,13 @@
+use derive_new::new;
+
+use super::LogicalOperatorBase;
+use crate::catalog_v2::TableCatalogEntry;
+
+/// LogicalAnalyze computes the statistics of a set of tables and stores them in the catalog.
+#[derive(new, Debug, Clone)]
+pub struct LogicalAnalyze {
+    #[new(default)]
+    pub(crate) base: LogicalOperatorBase,
+    /// The tables to analyze
+    pub(crate) tables: Vec<TableCatalogEntry>,
+}
@@ -0,0 +1,49 @@
+use derive_new::new;
+
+use super::LogicalOperatorBase;
+use crate::function::{CSVWriterOptions, ParquetWriterOptions};
+use crate::types_v2::LogicalType;
+
+/// The format of the files written by COPY TO, with its options.
+#[derive(Debug, Clone)]
+pub enum CopyToFileFormat {
+    Csv(CSVWriterOptions),
+    Parquet(ParquetWriterOptions),
+    /// Newline delimited json
+    Json,
+    /// The arrow IPC file format
+    Arrow,
+}
+
+impl CopyToFileFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            CopyToFileFormat::Csv(_) => "csv",
+            CopyToFileFormat::Parquet(_) => "parquet",
+            CopyToFileFormat::Json => "json",
+            CopyToFileFormat::Arrow => "arrow",
+        }
+    }
+}
+
+/// The target of a COPY TO statement.
+#[derive(new, Debug, Clone)]
+pub struct CopyToFileInfo {
+    /// The file to write, or the directory to write the partitions to
+    pub(crate) file_path: String,
+    /// The names of the columns of the child
+    pub(crate) names: Vec<String>,
+    /// The types of the columns of the child
+    pub(crate) types: Vec<LogicalType>,
+    /// The indices of the partition columns in the child, their values are written as
+    /// `key=value` directories instead of columns
+    pub(crate) partition_by: Vec<usize>,
+    pub(crate) format: CopyToFileFormat,
+}
+
+/// LogicalCopyToFile writes the rows of its child into files.
+#[derive(new, Debug, Clone)]
+pub struct LogicalCopyToFile {
+    pub(crate) base: LogicalOperatorBase,
+    pub(crate) info: CopyToFileInfo,
+}
@@ -0,0 +1,20 @@
+use derive_new::new;
+
+use super::{LogicalOperator, LogicalOperatorBase};
+
+/// LogicalCrossProduct represents a cross product between two relations. Inner joins are planned
+/// as a cross product with a filter on top, the join order optimizer reorders these.
+#[derive(new, Debug, Clone)]
+pub struct LogicalCrossProduct {
+    pub(crate) base: LogicalOperatorBase,
+}
+
+impl LogicalCrossProduct {
+    pub fn create(left: LogicalOperator, right: LogicalOperator) -> LogicalOperator {
+        LogicalOperator::LogicalCrossProduct(LogicalCrossProduct::new(LogicalOperatorBase::new(
+            vec![left, right],
+            vec![],
+            vec![],
+        )))
+    }
+}
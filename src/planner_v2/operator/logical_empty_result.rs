@@ -0,0 +1,27 @@
+use super::{LogicalOperator, LogicalOperatorBase};
+use crate::planner_v2::ColumnBinding;
+use crate::types_v2::LogicalType;
+
+/// LogicalEmptyResult returns an empty result. This is created by the optimizer if it can reason
+/// that certain parts of the tree will always return an empty result.
+#[derive(Debug, Clone)]
+pub struct LogicalEmptyResult {
+    pub(crate) base: LogicalOperatorBase,
+    /// The set of return types of the empty result
+    pub(crate) return_types: Vec<LogicalType>,
+    /// The columns that would be bound at this location (if the subtree was not optimized away)
+    pub(crate) bindings: Vec<ColumnBinding>,
+}
+
+impl LogicalEmptyResult {
+    pub fn new(mut op: LogicalOperator) -> Self {
+        let bindings = op.get_column_bindings();
+        op.resolve_operator_types();
+        let return_types = op.types().to_vec();
+        Self {
+            base: LogicalOperatorBase::default(),
+            return_types,
+            bindings,
+        }
+    }
+}
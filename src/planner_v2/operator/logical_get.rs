@@ -17,8 +17,32 @@ pub struct LogicalGet {
     pub(crate) returned_types: Vec<LogicalType>,
     /// The names of ALL columns that can be returned by the table function
     pub(crate) names: Vec<String>,
-    /// Bound column IDs
+    /// Bound column IDs, filled by the optimizer after removing unused columns. Empty means all
+    /// columns are returned.
     #[new(default)]
-    #[allow(dead_code)]
     pub(crate) column_ids: Vec<usize>,
 }
+
+impl LogicalGet {
+    /// The types of the columns that are actually returned by this scan
+    pub fn projected_types(&self) -> Vec<LogicalType> {
+        if self.column_ids.is_empty() {
+            return self.returned_types.clone();
+        }
+        self.column_ids
+            .iter()
+            .map(|idx| self.returned_types[*idx].clone())
+            .collect()
+    }
+
+    /// The names of the columns that are actually returned by this scan
+    pub fn projected_names(&self) -> Vec<String> {
+        if self.column_ids.is_empty() {
+            return self.names.clone();
+        }
+        self.column_ids
+            .iter()
+            .map(|idx| self.names[*idx].clone())
+            .collect()
+    }
+}
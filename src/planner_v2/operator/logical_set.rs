@@ -0,0 +1,12 @@
+use derive_new::new;
+
+use super::LogicalOperatorBase;
+use crate::main_entry::ConfigOption;
+
+/// LogicalSet changes an option of the database or of the session configuration.
+#[derive(new, Debug, Clone)]
+pub struct LogicalSet {
+    #[new(default)]
+    pub(crate) base: LogicalOperatorBase,
+    pub(crate) option: ConfigOption,
+}
@@ -1,7 +1,11 @@
 use crate::types_v2::LogicalType;
 
+mod logical_analyze;
+mod logical_copy_to_file;
 mod logical_create_table;
+mod logical_cross_product;
 mod logical_dummy_scan;
+mod logical_empty_result;
 mod logical_explain;
 mod logical_expression_get;
 mod logical_filter;
@@ -9,9 +13,14 @@ mod logical_get;
 mod logical_insert;
 mod logical_limit;
 mod logical_projection;
+mod logical_set;
 use derive_new::new;
+pub use logical_analyze::*;
+pub use logical_copy_to_file::*;
 pub use logical_create_table::*;
+pub use logical_cross_product::*;
 pub use logical_dummy_scan::*;
+pub use logical_empty_result::*;
 pub use logical_explain::*;
 pub use logical_expression_get::*;
 pub use logical_filter::*;
@@ -19,6 +28,7 @@ pub use logical_get::*;
 pub use logical_insert::*;
 pub use logical_limit::*;
 pub use logical_projection::*;
+pub use logical_set::*;
 
 use super::{BoundExpression, ColumnBinding};
 
@@ -29,6 +39,9 @@ pub struct LogicalOperatorBase {
     pub(crate) expressioins: Vec<BoundExpression>,
     /// The types returned by this logical operator.
     pub(crate) types: Vec<LogicalType>,
+    /// Estimated cardinality of this operator, set by the join order optimizer.
+    #[new(default)]
+    pub(crate) estimated_cardinality: Option<usize>,
 }
 
 #[derive(Debug, Clone)]
@@ -42,6 +55,11 @@ pub enum LogicalOperator {
     LogicalExplain(LogicalExplain),
     LogicalFilter(LogicalFilter),
     LogicalLimit(LogicalLimit),
+    LogicalEmptyResult(LogicalEmptyResult),
+    LogicalCrossProduct(LogicalCrossProduct),
+    LogicalAnalyze(LogicalAnalyze),
+    LogicalSet(LogicalSet),
+    LogicalCopyToFile(LogicalCopyToFile),
 }
 
 impl LogicalOperator {
@@ -56,6 +74,11 @@ impl LogicalOperator {
             LogicalOperator::LogicalExplain(op) => &mut op.base.children,
             LogicalOperator::LogicalFilter(op) => &mut op.base.children,
             LogicalOperator::LogicalLimit(op) => &mut op.base.children,
+            LogicalOperator::LogicalEmptyResult(op) => &mut op.base.children,
+            LogicalOperator::LogicalCrossProduct(op) => &mut op.base.children,
+            LogicalOperator::LogicalAnalyze(op) => &mut op.base.children,
+            LogicalOperator::LogicalSet(op) => &mut op.base.children,
+            LogicalOperator::LogicalCopyToFile(op) => &mut op.base.children,
         }
     }
 
@@ -70,6 +93,11 @@ impl LogicalOperator {
             LogicalOperator::LogicalExplain(op) => &op.base.children,
             LogicalOperator::LogicalFilter(op) => &op.base.children,
             LogicalOperator::LogicalLimit(op) => &op.base.children,
+            LogicalOperator::LogicalEmptyResult(op) => &op.base.children,
+            LogicalOperator::LogicalCrossProduct(op) => &op.base.children,
+            LogicalOperator::LogicalAnalyze(op) => &op.base.children,
+            LogicalOperator::LogicalSet(op) => &op.base.children,
+            LogicalOperator::LogicalCopyToFile(op) => &op.base.children,
         }
     }
 
@@ -84,6 +112,30 @@ impl LogicalOperator {
             LogicalOperator::LogicalExplain(op) => op.base.children.push(child),
             LogicalOperator::LogicalFilter(op) => op.base.children.push(child),
             LogicalOperator::LogicalLimit(op) => op.base.children.push(child),
+            LogicalOperator::LogicalEmptyResult(op) => op.base.children.push(child),
+            LogicalOperator::LogicalCrossProduct(op) => op.base.children.push(child),
+            LogicalOperator::LogicalAnalyze(op) => op.base.children.push(child),
+            LogicalOperator::LogicalSet(op) => op.base.children.push(child),
+            LogicalOperator::LogicalCopyToFile(op) => op.base.children.push(child),
+        }
+    }
+
+    pub fn take_children(&mut self) -> Vec<LogicalOperator> {
+        match self {
+            LogicalOperator::LogicalCreateTable(op) => std::mem::take(&mut op.base.children),
+            LogicalOperator::LogicalExpressionGet(op) => std::mem::take(&mut op.base.children),
+            LogicalOperator::LogicalInsert(op) => std::mem::take(&mut op.base.children),
+            LogicalOperator::LogicalGet(op) => std::mem::take(&mut op.base.children),
+            LogicalOperator::LogicalProjection(op) => std::mem::take(&mut op.base.children),
+            LogicalOperator::LogicalDummyScan(op) => std::mem::take(&mut op.base.children),
+            LogicalOperator::LogicalExplain(op) => std::mem::take(&mut op.base.children),
+            LogicalOperator::LogicalFilter(op) => std::mem::take(&mut op.base.children),
+            LogicalOperator::LogicalLimit(op) => std::mem::take(&mut op.base.children),
+            LogicalOperator::LogicalEmptyResult(op) => std::mem::take(&mut op.base.children),
+            LogicalOperator::LogicalCrossProduct(op) => std::mem::take(&mut op.base.children),
+            LogicalOperator::LogicalAnalyze(op) => std::mem::take(&mut op.base.children),
+            LogicalOperator::LogicalSet(op) => std::mem::take(&mut op.base.children),
+            LogicalOperator::LogicalCopyToFile(op) => std::mem::take(&mut op.base.children),
         }
     }
 
@@ -98,6 +150,11 @@ impl LogicalOperator {
             LogicalOperator::LogicalExplain(op) => &mut op.base.expressioins,
             LogicalOperator::LogicalFilter(op) => &mut op.base.expressioins,
             LogicalOperator::LogicalLimit(op) => &mut op.base.expressioins,
+            LogicalOperator::LogicalEmptyResult(op) => &mut op.base.expressioins,
+            LogicalOperator::LogicalCrossProduct(op) => &mut op.base.expressioins,
+            LogicalOperator::LogicalAnalyze(op) => &mut op.base.expressioins,
+            LogicalOperator::LogicalSet(op) => &mut op.base.expressioins,
+            LogicalOperator::LogicalCopyToFile(op) => &mut op.base.expressioins,
         }
     }
 
@@ -112,6 +169,11 @@ impl LogicalOperator {
             LogicalOperator::LogicalExplain(op) => &op.base.types,
             LogicalOperator::LogicalFilter(op) => &op.base.types,
             LogicalOperator::LogicalLimit(op) => &op.base.types,
+            LogicalOperator::LogicalEmptyResult(op) => &op.base.types,
+            LogicalOperator::LogicalCrossProduct(op) => &op.base.types,
+            LogicalOperator::LogicalAnalyze(op) => &op.base.types,
+            LogicalOperator::LogicalSet(op) => &op.base.types,
+            LogicalOperator::LogicalCopyToFile(op) => &op.base.types,
         }
     }
 
@@ -119,12 +181,22 @@ impl LogicalOperator {
         let default = vec![ColumnBinding::new(0, 0)];
         match self {
             LogicalOperator::LogicalCreateTable(_) => default,
+            LogicalOperator::LogicalAnalyze(_) => default,
+            LogicalOperator::LogicalSet(_) => default,
+            LogicalOperator::LogicalCopyToFile(_) => default,
             LogicalOperator::LogicalExpressionGet(op) => {
                 self.generate_column_bindings(op.table_idx, op.expr_types.len())
             }
             LogicalOperator::LogicalInsert(_) => default,
             LogicalOperator::LogicalGet(op) => {
-                self.generate_column_bindings(op.table_idx, op.returned_types.len())
+                if op.column_ids.is_empty() {
+                    self.generate_column_bindings(op.table_idx, op.returned_types.len())
+                } else {
+                    op.column_ids
+                        .iter()
+                        .map(|idx| ColumnBinding::new(op.table_idx, *idx))
+                        .collect()
+                }
             }
             LogicalOperator::LogicalProjection(op) => {
                 self.generate_column_bindings(op.table_idx, op.base.expressioins.len())
@@ -135,6 +207,12 @@ impl LogicalOperator {
             }
             LogicalOperator::LogicalFilter(op) => op.base.children[0].get_column_bindings(),
             LogicalOperator::LogicalLimit(op) => op.base.children[0].get_column_bindings(),
+            LogicalOperator::LogicalEmptyResult(op) => op.bindings.clone(),
+            LogicalOperator::LogicalCrossProduct(op) => {
+                let mut bindings = op.base.children[0].get_column_bindings();
+                bindings.extend(op.base.children[1].get_column_bindings());
+                bindings
+            }
         }
     }
 
@@ -150,7 +228,10 @@ impl LogicalOperator {
                 op.base.types = op.expr_types.clone();
             }
             LogicalOperator::LogicalInsert(op) => op.base.types.push(LogicalType::Bigint),
-            LogicalOperator::LogicalGet(op) => op.base.types.extend(op.returned_types.clone()),
+            LogicalOperator::LogicalAnalyze(op) => op.base.types.push(LogicalType::Varchar),
+            LogicalOperator::LogicalSet(op) => op.base.types.push(LogicalType::Varchar),
+            LogicalOperator::LogicalCopyToFile(op) => op.base.types.push(LogicalType::Bigint),
+            LogicalOperator::LogicalGet(op) => op.base.types.extend(op.projected_types()),
             LogicalOperator::LogicalProjection(op) => {
                 let types = op
                     .base
@@ -170,9 +251,63 @@ impl LogicalOperator {
             LogicalOperator::LogicalLimit(op) => {
                 op.base.types = op.base.children[0].types().to_vec();
             }
+            LogicalOperator::LogicalEmptyResult(op) => {
+                op.base.types = op.return_types.clone();
+            }
+            LogicalOperator::LogicalCrossProduct(op) => {
+                let mut types = op.base.children[0].types().to_vec();
+                types.extend_from_slice(op.base.children[1].types());
+                op.base.types = types;
+            }
         }
     }
 
+    fn base(&self) -> &LogicalOperatorBase {
+        match self {
+            LogicalOperator::LogicalCreateTable(op) => &op.base,
+            LogicalOperator::LogicalExpressionGet(op) => &op.base,
+            LogicalOperator::LogicalInsert(op) => &op.base,
+            LogicalOperator::LogicalGet(op) => &op.base,
+            LogicalOperator::LogicalProjection(op) => &op.base,
+            LogicalOperator::LogicalDummyScan(op) => &op.base,
+            LogicalOperator::LogicalExplain(op) => &op.base,
+            LogicalOperator::LogicalFilter(op) => &op.base,
+            LogicalOperator::LogicalLimit(op) => &op.base,
+            LogicalOperator::LogicalEmptyResult(op) => &op.base,
+            LogicalOperator::LogicalCrossProduct(op) => &op.base,
+            LogicalOperator::LogicalAnalyze(op) => &op.base,
+            LogicalOperator::LogicalSet(op) => &op.base,
+            LogicalOperator::LogicalCopyToFile(op) => &op.base,
+        }
+    }
+
+    fn base_mut(&mut self) -> &mut LogicalOperatorBase {
+        match self {
+            LogicalOperator::LogicalCreateTable(op) => &mut op.base,
+            LogicalOperator::LogicalExpressionGet(op) => &mut op.base,
+            LogicalOperator::LogicalInsert(op) => &mut op.base,
+            LogicalOperator::LogicalGet(op) => &mut op.base,
+            LogicalOperator::LogicalProjection(op) => &mut op.base,
+            LogicalOperator::LogicalDummyScan(op) => &mut op.base,
+            LogicalOperator::LogicalExplain(op) => &mut op.base,
+            LogicalOperator::LogicalFilter(op) => &mut op.base,
+            LogicalOperator::LogicalLimit(op) => &mut op.base,
+            LogicalOperator::LogicalEmptyResult(op) => &mut op.base,
+            LogicalOperator::LogicalCrossProduct(op) => &mut op.base,
+            LogicalOperator::LogicalAnalyze(op) => &mut op.base,
+            LogicalOperator::LogicalSet(op) => &mut op.base,
+            LogicalOperator::LogicalCopyToFile(op) => &mut op.base,
+        }
+    }
+
+    pub fn estimated_cardinality(&self) -> Option<usize> {
+        self.base().estimated_cardinality
+    }
+
+    pub fn set_estimated_cardinality(&mut self, cardinality: usize) {
+        self.base_mut().estimated_cardinality = Some(cardinality);
+    }
+
     fn generate_column_bindings(
         &self,
         table_idx: usize,
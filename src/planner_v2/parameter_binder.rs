@@ -0,0 +1,112 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+use super::{
+    BindError, BoundCastExpression, BoundConstantExpression, BoundExpression, BoundExpressionBase,
+    BoundParameterExpression, LogicalOperator, LogicalOperatorVisitor,
+};
+use crate::execution::ExpressionExecutor;
+use crate::types_v2::{LogicalType, ScalarValue};
+
+/// The ParameterBinder finds the parameters of a bound plan, and replaces them by the constants
+/// of their values before the plan is optimized.
+#[derive(Default)]
+pub struct ParameterBinder {
+    /// The values of the parameters, the first one is the value of `$1`
+    values: Vec<ScalarValue>,
+    /// The types of the parameters found in the plan
+    types: RefCell<BTreeMap<usize, LogicalType>>,
+    /// The first error of casting a value to the type of its parameter
+    error: RefCell<Option<BindError>>,
+}
+
+impl ParameterBinder {
+    /// The types of the parameters of the plan, the type of `$1` is the first one. Every
+    /// parameter must be used, and have a type inferred from its context.
+    pub fn parameter_types(plan: &mut LogicalOperator) -> Result<Vec<LogicalType>, BindError> {
+        let mut binder = Self::default();
+        binder.visit_operator(plan);
+        let types = binder.types.into_inner();
+        let count = types.keys().next_back().copied().unwrap_or(0);
+        (1..=count)
+            .map(|identifier| match types.get(&identifier) {
+                Some(ty) if *ty != LogicalType::SqlNull => Ok(ty.clone()),
+                _ => Err(BindError::Internal(format!(
+                    "could not determine the type of parameter ${}",
+                    identifier
+                ))),
+            })
+            .collect()
+    }
+
+    /// Replace the parameters of the plan by their values, cast to the types of the parameters.
+    pub fn bind_values(
+        plan: &mut LogicalOperator,
+        values: Vec<ScalarValue>,
+    ) -> Result<(), BindError> {
+        let mut binder = Self {
+            values,
+            ..Default::default()
+        };
+        binder.visit_operator(plan);
+        match binder.error.into_inner() {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    fn bind_value(&self, e: &BoundParameterExpression) -> Result<BoundExpression, BindError> {
+        let value = self.values.get(e.identifier - 1).ok_or_else(|| {
+            BindError::Internal(format!("no value for parameter ${}", e.identifier))
+        })?;
+        let base = BoundExpressionBase::new(value.to_string(), value.get_logical_type());
+        let constant = BoundExpression::BoundConstantExpression(BoundConstantExpression::new(
+            base,
+            value.clone(),
+        ));
+        let ty = e.base.return_type.clone();
+        let value = if value.get_logical_type() == ty {
+            value.clone()
+        } else {
+            let cast = BoundCastExpression::try_add_cast_to_type(constant, ty.clone(), false)?;
+            ExpressionExecutor::execute_scalar(&cast)?
+        };
+        let base = BoundExpressionBase::new(e.base.alias.clone(), ty);
+        Ok(BoundExpression::BoundConstantExpression(
+            BoundConstantExpression::new(base, value),
+        ))
+    }
+}
+
+impl LogicalOperatorVisitor for ParameterBinder {
+    fn visit_operator_expressions(&mut self, op: &mut LogicalOperator) {
+        // the rows of a VALUES list are not in the expressions of the operator
+        if let LogicalOperator::LogicalExpressionGet(get) = op {
+            for expr in get.expressions.iter_mut().flatten() {
+                self.visit_expression(expr);
+            }
+        }
+        Self::eumerate_expressions(op, |e| self.visit_expression(e))
+    }
+
+    fn visit_replace_parameter(&self, e: &BoundParameterExpression) -> Option<BoundExpression> {
+        {
+            // a parameter used several times has the first type inferred for it
+            let mut types = self.types.borrow_mut();
+            let ty = types.entry(e.identifier).or_insert(LogicalType::SqlNull);
+            if *ty == LogicalType::SqlNull {
+                *ty = e.base.return_type.clone();
+            }
+        }
+        if self.values.is_empty() {
+            return None;
+        }
+        match self.bind_value(e) {
+            Ok(expr) => Some(expr),
+            Err(err) => {
+                self.error.borrow_mut().get_or_insert(err);
+                None
+            }
+        }
+    }
+}
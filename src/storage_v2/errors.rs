@@ -0,0 +1,15 @@
+use crate::catalog_v2::CatalogError;
+
+#[derive(thiserror::Error, Debug)]
+pub enum StorageError {
+    #[error("Table data: {0} not exists")]
+    TableDataNotExists(String),
+    #[error("Storage lock poisoned by a panic")]
+    StoragePoisonedError,
+    #[error("catalog error: {0}")]
+    CatalogError(
+        #[source]
+        #[from]
+        CatalogError,
+    ),
+}
@@ -1,13 +1,18 @@
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, RwLockReadGuard, RwLockWriteGuard};
 
 use arrow::compute;
 use arrow::record_batch::RecordBatch;
 use derive_new::new;
 
-use crate::catalog_v2::DataTable;
+use super::StorageError;
+use crate::catalog_v2::{Catalog, DataTable, TableStatistics};
 use crate::main_entry::ClientContext;
 
+/// The maximum number of rows of a row group, a row group is the unit handed out to the
+/// pipeline workers.
+pub(crate) const ROW_GROUP_SIZE: usize = 1024;
+
 /// Used as in-memory storage
 #[derive(Default)]
 pub struct LocalStorage {
@@ -24,14 +29,104 @@ impl LocalStorage {
         self.table_manager.append(table, batch);
     }
 
-    pub fn init_table(client_context: Arc<ClientContext>, table: &DataTable) {
-        let mut storage = client_context.db.storage.try_write().unwrap();
+    pub fn init_table(
+        client_context: Arc<ClientContext>,
+        table: &DataTable,
+    ) -> Result<(), StorageError> {
+        let mut storage = Self::write(&client_context)?;
         storage.init_table_internal(table);
+        Ok(())
     }
 
-    pub fn append(client_context: Arc<ClientContext>, table: &DataTable, batch: RecordBatch) {
-        let mut storage = client_context.db.storage.try_write().unwrap();
+    pub fn append(
+        client_context: Arc<ClientContext>,
+        table: &DataTable,
+        batch: RecordBatch,
+    ) -> Result<(), StorageError> {
+        let mut storage = Self::write(&client_context)?;
         storage.append_internal(table, batch);
+        Ok(())
+    }
+
+    /// Append the batches to the table, and merge their statistics into the table entry. The
+    /// catalog and the storage are both locked before any batch is appended, so either all the
+    /// batches are appended with their statistics or none of them is.
+    pub fn append_with_statistics(
+        client_context: Arc<ClientContext>,
+        table: &DataTable,
+        batches: Vec<RecordBatch>,
+        appended_stats: &TableStatistics,
+    ) -> Result<(), StorageError> {
+        Catalog::update_table_statistics(
+            client_context.clone(),
+            table.info.schema.clone(),
+            table.info.table.clone(),
+            |stats| -> Result<(), StorageError> {
+                let mut storage = Self::write(&client_context)?;
+                for batch in batches {
+                    storage.append_internal(table, batch);
+                }
+                stats.merge(appended_stats);
+                Ok(())
+            },
+        )?
+    }
+
+    /// Store the batches as the data of the table. The batches are sliced into row groups, so
+    /// the table shares their arrays with the caller instead of copying them.
+    pub fn register(
+        client_context: Arc<ClientContext>,
+        table: &DataTable,
+        batches: Vec<RecordBatch>,
+    ) -> Result<(), StorageError> {
+        let mut storage = Self::write(&client_context)?;
+        storage.table_manager.register(table, batches);
+        Ok(())
+    }
+
+    pub fn drop_table(
+        client_context: Arc<ClientContext>,
+        table: &DataTable,
+    ) -> Result<(), StorageError> {
+        let mut storage = Self::write(&client_context)?;
+        storage.table_manager.drop_storage(table);
+        Ok(())
+    }
+
+    /// Fetch one row group of the table, row groups are handed out as morsels to the pipeline
+    /// workers, so they can be scanned in parallel.
+    pub fn fetch_row_group(
+        client_context: Arc<ClientContext>,
+        table: &DataTable,
+        row_group_idx: usize,
+    ) -> Result<Option<RecordBatch>, StorageError> {
+        let storage = Self::read(&client_context)?;
+        storage
+            .table_manager
+            .fetch_table_batch(table, row_group_idx)
+    }
+
+    /// The storage is locked only while a batch is appended or fetched, so the lock is waited
+    /// for instead of failing the query when another query holds it. A query locking both the
+    /// catalog and the storage locks the catalog first.
+    fn read(
+        client_context: &ClientContext,
+    ) -> Result<RwLockReadGuard<'_, LocalStorage>, StorageError> {
+        client_context
+            .db
+            .storage
+            .read()
+            .map_err(|_| StorageError::StoragePoisonedError)
+    }
+
+    fn write(
+        client_context: &ClientContext,
+    ) -> Result<RwLockWriteGuard<'_, LocalStorage>, StorageError> {
+        client_context
+            .db
+            .storage
+            .write()
+            .map_err(|_| StorageError::StoragePoisonedError)
     }
 
     pub fn create_reader(table: &DataTable) -> LocalStorageReader {
@@ -47,13 +142,16 @@ pub struct LocalStorageReader {
 }
 
 impl LocalStorageReader {
-    pub fn next_batch(&mut self, client_context: Arc<ClientContext>) -> Option<RecordBatch> {
-        let storage = client_context.db.storage.try_read().unwrap();
+    pub fn next_batch(
+        &mut self,
+        client_context: Arc<ClientContext>,
+    ) -> Result<Option<RecordBatch>, StorageError> {
+        let storage = LocalStorage::read(&client_context)?;
         let batch = storage
             .table_manager
-            .fetch_table_batch(&self.table, self.current_batch_cursor);
+            .fetch_table_batch(&self.table, self.current_batch_cursor)?;
         self.current_batch_cursor += 1;
-        batch
+        Ok(batch)
     }
 }
 
@@ -74,11 +172,34 @@ impl LocalTableManager {
         self.table_storage.get_mut(table).unwrap().append(batch);
     }
 
-    pub fn fetch_table_batch(&self, table: &DataTable, batch_idx: usize) -> Option<RecordBatch> {
-        self.table_storage
-            .get(table)
-            .unwrap()
-            .fetch_batch(batch_idx)
+    fn register(&mut self, table: &DataTable, batches: Vec<RecordBatch>) {
+        let mut storage = LocalTableStorage::new(table.clone());
+        for batch in batches {
+            let mut offset = 0;
+            while offset < batch.num_rows() {
+                let length = ROW_GROUP_SIZE.min(batch.num_rows() - offset);
+                storage.data.push(batch.slice(offset, length));
+                offset += length;
+            }
+        }
+        self.table_storage.insert(table.clone(), storage);
+    }
+
+    fn drop_storage(&mut self, table: &DataTable) {
+        self.table_storage.remove(table);
+    }
+
+    /// Fetch a batch of the table, the table may be dropped after a query scanning it was
+    /// planned.
+    pub fn fetch_table_batch(
+        &self,
+        table: &DataTable,
+        batch_idx: usize,
+    ) -> Result<Option<RecordBatch>, StorageError> {
+        let storage = self.table_storage.get(table).ok_or_else(|| {
+            StorageError::TableDataNotExists(format!("{}.{}", table.info.schema, table.info.table))
+        })?;
+        Ok(storage.fetch_batch(batch_idx))
     }
 }
 
@@ -95,27 +216,30 @@ impl LocalTableStorage {
         }
     }
 
+    /// Append the batch to the row groups, the rows fill up the last row group first, and the
+    /// rest of them are split into new row groups of at most ROW_GROUP_SIZE rows.
     fn append(&mut self, batch: RecordBatch) {
+        let mut offset = 0;
         if let Some(last_batch) = self.data.last_mut() {
-            let remaing_count = 1024 - last_batch.num_rows();
-            if remaing_count > 0 {
+            let remaining_count = ROW_GROUP_SIZE
+                .saturating_sub(last_batch.num_rows())
+                .min(batch.num_rows());
+            if remaining_count > 0 {
                 // need to merge current batch into last unfull batch
-                let appended_batch = batch.slice(0, remaing_count.min(batch.num_rows()));
+                let appended_batch = batch.slice(0, remaining_count);
                 *last_batch = compute::concat_batches(
                     &last_batch.schema(),
                     &[last_batch.clone(), appended_batch],
                 )
                 .unwrap();
-
-                if batch.num_rows() > remaing_count {
-                    // need a new batch
-                    let new_batch = batch.slice(remaing_count, batch.num_rows());
-                    self.data.push(new_batch);
-                }
-                return;
+                offset = remaining_count;
             }
         }
-        self.data.push(batch);
+        while offset < batch.num_rows() {
+            let length = ROW_GROUP_SIZE.min(batch.num_rows() - offset);
+            self.data.push(batch.slice(offset, length));
+            offset += length;
+        }
     }
 
     fn fetch_batch(&self, batch_idx: usize) -> Option<RecordBatch> {
@@ -1,2 +1,4 @@
+mod errors;
 mod local_storage;
+pub use errors::*;
 pub use local_storage::*;
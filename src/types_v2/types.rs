@@ -23,6 +23,10 @@ pub enum LogicalType {
     Varchar,
     Date,
     Interval(IntervalUnit),
+    /// The names and types of the fields
+    Struct(Vec<(String, LogicalType)>),
+    /// The type of the elements
+    List(Box<LogicalType>),
 }
 
 impl LogicalType {
@@ -196,6 +200,7 @@ impl LogicalType {
             LogicalType::Varchar => false,
             LogicalType::Date => false,
             LogicalType::Interval(_) => false,
+            LogicalType::Struct(_) | LogicalType::List(_) => false,
         }
     }
 }
@@ -237,7 +242,7 @@ impl TryFrom<sqlparser::ast::DataType> for LogicalType {
 
 impl From<LogicalType> for arrow::datatypes::DataType {
     fn from(value: LogicalType) -> Self {
-        use arrow::datatypes::DataType;
+        use arrow::datatypes::{DataType, Field};
         match value {
             LogicalType::Invalid => panic!("invalid logical type"),
             LogicalType::SqlNull => DataType::Null,
@@ -255,6 +260,15 @@ impl From<LogicalType> for arrow::datatypes::DataType {
             LogicalType::Varchar => DataType::Utf8,
             LogicalType::Date => DataType::Date32,
             LogicalType::Interval(u) => DataType::Interval(u),
+            LogicalType::Struct(fields) => DataType::Struct(
+                fields
+                    .into_iter()
+                    .map(|(name, ty)| Field::new(&name, ty.into(), true))
+                    .collect(),
+            ),
+            LogicalType::List(ty) => {
+                DataType::List(Box::new(Field::new("item", (*ty).into(), true)))
+            }
         }
     }
 }
@@ -282,6 +296,15 @@ impl TryFrom<&arrow::datatypes::DataType> for LogicalType {
             DataType::LargeUtf8 => LogicalType::Varchar,
             DataType::Date32 => LogicalType::Date,
             DataType::Interval(u) => LogicalType::Interval(u.clone()),
+            DataType::Struct(fields) => LogicalType::Struct(
+                fields
+                    .iter()
+                    .map(|f| Ok((f.name().clone(), f.data_type().try_into()?)))
+                    .collect::<Result<Vec<_>, TypeError>>()?,
+            ),
+            DataType::List(field) | DataType::LargeList(field) => {
+                LogicalType::List(Box::new(field.data_type().try_into()?))
+            }
             DataType::Timestamp(_, _)
             | DataType::Date64
             | DataType::Time32(_)
@@ -290,10 +313,7 @@ impl TryFrom<&arrow::datatypes::DataType> for LogicalType {
             | DataType::Binary
             | DataType::FixedSizeBinary(_)
             | DataType::LargeBinary
-            | DataType::List(_)
             | DataType::FixedSizeList(_, _)
-            | DataType::LargeList(_)
-            | DataType::Struct(_)
             | DataType::Union(_, _, _)
             | DataType::Dictionary(_, _)
             | DataType::Decimal128(_, _)
@@ -307,6 +327,16 @@ impl TryFrom<&arrow::datatypes::DataType> for LogicalType {
 
 impl std::fmt::Display for LogicalType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.as_ref())
+        match self {
+            LogicalType::Struct(fields) => {
+                let fields = fields
+                    .iter()
+                    .map(|(name, ty)| format!("{} {}", name, ty))
+                    .collect::<Vec<_>>();
+                write!(f, "Struct({})", fields.join(", "))
+            }
+            LogicalType::List(ty) => write!(f, "List({})", ty),
+            _ => write!(f, "{}", self.as_ref()),
+        }
     }
 }
@@ -221,10 +221,34 @@ impl ScalarValue {
             DataType::UInt32 => Ok(ScalarValue::UInt32(None)),
             DataType::UInt64 => Ok(ScalarValue::UInt64(None)),
             DataType::Utf8 => Ok(ScalarValue::Utf8(None)),
+            DataType::Date32 => Ok(ScalarValue::Date32(None)),
+            DataType::Interval(IntervalUnit::YearMonth) => Ok(ScalarValue::IntervalYearMonth(None)),
+            DataType::Interval(IntervalUnit::DayTime) => Ok(ScalarValue::IntervalDayTime(None)),
             other => Err(TypeError::NotImplementedArrowDataType(other.to_string())),
         }
     }
 
+    pub fn is_null(&self) -> bool {
+        match self {
+            ScalarValue::Null => true,
+            ScalarValue::Boolean(v) => v.is_none(),
+            ScalarValue::Float32(v) => v.is_none(),
+            ScalarValue::Float64(v) => v.is_none(),
+            ScalarValue::Int8(v) => v.is_none(),
+            ScalarValue::Int16(v) => v.is_none(),
+            ScalarValue::Int32(v) => v.is_none(),
+            ScalarValue::Int64(v) => v.is_none(),
+            ScalarValue::UInt8(v) => v.is_none(),
+            ScalarValue::UInt16(v) => v.is_none(),
+            ScalarValue::UInt32(v) => v.is_none(),
+            ScalarValue::UInt64(v) => v.is_none(),
+            ScalarValue::Utf8(v) => v.is_none(),
+            ScalarValue::Date32(v) => v.is_none(),
+            ScalarValue::IntervalYearMonth(v) => v.is_none(),
+            ScalarValue::IntervalDayTime(v) => v.is_none(),
+        }
+    }
+
     /// Converts a value in `array` at `index` into a ScalarValue
     pub fn try_from_array(array: &ArrayRef, index: usize) -> Result<Self, TypeError> {
         if !array.is_valid(index) {
@@ -247,6 +271,13 @@ impl ScalarValue {
             DataType::Int16 => typed_cast!(array, index, Int16Array, Int16),
             DataType::Int8 => typed_cast!(array, index, Int8Array, Int8),
             DataType::Utf8 => typed_cast!(array, index, StringArray, Utf8),
+            DataType::Date32 => typed_cast!(array, index, Date32Array, Date32),
+            DataType::Interval(IntervalUnit::YearMonth) => {
+                typed_cast!(array, index, IntervalYearMonthArray, IntervalYearMonth)
+            }
+            DataType::Interval(IntervalUnit::DayTime) => {
+                typed_cast!(array, index, IntervalDayTimeArray, IntervalDayTime)
+            }
             other => {
                 return Err(TypeError::NotImplementedArrowDataType(other.to_string()));
             }
@@ -331,7 +362,10 @@ impl ScalarValue {
 
     pub fn new_builder(data_type: &LogicalType) -> Result<Box<dyn ArrayBuilder>, TypeError> {
         match data_type {
-            LogicalType::Invalid | LogicalType::SqlNull => Err(TypeError::InternalError(format!(
+            LogicalType::Invalid
+            | LogicalType::SqlNull
+            | LogicalType::Struct(_)
+            | LogicalType::List(_) => Err(TypeError::InternalError(format!(
                 "Unsupported type {:?} for builder",
                 data_type
             ))),
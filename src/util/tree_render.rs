@@ -60,6 +60,9 @@ impl TreeRender {
                     .join(", ");
                 format!("{}({}])", e.function.name, args)
             }
+            BoundExpression::BoundParameterExpression(e) => {
+                format!("Parameter({})", e.base.alias)
+            }
         }
     }
 
@@ -96,12 +99,25 @@ impl TreeRender {
                             )
                         }
                         FunctionData::SqlrsColumnsData(_) => "sqlrs_columns".to_string(),
+                        FunctionData::SqlrsColumnStatsData(_) => "sqlrs_column_stats".to_string(),
                         FunctionData::SqlrsTablesData(_) => "sqlrs_tables".to_string(),
                         FunctionData::ReadCSVInputData(_) => "read_csv".to_string(),
+                        FunctionData::ReadParquetInputData(_) => "read_parquet".to_string(),
+                        FunctionData::ReadJSONInputData(_) => "read_json".to_string(),
+                        FunctionData::ReadArrowInputData(_) => "read_arrow".to_string(),
+                        FunctionData::SniffCSVData(_) => "sniff_csv".to_string(),
+                        FunctionData::ArrowScanInputData(data) => {
+                            format!("arrow_scan({})", data.name)
+                        }
                     },
                     None => "None".to_string(),
                 };
-                format!("LogicalGet: {}", get_table_str)
+                if op.column_ids.is_empty() {
+                    format!("LogicalGet: {}", get_table_str)
+                } else {
+                    let columns = op.projected_names().join(", ");
+                    format!("LogicalGet: {}[{}]", get_table_str, columns)
+                }
             }
             LogicalOperator::LogicalProjection(op) => {
                 let exprs = op
@@ -138,6 +154,21 @@ impl TreeRender {
                     .unwrap_or_else(|| "None".to_string());
                 format!("LogicalLimit: limit[{}], offset[{}]", limit, offset)
             }
+            LogicalOperator::LogicalEmptyResult(_) => "LogicalEmptyResult".to_string(),
+            LogicalOperator::LogicalCrossProduct(_) => "LogicalCrossProduct".to_string(),
+            LogicalOperator::LogicalAnalyze(op) => {
+                let tables = op
+                    .tables
+                    .iter()
+                    .map(|t| t.base.name.clone())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("LogicalAnalyze: {}", tables)
+            }
+            LogicalOperator::LogicalSet(op) => format!("LogicalSet: {}", op.option),
+            LogicalOperator::LogicalCopyToFile(op) => {
+                format!("LogicalCopyToFile: {}", op.info.file_path)
+            }
         }
     }
 
@@ -146,7 +177,10 @@ impl TreeRender {
         level: usize,
         explain_result: &mut dyn Write,
     ) {
-        let plan_string = Self::logical_plan_to_string(plan);
+        let mut plan_string = Self::logical_plan_to_string(plan);
+        if let Some(cardinality) = plan.estimated_cardinality() {
+            write!(plan_string, " [estimated_cardinality: {}]", cardinality).unwrap();
+        }
         writeln!(explain_result, "{}{}", " ".repeat(level * 2), plan_string).unwrap();
         for child in plan.children() {
             Self::logical_plan_tree_internal(child, level + 1, explain_result);
@@ -170,6 +204,12 @@ impl TreeRender {
             PhysicalOperator::PhysicalColumnDataScan(_) => "PhysicalColumnDataScan".to_string(),
             PhysicalOperator::PhysicalFilter(_) => "PhysicalFilter".to_string(),
             PhysicalOperator::PhysicalLimit(_) => "PhysicalLimit".to_string(),
+            PhysicalOperator::PhysicalCrossProduct(_) => "PhysicalCrossProduct".to_string(),
+            PhysicalOperator::PhysicalAnalyze(_) => "PhysicalAnalyze".to_string(),
+            PhysicalOperator::PhysicalSet(op) => format!("PhysicalSet: {}", op.option),
+            PhysicalOperator::PhysicalCopyToFile(op) => {
+                format!("PhysicalCopyToFile: {}", op.info.file_path)
+            }
         }
     }
 
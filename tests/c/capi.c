@@ -0,0 +1,98 @@
+/* Check include/sqlrs.h against the library: every function of the header is called, so the
+ * program only links when the library exports all of them. Run by `make capi_test`. */
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "sqlrs.h"
+
+#define CHECK(call)                                                                    \
+  do {                                                                                 \
+    if ((call) != SQLRS_OK) {                                                          \
+      fprintf(stderr, "%s:%d: %s: %s\n", __FILE__, __LINE__, #call, error.message);   \
+      exit(1);                                                                         \
+    }                                                                                  \
+  } while (0)
+
+/* Read all the batches of a result, and return its number of rows. */
+static int64_t read_rows(struct ArrowArrayStream *stream) {
+  struct ArrowSchema schema;
+  if (stream->get_schema(stream, &schema) != 0) {
+    fprintf(stderr, "get_schema: %s\n", stream->get_last_error(stream));
+    exit(1);
+  }
+  schema.release(&schema);
+  int64_t rows = 0;
+  for (;;) {
+    struct ArrowArray array;
+    if (stream->get_next(stream, &array) != 0) {
+      fprintf(stderr, "get_next: %s\n", stream->get_last_error(stream));
+      exit(1);
+    }
+    if (array.release == NULL) {
+      break;
+    }
+    rows += array.length;
+    array.release(&array);
+  }
+  stream->release(stream);
+  return rows;
+}
+
+int main(void) {
+  SqlrsError error = {0};
+  SqlrsDatabase *database = NULL;
+  SqlrsConnection *connection = NULL;
+  struct ArrowArrayStream stream;
+
+  CHECK(sqlrs_open(&database, &error));
+  CHECK(sqlrs_connect(database, &connection, &error));
+  CHECK(sqlrs_query(connection,
+                    "create table t1(a int, b bigint, c double, d boolean, e varchar);"
+                    "insert into t1 values (1, 2, 3.0, true, 'x'), (2, 3, 4.0, false, 'y');",
+                    &stream, &error));
+  read_rows(&stream);
+  CHECK(sqlrs_query(connection, "select * from t1", &stream, &error));
+  if (read_rows(&stream) != 2) {
+    fprintf(stderr, "expected 2 rows\n");
+    return 1;
+  }
+
+  /* the error is reused without being freed, its first message is freed by sqlrs */
+  if (sqlrs_query(connection, "selec a from t1", &stream, &error) != SQLRS_PARSER_ERROR ||
+      sqlrs_query(connection, NULL, &stream, &error) != SQLRS_INVALID_ARGUMENT ||
+      error.code != SQLRS_INVALID_ARGUMENT || error.message == NULL) {
+    fprintf(stderr, "expected an invalid argument error\n");
+    return 1;
+  }
+  sqlrs_error_free(&error);
+
+  SqlrsPreparedStatement *statement = NULL;
+  CHECK(sqlrs_prepare(connection,
+                      "select a from t1 where a = $1 or b = $2 or c = $3 or d = $4 or e = $5 "
+                      "or e = $6",
+                      &statement, &error));
+  if (sqlrs_parameter_count(statement) != 6) {
+    fprintf(stderr, "expected 6 parameters\n");
+    return 1;
+  }
+  CHECK(sqlrs_bind_int32(statement, 1, 1, &error));
+  CHECK(sqlrs_bind_int64(statement, 2, 0, &error));
+  CHECK(sqlrs_bind_double(statement, 3, 0.0, &error));
+  CHECK(sqlrs_bind_boolean(statement, 4, false, &error));
+  CHECK(sqlrs_bind_varchar(statement, 5, "z", &error));
+  CHECK(sqlrs_bind_null(statement, 6, &error));
+  CHECK(sqlrs_execute_prepared(statement, &stream, &error));
+  if (read_rows(&stream) != 2) {
+    fprintf(stderr, "expected 2 rows\n");
+    return 1;
+  }
+  sqlrs_clear_bindings(statement);
+  sqlrs_destroy_prepared(statement);
+
+  /* no query is running, the interrupt is a no-op */
+  CHECK(sqlrs_interrupt(connection, &error));
+  sqlrs_disconnect(connection);
+  sqlrs_close(database);
+  return 0;
+}
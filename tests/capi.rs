@@ -0,0 +1,171 @@
+//! Call the C interface through the FFI boundary like a C program does: with C strings, out
+//! pointers, error structs and arrow C streams.
+use std::ffi::{CStr, CString};
+use std::ptr;
+
+use arrow::array::{Int32Array, StringArray};
+use arrow::ffi_stream::{ArrowArrayStreamReader, FFI_ArrowArrayStream};
+use arrow::record_batch::RecordBatch;
+use sqlrs::capi::*;
+
+struct Session {
+    database: *mut SqlrsDatabase,
+    connection: *mut SqlrsConnection,
+}
+
+impl Session {
+    fn open() -> Self {
+        let mut database = ptr::null_mut();
+        let mut connection = ptr::null_mut();
+        let mut error = SqlrsError::default();
+        unsafe {
+            assert_eq!(sqlrs_open(&mut database, &mut error), SqlrsCode::SqlrsOk);
+            assert_eq!(
+                sqlrs_connect(database, &mut connection, &mut error),
+                SqlrsCode::SqlrsOk
+            );
+        }
+        Self {
+            database,
+            connection,
+        }
+    }
+
+    fn query(&self, sql: &str) -> Result<Vec<RecordBatch>, (SqlrsCode, String)> {
+        let sql = CString::new(sql).unwrap();
+        let mut stream = FFI_ArrowArrayStream::empty();
+        let mut error = SqlrsError::default();
+        let code = unsafe { sqlrs_query(self.connection, sql.as_ptr(), &mut stream, &mut error) };
+        check(code, &mut error)?;
+        Ok(read_stream(stream))
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        unsafe {
+            sqlrs_disconnect(self.connection);
+            sqlrs_close(self.database);
+        }
+    }
+}
+
+/// Take the code and the message of an error, the message is freed.
+fn check(code: SqlrsCode, error: &mut SqlrsError) -> Result<(), (SqlrsCode, String)> {
+    if code == SqlrsCode::SqlrsOk {
+        assert!(error.message.is_null());
+        return Ok(());
+    }
+    assert_eq!(error.code, code);
+    let message = unsafe { CStr::from_ptr(error.message) }
+        .to_str()
+        .unwrap()
+        .to_string();
+    unsafe { sqlrs_error_free(error) };
+    assert!(error.message.is_null());
+    Err((code, message))
+}
+
+fn read_stream(stream: FFI_ArrowArrayStream) -> Vec<RecordBatch> {
+    let reader = ArrowArrayStreamReader::try_new(stream).unwrap();
+    reader.collect::<Result<Vec<_>, _>>().unwrap()
+}
+
+fn int32_values(batches: &[RecordBatch], column: usize) -> Vec<i32> {
+    batches
+        .iter()
+        .flat_map(|b| {
+            let array = b.column(column).as_any().downcast_ref::<Int32Array>();
+            array.unwrap().iter().flatten().collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+#[test]
+fn test_query_through_c_interface() {
+    let session = Session::open();
+    session
+        .query("create table t1(a int, b varchar); insert into t1 values (1, 'x'), (2, 'y');")
+        .unwrap();
+    let batches = session.query("select a, b from t1 where a > 1").unwrap();
+    assert_eq!(batches[0].schema().field(1).name(), "b");
+    assert_eq!(int32_values(&batches, 0), vec![2]);
+    assert_eq!(
+        batches[0].column(1).as_any().downcast_ref::<StringArray>(),
+        Some(&StringArray::from(vec!["y"]))
+    );
+
+    let (code, message) = session.query("selec a from t1").unwrap_err();
+    assert_eq!(code, SqlrsCode::SqlrsParserError);
+    assert!(message.contains("parse error"), "{}", message);
+    assert!(session.query("select * from t2").is_err());
+
+    let mut stream = FFI_ArrowArrayStream::empty();
+    let mut error = SqlrsError::default();
+    let code = unsafe { sqlrs_query(session.connection, ptr::null(), &mut stream, &mut error) };
+    assert_eq!(
+        check(code, &mut error).unwrap_err().0,
+        SqlrsCode::SqlrsInvalidArgument
+    );
+
+    // the message of the first error is freed when the second one is written
+    let sql = CString::new("selec a from t1").unwrap();
+    let code = unsafe { sqlrs_query(session.connection, ptr::null(), &mut stream, &mut error) };
+    assert_eq!(code, SqlrsCode::SqlrsInvalidArgument);
+    let code = unsafe { sqlrs_query(session.connection, sql.as_ptr(), &mut stream, &mut error) };
+    let (code, message) = check(code, &mut error).unwrap_err();
+    assert_eq!(code, SqlrsCode::SqlrsParserError);
+    assert!(message.contains("parse error"), "{}", message);
+}
+
+#[test]
+fn test_prepared_statement_through_c_interface() {
+    let session = Session::open();
+    session
+        .query("create table t1(a int, b varchar); insert into t1 values (1, 'x'), (2, 'y');")
+        .unwrap();
+
+    let sql = CString::new("select a from t1 where a >= $1 and b <> $2").unwrap();
+    let mut statement = ptr::null_mut();
+    let mut error = SqlrsError::default();
+    unsafe {
+        let code = sqlrs_prepare(session.connection, sql.as_ptr(), &mut statement, &mut error);
+        check(code, &mut error).unwrap();
+        assert_eq!(sqlrs_parameter_count(statement), 2);
+
+        // every parameter must be bound before the statement is executed
+        let mut stream = FFI_ArrowArrayStream::empty();
+        let code = sqlrs_bind_int32(statement, 1, 1, &mut error);
+        check(code, &mut error).unwrap();
+        let code = sqlrs_execute_prepared(statement, &mut stream, &mut error);
+        let (code, message) = check(code, &mut error).unwrap_err();
+        assert_eq!(code, SqlrsCode::SqlrsInvalidArgument);
+        assert!(message.contains("$2"), "{}", message);
+
+        let value = CString::new("x").unwrap();
+        let code = sqlrs_bind_varchar(statement, 2, value.as_ptr(), &mut error);
+        check(code, &mut error).unwrap();
+        let code = sqlrs_execute_prepared(statement, &mut stream, &mut error);
+        check(code, &mut error).unwrap();
+        assert_eq!(int32_values(&read_stream(stream), 0), vec![2]);
+
+        // a bigint is cast to the type of the parameter, the other values stay bound
+        let mut stream = FFI_ArrowArrayStream::empty();
+        let code = sqlrs_bind_int64(statement, 1, 3, &mut error);
+        check(code, &mut error).unwrap();
+        let code = sqlrs_execute_prepared(statement, &mut stream, &mut error);
+        check(code, &mut error).unwrap();
+        assert!(int32_values(&read_stream(stream), 0).is_empty());
+
+        let code = sqlrs_bind_int32(statement, 3, 1, &mut error);
+        assert_eq!(
+            check(code, &mut error).unwrap_err().0,
+            SqlrsCode::SqlrsInvalidArgument
+        );
+        sqlrs_clear_bindings(statement);
+        let mut stream = FFI_ArrowArrayStream::empty();
+        let code = sqlrs_execute_prepared(statement, &mut stream, &mut error);
+        assert!(check(code, &mut error).is_err());
+        sqlrs_destroy_prepared(statement);
+    }
+}
@@ -0,0 +1,255 @@
+//! Query the Flight SQL service with a flight client: statements, prepared statements and the
+//! catalog metadata.
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use arrow::array::{Int32Array, StringArray};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use arrow::ipc::writer::IpcWriteOptions;
+use arrow::record_batch::RecordBatch;
+use arrow_flight::flight_service_client::FlightServiceClient;
+use arrow_flight::sql::{
+    ActionCreatePreparedStatementRequest, ActionCreatePreparedStatementResult, Any,
+    CommandGetDbSchemas, CommandGetTables, CommandPreparedStatementQuery, CommandStatementQuery,
+    CommandStatementUpdate, ProstMessageExt,
+};
+use arrow_flight::utils::{flight_data_from_arrow_batch, flight_data_to_arrow_batch};
+use arrow_flight::{
+    Action, FlightData, FlightDescriptor, HandshakeRequest, IpcMessage, SchemaAsIpc,
+};
+use futures::{stream, StreamExt};
+use prost::Message;
+use sqlrs::flight_sql::FlightSqlServer;
+use sqlrs::main_entry::Database;
+use tokio::net::TcpListener;
+use tonic::metadata::MetadataValue;
+use tonic::transport::Channel;
+use tonic::Request;
+
+/// A client of a session, it sends the token of its handshake with each request.
+struct Client {
+    client: FlightServiceClient<Channel>,
+    authorization: MetadataValue<tonic::metadata::Ascii>,
+}
+
+impl Client {
+    /// Start a server on a free port, and open a session on it.
+    async fn connect() -> Self {
+        Self::connect_to(FlightSqlServer::new(Database::open().unwrap())).await
+    }
+
+    async fn connect_to(server: FlightSqlServer) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(server.serve(listener));
+
+        let mut client = FlightServiceClient::connect(format!("http://{}", addr))
+            .await
+            .unwrap();
+        let request = HandshakeRequest::default();
+        let response = client.handshake(stream::iter(vec![request])).await.unwrap();
+        let authorization = response.metadata().get("authorization").unwrap().clone();
+        Self {
+            client,
+            authorization,
+        }
+    }
+
+    fn request<T>(&self, message: T) -> Request<T> {
+        let mut request = Request::new(message);
+        request
+            .metadata_mut()
+            .insert("authorization", self.authorization.clone());
+        request
+    }
+
+    /// Send the flight data by DoPut, the descriptor is attached to the first message.
+    async fn put(&mut self, cmd: impl ProstMessageExt, mut data: Vec<FlightData>) {
+        let descriptor = FlightDescriptor::new_cmd(cmd.as_any().encode_to_vec());
+        if data.is_empty() {
+            data.push(FlightData::default());
+        }
+        data[0].flight_descriptor = Some(descriptor);
+        let request = self.request(stream::iter(data));
+        let mut results = self.client.do_put(request).await.unwrap().into_inner();
+        while let Some(result) = results.next().await {
+            result.unwrap();
+        }
+    }
+
+    async fn update(&mut self, query: &str) {
+        let cmd = CommandStatementUpdate {
+            query: query.to_string(),
+        };
+        self.put(cmd, vec![]).await;
+    }
+
+    /// Fetch the result of the command by GetFlightInfo and DoGet.
+    async fn fetch(&mut self, cmd: impl ProstMessageExt) -> (SchemaRef, Vec<RecordBatch>) {
+        let descriptor = FlightDescriptor::new_cmd(cmd.as_any().encode_to_vec());
+        let request = self.request(descriptor);
+        let info = self.client.get_flight_info(request).await.unwrap();
+        let ticket = info.into_inner().endpoint[0].ticket.clone().unwrap();
+        let request = self.request(ticket);
+        let mut stream = self.client.do_get(request).await.unwrap().into_inner();
+
+        let data = stream.next().await.unwrap().unwrap();
+        let schema = Arc::new(Schema::try_from(&data).unwrap());
+        let mut batches = vec![];
+        while let Some(data) = stream.next().await {
+            let data = data.unwrap();
+            let batch = flight_data_to_arrow_batch(&data, schema.clone(), &HashMap::new());
+            batches.push(batch.unwrap());
+        }
+        (schema, batches)
+    }
+}
+
+fn int32_values(batches: &[RecordBatch], column: usize) -> Vec<i32> {
+    let mut values = batches
+        .iter()
+        .flat_map(|batch| {
+            let array = batch.column(column);
+            let array = array.as_any().downcast_ref::<Int32Array>().unwrap();
+            array.values().to_vec()
+        })
+        .collect::<Vec<_>>();
+    values.sort();
+    values
+}
+
+fn string_values(batches: &[RecordBatch], column: usize) -> Vec<String> {
+    batches
+        .iter()
+        .flat_map(|batch| {
+            let array = batch.column(column);
+            let array = array.as_any().downcast_ref::<StringArray>().unwrap();
+            array
+                .iter()
+                .map(|v| v.unwrap().to_string())
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_statement_query() {
+    let mut client = Client::connect().await;
+    client.update("create table t1(a int, b varchar)").await;
+    client
+        .update("insert into t1 values (1, 'x'), (2, 'y'), (3, 'z')")
+        .await;
+
+    let cmd = CommandStatementQuery {
+        query: "select a, b from t1".to_string(),
+    };
+    let (schema, batches) = client.fetch(cmd).await;
+    assert_eq!(schema.field(0).name(), "a");
+    assert_eq!(schema.field(0).data_type(), &DataType::Int32);
+    assert_eq!(schema.field(1).data_type(), &DataType::Utf8);
+    assert_eq!(int32_values(&batches, 0), vec![1, 2, 3]);
+
+    let descriptor = FlightDescriptor::new_cmd(
+        CommandStatementQuery {
+            query: "select * from t2".to_string(),
+        }
+        .as_any()
+        .encode_to_vec(),
+    );
+    let request = client.request(descriptor);
+    let status = client.client.get_flight_info(request).await.unwrap_err();
+    assert_eq!(status.code(), tonic::Code::InvalidArgument, "{}", status);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_prepared_statement() {
+    let mut client = Client::connect().await;
+    client.update("create table t1(a int)").await;
+    client.update("insert into t1 values (1), (2), (3)").await;
+
+    let request = ActionCreatePreparedStatementRequest {
+        query: "select a from t1 where a > $1".to_string(),
+    };
+    let action = Action {
+        r#type: "CreatePreparedStatement".to_string(),
+        body: request.as_any().encode_to_vec().into(),
+    };
+    let request = client.request(action);
+    let mut results = client.client.do_action(request).await.unwrap().into_inner();
+    let result = results.next().await.unwrap().unwrap();
+    let result = Any::decode(&*result.body)
+        .unwrap()
+        .unpack::<ActionCreatePreparedStatementResult>()
+        .unwrap()
+        .unwrap();
+    let parameter_schema =
+        Schema::try_from(IpcMessage(result.parameter_schema.clone().into())).unwrap();
+    assert_eq!(parameter_schema.field(0).data_type(), &DataType::Int32);
+
+    // bind $1 to 1
+    let schema = Arc::new(Schema::new(vec![Field::new("$1", DataType::Int32, true)]));
+    let batch =
+        RecordBatch::try_new(schema.clone(), vec![Arc::new(Int32Array::from(vec![1]))]).unwrap();
+    let options = IpcWriteOptions::default();
+    let (_, data) = flight_data_from_arrow_batch(&batch, &options);
+    let cmd = CommandPreparedStatementQuery {
+        prepared_statement_handle: result.prepared_statement_handle.clone(),
+    };
+    let schema_data = FlightData::from(SchemaAsIpc::new(&schema, &options));
+    client.put(cmd.clone(), vec![schema_data, data]).await;
+
+    let (_, batches) = client.fetch(cmd).await;
+    assert_eq!(int32_values(&batches, 0), vec![2, 3]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_catalog_metadata() {
+    let mut client = Client::connect().await;
+    client.update("create table t1(a int)").await;
+    client.update("create table t2(b varchar)").await;
+
+    let (_, batches) = client.fetch(CommandGetDbSchemas::default()).await;
+    assert!(string_values(&batches, 1).contains(&"main".to_string()));
+
+    let cmd = CommandGetTables {
+        table_name_filter_pattern: Some("t%".to_string()),
+        include_schema: true,
+        ..Default::default()
+    };
+    let (schema, batches) = client.fetch(cmd).await;
+    assert_eq!(schema.field(4).name(), "table_schema");
+    assert_eq!(string_values(&batches, 2), vec!["t1", "t2"]);
+    assert_eq!(string_values(&batches, 3), vec!["TABLE", "TABLE"]);
+
+    let cmd = CommandGetTables {
+        table_name_filter_pattern: Some("t2".to_string()),
+        ..Default::default()
+    };
+    let (schema, batches) = client.fetch(cmd).await;
+    assert_eq!(schema.fields().len(), 4);
+    assert_eq!(string_values(&batches, 2), vec!["t2"]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_session_expiry() {
+    let server = FlightSqlServer::new(Database::open().unwrap())
+        .with_session_timeout(Duration::from_millis(200));
+    let mut client = Client::connect_to(server).await;
+    let token = client.authorization.to_str().unwrap().to_string();
+    // a bearer token of 32 random bytes
+    assert_eq!(token.strip_prefix("Bearer ").unwrap().len(), 64);
+    client.update("create table t1(a int)").await;
+
+    tokio::time::sleep(Duration::from_millis(400)).await;
+    let descriptor = FlightDescriptor::new_cmd(
+        CommandStatementQuery {
+            query: "select * from t1".to_string(),
+        }
+        .as_any()
+        .encode_to_vec(),
+    );
+    let request = client.request(descriptor);
+    let status = client.client.get_flight_info(request).await.unwrap_err();
+    assert_eq!(status.code(), tonic::Code::Unauthenticated, "{}", status);
+}
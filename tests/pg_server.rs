@@ -0,0 +1,171 @@
+//! Query the postgres wire protocol server with tokio-postgres, through the simple and the
+//! extended query protocol.
+use sqlrs::main_entry::Database;
+use sqlrs::pg_server::PgServer;
+use tokio::net::TcpListener;
+use tokio_postgres::types::Type;
+use tokio_postgres::{Client, NoTls, SimpleQueryMessage};
+
+/// Start a server on a free port, and connect a client to it.
+async fn connect() -> Client {
+    connect_to(start_server().await).await
+}
+
+/// Start a server on a free port, returns the port.
+async fn start_server() -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = PgServer::new(Database::open().unwrap());
+    tokio::spawn(server.serve(listener));
+    port
+}
+
+async fn connect_to(port: u16) -> Client {
+    let config = format!("host=127.0.0.1 port={} user=sqlrs", port);
+    let (client, connection) = tokio_postgres::connect(&config, NoTls).await.unwrap();
+    tokio::spawn(connection);
+    client
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_simple_query() {
+    let client = connect().await;
+    client
+        .batch_execute(
+            "create table t1(a int, b varchar); insert into t1 values (1, 'x'), (2, NULL);",
+        )
+        .await
+        .unwrap();
+
+    let messages = client.simple_query("select a, b from t1").await.unwrap();
+    let mut rows = messages
+        .iter()
+        .filter_map(|message| match message {
+            SimpleQueryMessage::Row(row) => Some((row.get(0), row.get(1))),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    rows.sort();
+    assert_eq!(rows, vec![(Some("1"), Some("x")), (Some("2"), None)]);
+    assert!(matches!(
+        messages.last(),
+        Some(SimpleQueryMessage::CommandComplete(2))
+    ));
+
+    let error = client.simple_query("select * from t2").await.unwrap_err();
+    assert!(error.as_db_error().is_some(), "{}", error);
+    // the connection is still usable after an error
+    let messages = client.simple_query("select 1").await.unwrap();
+    assert!(matches!(
+        messages.last(),
+        Some(SimpleQueryMessage::CommandComplete(1))
+    ));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_extended_query() {
+    let client = connect().await;
+    client
+        .batch_execute("create table t1(a int, b varchar, c double)")
+        .await
+        .unwrap();
+
+    let insert = client
+        .prepare("insert into t1 values ($1, $2, $3)")
+        .await
+        .unwrap();
+    assert_eq!(insert.params(), &[Type::INT4, Type::VARCHAR, Type::FLOAT8]);
+    let rows = client
+        .execute(&insert, &[&1i32, &"x", &1.5f64])
+        .await
+        .unwrap();
+    assert_eq!(rows, 1);
+    client
+        .execute(&insert, &[&2i32, &None::<&str>, &2.5f64])
+        .await
+        .unwrap();
+
+    let select = client
+        .prepare("select a, b, c from t1 where a >= $1")
+        .await
+        .unwrap();
+    assert_eq!(select.params(), &[Type::INT4]);
+    let types = select.columns().iter().map(|c| c.type_().clone());
+    assert_eq!(
+        types.collect::<Vec<_>>(),
+        vec![Type::INT4, Type::VARCHAR, Type::FLOAT8]
+    );
+    let rows = client.query(&select, &[&1i32]).await.unwrap();
+    let mut values = rows
+        .iter()
+        .map(|row| (row.get(0), row.get(1), row.get(2)))
+        .collect::<Vec<(i32, Option<String>, f64)>>();
+    values.sort_by_key(|(a, _, _)| *a);
+    assert_eq!(
+        values,
+        vec![(1, Some("x".to_string()), 1.5), (2, None, 2.5)]
+    );
+
+    // a query without parameters is prepared by an unnamed statement
+    let row = client
+        .query_one("select a + 1, b from t1 where c < 2", &[])
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, i32>(0), 2);
+    assert_eq!(row.get::<_, &str>(1), "x");
+    assert!(client.prepare("select * from t2").await.is_err());
+    assert_eq!(client.query(&select, &[&2i32]).await.unwrap().len(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_concurrent_sessions() {
+    let port = start_server().await;
+    let client = connect_to(port).await;
+    client
+        .batch_execute("create table t1(a int)")
+        .await
+        .unwrap();
+
+    // the sessions change and read the catalog and the storage at the same time, they wait for
+    // each other's locks instead of failing
+    let sessions = (0..8)
+        .map(|session| {
+            tokio::spawn(async move {
+                let client = connect_to(port).await;
+                let table = format!("s{}", session);
+                client
+                    .batch_execute(&format!("create table {}(a int)", table))
+                    .await
+                    .unwrap();
+                for i in 0..20 {
+                    client
+                        .batch_execute(&format!("insert into t1 values ({})", i))
+                        .await
+                        .unwrap();
+                    client
+                        .batch_execute(&format!("insert into {} select a from t1 limit 1", table))
+                        .await
+                        .unwrap();
+                    client
+                        .simple_query("select count(*) from t1")
+                        .await
+                        .unwrap();
+                }
+                client.batch_execute("analyze").await.unwrap();
+            })
+        })
+        .collect::<Vec<_>>();
+    for session in sessions {
+        session.await.unwrap();
+    }
+
+    let messages = client
+        .simple_query("select count(*) from t1")
+        .await
+        .unwrap();
+    let count = messages.iter().find_map(|message| match message {
+        SimpleQueryMessage::Row(row) => row.get(0).map(|c| c.to_string()),
+        _ => None,
+    });
+    assert_eq!(count.as_deref(), Some("160"));
+}